@@ -7,6 +7,13 @@ const VT_PROGRESS_BAR = 6
 const VT_SCROLL = 7
 const VT_TEXT_EDIT = 8
 const VT_IMAGE = 9;
+const VT_VIRTUAL_LIST = 10;
+const VT_CHECKBOX = 11;
+const VT_RADIO = 12;
+const VT_SWITCH = 13;
+const VT_SLIDER = 14;
+const VT_SELECT = 15;
+const VT_CANVAS = 16;
 
 export class Frame {
 
@@ -50,6 +57,15 @@ export class Frame {
         frame_set_modal(this.frameId, owner.frameId)
     }
 
+    /**
+     * Make this frame a popup of owner, it receives keyboard input of owner
+     * and will be closed when owner is pressed or closed
+     * @param owner {Frame}
+     */
+    setPopup(owner) {
+        frame_set_popup(this.frameId, owner.frameId)
+    }
+
     close() {
         frame_close(this.frameId);
     }
@@ -72,6 +88,118 @@ export class Frame {
 
 }
 
+export class DataTransfer {
+    #id;
+    #data;
+    #types;
+    #effectAllowed;
+    #dropEffect;
+    /**
+     * @type {string[]} paths of files dropped from the OS
+     */
+    files;
+
+    constructor(detail) {
+        this.#id = detail.id;
+        this.#data = detail.data || {};
+        this.#types = detail.types || [];
+        this.#effectAllowed = detail.effectAllowed;
+        this.#dropEffect = detail.dropEffect;
+        this.files = detail.files || [];
+    }
+
+    /**
+     * Mime types of data, available in all drag events. The data itself could only be read
+     * in dragstart and drop events, getData returns undefined in dragenter/dragover/dragleave
+     * @returns {string[]}
+     */
+    get types() {
+        return this.#types;
+    }
+
+    /**
+     * Only available in dragstart event
+     * @param type {string} mime type, e.g. "text/plain", "application/json"
+     * @param data {string}
+     */
+    setData(type, data) {
+        data_transfer_set_data(this.#id, type, data);
+        if (!this.#types.includes(type)) {
+            this.#types.push(type);
+        }
+        this.#data[type] = data;
+    }
+
+    /**
+     *
+     * @param type {string}
+     * @returns {string | undefined}
+     */
+    getData(type) {
+        return this.#data[type];
+    }
+
+    setJson(value) {
+        this.setData("application/json", JSON.stringify(value));
+    }
+
+    getJson() {
+        const data = this.getData("application/json");
+        return data === undefined ? undefined : JSON.parse(data);
+    }
+
+    /**
+     *
+     * @param type {string} clear all data if not specified
+     */
+    clearData(type) {
+        data_transfer_clear_data(this.#id, type);
+        if (type) {
+            this.#types = this.#types.filter(t => t !== type);
+            delete this.#data[type];
+        } else {
+            this.#types = [];
+            this.#data = {};
+        }
+    }
+
+    /**
+     * Set the image following the pointer while dragging
+     * @param src {string}
+     * @param offsetX {number} pointer offset in the image
+     * @param offsetY {number}
+     */
+    setDragImage(src, offsetX, offsetY) {
+        data_transfer_set_drag_image(this.#id, src, offsetX || 0, offsetY || 0);
+    }
+
+    /**
+     * @returns {"none" | "copy" | "move" | "link" | "copyMove" | "copyLink" | "linkMove" | "all"}
+     */
+    get effectAllowed() {
+        return this.#effectAllowed;
+    }
+
+    set effectAllowed(value) {
+        data_transfer_set_effect_allowed(this.#id, value);
+        this.#effectAllowed = value;
+    }
+
+    /**
+     * Set in dragover and call preventDefault() to accept drop
+     * @returns {"none" | "copy" | "move" | "link"}
+     */
+    get dropEffect() {
+        return this.#dropEffect;
+    }
+
+    set dropEffect(value) {
+        data_transfer_set_drop_effect(this.#id, value);
+        this.#dropEffect = value;
+    }
+
+}
+
 export class EventObject {
     _propagationCancelled = false
     _preventDefault = false
@@ -83,6 +211,9 @@ export class EventObject {
         this.type = type;
         this.detail = detail;
         this.target = target;
+        if (detail && detail.dataTransfer) {
+            detail.dataTransfer = new DataTransfer(detail.dataTransfer);
+        }
     }
 
     stopPropagation() {
@@ -174,6 +305,89 @@ export class SystemTray {
     }
 
 }
+/**
+ * Collects tree mutations and applies them by one native call, which is much cheaper than
+ * calling view apis one by one when building large screens.
+ * Operations work on raw element handles, View wrappers are not updated.
+ */
+export class ViewBatch {
+    #ops = [];
+    #created = 0;
+
+    /**
+     *
+     * @param target {number | View | unknown} index returned by create, a view or an element handle
+     * @returns {unknown}
+     */
+    #target(target) {
+        return target instanceof View ? target.el : target;
+    }
+
+    /**
+     *
+     * @param viewType {number | string}
+     * @returns {number} index of the element in the batch, could be used as target of other operations
+     */
+    create(viewType) {
+        this.#ops.push(["create", viewType]);
+        return this.#created++;
+    }
+
+    setProperty(target, name, value) {
+        this.#ops.push(["setProperty", this.#target(target), name, value]);
+    }
+
+    /**
+     *
+     * @param target {number | View | unknown}
+     * @param style {Record<string, any>}
+     */
+    setStyle(target, style) {
+        this.#ops.push(["setStyle", this.#target(target), style]);
+    }
+
+    setHoverStyle(target, style) {
+        this.#ops.push(["setHoverStyle", this.#target(target), style]);
+    }
+
+    setFocusStyle(target, style) {
+        this.#ops.push(["setFocusStyle", this.#target(target), style]);
+    }
+
+    insert(parent, child, index = -1) {
+        this.#ops.push(["insert", this.#target(parent), this.#target(child), index]);
+    }
+
+    remove(parent, index) {
+        this.#ops.push(["remove", this.#target(parent), index]);
+    }
+
+    /**
+     *
+     * @param target {number | View | unknown}
+     * @param type {string}
+     * @param callback {(e: EventObject) => void}
+     */
+    bindEvent(target, type, callback) {
+        this.#ops.push(["bindEvent", this.#target(target), type.toLowerCase(), (type, detail, target) => {
+            const event = new EventObject(type, detail, target);
+            callback(event);
+            return event.result();
+        }]);
+    }
+
+    /**
+     * Apply all operations, nothing is applied if any of them is invalid
+     * @returns {{elements: unknown[], listeners: number[]}} created elements and listener ids in order
+     */
+    commit() {
+        const ops = this.#ops;
+        this.#ops = [];
+        this.#created = 0;
+        return view_batch(ops);
+    }
+}
+
 export class View {
     /**
      * @type {ContainerBasedElement}
@@ -193,7 +407,7 @@ export class View {
 
     /**
      *
-     * @param viewType {number}
+     * @param viewType {number | string} built-in view type or registered name of custom element
      */
     constructor(viewType) {
         this.viewType = viewType;
@@ -224,20 +438,62 @@ export class View {
         view_set_hover_style(this.el, style);
     }
 
+    /**
+     * Style applied when the view is focused by keyboard
+     * @param style {Record<string, any>}
+     */
+    setFocusStyle(style) {
+        view_set_focus_style(this.el, style);
+    }
+
+    /**
+     *
+     * @param value {number} negative value means focusable but not reachable by Tab
+     */
+    setTabIndex(value) {
+        view_set_property(this.el, "tabIndex", value);
+    }
+
+    focus() {
+        view_focus(this.el);
+    }
+
+    blur() {
+        view_blur(this.el);
+    }
+
     /**
      *
      * @param value {number}
+     * @param behavior {"instant"|"smooth"}
      */
-    setScrollTop(value) {
-        view_set_property(this.el, "scrollTop", value);
+    setScrollTop(value, behavior = "instant") {
+        view_set_property(this.el, "scroll_to", {y: value, behavior});
     }
 
     /**
      *
      * @param value {number}
+     * @param behavior {"instant"|"smooth"}
+     */
+    setScrollLeft(value, behavior = "instant") {
+        view_set_property(this.el, "scroll_to", {x: value, behavior});
+    }
+
+    /**
+     *
+     * @param options {{x?: number, y?: number, behavior?: "instant"|"smooth"}}
      */
-    setScrollLeft(value) {
-        view_set_property(this.el, "scrollLeft", value);
+    scrollTo(options) {
+        view_set_property(this.el, "scroll_to", options);
+    }
+
+    /**
+     * Scroll ancestors to make this view visible
+     * @param options {{block?: "start"|"center"|"end"|"nearest", inline?: "start"|"center"|"end"|"nearest", behavior?: "instant"|"smooth"}}
+     */
+    scrollIntoView(options = {}) {
+        view_set_property(this.el, "scroll_into_view", options);
     }
 
 
@@ -257,6 +513,30 @@ export class View {
         view_set_property(this.el, "cursor", value);
     }
 
+    /**
+     *
+     * @param value {string}
+     */
+    setRole(value) {
+        view_set_property(this.el, "role", value);
+    }
+
+    /**
+     *
+     * @param value {string}
+     */
+    setAriaLabel(value) {
+        view_set_property(this.el, "ariaLabel", value);
+    }
+
+    /**
+     *
+     * @param value {string}
+     */
+    setAriaDescription(value) {
+        view_set_property(this.el, "ariaDescription", value);
+    }
+
     /**
      *
      * @returns {[number, number]}
@@ -301,6 +581,10 @@ export class View {
         this.bindEvent("mouseleave", callback);
     }
 
+    bindContextMenu(callback) {
+        this.bindEvent("contextmenu", callback);
+    }
+
     bindKeyDown(callback) {
         this.bindEvent("keydown", callback);
     }
@@ -333,6 +617,30 @@ export class View {
         this.bindEvent("drop", callback);
     }
 
+    bindCompositionStart(callback) {
+        this.bindEvent("compositionstart", callback);
+    }
+
+    bindCompositionUpdate(callback) {
+        this.bindEvent("compositionupdate", callback);
+    }
+
+    bindCompositionEnd(callback) {
+        this.bindEvent("compositionend", callback);
+    }
+
+    bindDragEnter(callback) {
+        this.bindEvent("dragenter", callback);
+    }
+
+    bindDragLeave(callback) {
+        this.bindEvent("dragleave", callback);
+    }
+
+    bindDragEnd(callback) {
+        this.bindEvent("dragend", callback);
+    }
+
     bindEvent(type, callback) {
         this.eventRegistry.bindEvent(type, callback);
     }
@@ -343,6 +651,91 @@ export class View {
 
 }
 
+/**
+ * Search api shared by text elements
+ * @param Base {typeof View}
+ */
+const SearchableView = (Base) => class extends Base {
+
+    /**
+     * Highlight all matches, empty query to clear search
+     * @param query {string}
+     * @param options {{ignoreCase?: boolean, regex?: boolean}}
+     * @throws if query is an invalid regular expression
+     */
+    search(query, options) {
+        view_search(this.el, {query: query || "", ...options});
+    }
+
+    /**
+     *
+     * @returns {[number, number][]} ranges of matches
+     */
+    getSearchMatches() {
+        return view_get_property(this.el, "searchmatches");
+    }
+
+    /**
+     *
+     * @returns {number | null} index of the active match
+     */
+    getActiveMatch() {
+        return view_get_property(this.el, "activematch");
+    }
+
+    /**
+     * Activate the next match and scroll it into view
+     */
+    findNext() {
+        view_set_property(this.el, "gotomatch", "next");
+    }
+
+    findPrevious() {
+        view_set_property(this.el, "gotomatch", "previous");
+    }
+
+    /**
+     *
+     * @param color {string} hex color of matches
+     */
+    setSearchColor(color) {
+        view_set_property(this.el, "searchcolor", color);
+    }
+
+    /**
+     *
+     * @param color {string} hex color of the active match
+     */
+    setActiveSearchColor(color) {
+        view_set_property(this.el, "activesearchcolor", color);
+    }
+
+};
+
+/**
+ * Search and replace api shared by editable text elements
+ * @param Base {typeof View}
+ */
+const ReplaceableView = (Base) => class extends SearchableView(Base) {
+
+    /**
+     * Replace the selected match and select the next one
+     * @param replacement {string} captures could be referenced as $1 in regex search
+     */
+    replaceMatch(replacement) {
+        view_set_property(this.el, "replacematch", replacement);
+    }
+
+    /**
+     * Replace all matches, which could be undone at once
+     * @param replacement {string} captures could be referenced as $1 in regex search
+     */
+    replaceAll(replacement) {
+        view_set_property(this.el, "replaceall", replacement);
+    }
+
+};
+
 export class Audio {
     context;
     eventRegistry;
@@ -394,7 +787,7 @@ export class Audio {
 
 }
 
-export class LabelElement extends View {
+export class LabelElement extends SearchableView(View) {
     constructor() {
         super(VT_LABEL);
     }
@@ -409,12 +802,52 @@ export class LabelElement extends View {
 
     /**
      *
-     * @param align {"left" | "right" | "center"}
+     * @param align {"left" | "right" | "center" | "start" | "end"}
      */
     setAlign(align) {
         view_set_property(this.el, "align", align);
     }
 
+    /**
+     *
+     * @param textOverflow {"clip" | "ellipsis"}
+     */
+    setTextOverflow(textOverflow) {
+        view_set_property(this.el, "textoverflow", textOverflow);
+    }
+
+    /**
+     * Clamp text to lines, the last line is ended with ellipsis if textOverflow is ellipsis
+     * @param maxLines {number} zero means unlimited
+     */
+    setMaxLines(maxLines) {
+        view_set_property(this.el, "maxlines", maxLines);
+    }
+
+    /**
+     *
+     * @param whiteSpace {"normal" | "nowrap" | "pre" | "pre-wrap"} default is pre-wrap
+     */
+    setWhiteSpace(whiteSpace) {
+        view_set_property(this.el, "whitespace", whiteSpace);
+    }
+
+    /**
+     * CJK text is broken between chars unless keep-all is set
+     * @param wordBreak {"normal" | "break-all" | "keep-all"}
+     */
+    setWordBreak(wordBreak) {
+        view_set_property(this.el, "wordbreak", wordBreak);
+    }
+
+    /**
+     * Whether words longer than the line are broken, default is break-word
+     * @param overflowWrap {"normal" | "break-word" | "anywhere"}
+     */
+    setOverflowWrap(overflowWrap) {
+        view_set_property(this.el, "overflowwrap", overflowWrap);
+    }
+
 }
 
 export class ImageElement extends View {
@@ -441,14 +874,14 @@ export class ButtonElement extends View {
 
 }
 
-export class EntryElement extends View {
+export class EntryElement extends ReplaceableView(View) {
     constructor() {
         super(VT_ENTRY);
     }
 
     /**
      *
-     * @param align {"left"|"right"|"center"}
+     * @param align {"left"|"right"|"center"|"start"|"end"} start and end depend on the direction style
      */
     setAlign(align) {
         view_set_property(this.el, "align", align);
@@ -478,178 +911,1481 @@ export class EntryElement extends View {
         return view_get_property(this.el, "text");
     }
 
-    bindTextChange(callback) {
-        this.bindEvent("textchange", callback);
+    /**
+     * Bind shortcut to edit command, unbind the shortcut if command is empty
+     * @param shortcut {string} e.g. "Ctrl+Shift+Z", "Mod+D", Mod is Meta on macOS and Ctrl on other platforms
+     * @param command {string} e.g. "redo", "deleteWordBackward", "moveLineStart", "selectDocumentEnd"
+     */
+    setKeyBinding(shortcut, command) {
+        view_set_property(this.el, "keybinding", [shortcut, command || ""]);
     }
 
-}
+    /**
+     * Underline misspelled words, dictionary should be loaded by spellCheck.loadDictionary
+     * @param spellCheck {boolean}
+     */
+    setSpellCheck(spellCheck) {
+        view_set_property(this.el, "spellcheck", !!spellCheck);
+    }
 
-class TextEditElement extends View {
-    constructor() {
-        super(VT_TEXT_EDIT);
+    /**
+     * Misspelled word at caret, e.g. for suggestions in context menu
+     * @returns {{start: number, end: number, word: string} | null}
+     */
+    getMisspelledWord() {
+        return view_get_property(this.el, "misspelledword");
     }
 
     /**
      *
-     * @param align {"left"|"right"|"center"}
+     * @param password {boolean}
      */
-    setAlign(align) {
-        view_set_property(this.el, "align", align);
+    setPassword(password) {
+        view_set_property(this.el, "password", !!password);
+    }
+
+    /**
+     * Text displayed when entry is empty
+     * @param placeholder {string}
+     */
+    setPlaceholder(placeholder) {
+        view_set_property(this.el, "placeholder", placeholder);
     }
 
     /**
      *
-     * @param text {string}
+     * @param color {string} hex color, e.g. "#999999"
      */
-    setText(text) {
-        view_set_property(this.el, "text", text);
+    setPlaceholderColor(color) {
+        view_set_property(this.el, "placeholdercolor", color);
+    }
+
+    /**
+     * Unset fields follow the text style of entry
+     * @param style {{color?: string, fontSize?: number, lineHeight?: number, align?: "left"|"center"|"right"}}
+     */
+    setPlaceholderStyle(style) {
+        view_set_property(this.el, "placeholderstyle", style);
     }
 
     /**
      *
-     * @returns {string}
+     * @param maxLength {number} negative value means unlimited
      */
-    getText() {
-        return view_get_property(this.el, "text");
+    setMaxLength(maxLength) {
+        view_set_property(this.el, "maxlength", maxLength);
     }
 
     /**
      *
-     * @param selection {[number, number]}
+     * @param readOnly {boolean}
      */
-    setSelection(selection) {
-        view_set_property(this.el, "selection", selection);
+    setReadOnly(readOnly) {
+        view_set_property(this.el, "readonly", !!readOnly);
     }
 
     /**
      *
-     * @param caret {number}
+     * @param disabled {boolean}
      */
-    setCaret(caret) {
-        view_set_property(this.el, "caret", caret);
+    setDisabled(disabled) {
+        view_set_property(this.el, "disabled", !!disabled);
     }
 
     /**
      *
-     * @param top {number}
+     * @param filter {string} "numeric" or a regex tested against each inserted char, empty to remove filter
      */
-    scrollToTop(top) {
-        view_set_property(this.el, "scroll_to_top", top);
+    setInputFilter(filter) {
+        view_set_property(this.el, "inputfilter", filter || "");
+    }
+
+    /**
+     * Insert text at caret without emitting beforeinput, e.g. to rewrite input in beforeinput handler
+     * @param text {string}
+     */
+    insertText(text) {
+        view_set_property(this.el, "inserttext", text);
     }
 
     bindTextChange(callback) {
         this.bindEvent("textchange", callback);
     }
 
-    bindCaretChange(callback) {
-        this.bindEvent("caretchange", callback);
+    /**
+     * Emitted before input is applied, call preventDefault to reject or rewrite it
+     * @param callback {(event) => void} event.detail is {inputType: "insertText"|"deleteContent", data: string, rangeStart: number, rangeEnd: number},
+     *  the range is the text to be replaced when the event is emitted, it is applied later unless preventDefault is called
+     */
+    bindBeforeInput(callback) {
+        this.bindEvent("beforeinput", callback);
+    }
+
+}
+
+class TextEditElement extends ReplaceableView(View) {
+    constructor() {
+        super(VT_TEXT_EDIT);
+    }
+
+    /**
+     *
+     * @param align {"left"|"right"|"center"|"start"|"end"} start and end depend on the direction style
+     */
+    setAlign(align) {
+        view_set_property(this.el, "align", align);
+    }
+
+    /**
+     *
+     * @param text {string}
+     */
+    setText(text) {
+        view_set_property(this.el, "text", text);
+    }
+
+    /**
+     *
+     * @returns {string}
+     */
+    getText() {
+        return view_get_property(this.el, "text");
+    }
+
+    /**
+     *
+     * @param selection {[number, number]}
+     */
+    setSelection(selection) {
+        view_set_property(this.el, "selection", selection);
+    }
+
+    /**
+     *
+     * @param caret {number}
+     */
+    setCaret(caret) {
+        view_set_property(this.el, "caret", caret);
+    }
+
+    /**
+     *
+     * @param top {number}
+     */
+    scrollToTop(top) {
+        view_set_property(this.el, "scroll_to_top", top);
+    }
+
+    /**
+     * Tab inserts tab char, new line is auto indented, brackets are matched and current line is highlighted
+     * @param codeMode {boolean}
+     */
+    setCodeMode(codeMode) {
+        view_set_property(this.el, "codemode", !!codeMode);
+    }
+
+    /**
+     *
+     * @param tabSize {number} columns of a tab stop, Tab indents to the next stop with spaces outside code mode
+     */
+    setTabSize(tabSize) {
+        view_set_property(this.el, "tabsize", tabSize);
+    }
+
+    /**
+     * Highlight text line by line, the former rule wins if matches overlap
+     * @param rules {{pattern: string, color: string}[]} pattern is a regex, color is a hex color
+     */
+    setSyntaxRules(rules) {
+        view_set_property(this.el, "syntaxrules", (rules || []).map(r => [r.pattern, r.color]));
+    }
+
+    /**
+     *
+     * @param color {string} hex color of the caret line background in code mode
+     */
+    setCurrentLineColor(color) {
+        view_set_property(this.el, "currentlinecolor", color);
+    }
+
+    /**
+     *
+     * @param color {string} hex color of the boxes around matched brackets in code mode
+     */
+    setBracketColor(color) {
+        view_set_property(this.el, "bracketcolor", color);
+    }
+
+    /**
+     *
+     * @param lineNumbers {boolean}
+     */
+    setLineNumbers(lineNumbers) {
+        view_set_property(this.el, "linenumbers", !!lineNumbers);
+    }
+
+    /**
+     * Underline misspelled words, dictionary should be loaded by spellCheck.loadDictionary
+     * @param spellCheck {boolean}
+     */
+    setSpellCheck(spellCheck) {
+        view_set_property(this.el, "spellcheck", !!spellCheck);
+    }
+
+    /**
+     * Misspelled word at caret, e.g. for suggestions in context menu
+     * @returns {{start: number, end: number, word: string} | null}
+     */
+    getMisspelledWord() {
+        return view_get_property(this.el, "misspelledword");
+    }
+
+    bindTextChange(callback) {
+        this.bindEvent("textchange", callback);
+    }
+
+    bindCaretChange(callback) {
+        this.bindEvent("caretchange", callback);
+    }
+
+}
+
+class ContainerBasedElement extends View {
+    #children = [];
+    
+    /**
+     *
+     * @param child {View}
+     * @param index {number}
+     */
+    addChild(child, index= -1) {
+        if (child.parent === this) {
+            const oldIndex = this.#children.indexOf(child);
+            if (oldIndex === index) {
+                return;
+            }
+            index -= oldIndex < index ? 1 : 0;
+            this.removeChild(child);
+            this.addChild(child, index);
+            return;
+        }
+        if (child.parent) {
+            child.parent.removeChild(child);
+        }
+        child.parent = this;
+        if (typeof index === "number" && index >= 0 && index < this.#children.length) {
+            view_add_child(this.el, child.el, index);
+            this.#children.splice(index, 0, child);
+        } else {
+            view_add_child(this.el, child.el, -1);
+            this.#children.push(child);
+        }
+    }
+
+    /**
+     *
+     * @param newNode {View}
+     * @param referenceNode {View}
+     */
+    addChildBefore(newNode, referenceNode) {
+        const index = this.#children.indexOf(referenceNode);
+        this.addChild(newNode, index);
+    }
+
+    /**
+     *
+     * @param newNode {View}
+     * @param referenceNode {View}
+     */
+    addChildAfter(newNode, referenceNode) {
+        const index = this.#children.indexOf(referenceNode);
+        if (index >= 0) {
+            this.addChild(newNode, index + 1);
+        } else {
+            this.addChild(newNode);
+        }
+    }
+
+    /**
+     *
+     * @param child {View}
+     */
+    removeChild(child) {
+        const index = this.#children.indexOf(child);
+        if (index >= 0) {
+            child.parent = null;
+            view_remove_child(this.el, index);
+            this.#children.splice(index, 1);
+        } else {
+            console.log("remove child failed")
+        }
+    }
+}
+
+export class ContainerElement extends ContainerBasedElement {
+    constructor() {
+        super(VT_CONTAINER);
+    }
+}
+
+export class ScrollElement extends ContainerBasedElement {
+    constructor() {
+        super(VT_SCROLL);
+    }
+
+    /**
+     *
+     * @param value {"auto"|"always"|"never"}
+     */
+    setScrollX(value) {
+        view_set_property(this.el, "scroll_x", value);
+    }
+
+    /**
+     *
+     * @param value {"auto"|"always"|"never"}
+     */
+    setScrollY(value) {
+        view_set_property(this.el, "scroll_y", value);
+    }
+
+    /**
+     *
+     * @param value {{x?: number, y?: number, behavior?: "instant"|"smooth"}}
+     */
+    scrollBy(value) {
+        value.x = value.x || 0;
+        value.y = value.y || 0;
+        view_set_property(this.el, "scroll_by", value);
+    }
+
+    /**
+     * Scroll offsets to settle at when scrolling ends
+     * @param points {number[]}
+     */
+    setSnapPointsX(points) {
+        view_set_property(this.el, "snap_points_x", points);
+    }
+
+    /**
+     * Scroll offsets to settle at when scrolling ends
+     * @param points {number[]}
+     */
+    setSnapPointsY(points) {
+        view_set_property(this.el, "snap_points_y", points);
+    }
+
+    /**
+     * Snap to the start of children
+     * @param axis {"x"|"y"|"both"|"none"}
+     */
+    setSnapChildren(axis) {
+        view_set_property(this.el, "snap_children", axis);
+    }
+
+}
+
+export class VirtualListElement extends View {
+    /**
+     * @type {Map<number, View>}
+     */
+    #views = new Map();
+    /**
+     * @type {View[]}
+     */
+    #recycledViews = [];
+    #renderItem = null;
+    #rangeChangeCallback = null;
+    #range = {start: 0, end: 0};
+
+    constructor() {
+        super(VT_VIRTUAL_LIST);
+        this.eventRegistry.bindEvent("rangechange", (e) => {
+            // Don't leak to outer lists
+            e.stopPropagation();
+            this.#range = e.detail;
+            this.#updateViews();
+            this.#rangeChangeCallback && this.#rangeChangeCallback(e);
+        });
+    }
+
+    /**
+     * Set the function to create or update the view of an item
+     * @param renderItem {(index: number, recycledView: View | undefined) => View} recycledView is a view of an off-screen item which could be reused
+     */
+    setItemRenderer(renderItem) {
+        this.#renderItem = renderItem;
+        this.refreshItems();
+    }
+
+    /**
+     *
+     * @param count {number}
+     */
+    setItemCount(count) {
+        for (const [index, view] of this.#views) {
+            if (index >= count) {
+                this.#views.delete(index);
+                this.#recycledViews.push(view);
+            }
+        }
+        view_set_property(this.el, "itemcount", count);
+    }
+
+    /**
+     * Insert items before index, the visible items keep their position if they are below index
+     * @param index {number}
+     * @param count {number}
+     */
+    insertItems(index, count = 1) {
+        this.#shiftViews(index, count);
+        view_set_property(this.el, "insertitems", {index, count});
+    }
+
+    /**
+     *
+     * @param index {number}
+     * @param count {number}
+     */
+    removeItems(index, count = 1) {
+        for (let i = index; i < index + count; i++) {
+            const view = this.#views.get(i);
+            if (view) {
+                this.#views.delete(i);
+                this.#recycledViews.push(view);
+            }
+        }
+        this.#shiftViews(index + count, -count);
+        view_set_property(this.el, "removeitems", {index, count});
+    }
+
+    /**
+     * Render visible items again, should be called when the data of items is changed
+     */
+    refreshItems() {
+        if (!this.#renderItem) {
+            return;
+        }
+        for (const [index, view] of this.#views) {
+            const newView = this.#renderItem(index, view);
+            if (newView !== view) {
+                view_add_child(this.el, newView.el, index);
+                this.#views.set(index, newView);
+            }
+        }
+        this.#updateViews();
+    }
+
+    /**
+     * Use the same height for all items, 0 means the height of each item is measured
+     * @param value {number}
+     */
+    setItemHeight(value) {
+        view_set_property(this.el, "itemheight", value);
+    }
+
+    /**
+     * Height of items which are not measured yet
+     * @param value {number}
+     */
+    setEstimatedItemHeight(value) {
+        view_set_property(this.el, "estimateditemheight", value);
+    }
+
+    /**
+     * Distance above and below the viewport in which items are rendered
+     * @param value {number}
+     */
+    setOverscan(value) {
+        view_set_property(this.el, "overscan", value);
+    }
+
+    /**
+     *
+     * @param index {number}
+     */
+    scrollToIndex(index) {
+        view_set_property(this.el, "scrolltoindex", index);
+    }
+
+    /**
+     *
+     * @param value {"auto"|"always"|"never"}
+     */
+    setScrollY(value) {
+        view_set_property(this.el, "scroll_y", value);
+    }
+
+    /**
+     *
+     * @returns {{start: number, end: number}}
+     */
+    getRange() {
+        const [start, end] = view_get_property(this.el, "range");
+        return {start, end};
+    }
+
+    /**
+     *
+     * @param callback {(e: EventObject) => void} detail is {start, end} of rendered items
+     */
+    bindRangeChange(callback) {
+        this.#rangeChangeCallback = callback;
+    }
+
+    #shiftViews(from, distance) {
+        const shifted = [];
+        for (const [index, view] of this.#views) {
+            if (index >= from) {
+                this.#views.delete(index);
+                shifted.push([index + distance, view]);
+            }
+        }
+        for (const [index, view] of shifted) {
+            this.#views.set(index, view);
+        }
+    }
+
+    #updateViews() {
+        const {start, end} = this.#range;
+        for (const [index, view] of this.#views) {
+            if (index < start || index >= end) {
+                view_remove_child(this.el, index);
+                this.#views.delete(index);
+                this.#recycledViews.push(view);
+            }
+        }
+        if (!this.#renderItem) {
+            return;
+        }
+        for (let index = start; index < end; index++) {
+            if (!this.#views.has(index)) {
+                const view = this.#renderItem(index, this.#recycledViews.pop());
+                view_add_child(this.el, view.el, index);
+                this.#views.set(index, view);
+            }
+        }
+    }
+
+}
+
+export class ProgressBarElement extends View {
+    constructor() {
+        super(VT_PROGRESS_BAR);
+    }
+
+    /**
+     *
+     * @param value {number} between 0 and max
+     */
+    setValue(value) {
+        view_set_property(this.el, "value", value);
+    }
+
+    /**
+     *
+     * @param value {number} default is 1
+     */
+    setMax(value) {
+        view_set_property(this.el, "max", value);
+    }
+
+    /**
+     * Show an animated indicator when the progress is unknown
+     * @param value {boolean}
+     */
+    setIndeterminate(value) {
+        view_set_property(this.el, "indeterminate", value);
+    }
+}
+
+export class CheckboxElement extends View {
+    constructor() {
+        super(VT_CHECKBOX);
+    }
+
+    /**
+     *
+     * @param value {boolean}
+     */
+    setChecked(value) {
+        view_set_property(this.el, "checked", value);
+    }
+
+    /**
+     *
+     * @returns {boolean}
+     */
+    isChecked() {
+        return view_get_property(this.el, "checked");
+    }
+
+    /**
+     * Neither checked nor unchecked, cleared when toggled
+     * @param value {boolean}
+     */
+    setIndeterminate(value) {
+        view_set_property(this.el, "indeterminate", value);
+    }
+
+    /**
+     *
+     * @param value {boolean}
+     */
+    setDisabled(value) {
+        view_set_property(this.el, "disabled", value);
+    }
+
+    /**
+     *
+     * @param callback {(e: EventObject) => void} detail is {checked}
+     */
+    bindChange(callback) {
+        this.bindEvent("change", callback);
+    }
+}
+
+export class SwitchElement extends View {
+    constructor() {
+        super(VT_SWITCH);
+    }
+
+    /**
+     *
+     * @param value {boolean}
+     */
+    setChecked(value) {
+        view_set_property(this.el, "checked", value);
+    }
+
+    /**
+     *
+     * @returns {boolean}
+     */
+    isChecked() {
+        return view_get_property(this.el, "checked");
+    }
+
+    /**
+     *
+     * @param value {boolean}
+     */
+    setDisabled(value) {
+        view_set_property(this.el, "disabled", value);
+    }
+
+    /**
+     *
+     * @param callback {(e: EventObject) => void} detail is {checked}
+     */
+    bindChange(callback) {
+        this.bindEvent("change", callback);
+    }
+}
+
+export class RadioElement extends View {
+    constructor() {
+        super(VT_RADIO);
+    }
+
+    /**
+     * Value of the group when the radio is checked
+     * @param value {string}
+     */
+    setValue(value) {
+        view_set_property(this.el, "value", value);
+    }
+
+    /**
+     *
+     * @param value {boolean}
+     */
+    setChecked(value) {
+        view_set_property(this.el, "checked", value);
+    }
+
+    /**
+     *
+     * @returns {boolean}
+     */
+    isChecked() {
+        return view_get_property(this.el, "checked");
+    }
+
+    /**
+     *
+     * @param value {boolean}
+     */
+    setDisabled(value) {
+        view_set_property(this.el, "disabled", value);
+    }
+}
+
+export class RadioGroupElement extends ContainerBasedElement {
+    constructor() {
+        super(VT_GROUP);
+    }
+
+    /**
+     * Check the radio with the value
+     * @param value {string}
+     */
+    setValue(value) {
+        view_set_property(this.el, "value", value);
+    }
+
+    /**
+     *
+     * @returns {string | null}
+     */
+    getValue() {
+        return view_get_property(this.el, "value");
+    }
+
+    /**
+     *
+     * @param callback {(e: EventObject) => void} detail is {value} of the checked radio
+     */
+    bindChange(callback) {
+        this.bindEvent("change", callback);
+    }
+}
+
+export class SliderElement extends View {
+    constructor() {
+        super(VT_SLIDER);
+    }
+
+    /**
+     *
+     * @param value {number} default is 0
+     */
+    setMin(value) {
+        view_set_property(this.el, "min", value);
+    }
+
+    /**
+     *
+     * @param value {number} default is 100
+     */
+    setMax(value) {
+        view_set_property(this.el, "max", value);
+    }
+
+    /**
+     *
+     * @param value {number} default is 1, 0 means continuous
+     */
+    setStep(value) {
+        view_set_property(this.el, "step", value);
+    }
+
+    /**
+     *
+     * @param value {number}
+     */
+    setValue(value) {
+        view_set_property(this.el, "value", value);
+    }
+
+    /**
+     *
+     * @returns {number}
+     */
+    getValue() {
+        return view_get_property(this.el, "value");
+    }
+
+    /**
+     *
+     * @param value {boolean}
+     */
+    setDisabled(value) {
+        view_set_property(this.el, "disabled", value);
+    }
+
+    /**
+     *
+     * @param callback {(e: EventObject) => void} detail is {value}
+     */
+    bindChange(callback) {
+        this.bindEvent("change", callback);
+    }
+}
+
+export class SelectElement extends View {
+    constructor() {
+        super(VT_SELECT);
+    }
+
+    /**
+     *
+     * @param options {{value: string, label?: string, disabled?: boolean, group?: string}[]}
+     *          adjacent options with the same group are displayed under one header
+     */
+    setOptions(options) {
+        view_set_property(this.el, "options", options);
+    }
+
+    /**
+     *
+     * @param value {string}
+     */
+    setValue(value) {
+        view_set_property(this.el, "value", value);
+    }
+
+    /**
+     *
+     * @returns {string | null} input text in editable mode
+     */
+    getValue() {
+        return view_get_property(this.el, "value");
+    }
+
+    /**
+     *
+     * @param value {boolean} allow text not in options to be input
+     */
+    setEditable(value) {
+        view_set_property(this.el, "editable", value);
+    }
+
+    /**
+     *
+     * @param value {string}
+     */
+    setPlaceholder(value) {
+        view_set_property(this.el, "placeholder", value);
+    }
+
+    /**
+     *
+     * @param value {boolean}
+     */
+    setDisabled(value) {
+        view_set_property(this.el, "disabled", value);
+    }
+
+    /**
+     *
+     * @param value {boolean} show or hide the popup
+     */
+    setOpen(value) {
+        view_set_property(this.el, "open", value);
+    }
+
+    /**
+     *
+     * @returns {boolean}
+     */
+    isOpen() {
+        return view_get_property(this.el, "open");
     }
 
+    /**
+     *
+     * @param callback {(e: EventObject) => void} detail is {value}
+     */
+    bindChange(callback) {
+        this.bindEvent("change", callback);
+    }
 }
 
-class ContainerBasedElement extends View {
-    #children = [];
-    
+export class CanvasGradient {
     /**
      *
-     * @param child {View}
-     * @param index {number}
+     * @param geometry {(string|number)[]} e.g. ["linear", x0, y0, x1, y1]
      */
-    addChild(child, index= -1) {
-        if (child.parent === this) {
-            const oldIndex = this.#children.indexOf(child);
-            if (oldIndex === index) {
-                return;
-            }
-            index -= oldIndex < index ? 1 : 0;
-            this.removeChild(child);
-            this.addChild(child, index);
-            return;
+    constructor(geometry) {
+        this._geometry = geometry;
+        this._stops = [];
+    }
+
+    /**
+     *
+     * @param offset {number} 0 to 1
+     * @param color {string}
+     */
+    addColorStop(offset, color) {
+        this._stops.push(offset, color);
+    }
+
+    _toStyle() {
+        return this._geometry.concat(this._stops);
+    }
+}
+
+/**
+ * Html-like 2D context. Draw commands are batched and sent to the canvas at the end of the current task.
+ */
+export class CanvasRenderingContext2D {
+    /**
+     *
+     * @param canvas {CanvasElement}
+     */
+    constructor(canvas) {
+        this.canvas = canvas;
+        this._commands = [];
+        this._flushScheduled = false;
+        this._state = this._defaultState();
+        this._savedStates = [];
+    }
+
+    _defaultState() {
+        return {
+            fillStyle: "#000000",
+            strokeStyle: "#000000",
+            lineWidth: 1,
+            lineCap: "butt",
+            lineJoin: "miter",
+            miterLimit: 10,
+            lineDash: [],
+            lineDashOffset: 0,
+            globalAlpha: 1,
+            globalCompositeOperation: "source-over",
+            font: "10px sans-serif",
+            textAlign: "start",
+            textBaseline: "alphabetic",
+        };
+    }
+
+    _push(command) {
+        this._commands.push(command);
+        if (!this._flushScheduled) {
+            this._flushScheduled = true;
+            Promise.resolve().then(() => this.flush());
         }
-        if (child.parent) {
-            child.parent.removeChild(child);
+    }
+
+    _setState(key, value, arg) {
+        this._state[key] = value;
+        this._push([key, arg === undefined ? value : arg]);
+    }
+
+    /**
+     * Send batched commands to the canvas immediately
+     */
+    flush() {
+        this._flushScheduled = false;
+        if (this._commands.length) {
+            const commands = this._commands;
+            this._commands = [];
+            canvas_draw(this.canvas.el, commands);
         }
-        child.parent = this;
-        if (typeof index === "number" && index >= 0 && index < this.#children.length) {
-            view_add_child(this.el, child.el, index);
-            this.#children.splice(index, 0, child);
-        } else {
-            view_add_child(this.el, child.el, -1);
-            this.#children.push(child);
+    }
+
+    /**
+     * Called when the canvas is resized
+     */
+    _reset() {
+        this._commands = [];
+        this._state = this._defaultState();
+        this._savedStates = [];
+    }
+
+    get fillStyle() { return this._state.fillStyle; }
+    set fillStyle(value) { this._setState("fillStyle", value, value instanceof CanvasGradient ? value._toStyle() : value); }
+    get strokeStyle() { return this._state.strokeStyle; }
+    set strokeStyle(value) { this._setState("strokeStyle", value, value instanceof CanvasGradient ? value._toStyle() : value); }
+    get lineWidth() { return this._state.lineWidth; }
+    set lineWidth(value) { this._setState("lineWidth", value); }
+    get lineCap() { return this._state.lineCap; }
+    set lineCap(value) { this._setState("lineCap", value); }
+    get lineJoin() { return this._state.lineJoin; }
+    set lineJoin(value) { this._setState("lineJoin", value); }
+    get miterLimit() { return this._state.miterLimit; }
+    set miterLimit(value) { this._setState("miterLimit", value); }
+    get lineDashOffset() { return this._state.lineDashOffset; }
+    set lineDashOffset(value) { this._setState("lineDashOffset", value); }
+    get globalAlpha() { return this._state.globalAlpha; }
+    set globalAlpha(value) { this._setState("globalAlpha", value); }
+    get globalCompositeOperation() { return this._state.globalCompositeOperation; }
+    set globalCompositeOperation(value) { this._setState("globalCompositeOperation", value); }
+    get font() { return this._state.font; }
+    set font(value) { this._setState("font", value); }
+    get textAlign() { return this._state.textAlign; }
+    set textAlign(value) { this._setState("textAlign", value); }
+    get textBaseline() { return this._state.textBaseline; }
+    set textBaseline(value) { this._setState("textBaseline", value); }
+
+    /**
+     *
+     * @param segments {number[]}
+     */
+    setLineDash(segments) {
+        this._state.lineDash = segments.slice();
+        this._push(["lineDash", ...segments]);
+    }
+
+    getLineDash() {
+        return this._state.lineDash.slice();
+    }
+
+    save() {
+        this._savedStates.push(Object.assign({}, this._state));
+        this._push(["save"]);
+    }
+
+    restore() {
+        if (this._savedStates.length) {
+            this._state = this._savedStates.pop();
         }
+        this._push(["restore"]);
     }
 
+    translate(x, y) { this._push(["translate", x, y]); }
+    scale(x, y) { this._push(["scale", x, y]); }
     /**
      *
-     * @param newNode {View}
-     * @param referenceNode {View}
+     * @param angle {number} in radians
      */
-    addChildBefore(newNode, referenceNode) {
-        const index = this.#children.indexOf(referenceNode);
-        this.addChild(newNode, index);
+    rotate(angle) { this._push(["rotate", angle]); }
+    transform(a, b, c, d, e, f) { this._push(["transform", a, b, c, d, e, f]); }
+    setTransform(a, b, c, d, e, f) { this._push(["setTransform", a, b, c, d, e, f]); }
+    resetTransform() { this._push(["resetTransform"]); }
+
+    beginPath() { this._push(["beginPath"]); }
+    closePath() { this._push(["closePath"]); }
+    moveTo(x, y) { this._push(["moveTo", x, y]); }
+    lineTo(x, y) { this._push(["lineTo", x, y]); }
+    bezierCurveTo(cp1x, cp1y, cp2x, cp2y, x, y) { this._push(["bezierCurveTo", cp1x, cp1y, cp2x, cp2y, x, y]); }
+    quadraticCurveTo(cpx, cpy, x, y) { this._push(["quadraticCurveTo", cpx, cpy, x, y]); }
+    arc(x, y, radius, startAngle, endAngle, counterclockwise = false) {
+        this._push(["arc", x, y, radius, startAngle, endAngle, counterclockwise]);
+    }
+    arcTo(x1, y1, x2, y2, radius) { this._push(["arcTo", x1, y1, x2, y2, radius]); }
+    ellipse(x, y, radiusX, radiusY, rotation, startAngle, endAngle, counterclockwise = false) {
+        this._push(["ellipse", x, y, radiusX, radiusY, rotation, startAngle, endAngle, counterclockwise]);
+    }
+    rect(x, y, width, height) { this._push(["rect", x, y, width, height]); }
+
+    /**
+     *
+     * @param fillRule {"nonzero" | "evenodd"}
+     */
+    fill(fillRule = "nonzero") { this._push(["fill", fillRule]); }
+    stroke() { this._push(["stroke"]); }
+    /**
+     *
+     * @param fillRule {"nonzero" | "evenodd"}
+     */
+    clip(fillRule = "nonzero") { this._push(["clip", fillRule]); }
+
+    fillRect(x, y, width, height) { this._push(["fillRect", x, y, width, height]); }
+    strokeRect(x, y, width, height) { this._push(["strokeRect", x, y, width, height]); }
+    clearRect(x, y, width, height) { this._push(["clearRect", x, y, width, height]); }
+
+    fillText(text, x, y) { this._push(["fillText", String(text), x, y]); }
+    strokeText(text, x, y) { this._push(["strokeText", String(text), x, y]); }
+
+    /**
+     *
+     * @param text {string}
+     * @returns {{width: number}}
+     */
+    measureText(text) {
+        this.flush();
+        return {width: canvas_measure_text(this.canvas.el, String(text))};
+    }
+
+    /**
+     * Draw image loaded from path or data url, arguments are (src, dx, dy), (src, dx, dy, dw, dh)
+     * or (src, sx, sy, sw, sh, dx, dy, dw, dh)
+     * @param src {string}
+     * @param args {number}
+     */
+    drawImage(src, ...args) {
+        this._push(["drawImage", src, ...args]);
+    }
+
+    createLinearGradient(x0, y0, x1, y1) {
+        return new CanvasGradient(["linear", x0, y0, x1, y1]);
+    }
+
+    createRadialGradient(x0, y0, r0, x1, y1, r1) {
+        return new CanvasGradient(["radial", x0, y0, r0, x1, y1, r1]);
     }
 
     /**
      *
-     * @param newNode {View}
-     * @param referenceNode {View}
+     * @returns {{width: number, height: number, data: number[]}} RGBA pixels
      */
-    addChildAfter(newNode, referenceNode) {
-        const index = this.#children.indexOf(referenceNode);
-        if (index >= 0) {
-            this.addChild(newNode, index + 1);
-        } else {
-            this.addChild(newNode);
-        }
+    getImageData(x, y, width, height) {
+        this.flush();
+        return canvas_get_image_data(this.canvas.el, {x, y, width, height});
     }
 
     /**
      *
-     * @param child {View}
+     * @param imageData {{width: number, height: number, data: number[]}}
      */
-    removeChild(child) {
-        const index = this.#children.indexOf(child);
-        if (index >= 0) {
-            child.parent = null;
-            view_remove_child(this.el, index);
-            this.#children.splice(index, 1);
-        } else {
-            console.log("remove child failed")
-        }
+    putImageData(imageData, dx, dy) {
+        this._push(["putImageData", imageData.width, imageData.height, Array.from(imageData.data), dx, dy]);
     }
 }
 
-export class ContainerElement extends ContainerBasedElement {
+export class CanvasElement extends View {
     constructor() {
-        super(VT_CONTAINER);
+        super(VT_CANVAS);
+        this._context = null;
+    }
+
+    /**
+     *
+     * @param value {number} width of drawing surface in pixels, default is 300
+     */
+    setWidth(value) {
+        this._context && this._context.flush();
+        view_set_property(this.el, "width", value);
+        this._context && this._context._reset();
+    }
+
+    /**
+     *
+     * @param value {number} height of drawing surface in pixels, default is 150
+     */
+    setHeight(value) {
+        this._context && this._context.flush();
+        view_set_property(this.el, "height", value);
+        this._context && this._context._reset();
+    }
+
+    getWidth() {
+        return view_get_property(this.el, "width");
+    }
+
+    getHeight() {
+        return view_get_property(this.el, "height");
+    }
+
+    /**
+     *
+     * @param type {"2d"}
+     * @returns {CanvasRenderingContext2D | null}
+     */
+    getContext(type) {
+        if (type !== "2d") {
+            return null;
+        }
+        if (!this._context) {
+            this._context = new CanvasRenderingContext2D(this);
+        }
+        return this._context;
     }
 }
 
-export class ScrollElement extends ContainerBasedElement {
-    constructor() {
-        super(VT_SCROLL);
+/**
+ * Element of backend registered by rust app, see `JsEngine::register_element`
+ */
+export class CustomElement extends View {
+    /**
+     *
+     * @param name {string} registered name of backend
+     */
+    constructor(name) {
+        super(name);
     }
 
     /**
      *
-     * @param value {"auto"|"always"|"never"}
+     * @param name {string}
+     * @param value {any}
      */
-    setScrollX(value) {
-        view_set_property(this.el, "scroll_x", value);
+    setProperty(name, value) {
+        view_set_property(this.el, name, value);
     }
 
     /**
      *
-     * @param value {"auto"|"always"|"never"}
+     * @param name {string}
+     * @returns {any}
      */
-    setScrollY(value) {
-        view_set_property(this.el, "scroll_y", value);
+    getProperty(name) {
+        return view_get_property(this.el, name);
     }
+}
 
-    scrollBy(value) {
-        value.x = value.x || 0;
-        value.y = value.y || 0;
-        view_set_property(this.el, "scroll_by", value);
+const MENU_WIDTH = 220;
+const MENU_PADDING = 4;
+const MENU_ITEM_HEIGHT = 28;
+const MENU_SEPARATOR_HEIGHT = 9;
+const MENU_COLOR = "#DFE1E5";
+const MENU_DISABLED_COLOR = "#6F737A";
+const MENU_BACKGROUND = "#2B2D30";
+const MENU_ACTIVE_BACKGROUND = "#2E436E";
+const MENU_BORDER_COLOR = "#43454A";
+
+/**
+ * @typedef {Object} MenuItem
+ * @property {string} [label]
+ * @property {boolean} [separator]
+ * @property {boolean} [disabled]
+ * @property {boolean} [checkable]
+ * @property {boolean} [checked]
+ * @property {string} [accelerator] shown at the right side, e.g. "Ctrl+C"
+ * @property {MenuItem[]} [submenu]
+ * @property {function(MenuItem): void} [onClick]
+ */
+
+export class Menu {
+    /**
+     * @type {MenuItem[]}
+     */
+    items;
+    /**
+     * @type {Frame | null}
+     */
+    frame = null;
+    /**
+     * @type {Menu | null}
+     */
+    parentMenu = null;
+    #submenu = null;
+    #rows = [];
+    #rowStyles = [];
+    #activeIndex = -1;
+    #x = 0;
+    #y = 0;
+    #onClose = null;
+
+    /**
+     *
+     * @param items {MenuItem[]}
+     */
+    constructor(items) {
+        this.items = items || [];
+    }
+
+    /**
+     * Height of the menu frame, including paddings and borders
+     * @param items {MenuItem[]}
+     * @returns {number}
+     */
+    static measureHeight(items) {
+        return Menu.itemTop(items, items.length) + MENU_PADDING + 1;
+    }
+
+    /**
+     * Offset of the item from the top of the menu frame
+     * @param items {MenuItem[]}
+     * @param index {number}
+     * @returns {number}
+     */
+    static itemTop(items, index) {
+        let top = MENU_PADDING + 1;
+        for (let i = 0; i < index; i++) {
+            top += items[i].separator ? MENU_SEPARATOR_HEIGHT : MENU_ITEM_HEIGHT;
+        }
+        return top;
+    }
+
+    /**
+     * Next item which could be activated, wrapping around, or -1 if there is none
+     * @param items {MenuItem[]}
+     * @param index {number} current index, -1 if no item is active
+     * @param delta {number} 1 or -1
+     * @returns {number}
+     */
+    static nextSelectableIndex(items, index, delta) {
+        const count = items.length;
+        for (let i = 0; i < count; i++) {
+            index = (index + delta + count) % count;
+            const item = items[index];
+            if (!item.separator && !item.disabled) {
+                return index;
+            }
+        }
+        return -1;
+    }
+
+    bindClose(callback) {
+        this.#onClose = callback;
+    }
+
+    /**
+     *
+     * @param owner {Frame}
+     * @param x {number} screen x
+     * @param y {number} screen y
+     */
+    show(owner, x, y) {
+        this.close();
+        this.#x = x;
+        this.#y = y;
+        const frame = new Frame({
+            width: MENU_WIDTH,
+            height: Menu.measureHeight(this.items),
+            position: [x, y],
+            resizable: false,
+            decorations: false,
+            override_redirect: true,
+        });
+        frame.setPopup(owner);
+        frame.bindClose(() => {
+            this.frame = null;
+            this.#submenu = null;
+            this.#activeIndex = -1;
+            this.#onClose && this.#onClose();
+        });
+        const body = new ContainerElement();
+        body.setStyle({
+            color: MENU_COLOR,
+            background: MENU_BACKGROUND,
+            border: "1 " + MENU_BORDER_COLOR,
+            padding: MENU_PADDING,
+        });
+        this.#rows = this.items.map((item, index) => this.#createRow(item, index));
+        this.#rows.forEach(row => body.addChild(row));
+        body.bindKeyDown(e => this.#handleKeyDown(e.detail));
+        frame.setBody(body);
+        this.frame = frame;
+    }
+
+    close() {
+        if (this.frame) {
+            this.frame.close();
+        }
+    }
+
+    /**
+     * Close the menu and all its ancestors
+     */
+    closeAll() {
+        let menu = this;
+        while (menu.parentMenu) {
+            menu = menu.parentMenu;
+        }
+        menu.close();
+    }
+
+    #createRow(item, index) {
+        const row = new ContainerElement();
+        if (item.separator) {
+            const style = {height: MENU_SEPARATOR_HEIGHT, justifyContent: "center"};
+            row.setStyle(style);
+            this.#rowStyles[index] = style;
+            const line = new ContainerElement();
+            line.setStyle({height: 1, background: MENU_BORDER_COLOR});
+            row.addChild(line);
+            return row;
+        }
+        const style = {
+            flexDirection: "row",
+            alignItems: "center",
+            height: MENU_ITEM_HEIGHT,
+            paddingLeft: 4,
+            paddingRight: 8,
+            color: item.disabled ? MENU_DISABLED_COLOR : MENU_COLOR,
+        };
+        row.setStyle(style);
+        this.#rowStyles[index] = style;
+
+        const check = new LabelElement();
+        check.setStyle({width: 20});
+        check.setText(item.checkable && item.checked ? "✓" : "");
+        const label = new LabelElement();
+        label.setStyle({flex: 1});
+        label.setText(item.label || "");
+        const accelerator = new LabelElement();
+        accelerator.setText(item.submenu ? "▸" : (item.accelerator || ""));
+        row.addChild(check);
+        row.addChild(label);
+        row.addChild(accelerator);
+
+        row.bindMouseEnter(() => {
+            this.#setActive(index);
+            if (item.submenu && !item.disabled) {
+                this.#openSubmenu(index);
+            } else if (this.#submenu) {
+                this.#submenu.close();
+            }
+        });
+        row.bindClick(() => this.#activate(index));
+        return row;
+    }
+
+    #isSelectable(index) {
+        const item = this.items[index];
+        return item && !item.separator && !item.disabled;
+    }
+
+    #setActive(index) {
+        const oldIndex = this.#activeIndex;
+        if (oldIndex === index) {
+            return;
+        }
+        if (oldIndex >= 0) {
+            this.#rows[oldIndex].setStyle(this.#rowStyles[oldIndex]);
+        }
+        this.#activeIndex = index;
+        if (this.#isSelectable(index)) {
+            this.#rows[index].setStyle({...this.#rowStyles[index], background: MENU_ACTIVE_BACKGROUND});
+        }
+    }
+
+    #moveActive(delta) {
+        const index = Menu.nextSelectableIndex(this.items, this.#activeIndex, delta);
+        if (index >= 0) {
+            this.#setActive(index);
+        }
+    }
+
+    #openSubmenu(index) {
+        const item = this.items[index];
+        if (this.#submenu && this.#submenu.frame && this.#submenu.items === item.submenu) {
+            return;
+        }
+        const top = this.#y + Menu.itemTop(this.items, index);
+        const submenu = new Menu(item.submenu);
+        submenu.parentMenu = this;
+        submenu.show(this.frame, this.#x + MENU_WIDTH - MENU_PADDING, top - MENU_PADDING - 1);
+        this.#submenu = submenu;
+    }
+
+    #activate(index) {
+        if (!this.#isSelectable(index)) {
+            return;
+        }
+        const item = this.items[index];
+        if (item.submenu) {
+            this.#openSubmenu(index);
+            return;
+        }
+        if (item.checkable) {
+            item.checked = !item.checked;
+        }
+        this.closeAll();
+        item.onClick && item.onClick(item);
+    }
+
+    #handleKeyDown(detail) {
+        switch (detail.key) {
+            case "ArrowDown":
+                this.#moveActive(1);
+                break;
+            case "ArrowUp":
+                this.#moveActive(-1);
+                break;
+            case "ArrowRight":
+                if (this.#isSelectable(this.#activeIndex) && this.items[this.#activeIndex].submenu) {
+                    this.#openSubmenu(this.#activeIndex);
+                    this.#submenu.#moveActive(1);
+                }
+                break;
+            case "ArrowLeft":
+                if (this.parentMenu) {
+                    this.close();
+                }
+                break;
+            case "Enter":
+            case "Space":
+                this.#activate(this.#activeIndex);
+                break;
+            case "Escape":
+                this.close();
+                break;
+        }
     }
 
 }
@@ -829,18 +2565,88 @@ const localStorage = {
     }
 }
 
+const clipboard = {
+    readText() {
+        return clipboard_read_text();
+    },
+    writeText(text) {
+        return clipboard_write_text(text);
+    },
+    readHtml() {
+        return clipboard_read_html();
+    },
+    /**
+     *
+     * @param html {string}
+     * @param altText {string | undefined} plain text for applications not supporting html
+     */
+    writeHtml(html, altText) {
+        return clipboard_write_html(html, altText);
+    },
+    /**
+     * Read image as png data url
+     */
+    readImage() {
+        return clipboard_read_image();
+    },
+    /**
+     *
+     * @param src {string} image path or data url
+     */
+    writeImage(src) {
+        return clipboard_write_image(src);
+    },
+}
+
+const spellCheck = {
+    /**
+     * Load Hunspell dictionary, the .aff file with the same name is loaded as well.
+     * No dictionary is bundled, applications should ship their own .dic/.aff files
+     * @param path {string} path of .dic file, e.g. "dict/en_US.dic"
+     * @returns {Promise<void>}
+     */
+    loadDictionary(path) {
+        return spellcheck_load_dictionary(path);
+    },
+    unloadDictionary() {
+        return spellcheck_unload_dictionary();
+    },
+    /**
+     *
+     * @param word {string}
+     * @returns {Promise<string[]>}
+     */
+    suggest(word) {
+        return spellcheck_suggest(word);
+    },
+}
+
 globalThis.Frame = Frame;
 if (globalThis.tray_create) {
     globalThis.SystemTray = SystemTray;
 }
 globalThis.View = View;
+globalThis.ViewBatch = ViewBatch;
 globalThis.ContainerElement = ContainerElement;
 globalThis.ScrollElement = ScrollElement;
+globalThis.VirtualListElement = VirtualListElement;
 globalThis.LabelElement = LabelElement;
 globalThis.EntryElement = EntryElement;
 globalThis.TextEditElement = TextEditElement;
 globalThis.ButtonElement = ButtonElement;
 globalThis.ImageElement  = ImageElement;
+globalThis.ProgressBarElement = ProgressBarElement;
+globalThis.CheckboxElement = CheckboxElement;
+globalThis.SwitchElement = SwitchElement;
+globalThis.RadioElement = RadioElement;
+globalThis.RadioGroupElement = RadioGroupElement;
+globalThis.SliderElement = SliderElement;
+globalThis.SelectElement = SelectElement;
+globalThis.CanvasElement = CanvasElement;
+globalThis.CanvasGradient = CanvasGradient;
+globalThis.CanvasRenderingContext2D = CanvasRenderingContext2D;
+globalThis.CustomElement = CustomElement;
+globalThis.Menu = Menu;
 globalThis.Audio = Audio;
 globalThis.WebSocket = WebSocket;
 globalThis.KEY_MOD_CTRL = 0x1;
@@ -848,4 +2654,6 @@ globalThis.KEY_MOD_ALT = 0x1 << 1;
 globalThis.KEY_MOD_META = 0x1 << 2;
 globalThis.KEY_MOD_SHIFT = 0x1 << 3;
 
-globalThis.localStorage = localStorage;
\ No newline at end of file
+globalThis.localStorage = localStorage;
+globalThis.clipboard = clipboard;
+globalThis.spellCheck = spellCheck;
\ No newline at end of file
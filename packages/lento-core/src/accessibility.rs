@@ -0,0 +1,362 @@
+use std::collections::HashMap;
+
+use accesskit::{Action, ActionHandler, ActionRequest, ActivationHandler, Affine, DeactivationHandler, Node, NodeBuilder, NodeId, Rect, Role, Tree, TreeUpdate};
+
+use crate::app::AppEvent;
+use crate::base;
+use crate::element::ElementRef;
+use crate::event_loop::send_event;
+
+/// Node id of the window node which is the parent of the body element
+pub const ROOT_NODE_ID: NodeId = NodeId(0);
+
+pub fn element_node_id(element: &ElementRef) -> NodeId {
+    NodeId(element.get_id() as u64)
+}
+
+pub fn parse_role(role: &str) -> Option<Role> {
+    let role = match role {
+        "button" => Role::Button,
+        "checkbox" => Role::CheckBox,
+        "radio" => Role::RadioButton,
+        "radiogroup" => Role::RadioGroup,
+        "switch" => Role::Switch,
+        "slider" => Role::Slider,
+        "progressbar" => Role::ProgressIndicator,
+        "textbox" => Role::TextInput,
+        "combobox" => Role::ComboBox,
+        "listbox" => Role::ListBox,
+        "option" => Role::ListBoxOption,
+        "list" => Role::List,
+        "listitem" => Role::ListItem,
+        "menu" => Role::Menu,
+        "menubar" => Role::MenuBar,
+        "menuitem" => Role::MenuItem,
+        "menuitemcheckbox" => Role::MenuItemCheckBox,
+        "menuitemradio" => Role::MenuItemRadio,
+        "tab" => Role::Tab,
+        "tablist" => Role::TabList,
+        "tabpanel" => Role::TabPanel,
+        "dialog" => Role::Dialog,
+        "alert" => Role::Alert,
+        "img" | "image" => Role::Image,
+        "link" => Role::Link,
+        "heading" => Role::Heading,
+        "label" => Role::Label,
+        "group" => Role::Group,
+        "toolbar" => Role::Toolbar,
+        "none" | "presentation" | "generic" => Role::GenericContainer,
+        _ => return None,
+    };
+    Some(role)
+}
+
+/// Keeps the last synchronised nodes so that only changed nodes are pushed to AccessKit
+pub struct AccessibilityTree {
+    nodes: HashMap<NodeId, Node>,
+    initialized: bool,
+}
+
+impl AccessibilityTree {
+    pub fn new() -> Self {
+        Self {
+            nodes: HashMap::new(),
+            initialized: false,
+        }
+    }
+
+    /// Reset state so that the next update contains the whole tree
+    pub fn reset(&mut self) {
+        self.nodes.clear();
+        self.initialized = false;
+    }
+
+    /// Build the update of the tree, only `dirty` elements and their parents are rebuilt after the first update.
+    /// Bounds of descendants are checked too, they move with scrolling, or anywhere if `layout_changed`
+    pub fn build_update(&mut self, body: Option<&ElementRef>, focusing: Option<&ElementRef>, scale_factor: f64, dirty: &[ElementRef], layout_changed: bool) -> TreeUpdate {
+        let tree = if self.initialized {
+            None
+        } else {
+            self.initialized = true;
+            self.nodes.clear();
+            Some(Tree::new(ROOT_NODE_ID))
+        };
+        let mut changed_nodes = Vec::new();
+        let mut root = NodeBuilder::new(Role::Window);
+        root.set_transform(Affine::scale(scale_factor));
+        if let Some(body) = body {
+            root.push_child(element_node_id(body));
+        }
+        let mut built = vec![(None, ROOT_NODE_ID, root.build())];
+
+        if let Some(body) = body {
+            let mut targets = HashMap::new();
+            for e in dirty.iter().filter(|e| is_in_tree(e, body)) {
+                targets.insert(element_node_id(e), e.clone());
+                // children of the parent may change
+                if let Some(p) = e.get_parent() {
+                    targets.insert(element_node_id(&p), p);
+                }
+                if !layout_changed {
+                    self.collect_moved(e, &e.get_origin_bounds(), &mut targets);
+                }
+            }
+            if layout_changed {
+                self.collect_moved(body, &body.get_bounds(), &mut targets);
+            }
+            for (id, e) in targets {
+                built.push((Some(e.clone()), id, build_node(&e)));
+            }
+        }
+
+        // removed children are dropped before adding new ones, which may be moved from other parents
+        for (_, id, node) in &built {
+            let old_children = self.nodes.get(id).map(|n| n.children().to_vec()).unwrap_or_default();
+            for c in old_children {
+                if !node.children().contains(&c) {
+                    self.remove_subtree(c);
+                }
+            }
+        }
+        let mut elements = Vec::new();
+        for (element, id, node) in built {
+            // nodes out of the synchronised tree are added by their parents
+            if element.is_none() || self.nodes.contains_key(&id) {
+                self.update_node(id, node, &mut changed_nodes);
+            }
+            if let Some(e) = element {
+                elements.push(e);
+            }
+        }
+        let added = body.cloned().into_iter().chain(elements.iter().flat_map(|e| e.get_children()));
+        for e in added.collect::<Vec<_>>() {
+            if !self.nodes.contains_key(&element_node_id(&e)) {
+                self.add_subtree(&e, &mut changed_nodes);
+            }
+        }
+
+        TreeUpdate {
+            nodes: changed_nodes,
+            tree,
+            focus: focusing.map(element_node_id).unwrap_or(ROOT_NODE_ID),
+        }
+    }
+
+    fn update_node(&mut self, id: NodeId, node: Node, changed_nodes: &mut Vec<(NodeId, Node)>) {
+        if self.nodes.get(&id) != Some(&node) {
+            changed_nodes.push((id, node.clone()));
+            self.nodes.insert(id, node);
+        }
+    }
+
+    fn add_subtree(&mut self, element: &ElementRef, changed_nodes: &mut Vec<(NodeId, Node)>) {
+        self.update_node(element_node_id(element), build_node(element), changed_nodes);
+        for c in element.get_children() {
+            self.add_subtree(&c, changed_nodes);
+        }
+    }
+
+    fn remove_subtree(&mut self, id: NodeId) {
+        if let Some(node) = self.nodes.remove(&id) {
+            for c in node.children() {
+                self.remove_subtree(*c);
+            }
+        }
+    }
+
+    /// Collect descendants whose bounds differ from the synchronised ones, `bounds` is the frame bounds of `element`
+    fn collect_moved(&self, element: &ElementRef, bounds: &base::Rect, result: &mut HashMap<NodeId, ElementRef>) {
+        let id = element_node_id(element);
+        let Some(node) = self.nodes.get(&id) else {
+            return;
+        };
+        if node.bounds() != Some(to_node_rect(bounds)) {
+            result.insert(id, element.clone());
+        }
+        let x = bounds.x - element.get_scroll_left();
+        let y = bounds.y - element.get_scroll_top();
+        for c in element.get_children() {
+            let child_bounds = if c.is_fixed() { c.get_bounds() } else { c.get_bounds().translate(x, y) };
+            self.collect_moved(&c, &child_bounds, result);
+        }
+    }
+}
+
+fn is_in_tree(element: &ElementRef, body: &ElementRef) -> bool {
+    let mut node = Some(element.clone());
+    while let Some(n) = node {
+        if &n == body {
+            return true;
+        }
+        node = n.get_parent();
+    }
+    false
+}
+
+fn to_node_rect(bounds: &base::Rect) -> Rect {
+    Rect::new(
+        bounds.x as f64,
+        bounds.y as f64,
+        bounds.right() as f64,
+        bounds.bottom() as f64,
+    )
+}
+
+fn build_node(element: &ElementRef) -> Node {
+    let mut builder = NodeBuilder::new(element.get_backend().get_accessibility_role());
+    element.get_backend().build_accessibility_node(&mut builder);
+    if let Some(role) = element.get_role() {
+        builder.set_role(role);
+    }
+    if let Some(label) = element.get_aria_label() {
+        builder.set_name(label.to_string());
+    }
+    if let Some(description) = element.get_aria_description() {
+        builder.set_description(description.to_string());
+    }
+    let bounds = element.get_origin_bounds();
+    builder.set_bounds(to_node_rect(&bounds));
+    if bounds.is_empty() {
+        builder.set_hidden();
+    }
+    if element.get_tab_index().is_some() {
+        builder.add_action(Action::Focus);
+    }
+    builder.add_action(Action::ScrollIntoView);
+    for c in element.get_children() {
+        builder.push_child(element_node_id(&c));
+    }
+    builder.build()
+}
+
+pub fn find_element_by_node_id(root: &ElementRef, node_id: NodeId) -> Option<ElementRef> {
+    if element_node_id(root) == node_id {
+        return Some(root.clone());
+    }
+    for c in root.get_children() {
+        if let Some(e) = find_element_by_node_id(&c, node_id) {
+            return Some(e);
+        }
+    }
+    None
+}
+
+/// Forwards AccessKit requests, which may come from any thread, to the event loop
+pub struct FrameAccessibilityHandler {
+    frame_id: i32,
+}
+
+impl FrameAccessibilityHandler {
+    pub fn new(frame_id: i32) -> Self {
+        Self { frame_id }
+    }
+}
+
+impl ActivationHandler for FrameAccessibilityHandler {
+    fn request_initial_tree(&mut self) -> Option<TreeUpdate> {
+        let _ = send_event(AppEvent::AccessibilityActivated(self.frame_id));
+        None
+    }
+}
+
+impl ActionHandler for FrameAccessibilityHandler {
+    fn do_action(&mut self, request: ActionRequest) {
+        let _ = send_event(AppEvent::AccessibilityAction(self.frame_id, request));
+    }
+}
+
+impl DeactivationHandler for FrameAccessibilityHandler {
+    fn deactivate_accessibility(&mut self) {
+        let _ = send_event(AppEvent::AccessibilityDeactivated(self.frame_id));
+    }
+}
+
+#[test]
+fn test_build_tree_update() {
+    use crate::element::ElementBackend;
+    use crate::element::container::Container;
+    use crate::element::entry::Entry;
+    let mut body = ElementRef::new(Container::create);
+    let mut entry = ElementRef::new(Entry::create);
+    body.add_child_view(entry.clone(), None);
+
+    let mut tree = AccessibilityTree::new();
+    let update = tree.build_update(Some(&body), Some(&entry), 1.0, &[], false);
+    assert!(update.tree.is_some());
+    assert_eq!(3, update.nodes.len());
+    assert_eq!(element_node_id(&entry), update.focus);
+    let (_, entry_node) = update.nodes.iter().find(|(id, _)| *id == element_node_id(&entry)).unwrap();
+    assert_eq!(Role::TextInput, entry_node.role());
+
+    // nothing changed
+    let update = tree.build_update(Some(&body), Some(&entry), 1.0, &[], false);
+    assert!(update.tree.is_none());
+    assert!(update.nodes.is_empty());
+
+    // only dirty elements and their parents are rebuilt, unchanged ones are not sent
+    entry.set_aria_label("name".to_string());
+    let update = tree.build_update(Some(&body), Some(&entry), 1.0, &[entry.clone()], false);
+    assert_eq!(1, update.nodes.len());
+    assert_eq!(Some("name"), update.nodes[0].1.name());
+    entry.set_aria_description("description".to_string());
+    let update = tree.build_update(Some(&body), Some(&entry), 1.0, &[], false);
+    assert!(update.nodes.is_empty());
+}
+
+#[test]
+fn test_tree_structure() {
+    use crate::element::ElementBackend;
+    use crate::element::container::Container;
+    let mut body = ElementRef::new(Container::create);
+    let mut group = ElementRef::new(Container::create);
+    let label = ElementRef::new(Container::create);
+    group.add_child_view(label.clone(), None);
+    body.add_child_view(group.clone(), None);
+    group.set_role("toolbar".to_string());
+    group.set_tab_index(0);
+    body.calculate_layout(100.0, 100.0);
+
+    let mut tree = AccessibilityTree::new();
+    let update = tree.build_update(Some(&body), None, 2.0, &[], false);
+    assert_eq!(ROOT_NODE_ID, update.focus);
+    let node = |id: NodeId| update.nodes.iter().find(|(i, _)| *i == id).map(|(_, n)| n.clone()).unwrap();
+    assert_eq!(vec![element_node_id(&body)], node(ROOT_NODE_ID).children().to_vec());
+    assert_eq!(vec![element_node_id(&label)], node(element_node_id(&group)).children().to_vec());
+    assert_eq!(Role::Toolbar, node(element_node_id(&group)).role());
+    assert!(node(element_node_id(&group)).supports_action(Action::Focus));
+    assert!(node(element_node_id(&group)).supports_action(Action::ScrollIntoView));
+    // an empty container has no height
+    assert!(node(element_node_id(&label)).is_hidden());
+
+    assert!(find_element_by_node_id(&body, element_node_id(&label)) == Some(label.clone()));
+    assert!(find_element_by_node_id(&body, NodeId(u64::MAX)).is_none());
+    assert_eq!(Some(Role::RadioGroup), parse_role("radiogroup"));
+    assert_eq!(None, parse_role("unknown"));
+
+    // a removed child disappears from the children of its parent
+    group.remove_child_view(0);
+    let update = tree.build_update(Some(&body), None, 2.0, &[group.clone()], false);
+    let (_, group_node) = update.nodes.iter().find(|(id, _)| *id == element_node_id(&group)).unwrap();
+    assert!(group_node.children().is_empty());
+
+    // a moved child is sent with its subtree under the new parent
+    let mut item = ElementRef::new(Container::create);
+    item.add_child_view(label.clone(), None);
+    body.add_child_view(item.clone(), None);
+    let update = tree.build_update(Some(&body), None, 2.0, &[item.clone(), label.clone()], false);
+    let ids: Vec<NodeId> = update.nodes.iter().map(|(id, _)| *id).collect();
+    assert_eq!(3, ids.len());
+    assert!(ids.contains(&element_node_id(&body)));
+    assert!(ids.contains(&element_node_id(&item)));
+    assert!(ids.contains(&element_node_id(&label)));
+
+    // elements moved by layout are found without being dirty
+    group.set_style_props(vec![crate::style::StyleProp::parse("height", "30").unwrap()]);
+    body.calculate_layout(100.0, 100.0);
+    let update = tree.build_update(Some(&body), None, 2.0, &[], true);
+    let ids: Vec<NodeId> = update.nodes.iter().map(|(id, _)| *id).collect();
+    assert!(ids.contains(&element_node_id(&group)));
+    assert!(ids.contains(&element_node_id(&item)));
+    assert!(ids.contains(&element_node_id(&label)));
+    assert!(!ids.contains(&element_node_id(&body)));
+}
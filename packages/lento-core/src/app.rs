@@ -1,5 +1,6 @@
 use std::fmt::{Debug, Formatter};
 
+use accesskit::ActionRequest;
 use anyhow::Error;
 use jni::objects::JValue;
 use jni::sys::{jboolean, jlong};
@@ -16,7 +17,7 @@ use winit::window::WindowId;
 use crate::event_loop::{get_event_proxy, run_event_loop_task};
 use crate::ext::ext_frame::FRAMES;
 use crate::ext::ext_localstorage::localstorage_flush;
-use crate::frame::frame_input;
+use crate::frame::{frame_input, with_frame};
 use crate::js::js_engine::JsEngine;
 use crate::js::js_runtime::JsContext;
 use crate::mrc::Mrc;
@@ -29,6 +30,9 @@ pub enum AppEvent {
     ShowSoftInput(i32),
     HideSoftInput(i32),
     CommitInput(i32, String),
+    AccessibilityActivated(i32),
+    AccessibilityAction(i32, ActionRequest),
+    AccessibilityDeactivated(i32),
 }
 
 impl Debug for AppEvent {
@@ -103,6 +107,15 @@ impl ApplicationHandler<AppEvent> for App {
                 AppEvent::CommitInput(frame_id, content) => {
                     frame_input(frame_id, content);
                 },
+                AppEvent::AccessibilityActivated(frame_id) => {
+                    with_frame(frame_id, |f| f.handle_accessibility_activated());
+                },
+                AppEvent::AccessibilityAction(frame_id, request) => {
+                    with_frame(frame_id, |f| f.handle_accessibility_action(request));
+                },
+                AppEvent::AccessibilityDeactivated(frame_id) => {
+                    with_frame(frame_id, |f| f.handle_accessibility_deactivated());
+                },
             }
         });
     }
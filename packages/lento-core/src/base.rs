@@ -40,6 +40,7 @@ pub enum MouseEventType {
     MouseMove,
     MouseEnter,
     MouseLeave,
+    ContextMenu,
 }
 
 pub struct FocusShiftDetail {
@@ -159,6 +160,32 @@ pub struct TextChangeDetail {
     pub value: String,
 }
 
+#[derive(Serialize)]
+pub struct CheckedChangeDetail {
+    pub checked: bool,
+}
+
+#[derive(Serialize)]
+pub struct ValueChangeDetail {
+    pub value: f32,
+}
+
+#[derive(Serialize)]
+pub struct SelectionChangeDetail {
+    pub value: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BeforeInputDetail {
+    /// "insertText" or "deleteContent"
+    pub input_type: String,
+    pub data: String,
+    /// Atom offsets of the text to be replaced, captured when the event is emitted
+    pub range_start: usize,
+    pub range_end: usize,
+}
+
 #[derive(Serialize)]
 pub struct TextUpdateDetail {
     pub value: String,
@@ -227,6 +254,10 @@ impl<E> EventRegistration<E> {
         }
     }
 
+    pub fn has_event_listener(&self, event_type: &str) -> bool {
+        self.listeners.get(event_type).map(|l| !l.is_empty()).unwrap_or(false)
+    }
+
     pub fn emit_event(&mut self, event: &mut Event<E>) {
         if let Some(listeners) = self.listeners.get_mut(&event.event_type) {
             for it in listeners {
@@ -1,9 +1,11 @@
-use skia_safe::{Canvas, Font, Paint, TextBlob};
+use skia_safe::{Canvas, Font, Paint, Path, TextBlob};
 use crate::base::{Rect, TextAlign, VerticalAlign};
 
 pub trait CanvasHelper {
     fn draw_text(&self, rect: &Rect, text: &str, font: &Font, paint: &Paint, align: TextAlign, vertical_align: VerticalAlign);
     fn session<F: FnOnce(&Self)>(&self, callback: F);
+    /// Wavy line centered at y, e.g. underline of misspelled words
+    fn draw_squiggly_line(&self, left: f32, right: f32, y: f32, paint: &Paint);
 }
 
 impl CanvasHelper for Canvas {
@@ -40,4 +42,19 @@ impl CanvasHelper for Canvas {
         callback(&self);
         self.restore();
     }
+
+    fn draw_squiggly_line(&self, left: f32, right: f32, y: f32, paint: &Paint) {
+        const HALF_WAVE_LENGTH: f32 = 2.0;
+        const AMPLITUDE: f32 = 1.0;
+        let mut path = Path::new();
+        path.move_to((left, y + AMPLITUDE));
+        let mut x = left;
+        let mut is_top = true;
+        while x < right {
+            x = f32::min(x + HALF_WAVE_LENGTH, right);
+            path.line_to((x, if is_top { y - AMPLITUDE } else { y + AMPLITUDE }));
+            is_top = !is_top;
+        }
+        self.draw_path(&path, paint);
+    }
 }
\ No newline at end of file
@@ -28,3 +28,47 @@ fn hex2u8(hex: [char; 2]) -> Option<u8> {
     let lo = hex[1].to_digit(16)?;
     Some((hi * 16 + lo) as u8)
 }
+
+/// Parse color in css syntax, e.g. `#RRGGBB`, `rgb(r, g, b)`, `rgba(r, g, b, a)` and a few names
+pub fn parse_css_color(value: &str) -> Option<Color> {
+    let value = value.trim();
+    if let Some(hex) = value.strip_prefix('#') {
+        return parse_hex_color(hex);
+    }
+    if let Some(args) = value.strip_prefix("rgba(").or_else(|| value.strip_prefix("rgb(")) {
+        let args = args.strip_suffix(')')?;
+        let parts: Vec<&str> = args.split(',').map(|p| p.trim()).collect();
+        if parts.len() != 3 && parts.len() != 4 {
+            return None;
+        }
+        let mut rgb = [0u8; 3];
+        for i in 0..3 {
+            rgb[i] = parts[i].parse::<f32>().ok()?.round().clamp(0.0, 255.0) as u8;
+        }
+        let alpha = match parts.get(3) {
+            Some(a) => a.parse::<f32>().ok()?.clamp(0.0, 1.0),
+            None => 1.0,
+        };
+        return Some(Color::from_argb((alpha * 255.0).round() as u8, rgb[0], rgb[1], rgb[2]));
+    }
+    match value.to_lowercase().as_str() {
+        "transparent" => Some(Color::TRANSPARENT),
+        "black" => Some(Color::BLACK),
+        "white" => Some(Color::WHITE),
+        "red" => Some(Color::from_rgb(255, 0, 0)),
+        "green" => Some(Color::from_rgb(0, 128, 0)),
+        "blue" => Some(Color::from_rgb(0, 0, 255)),
+        "yellow" => Some(Color::from_rgb(255, 255, 0)),
+        "gray" | "grey" => Some(Color::from_rgb(128, 128, 128)),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_parse_css_color() {
+    assert_eq!(Some(Color::from_rgb(255, 0, 0)), parse_css_color("#f00"));
+    assert_eq!(Some(Color::from_argb(128, 1, 2, 3)), parse_css_color("rgba(1, 2, 3, 0.5)"));
+    assert_eq!(Some(Color::from_rgb(10, 20, 30)), parse_css_color(" rgb(10,20,30) "));
+    assert_eq!(Some(Color::TRANSPARENT), parse_css_color("transparent"));
+    assert_eq!(None, parse_css_color("rgb(1, 2)"));
+}
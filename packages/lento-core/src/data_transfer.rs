@@ -0,0 +1,309 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Error};
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use skia_safe::Image;
+
+use crate::img_manager::IMG_MANAGER;
+use crate::mrc::Mrc;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum DropEffect {
+    None,
+    Copy,
+    Move,
+    Link,
+}
+
+impl DropEffect {
+    pub fn parse(str: &str) -> Option<Self> {
+        let effect = match str {
+            "none" => DropEffect::None,
+            "copy" => DropEffect::Copy,
+            "move" => DropEffect::Move,
+            "link" => DropEffect::Link,
+            _ => return None,
+        };
+        Some(effect)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DropEffect::None => "none",
+            DropEffect::Copy => "copy",
+            DropEffect::Move => "move",
+            DropEffect::Link => "link",
+        }
+    }
+}
+
+/// Check whether effect is allowed by effectAllowed, which has the same values as web
+fn is_effect_allowed(effect_allowed: &str, effect: DropEffect) -> bool {
+    let allowed: &[DropEffect] = match effect_allowed {
+        "none" => &[],
+        "copy" => &[DropEffect::Copy],
+        "move" => &[DropEffect::Move],
+        "link" => &[DropEffect::Link],
+        "copyMove" => &[DropEffect::Copy, DropEffect::Move],
+        "copyLink" => &[DropEffect::Copy, DropEffect::Link],
+        "linkMove" => &[DropEffect::Link, DropEffect::Move],
+        _ => &[DropEffect::Copy, DropEffect::Move, DropEffect::Link],
+    };
+    effect == DropEffect::None || allowed.contains(&effect)
+}
+
+pub struct DragImage {
+    pub image: Image,
+    pub offset_x: f32,
+    pub offset_y: f32,
+}
+
+pub struct DataTransferState {
+    id: u32,
+    /// (mime type, data), in insertion order
+    items: Vec<(String, String)>,
+    files: Vec<String>,
+    effect_allowed: String,
+    drop_effect: DropEffect,
+    drag_image: Option<DragImage>,
+    /// data could only be modified in dragstart
+    writable: bool,
+    /// drag from outside of the app, e.g. files from file manager
+    external: bool,
+    dropped: bool,
+}
+
+/// Payload of a drag session, shared by all drag events of the session
+#[derive(Clone)]
+pub struct DataTransfer {
+    inner: Mrc<DataTransferState>,
+}
+
+thread_local! {
+    static NEXT_DATA_TRANSFER_ID: Cell<u32> = Cell::new(1);
+    static DATA_TRANSFERS: RefCell<HashMap<u32, DataTransfer>> = RefCell::new(HashMap::new());
+}
+
+impl DataTransfer {
+    pub fn new(external: bool) -> Self {
+        let id = NEXT_DATA_TRANSFER_ID.get();
+        NEXT_DATA_TRANSFER_ID.set(id + 1);
+        let dt = Self {
+            inner: Mrc::new(DataTransferState {
+                id,
+                items: Vec::new(),
+                files: Vec::new(),
+                effect_allowed: if external { "copy" } else { "all" }.to_string(),
+                drop_effect: DropEffect::None,
+                drag_image: None,
+                writable: !external,
+                external,
+                dropped: false,
+            })
+        };
+        DATA_TRANSFERS.with_borrow_mut(|m| m.insert(id, dt.clone()));
+        dt
+    }
+
+    pub fn get_id(&self) -> u32 {
+        self.inner.id
+    }
+
+    /// Stop accepting modifications from js
+    pub fn release(&self) {
+        DATA_TRANSFERS.with_borrow_mut(|m| m.remove(&self.inner.id));
+    }
+
+    pub fn is_external(&self) -> bool {
+        self.inner.external
+    }
+
+    pub fn set_data(&mut self, mime_type: &str, data: String) -> Result<(), Error> {
+        if !self.inner.writable {
+            return Err(anyhow!("data transfer is read-only"));
+        }
+        let items = &mut self.inner.items;
+        if let Some((_, d)) = items.iter_mut().find(|(t, _)| t == mime_type) {
+            *d = data;
+        } else {
+            items.push((mime_type.to_string(), data));
+        }
+        Ok(())
+    }
+
+    pub fn get_data(&self, mime_type: &str) -> Option<&str> {
+        self.inner.items.iter().find(|(t, _)| t == mime_type).map(|(_, d)| d.as_str())
+    }
+
+    pub fn clear_data(&mut self, mime_type: Option<&str>) -> Result<(), Error> {
+        if !self.inner.writable {
+            return Err(anyhow!("data transfer is read-only"));
+        }
+        match mime_type {
+            Some(mime_type) => self.inner.items.retain(|(t, _)| t != mime_type),
+            None => self.inner.items.clear(),
+        }
+        Ok(())
+    }
+
+    pub fn add_file(&mut self, path: String) {
+        if !self.inner.files.contains(&path) {
+            self.inner.files.push(path);
+        }
+    }
+
+    pub fn get_files(&self) -> &Vec<String> {
+        &self.inner.files
+    }
+
+    pub fn set_writable(&mut self, writable: bool) {
+        self.inner.writable = writable;
+    }
+
+    pub fn set_effect_allowed(&mut self, effect_allowed: String) {
+        self.inner.effect_allowed = effect_allowed;
+    }
+
+    pub fn set_drop_effect(&mut self, effect: DropEffect) {
+        self.inner.drop_effect = effect;
+    }
+
+    /// The drop effect negotiated by the last dragover, none if not allowed by effectAllowed
+    pub fn get_drop_effect(&self) -> DropEffect {
+        let effect = self.inner.drop_effect;
+        if is_effect_allowed(&self.inner.effect_allowed, effect) {
+            effect
+        } else {
+            DropEffect::None
+        }
+    }
+
+    /// Reset drop effect before dispatching dragenter/dragover, handlers could change it
+    pub fn reset_drop_effect(&mut self) {
+        let effect_allowed = &self.inner.effect_allowed;
+        let effect = [DropEffect::Copy, DropEffect::Move, DropEffect::Link].into_iter()
+            .find(|e| is_effect_allowed(effect_allowed, *e))
+            .unwrap_or(DropEffect::None);
+        self.inner.drop_effect = effect;
+    }
+
+    pub fn set_drag_image(&mut self, drag_image: Option<DragImage>) {
+        self.inner.drag_image = drag_image;
+    }
+
+    pub fn get_drag_image(&self) -> Option<&DragImage> {
+        self.inner.drag_image.as_ref()
+    }
+
+    pub fn set_dropped(&mut self) {
+        self.inner.dropped = true;
+    }
+
+    pub fn is_dropped(&self) -> bool {
+        self.inner.dropped
+    }
+
+    /// Data could be read by the dragstart handlers which set it and after dropped, other events only see types
+    pub fn is_data_readable(&self) -> bool {
+        self.inner.writable || self.inner.dropped
+    }
+
+}
+
+impl Serialize for DataTransfer {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        let inner = &self.inner;
+        let types: Vec<&str> = inner.items.iter().map(|(t, _)| t.as_str()).collect();
+        let data: HashMap<&str, &str> = if self.is_data_readable() {
+            inner.items.iter().map(|(t, d)| (t.as_str(), d.as_str())).collect()
+        } else {
+            HashMap::new()
+        };
+        let mut s = serializer.serialize_struct("DataTransfer", 6)?;
+        s.serialize_field("id", &inner.id)?;
+        s.serialize_field("types", &types)?;
+        s.serialize_field("data", &data)?;
+        s.serialize_field("files", &inner.files)?;
+        s.serialize_field("effectAllowed", &inner.effect_allowed)?;
+        s.serialize_field("dropEffect", self.get_drop_effect().as_str())?;
+        s.end()
+    }
+}
+
+fn with_data_transfer<R, F: FnOnce(&mut DataTransfer) -> Result<R, Error>>(id: u32, callback: F) -> Result<R, Error> {
+    let mut dt = DATA_TRANSFERS.with_borrow(|m| m.get(&id).cloned())
+        .ok_or_else(|| anyhow!("drag session is over"))?;
+    callback(&mut dt)
+}
+
+// Js Api
+
+pub fn data_transfer_set_data(id: u32, mime_type: String, data: String) -> Result<(), Error> {
+    with_data_transfer(id, |dt| dt.set_data(&mime_type, data))
+}
+
+pub fn data_transfer_clear_data(id: u32, mime_type: Option<String>) -> Result<(), Error> {
+    with_data_transfer(id, |dt| dt.clear_data(mime_type.as_deref()))
+}
+
+pub fn data_transfer_set_effect_allowed(id: u32, effect_allowed: String) -> Result<(), Error> {
+    with_data_transfer(id, |dt| {
+        dt.set_effect_allowed(effect_allowed);
+        Ok(())
+    })
+}
+
+pub fn data_transfer_set_drop_effect(id: u32, effect: String) -> Result<(), Error> {
+    let effect = DropEffect::parse(&effect).ok_or_else(|| anyhow!("invalid drop effect:{}", effect))?;
+    with_data_transfer(id, |dt| {
+        dt.set_drop_effect(effect);
+        Ok(())
+    })
+}
+
+pub fn data_transfer_set_drag_image(id: u32, src: String, offset_x: f32, offset_y: f32) -> Result<(), Error> {
+    let image = IMG_MANAGER.with(|im| im.load_img(&src))?;
+    with_data_transfer(id, |dt| {
+        dt.set_drag_image(Some(DragImage { image, offset_x, offset_y }));
+        Ok(())
+    })
+}
+
+#[test]
+fn test_drop_effect() {
+    let mut dt = DataTransfer::new(false);
+    dt.set_data("text/plain", "hello".to_string()).unwrap();
+    dt.set_data("text/plain", "world".to_string()).unwrap();
+    assert_eq!(Some("world"), dt.get_data("text/plain"));
+
+    dt.set_effect_allowed("linkMove".to_string());
+    dt.reset_drop_effect();
+    assert_eq!(DropEffect::Move, dt.get_drop_effect());
+    dt.set_drop_effect(DropEffect::Copy);
+    assert_eq!(DropEffect::None, dt.get_drop_effect());
+
+    dt.set_writable(false);
+    assert!(dt.set_data("text/plain", "".to_string()).is_err());
+    dt.release();
+    assert!(data_transfer_set_data(dt.get_id(), "text/plain".to_string(), "".to_string()).is_err());
+}
+
+#[test]
+fn test_data_readable_in_drop() {
+    let mut dt = DataTransfer::new(false);
+    dt.set_data("text/plain", "hello".to_string()).unwrap();
+    let data_of = |dt: &DataTransfer| serde_json::to_value(dt).unwrap()["data"]["text/plain"].clone();
+    // dragstart
+    assert_eq!("hello", data_of(&dt));
+
+    // dragenter/dragover only see types
+    dt.set_writable(false);
+    assert_eq!(serde_json::Value::Null, data_of(&dt));
+    assert_eq!(serde_json::json!(["text/plain"]), serde_json::to_value(&dt).unwrap()["types"]);
+
+    dt.set_dropped();
+    assert_eq!("hello", data_of(&dt));
+    dt.release();
+}
@@ -1,9 +1,11 @@
-use std::cell::{Cell};
+use std::cell::{Cell, RefCell};
 use std::collections::{HashMap, HashSet};
 use std::default::Default;
 use std::ops::{Deref, DerefMut};
 use std::str::FromStr;
+use std::time::Instant;
 
+use accesskit::{ActionRequest, NodeBuilder, Role};
 use anyhow::{anyhow, Error};
 use ordered_float::Float;
 use quick_js::{JsValue};
@@ -14,26 +16,38 @@ use winit::window::CursorIcon;
 use yoga::{Direction, Edge, StyleUnit};
 
 use crate::{base, define_resource, js_call, js_call_rust, js_get_prop};
+use crate::accessibility::parse_role;
 use crate::base::{ElementEvent, ElementEventContext, ElementEventHandler, EventRegistration, ScrollEventDetail};
 use crate::border::build_rect_with_radius;
 use crate::element::button::Button;
+use crate::element::canvas::CanvasElement;
+use crate::element::checkbox::Checkbox;
 use crate::element::container::Container;
 use crate::element::entry::Entry;
 use crate::element::image::Image;
+use crate::element::progress_bar::ProgressBar;
+use crate::element::radio::{Radio, RadioGroup};
 use crate::element::scroll::Scroll;
+use crate::element::scroll_animation::{align_delta, ScrollAnimation, ScrollBehavior, FRAME_INTERVAL};
+use crate::element::select::Select;
+use crate::element::slider::Slider;
+use crate::element::switch::Switch;
 use crate::element::text::Text;
+use crate::element::text::search::SearchQuery;
 use crate::element::textedit::TextEdit;
+use crate::element::virtual_list::VirtualList;
 use crate::event::{ClickEventBind};
 use crate::animation::AnimationResource;
 use crate::event_loop::{schedule_macro_task_unsafe};
-use crate::ext::ext_frame::{VIEW_TYPE_BUTTON, VIEW_TYPE_CONTAINER, VIEW_TYPE_ENTRY, VIEW_TYPE_IMAGE, VIEW_TYPE_LABEL, VIEW_TYPE_SCROLL, VIEW_TYPE_TEXT_EDIT};
+use crate::ext::ext_frame::{VIEW_TYPE_BUTTON, VIEW_TYPE_CANVAS, VIEW_TYPE_CHECKBOX, VIEW_TYPE_CONTAINER, VIEW_TYPE_ENTRY, VIEW_TYPE_IMAGE, VIEW_TYPE_LABEL, VIEW_TYPE_PROGRESS_BAR, VIEW_TYPE_RADIO, VIEW_TYPE_RADIO_GROUP, VIEW_TYPE_SCROLL, VIEW_TYPE_SELECT, VIEW_TYPE_SLIDER, VIEW_TYPE_SWITCH, VIEW_TYPE_TEXT_EDIT, VIEW_TYPE_VIRTUAL_LIST};
 use crate::frame::{FrameRef, FrameWeak};
 use crate::img_manager::IMG_MANAGER;
 use crate::js::js_serde::JsValueSerializer;
 use crate::js::js_value_util::{FromJsValue, SerializeToJsValue, ToJsValue};
 use crate::mrc::{Mrc, MrcWeak};
 use crate::number::DeNan;
-use crate::style::{ColorHelper, parse_style_obj, StyleNode, StyleProp};
+use crate::timer::set_timeout;
+use crate::style::{ColorHelper, parse_style_obj, StyleNode, StylePosition, StyleProp};
 
 pub mod container;
 pub mod entry;
@@ -44,10 +58,26 @@ mod scroll_bar;
 pub mod image;
 pub mod label;
 mod edit_history;
+pub mod keymap;
 pub mod text;
+pub mod virtual_list;
+mod control;
+pub mod checkbox;
+pub mod radio;
+pub mod switch;
+pub mod slider;
+pub mod progress_bar;
+pub mod select;
+pub mod canvas;
+pub mod batch;
+pub mod scroll_animation;
+
+type ElementCreator = Box<dyn Fn() -> ElementRef>;
 
 thread_local! {
     pub static NEXT_ELEMENT_ID: Cell<u32> = Cell::new(1);
+    /// Backends registered by apps, created by name
+    static CUSTOM_ELEMENTS: RefCell<HashMap<String, ElementCreator>> = RefCell::new(HashMap::new());
 }
 
 #[derive(PartialEq, Clone)]
@@ -61,6 +91,31 @@ define_resource!(ElementRef);
 pub struct ScrollByOption {
     x: f32,
     y: f32,
+    /// "smooth" or "instant"
+    #[serde(default)]
+    behavior: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ScrollToOption {
+    #[serde(default)]
+    pub x: Option<f32>,
+    #[serde(default)]
+    pub y: Option<f32>,
+    #[serde(default)]
+    pub behavior: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ScrollIntoViewOption {
+    /// Vertical alignment, "start"(default), "center", "end" or "nearest"
+    #[serde(default)]
+    pub block: Option<String>,
+    /// Horizontal alignment, "start", "center", "end" or "nearest"(default)
+    #[serde(default)]
+    pub inline: Option<String>,
+    #[serde(default)]
+    pub behavior: Option<String>,
 }
 
 impl ElementRef {
@@ -116,7 +171,13 @@ impl ElementRef {
         js_call!("scrollLeft", f32, self, set_scroll_left, property_name, value, Ok(()));
         js_call!("draggable", bool, self, set_draggable, property_name, value, Ok(()));
         js_call!("cursor", CursorIcon, self, set_cursor, property_name, value, Ok(()));
+        js_call!("role", String, self, set_role, property_name, value, Ok(()));
+        js_call!("ariaLabel", String, self, set_aria_label, property_name, value, Ok(()));
+        js_call!("ariaDescription", String, self, set_aria_description, property_name, value, Ok(()));
+        js_call!("tabIndex", i32, self, set_tab_index, property_name, value, Ok(()));
         js_call_rust!("scroll_by", ScrollByOption, self, scroll_by, property_name, value, Ok(()));
+        js_call_rust!("scroll_to", ScrollToOption, self, scroll_to, property_name, value, Ok(()));
+        js_call_rust!("scroll_into_view", ScrollIntoViewOption, self, scroll_into_view, property_name, value, Ok(()));
         self.get_backend_mut().set_property(&property_name, value);
         Ok(())
     }
@@ -154,11 +215,120 @@ impl ElementRef {
 
     pub fn scroll_by(&mut self, option: ScrollByOption) {
         let mut el = self.backend.get_inner_element().unwrap_or(self.clone());
-        if option.x != 0.0 {
-            el.set_scroll_left(el.scroll_left + option.x);
+        let (left, top) = el.get_scroll_target();
+        let behavior = ScrollBehavior::parse(option.behavior.as_deref());
+        el.scroll_to_with_behavior(left + option.x, top + option.y, behavior);
+    }
+
+    pub fn scroll_to(&mut self, option: ScrollToOption) {
+        let mut el = self.backend.get_inner_element().unwrap_or(self.clone());
+        let (left, top) = el.get_scroll_target();
+        let behavior = ScrollBehavior::parse(option.behavior.as_deref());
+        el.scroll_to_with_behavior(option.x.unwrap_or(left), option.y.unwrap_or(top), behavior);
+    }
+
+    /// Scroll ancestors to make the element visible
+    pub fn scroll_into_view(&mut self, option: ScrollIntoViewOption) {
+        let behavior = ScrollBehavior::parse(option.behavior.as_deref());
+        let block = option.block.as_deref().unwrap_or("start");
+        let inline = option.inline.as_deref().unwrap_or("nearest");
+        let mut target = self.get_origin_bounds();
+        let mut parent = self.get_parent();
+        while let Some(mut p) = parent {
+            let (max_left, max_top) = p.get_max_scroll();
+            if max_left > 0.0 || max_top > 0.0 {
+                let bounds = p.get_origin_bounds();
+                let content = p.get_content_bounds();
+                let view_x = bounds.x + content.x;
+                let view_y = bounds.y + content.y;
+                let left = (p.scroll_left + align_delta(target.x, target.width, view_x, content.width, inline)).clamp(0.0, max_left);
+                let top = (p.scroll_top + align_delta(target.y, target.height, view_y, content.height, block)).clamp(0.0, max_top);
+                // Outer ancestors see the target at the position after scrolling
+                target.x -= left - p.scroll_left;
+                target.y -= top - p.scroll_top;
+                p.scroll_to_with_behavior(left, top, behavior);
+            }
+            parent = p.get_parent();
+        }
+    }
+
+    /// Scroll position when the running scroll animation ends
+    pub fn get_scroll_target(&self) -> (f32, f32) {
+        match &self.scroll_animation {
+            Some(animation) => animation.target(),
+            None => (self.scroll_left, self.scroll_top),
+        }
+    }
+
+    pub fn get_max_scroll(&self) -> (f32, f32) {
+        let content_bounds = self.get_content_bounds();
+        let (content_width, content_height) = self.get_real_content_size();
+        (
+            (content_width - content_bounds.width).max(0.0),
+            (content_height - content_bounds.height).max(0.0),
+        )
+    }
+
+    pub fn scroll_to_with_behavior(&mut self, left: f32, top: f32, behavior: ScrollBehavior) {
+        let (max_left, max_top) = self.get_max_scroll();
+        let target = (left.clamp(0.0, max_left), top.clamp(0.0, max_top));
+        let from = (self.scroll_left, self.scroll_top);
+        if behavior == ScrollBehavior::Instant || from == target {
+            self.scroll_animation = None;
+            self.set_scroll_left(target.0);
+            self.set_scroll_top(target.1);
+        } else {
+            self.start_scroll_animation(ScrollAnimation::smooth(from, target));
+        }
+    }
+
+    /// Keep scrolling with decaying velocity(px per ms), e.g. after a touch drag is released
+    pub fn fling(&mut self, velocity: (f32, f32)) {
+        let from = (self.scroll_left, self.scroll_top);
+        self.start_scroll_animation(ScrollAnimation::momentum(from, velocity));
+    }
+
+    pub fn stop_scroll_animation(&mut self) {
+        self.scroll_animation = None;
+    }
+
+    fn start_scroll_animation(&mut self, animation: ScrollAnimation) {
+        self.scroll_animation = Some(animation);
+        self.request_scroll_frame();
+    }
+
+    fn request_scroll_frame(&mut self) {
+        let weak = self.inner.as_weak();
+        let timer = set_timeout(move || {
+            if let Some(inner) = weak.upgrade() {
+                ElementRef::from_inner(inner).update_scroll_animation();
+            }
+        }, FRAME_INTERVAL);
+        if let Some(animation) = &mut self.scroll_animation {
+            animation.timer = Some(timer);
         }
-        if option.y != 0.0 {
-            el.set_scroll_top(el.scroll_top + option.y);
+    }
+
+    fn update_scroll_animation(&mut self) {
+        let Some(animation) = &self.scroll_animation else {
+            return;
+        };
+        let prev_position = animation.position;
+        if prev_position != (self.scroll_left, self.scroll_top) {
+            // Interrupted by other scrolling
+            self.scroll_animation = None;
+            return;
+        }
+        let ((left, top), finished) = animation.step(Instant::now());
+        self.set_scroll_left(left);
+        self.set_scroll_top(top);
+        let position = (self.scroll_left, self.scroll_top);
+        // Stop when reaching the edge
+        if finished || position == prev_position {
+            self.scroll_animation = None;
+        } else if let Some(animation) = &mut self.scroll_animation {
+            animation.position = position;
+            self.request_scroll_frame();
         }
     }
 
@@ -166,6 +336,79 @@ impl ElementRef {
         self.cursor
     }
 
+    pub fn set_role(&mut self, role: String) {
+        self.role = parse_role(&role);
+        self.mark_dirty(false);
+    }
+
+    pub fn get_role(&self) -> Option<Role> {
+        self.role
+    }
+
+    pub fn set_aria_label(&mut self, label: String) {
+        self.aria_label = Some(label);
+        self.mark_dirty(false);
+    }
+
+    pub fn get_aria_label(&self) -> Option<&str> {
+        self.aria_label.as_deref()
+    }
+
+    pub fn set_aria_description(&mut self, description: String) {
+        self.aria_description = Some(description);
+        self.mark_dirty(false);
+    }
+
+    pub fn get_aria_description(&self) -> Option<&str> {
+        self.aria_description.as_deref()
+    }
+
+    pub fn set_tab_index(&mut self, tab_index: i32) {
+        self.tab_index = Some(tab_index);
+        self.mark_dirty(false);
+    }
+
+    /// Explicit tab index, or 0 for elements focusable by default
+    pub fn get_tab_index(&self) -> Option<i32> {
+        match self.tab_index {
+            Some(i) => Some(i),
+            None => if self.backend.is_focusable() { Some(0) } else { None }
+        }
+    }
+
+    /// Whether the element could be reached by Tab / Shift+Tab
+    pub fn is_tabbable(&self) -> bool {
+        match self.get_tab_index() {
+            Some(i) => i >= 0 && !self.get_origin_bounds().is_empty(),
+            None => false,
+        }
+    }
+
+    pub fn focus(&mut self) -> Result<(), Error> {
+        let me = self.clone();
+        self.with_window(|w| w.request_focus(me));
+        Ok(())
+    }
+
+    pub fn blur(&mut self) -> Result<(), Error> {
+        let me = self.clone();
+        self.with_window(|w| w.blur(&me));
+        Ok(())
+    }
+
+    pub fn search(&mut self, query: SearchQuery) -> Result<(), Error> {
+        self.get_backend_mut().search(&query)
+    }
+
+    pub fn set_focus_visible(&mut self, focus_visible: bool) {
+        if self.focus_visible != focus_visible {
+            self.focus_visible = focus_visible;
+            if !self.focus_style_props.is_empty() {
+                self.apply_style();
+            }
+        }
+    }
+
     pub fn set_scroll_left(&mut self, mut value: f32) {
         if value.is_nan() {
             return
@@ -246,10 +489,17 @@ impl ElementRef {
     }
 
     pub fn set_parent(&mut self, parent: Option<ElementRef>) {
+        let (fixed, stacking) = self.get_layered_count();
+        if let Some(mut p) = self.get_parent() {
+            p.update_layered_descendants((-fixed, -stacking));
+        }
         self.parent = match parent {
             None => None,
             Some(p) => Some(p.inner.as_weak()),
         };
+        if let Some(mut p) = self.get_parent() {
+            p.update_layered_descendants((fixed, stacking));
+        }
         self.layout.compute_color();
         self.layout.compute_background_color();
     }
@@ -351,10 +601,58 @@ impl ElementRef {
     }
 
 
-    /// bounds relative to parent
+    /// bounds relative to parent, or relative to the frame for fixed elements
     pub fn get_bounds(&self) -> base::Rect {
         let ml = self.layout.get_layout();
-        base::Rect::from_layout(&ml)
+        let bounds = base::Rect::from_layout(&ml);
+        if self.is_fixed() {
+            let (x, y) = self.layout.get_fixed_position();
+            bounds.new_origin(x, y)
+        } else {
+            bounds
+        }
+    }
+
+    pub fn is_fixed(&self) -> bool {
+        self.layout.position == StylePosition::Fixed
+    }
+
+    /// Whether the element is painted in the z-index order of a stacking context instead of the tree order
+    pub fn is_stacking_context(&self) -> bool {
+        self.layout.z_index.is_some() || self.is_fixed()
+    }
+
+    pub fn get_z_index(&self) -> i32 {
+        self.layout.z_index.unwrap_or(0)
+    }
+
+    pub fn has_fixed_descendants(&self) -> bool {
+        self.fixed_descendants > 0
+    }
+
+    pub fn has_stacking_descendants(&self) -> bool {
+        self.stacking_descendants > 0
+    }
+
+    /// Number of (fixed, stacking context) elements in the subtree, including self
+    fn get_layered_count(&self) -> (i32, i32) {
+        (
+            self.fixed_descendants as i32 + self.is_fixed() as i32,
+            self.stacking_descendants as i32 + self.is_stacking_context() as i32,
+        )
+    }
+
+    /// Add `delta` to the descendant counts of self and ancestors
+    fn update_layered_descendants(&mut self, delta: (i32, i32)) {
+        if delta == (0, 0) {
+            return;
+        }
+        let mut node = Some(self.clone());
+        while let Some(mut n) = node {
+            n.fixed_descendants = (n.fixed_descendants as i32 + delta.0) as u32;
+            n.stacking_descendants = (n.stacking_descendants as i32 + delta.1) as u32;
+            node = n.get_parent();
+        }
     }
 
     pub fn get_relative_bounds(&self, target: &Self) -> base::Rect {
@@ -367,6 +665,9 @@ impl ElementRef {
         let mut content_width = 0.0;
         let mut content_height = 0.0;
         for c in self.get_children() {
+            if c.is_fixed() {
+                continue;
+            }
             let cb = c.get_bounds();
             content_width = f32::max(content_width, cb.right());
             content_height = f32::max(content_height, cb.bottom());
@@ -388,7 +689,9 @@ impl ElementRef {
     /// bounds relative to root node
     pub fn get_origin_bounds(&self) -> base::Rect {
         let b = self.get_bounds();
-        return if let Some(p) = self.get_parent() {
+        return if self.is_fixed() {
+            b
+        } else if let Some(p) = self.get_parent() {
             let pob = p.get_origin_bounds();
             let offset_top = p.scroll_top;
             let offset_left = p.scroll_left;
@@ -431,6 +734,18 @@ impl ElementRef {
         self.mark_all_layout_dirty();
         self.layout.calculate_layout(available_width, available_height, Direction::LTR);
         self.on_layout_update();
+        self.calculate_fixed_layout(available_width, available_height);
+    }
+
+    /// Lay out fixed descendants against the frame size, after they are laid out as absolute ones
+    fn calculate_fixed_layout(&mut self, frame_width: f32, frame_height: f32) {
+        for mut child in self.get_children() {
+            if child.is_fixed() {
+                child.layout.calculate_fixed_layout(frame_width, frame_height);
+                child.on_layout_update();
+            }
+            child.calculate_fixed_layout(frame_width, frame_height);
+        }
     }
 
     pub fn set_border_width(&mut self, width: (f32, f32, f32, f32)) {
@@ -478,12 +793,28 @@ impl ElementRef {
     }
 
     pub fn set_hover_style(&mut self, style: JsValue) {
-        self.hover_style_props = parse_style_obj(style);
+        self.set_hover_style_props(parse_style_obj(style));
+    }
+
+    pub fn set_hover_style_props(&mut self, styles: Vec<StyleProp>) {
+        self.hover_style_props = styles;
         if self.hover {
             self.apply_style();
         }
     }
 
+    /// Style applied when focused by keyboard, similar to `:focus-visible` in css
+    pub fn set_focus_style(&mut self, style: JsValue) {
+        self.set_focus_style_props(parse_style_obj(style));
+    }
+
+    pub fn set_focus_style_props(&mut self, styles: Vec<StyleProp>) {
+        self.focus_style_props = styles;
+        if self.focus_visible {
+            self.apply_style();
+        }
+    }
+
     pub fn set_animation(&self, mut animation_res: AnimationResource) {
         let mut ele = self.clone();
         println!("running animation");
@@ -538,6 +869,11 @@ impl ElementRef {
                 style_props.push(v.clone());
             }
         }
+        if self.focus_visible {
+            for v in &self.focus_style_props {
+                style_props.push(v.clone());
+            }
+        }
         for v in &self.animation_style_props {
             style_props.push(v.clone());
         }
@@ -547,6 +883,7 @@ impl ElementRef {
         let mut changed_style_props = Self::calculate_changed_style(&old_style, &new_style);
         // println!("changed style props:{:?}", changed_style_props);
 
+        let old_layered = (self.is_fixed() as i32, self.is_stacking_context() as i32);
         changed_style_props.iter().for_each(| e | {
             let (repaint, need_layout) = self.layout.set_style(e);
             if need_layout || repaint {
@@ -554,6 +891,11 @@ impl ElementRef {
             }
         });
         self.applied_style = new_style;
+        // position and z-index changes are counted by ancestors
+        if let Some(mut p) = self.get_parent() {
+            let new_layered = (self.is_fixed() as i32, self.is_stacking_context() as i32);
+            p.update_layered_descendants((new_layered.0 - old_layered.0, new_layered.1 - old_layered.1));
+        }
     }
 
     pub fn set_style_property(&mut self, name: &str, value: &str) {
@@ -611,7 +953,9 @@ impl ElementRef {
             "alignself" => self.layout.set_align_self(parse_align(value)),
             "direction" => self.layout.set_direction(parse_direction(value)),
             "position" => self.layout.set_position_type(parse_position_type(value)),
-            "overflow" => self.layout.set_overflow(parse_overflow(value)),
+            "overflow" => if let Some(overflow) = parse_overflow(value) {
+                self.layout.set_overflow(overflow)
+            },
             "borderradius" => {
                 let value = parse_float(value);
                 self.layout.border_radius = [value, value, value, value];
@@ -668,6 +1012,17 @@ impl ElementRef {
         self.event_registration.remove_event_listener(&event_type, id)
     }
 
+    /// Whether the element or its ancestors listen to the event
+    pub fn has_event_listener(&self, event_type: &str) -> bool {
+        if self.event_registration.has_event_listener(event_type) {
+            return true;
+        }
+        match self.get_parent() {
+            Some(p) => p.has_event_listener(event_type),
+            None => false,
+        }
+    }
+
     pub fn emit_event(&mut self, event_type: &str, mut event: ElementEvent) {
         let mut me = self.clone();
         let event_type = event_type.to_string();
@@ -709,6 +1064,8 @@ impl ElementRef {
         if !default_behavior {
             if let Some(mut parent) = self.get_parent() {
                 parent.handle_event_default_behavior(event_type, event);
+            } else if let Some(frame) = &self.window {
+                frame.upgrade_mut(|f| f.handle_event_default_behavior(event_type, event));
             }
         }
     }
@@ -717,13 +1074,19 @@ impl ElementRef {
         self.backend.handle_input(input);
     }
 
+    pub fn handle_ime_preedit(&mut self, text: &str, cursor: Option<(usize, usize)>) {
+        self.backend.handle_ime_preedit(text, cursor);
+    }
+
     pub fn mark_dirty(&mut self, layout_dirty: bool) {
         if layout_dirty && self.layout.get_own_context_mut().is_some() {
             self.layout.mark_dirty();
         }
 
+        let element = self.clone();
         self.with_window(|win| {
             win.mark_dirty(layout_dirty);
+            win.mark_accessibility_dirty(element);
         });
 
     }
@@ -803,8 +1166,10 @@ pub struct Element {
     pub layout: StyleNode,
     pub style_props: Vec<StyleProp>,
     pub hover_style_props: Vec<StyleProp>,
+    pub focus_style_props: Vec<StyleProp>,
     animation_style_props: Vec<StyleProp>,
     hover: bool,
+    focus_visible: bool,
 
     applied_style: Vec<StyleProp>,
     // animation_instance: Option<AnimationInstance>,
@@ -812,8 +1177,17 @@ pub struct Element {
 
     scroll_top: f32,
     scroll_left: f32,
+    scroll_animation: Option<ScrollAnimation>,
     draggable: bool,
     cursor: CursorIcon,
+    role: Option<Role>,
+    aria_label: Option<String>,
+    aria_description: Option<String>,
+    tab_index: Option<i32>,
+    /// Number of fixed elements in descendants, frame walks for them skip subtrees without any
+    fixed_descendants: u32,
+    /// Number of stacking context elements in descendants
+    stacking_descendants: u32,
 }
 
 
@@ -831,14 +1205,23 @@ impl Element {
             layout: StyleNode::new(),
             style_props: Vec::new(),
             hover_style_props: Vec::new(),
+            focus_style_props: Vec::new(),
             animation_style_props: Vec::new(),
             applied_style: Vec::new(),
             hover: false,
+            focus_visible: false,
 
             scroll_top: 0.0,
             scroll_left: 0.0,
+            scroll_animation: None,
             draggable: false,
             cursor: CursorIcon::Default,
+            role: None,
+            aria_label: None,
+            aria_description: None,
+            tab_index: None,
+            fixed_descendants: 0,
+            stacking_descendants: 0,
         }
     }
 
@@ -871,6 +1254,14 @@ pub trait ElementBackend {
 
     }
 
+    /// Draw over children, in the same coordinates as `draw`
+    fn draw_foreground(&self, _canvas: &Canvas) {}
+
+    /// Whether the foreground covers the point, which is hit instead of children, in the same coordinates as `draw`
+    fn is_foreground_at(&self, _x: f32, _y: f32) -> bool {
+        false
+    }
+
     fn get_inner_element(&self) -> Option<ElementRef> {
         None
     }
@@ -885,6 +1276,24 @@ pub trait ElementBackend {
 
     fn handle_input(&mut self, _input: &str) {}
 
+    /// Update composing text of input method, cursor is the byte range in text, None means hidden
+    fn handle_ime_preedit(&mut self, _text: &str, _cursor: Option<(usize, usize)>) {}
+
+    /// Highlight matches of query, empty query to clear search
+    fn search(&mut self, _query: &SearchQuery) -> Result<(), Error> {
+        Err(anyhow!("search is not supported by {}", self.get_name()))
+    }
+
+    /// Whether the element accepts text input, input method is only enabled for editable elements
+    fn is_editable(&self) -> bool {
+        false
+    }
+
+    /// Caret bounds relative to the element, used to position the candidate window of input method
+    fn get_caret_bounds(&self) -> Option<base::Rect> {
+        None
+    }
+
     fn handle_event_default_behavior(&mut self, event_type: &str, event: &mut ElementEvent) -> bool {
         (event_type, event);
         false
@@ -903,6 +1312,28 @@ pub trait ElementBackend {
     fn get_children(&self) -> Vec<ElementRef> {
         Vec::new()
     }
+
+    /// Whether children could be inserted by position with `add_child_view`
+    fn accepts_children(&self) -> bool {
+        false
+    }
+
+    /// Whether the element could receive keyboard focus without tabIndex
+    fn is_focusable(&self) -> bool {
+        false
+    }
+
+    fn get_accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    /// Fill name, value, states and supported actions of accessibility node
+    fn build_accessibility_node(&self, _builder: &mut NodeBuilder) {}
+
+    /// Return true if the action request is handled by backend
+    fn handle_accessibility_action(&mut self, _request: &ActionRequest) -> bool {
+        false
+    }
 }
 
 pub fn element_create(view_type: i32) -> Result<ElementRef, Error> {
@@ -914,7 +1345,114 @@ pub fn element_create(view_type: i32) -> Result<ElementRef, Error> {
         VIEW_TYPE_BUTTON => ElementRef::new(Button::create),
         VIEW_TYPE_TEXT_EDIT => ElementRef::new(TextEdit::create),
         VIEW_TYPE_IMAGE => ElementRef::new(Image::create),
+        VIEW_TYPE_VIRTUAL_LIST => ElementRef::new(VirtualList::create),
+        VIEW_TYPE_RADIO_GROUP => ElementRef::new(RadioGroup::create),
+        VIEW_TYPE_PROGRESS_BAR => ElementRef::new(ProgressBar::create),
+        VIEW_TYPE_CHECKBOX => ElementRef::new(Checkbox::create),
+        VIEW_TYPE_RADIO => ElementRef::new(Radio::create),
+        VIEW_TYPE_SWITCH => ElementRef::new(Switch::create),
+        VIEW_TYPE_SLIDER => ElementRef::new(Slider::create),
+        VIEW_TYPE_SELECT => ElementRef::new(Select::create),
+        VIEW_TYPE_CANVAS => ElementRef::new(CanvasElement::create),
         _ => return Err(anyhow!("invalid view_type")),
     };
     Ok(view)
-}
\ No newline at end of file
+}
+
+/// Register a custom backend, so that it could be created by `view_create(name)` in js.
+/// A registered name is replaced by the later registration.
+pub fn register_element<T: ElementBackend + 'static>(name: &str) {
+    CUSTOM_ELEMENTS.with_borrow_mut(|m| {
+        m.insert(name.to_string(), Box::new(|| ElementRef::new(T::create)));
+    });
+}
+
+pub fn element_create_by_name(name: &str) -> Result<ElementRef, Error> {
+    CUSTOM_ELEMENTS.with_borrow(|m| {
+        let creator = m.get(name).ok_or_else(|| anyhow!("unregistered element:{}", name))?;
+        Ok(creator())
+    })
+}
+
+/// Create element by a built-in view type or a registered name
+pub fn view_create(view_type: JsValue) -> Result<ElementRef, Error> {
+    match view_type {
+        JsValue::Int(t) => element_create(t),
+        JsValue::String(name) => element_create_by_name(&name),
+        _ => Err(anyhow!("invalid view_type")),
+    }
+}
+#[test]
+fn test_register_element() {
+    struct Gauge {}
+    impl ElementBackend for Gauge {
+        fn create(_element: ElementRef) -> Self {
+            Self {}
+        }
+
+        fn get_name(&self) -> &str {
+            "Gauge"
+        }
+    }
+
+    assert!(view_create(JsValue::String("test-gauge".to_string())).is_err());
+    register_element::<Gauge>("test-gauge");
+    let gauge = view_create(JsValue::String("test-gauge".to_string())).unwrap();
+    assert_eq!("Gauge", gauge.get_backend().get_name());
+    // every creation returns a new element
+    let another = view_create(JsValue::String("test-gauge".to_string())).unwrap();
+    assert_ne!(gauge.get_id(), another.get_id());
+
+    let container = view_create(JsValue::Int(VIEW_TYPE_CONTAINER)).unwrap();
+    assert_eq!("Container", container.get_backend().get_name());
+    assert!(view_create(JsValue::Bool(true)).is_err());
+}
+
+#[test]
+fn test_scroll_into_view_nested() {
+    use crate::style::StyleProp;
+    let create = |height: &str| {
+        let mut el = ElementRef::new(Container::create);
+        el.set_style_props(vec![
+            StyleProp::parse("height", height).unwrap(),
+            StyleProp::parse("flexShrink", "0").unwrap(),
+        ]);
+        el
+    };
+    // outer(100) > [150, inner(100) > [300, target(20)], 150]
+    let mut outer = create("100");
+    let mut inner = create("100");
+    let mut target = create("20");
+    outer.add_child_view(create("150"), None);
+    outer.add_child_view(inner.clone(), None);
+    outer.add_child_view(create("150"), None);
+    inner.add_child_view(create("300"), None);
+    inner.add_child_view(target.clone(), None);
+    outer.calculate_layout(100.0, 100.0);
+    assert_eq!((0.0, 300.0), outer.get_max_scroll());
+    assert_eq!((0.0, 220.0), inner.get_max_scroll());
+
+    target.scroll_into_view(ScrollIntoViewOption::default());
+    // the inner scroller is scrolled to the end, and the outer one scrolls the rest
+    assert_eq!(220.0, inner.scroll_top);
+    assert_eq!(230.0, outer.scroll_top);
+    assert_eq!(0.0, target.get_origin_bounds().y);
+
+    // visible target is not moved with "nearest"
+    target.scroll_into_view(ScrollIntoViewOption {
+        block: Some("nearest".to_string()),
+        ..Default::default()
+    });
+    assert_eq!(220.0, inner.scroll_top);
+    assert_eq!(230.0, outer.scroll_top);
+
+    outer.set_scroll_top(0.0);
+    inner.set_scroll_top(0.0);
+    target.scroll_into_view(ScrollIntoViewOption {
+        block: Some("end".to_string()),
+        ..Default::default()
+    });
+    let bounds = target.get_origin_bounds();
+    assert_eq!(100.0, bounds.bottom());
+    assert_eq!(220.0, inner.scroll_top);
+}
@@ -0,0 +1,375 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{anyhow, Error};
+use quick_js::JsValue;
+
+use crate::element::{view_create, ElementRef};
+use crate::js::js_value_util::{FromJsValue, JsValueHelper, ToJsValue};
+use crate::style::{parse_style_obj, StyleProp};
+
+enum StyleTarget {
+    Normal,
+    Hover,
+    Focus,
+}
+
+enum BatchOp {
+    SetProperty(ElementRef, String, JsValue),
+    SetStyle(ElementRef, StyleTarget, Vec<StyleProp>),
+    Insert(ElementRef, ElementRef, i32),
+    Remove(ElementRef, u32),
+    BindEvent(ElementRef, String, JsValue),
+}
+
+/// A list of tree mutations applied in one call.
+///
+/// Each operation is an array whose first item is the operation name:
+/// `["create", viewType]`, `["setProperty", target, name, value]`, `["setStyle", target, style]`,
+/// `["setHoverStyle", target, style]`, `["setFocusStyle", target, style]`,
+/// `["insert", parent, child, position]`, `["remove", parent, position]` and
+/// `["bindEvent", target, type, callback]`.
+/// A target is either an existing element or the index of an element created earlier in the batch.
+///
+/// All operations are checked before any of them is applied, so an invalid batch leaves the tree untouched.
+/// Insertions and removals are checked against the children the parent will have at that point of the batch,
+/// and an element could not be inserted into itself or its descendants.
+pub struct ViewBatch {
+    created: Vec<ElementRef>,
+    ops: Vec<BatchOp>,
+    /// Parsed style objects, a screen usually shares a few styles between many elements
+    style_cache: HashMap<String, Vec<StyleProp>>,
+    /// Children of parents changed by the batch, by element id
+    children: HashMap<u32, Vec<ElementRef>>,
+    /// Parents of children moved by the batch, by element id
+    parents: HashMap<u32, Option<ElementRef>>,
+}
+
+impl ViewBatch {
+    pub fn parse(ops: JsValue) -> Result<Self, Error> {
+        let JsValue::Array(ops) = ops else {
+            return Err(anyhow!("batch operations should be an array"));
+        };
+        let mut batch = Self {
+            created: Vec::new(),
+            ops: Vec::with_capacity(ops.len()),
+            style_cache: HashMap::new(),
+            children: HashMap::new(),
+            parents: HashMap::new(),
+        };
+        for (idx, op) in ops.into_iter().enumerate() {
+            batch.parse_op(op).map_err(|e| anyhow!("invalid batch operation #{}: {}", idx, e))?;
+        }
+        Ok(batch)
+    }
+
+    fn parse_op(&mut self, op: JsValue) -> Result<(), Error> {
+        let JsValue::Array(mut args) = op else {
+            return Err(anyhow!("operation should be an array"));
+        };
+        if args.is_empty() {
+            return Err(anyhow!("empty operation"));
+        }
+        let name = to_string(args.remove(0))?;
+        let mut args = args.into_iter();
+        let mut next = || args.next().ok_or_else(|| anyhow!("missing argument"));
+        let op = match name.as_str() {
+            "create" => {
+                let element = view_create(next()?)?;
+                self.created.push(element);
+                return Ok(());
+            }
+            "setProperty" => {
+                let target = self.resolve(next()?)?;
+                let name = to_string(next()?)?;
+                BatchOp::SetProperty(target, name, next()?)
+            }
+            "setStyle" | "setHoverStyle" | "setFocusStyle" => {
+                let target = self.resolve(next()?)?;
+                let styles = self.parse_style(next()?);
+                let style_target = match name.as_str() {
+                    "setHoverStyle" => StyleTarget::Hover,
+                    "setFocusStyle" => StyleTarget::Focus,
+                    _ => StyleTarget::Normal,
+                };
+                BatchOp::SetStyle(target, style_target, styles)
+            }
+            "insert" => {
+                let parent = self.resolve(next()?)?;
+                let child = self.resolve(next()?)?;
+                let position = next().ok().and_then(|p| p.as_number()).unwrap_or(-1.0) as i32;
+                self.check_insert(&parent, &child, position)?;
+                BatchOp::Insert(parent, child, position)
+            }
+            "remove" => {
+                let parent = self.resolve(next()?)?;
+                let position = next()?.as_number().ok_or_else(|| anyhow!("invalid position"))?;
+                if position < 0.0 {
+                    return Err(anyhow!("invalid position"));
+                }
+                self.check_remove(&parent, position as usize)?;
+                BatchOp::Remove(parent, position as u32)
+            }
+            "bindEvent" => {
+                let target = self.resolve(next()?)?;
+                let event_type = to_string(next()?)?;
+                BatchOp::BindEvent(target, event_type, next()?)
+            }
+            _ => return Err(anyhow!("unknown operation:{}", name)),
+        };
+        self.ops.push(op);
+        Ok(())
+    }
+
+    fn resolve(&self, target: JsValue) -> Result<ElementRef, Error> {
+        match target {
+            JsValue::Int(idx) => {
+                self.created.get(idx as usize).cloned().ok_or_else(|| anyhow!("invalid element index:{}", idx))
+            }
+            v => ElementRef::from_js_value(v),
+        }
+    }
+
+    /// Parent of element after the operations parsed so far
+    fn get_parent(&self, element: &ElementRef) -> Option<ElementRef> {
+        match self.parents.get(&element.get_id()) {
+            Some(p) => p.clone(),
+            None => element.get_parent(),
+        }
+    }
+
+    /// Children of parent after the operations parsed so far
+    fn children_mut(&mut self, parent: &ElementRef) -> &mut Vec<ElementRef> {
+        self.children.entry(parent.get_id()).or_insert_with(|| parent.get_children())
+    }
+
+    fn check_insert(&mut self, parent: &ElementRef, child: &ElementRef, position: i32) -> Result<(), Error> {
+        if !parent.get_backend().accepts_children() {
+            return Err(anyhow!("{} doesn't accept children", parent.get_backend().get_name()));
+        }
+        if self.get_parent(child).is_some() {
+            return Err(anyhow!("child has a parent already"));
+        }
+        let mut ancestor = Some(parent.clone());
+        while let Some(a) = ancestor {
+            if &a == child {
+                return Err(anyhow!("element could not be inserted into itself or its descendants"));
+            }
+            ancestor = self.get_parent(&a);
+        }
+        let children = self.children_mut(parent);
+        let position = if position < 0 { children.len() } else { position as usize };
+        if position > children.len() {
+            return Err(anyhow!("invalid position:{}", position));
+        }
+        children.insert(position, child.clone());
+        self.parents.insert(child.get_id(), Some(parent.clone()));
+        Ok(())
+    }
+
+    fn check_remove(&mut self, parent: &ElementRef, position: usize) -> Result<(), Error> {
+        let children = self.children_mut(parent);
+        if position >= children.len() {
+            return Err(anyhow!("invalid position:{}", position));
+        }
+        let child = children.remove(position);
+        self.parents.insert(child.get_id(), None);
+        Ok(())
+    }
+
+    fn parse_style(&mut self, style: JsValue) -> Vec<StyleProp> {
+        let Some(key) = style_cache_key(&style) else {
+            return parse_style_obj(style);
+        };
+        self.style_cache.entry(key).or_insert_with(|| parse_style_obj(style)).clone()
+    }
+
+    /// Apply all operations, returns created elements and ids of bound listeners in order.
+    /// Tree changes are checked by `parse`, and the other operations don't fail.
+    pub fn apply(self) -> Result<(Vec<ElementRef>, Vec<i32>), Error> {
+        let mut listeners = Vec::new();
+        for op in self.ops {
+            match op {
+                BatchOp::SetProperty(mut target, name, value) => {
+                    target.set_property(name, value)?;
+                }
+                BatchOp::SetStyle(mut target, style_target, styles) => {
+                    match style_target {
+                        StyleTarget::Normal => target.set_style_props(styles),
+                        StyleTarget::Hover => target.set_hover_style_props(styles),
+                        StyleTarget::Focus => target.set_focus_style_props(styles),
+                    }
+                }
+                BatchOp::Insert(mut parent, child, position) => {
+                    parent.add_child(child, position)?;
+                }
+                BatchOp::Remove(mut parent, position) => {
+                    parent.remove_child(position)?;
+                }
+                BatchOp::BindEvent(mut target, event_type, callback) => {
+                    listeners.push(target.bind_event(event_type, callback)?);
+                }
+            }
+        }
+        Ok((self.created, listeners))
+    }
+}
+
+fn to_string(value: JsValue) -> Result<String, Error> {
+    match value {
+        JsValue::String(s) => Ok(s),
+        _ => Err(anyhow!("string expected")),
+    }
+}
+
+fn style_cache_key(style: &JsValue) -> Option<String> {
+    let props = style.get_properties()?;
+    let sorted: BTreeMap<_, _> = props.into_iter().collect();
+    let mut key = String::new();
+    for (k, v) in sorted {
+        let v = match v {
+            JsValue::String(s) => s,
+            JsValue::Int(i) => i.to_string(),
+            JsValue::Float(f) => f.to_string(),
+            // Not cacheable, e.g. nested objects
+            _ => return None,
+        };
+        key.push_str(&k);
+        key.push(':');
+        key.push_str(&v);
+        key.push(';');
+    }
+    Some(key)
+}
+
+/// Apply a batch of mutations, see `ViewBatch`
+pub fn view_batch(ops: JsValue) -> Result<JsValue, Error> {
+    let (elements, listeners) = ViewBatch::parse(ops)?.apply()?;
+    let mut result = HashMap::new();
+    let elements = elements.into_iter().map(|e| e.to_js_value()).collect::<Result<Vec<_>, _>>()?;
+    result.insert("elements".to_string(), JsValue::Array(elements));
+    result.insert("listeners".to_string(), JsValue::Array(listeners.into_iter().map(JsValue::Int).collect()));
+    Ok(JsValue::Object(result))
+}
+
+#[test]
+fn test_view_batch() {
+    use crate::ext::ext_frame::VIEW_TYPE_CONTAINER;
+    let style = || JsValue::Object(HashMap::from([("width".to_string(), JsValue::Int(10))]));
+    let ops = JsValue::Array(vec![
+        JsValue::Array(vec![JsValue::String("create".into()), JsValue::Int(VIEW_TYPE_CONTAINER)]),
+        JsValue::Array(vec![JsValue::String("create".into()), JsValue::Int(VIEW_TYPE_CONTAINER)]),
+        JsValue::Array(vec![JsValue::String("setStyle".into()), JsValue::Int(1), style()]),
+        JsValue::Array(vec![JsValue::String("insert".into()), JsValue::Int(0), JsValue::Int(1)]),
+    ]);
+    let (elements, _) = ViewBatch::parse(ops).unwrap().apply().unwrap();
+    assert_eq!(2, elements.len());
+    assert_eq!(1, elements[0].get_children().len());
+
+    let invalid = JsValue::Array(vec![
+        JsValue::Array(vec![JsValue::String("create".into()), JsValue::Int(VIEW_TYPE_CONTAINER)]),
+        JsValue::Array(vec![JsValue::String("insert".into()), JsValue::Int(0), JsValue::Int(5)]),
+    ]);
+    assert!(ViewBatch::parse(invalid).is_err());
+}
+
+#[test]
+fn test_invalid_view_batch() {
+    use crate::element::element_create;
+    use crate::ext::ext_frame::{VIEW_TYPE_CONTAINER, VIEW_TYPE_LABEL};
+    let mut root = element_create(VIEW_TYPE_CONTAINER).unwrap();
+    let child = element_create(VIEW_TYPE_CONTAINER).unwrap();
+    root.add_child(child.clone(), -1).unwrap();
+    let label = element_create(VIEW_TYPE_LABEL).unwrap();
+    let op = |name: &str, args: Vec<JsValue>| {
+        let mut op = vec![JsValue::String(name.into())];
+        op.extend(args);
+        JsValue::Array(op)
+    };
+    let root_value = || root.clone().to_js_value().unwrap();
+    let child_value = || child.clone().to_js_value().unwrap();
+    let create = || op("create", vec![JsValue::Int(VIEW_TYPE_CONTAINER)]);
+
+    let invalid_batches = vec![
+        // the second removal is out of range once the first one is applied
+        vec![op("remove", vec![root_value(), JsValue::Int(0)]), op("remove", vec![root_value(), JsValue::Int(0)])],
+        vec![create(), op("insert", vec![root_value(), JsValue::Int(0), JsValue::Int(2)])],
+        // child is in the tree already
+        vec![op("insert", vec![root_value(), child_value()])],
+        vec![op("insert", vec![label.clone().to_js_value().unwrap(), child_value()])],
+        vec![create(), op("insert", vec![JsValue::Int(0), JsValue::Int(0)])],
+        // root is an ancestor of child
+        vec![op("insert", vec![child_value(), root_value()])],
+        // root would be an ancestor of the created element once it's inserted into child
+        vec![
+            create(),
+            op("insert", vec![child_value(), JsValue::Int(0)]),
+            op("insert", vec![JsValue::Int(0), root_value()]),
+        ],
+    ];
+    for ops in invalid_batches {
+        assert!(ViewBatch::parse(JsValue::Array(ops)).is_err());
+        assert_eq!(1, root.get_children().len());
+        assert!(child.get_parent() == Some(root.clone()));
+    }
+
+    // moving a child is valid
+    let ops = vec![
+        create(),
+        op("remove", vec![root_value(), JsValue::Int(0)]),
+        op("insert", vec![JsValue::Int(0), child_value()]),
+        op("insert", vec![root_value(), JsValue::Int(0), JsValue::Int(0)]),
+    ];
+    let (elements, _) = ViewBatch::parse(JsValue::Array(ops)).unwrap().apply().unwrap();
+    assert!(root.get_children() == elements);
+    assert!(elements[0].get_children() == vec![child.clone()]);
+}
+
+/// Compare building a 5,000-node tree through lib.js by calling `View` methods one by one with committing
+/// one `ViewBatch`, so both sides include the cost of crossing the js boundary.
+/// Run by `cargo test --release bench_view_batch -- --ignored --nocapture`
+#[test]
+#[ignore]
+fn bench_view_batch() {
+    use std::time::Instant;
+    use crate::js::js_engine::JsEngine;
+    use crate::ext::ext_frame::VIEW_TYPE_CONTAINER;
+    use crate::loader::StaticModuleLoader;
+    const COUNT: usize = 5000;
+    let engine = JsEngine::new(StaticModuleLoader::new());
+    let js = &engine.js_context;
+    js.eval(&format!("
+        globalThis.COUNT = {};
+        globalThis.CONTAINER = {};
+        globalThis.itemStyle = (i) => ({{padding: '4', border: '1 #ccc', background: i % 2 === 0 ? '#fff' : '#eee'}});
+    ", COUNT, VIEW_TYPE_CONTAINER)).unwrap();
+    let root_children = |root: JsValue| ElementRef::from_js_value(root).unwrap().get_children().len();
+
+    let start = Instant::now();
+    let root = js.eval("(() => {
+        const root = new ContainerElement();
+        for (let i = 0; i < COUNT; i++) {
+            const child = new ContainerElement();
+            child.setStyle(itemStyle(i));
+            root.addChild(child);
+        }
+        return root.el;
+    })()").unwrap();
+    let per_call = start.elapsed();
+    assert_eq!(COUNT, root_children(root));
+
+    let start = Instant::now();
+    let root = js.eval("(() => {
+        const batch = new ViewBatch();
+        const root = batch.create(CONTAINER);
+        for (let i = 0; i < COUNT; i++) {
+            const child = batch.create(CONTAINER);
+            batch.setStyle(child, itemStyle(i));
+            batch.insert(root, child);
+        }
+        return batch.commit().elements[0];
+    })()").unwrap();
+    let batched = start.elapsed();
+    assert_eq!(COUNT, root_children(root));
+
+    println!("{} nodes, per call: {:?}, batched: {:?}", COUNT, per_call, batched);
+}
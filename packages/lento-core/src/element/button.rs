@@ -1,10 +1,13 @@
+use accesskit::{Action, DefaultActionVerb, NodeBuilder, Role};
 use ordered_float::OrderedFloat;
 use quick_js::JsValue;
 use skia_safe::{Canvas, Color};
 use yoga::{Edge, StyleUnit};
-use crate::base::PropertyValue;
+use crate::base::{ElementEvent, PropertyValue};
 use crate::element::{ElementBackend, ElementRef};
+use crate::element::control::{emit_keyboard_click, is_activate_key};
 use crate::element::label::Label;
+use crate::event::KeyDownEvent;
 
 pub struct Button {
     label: Label,
@@ -20,6 +23,12 @@ impl Button {
     pub fn get_title(&self) -> &str {
         self.label.get_text()
     }
+
+    fn emit_keyboard_click(&mut self) {
+        if let Some(element) = &mut self.element {
+            emit_keyboard_click(element);
+        }
+    }
 }
 
 impl ElementBackend for Button {
@@ -68,4 +77,30 @@ impl ElementBackend for Button {
         self.label.handle_style_changed(key)
     }
 
+    fn handle_event_default_behavior(&mut self, event_type: &str, event: &mut ElementEvent) -> bool {
+        let mut handled = false;
+        KeyDownEvent::try_match(event_type, event, |d| {
+            handled = is_activate_key(d);
+        });
+        if handled {
+            self.emit_keyboard_click();
+        }
+        handled
+    }
+
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
+    fn get_accessibility_role(&self) -> Role {
+        Role::Button
+    }
+
+    fn build_accessibility_node(&self, builder: &mut NodeBuilder) {
+        builder.set_name(self.get_title());
+        builder.set_default_action_verb(DefaultActionVerb::Click);
+        builder.add_action(Action::Focus);
+        builder.add_action(Action::Default);
+    }
+
 }
\ No newline at end of file
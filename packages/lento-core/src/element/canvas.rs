@@ -0,0 +1,737 @@
+use std::collections::HashMap;
+use std::f32::consts::PI;
+
+use accesskit::Role;
+use anyhow::{anyhow, Error};
+use quick_js::JsValue;
+use skia_safe::canvas::SrcRectConstraint;
+use skia_safe::font_style::{Slant, Weight, Width};
+use skia_safe::path::AddPathMode;
+use skia_safe::{paint, surfaces, AlphaType, BlendMode, ClipOp, Color, ColorSpace, ColorType, Font, FontStyle, Image, ImageInfo, Matrix, Paint, PaintStyle, Path, PathEffect, PathFillType, Point, Rect, Shader, Surface, TileMode};
+
+use crate::color::parse_css_color;
+use crate::element::control::{content_rect, set_intrinsic_size};
+use crate::element::text::{DEFAULT_TYPE_FACE, FONT_MGR};
+use crate::element::{ElementBackend, ElementRef};
+use crate::img_manager::{rgba_to_skia_image, IMG_MANAGER};
+use crate::js::js_value_util::JsValueHelper;
+use crate::js_call;
+
+const DEFAULT_WIDTH: i32 = 300;
+const DEFAULT_HEIGHT: i32 = 150;
+const DEFAULT_FONT_SIZE: f32 = 10.0;
+
+#[derive(Clone)]
+enum FillStyle {
+    Color(Color),
+    Shader(Shader),
+}
+
+#[derive(Clone, Copy)]
+enum CanvasTextAlign {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Clone, Copy)]
+enum CanvasTextBaseline {
+    Alphabetic,
+    Top,
+    Middle,
+    Bottom,
+}
+
+/// Drawing state saved and restored by `save()` and `restore()`, the transform and clip are saved by skia canvas
+#[derive(Clone)]
+struct DrawState {
+    fill_style: FillStyle,
+    stroke_style: FillStyle,
+    line_width: f32,
+    line_cap: paint::Cap,
+    line_join: paint::Join,
+    miter_limit: f32,
+    line_dash: Vec<f32>,
+    line_dash_offset: f32,
+    global_alpha: f32,
+    blend_mode: BlendMode,
+    font: Font,
+    text_align: CanvasTextAlign,
+    text_baseline: CanvasTextBaseline,
+}
+
+impl Default for DrawState {
+    fn default() -> Self {
+        let typeface = DEFAULT_TYPE_FACE.with(|t| t.clone());
+        Self {
+            fill_style: FillStyle::Color(Color::BLACK),
+            stroke_style: FillStyle::Color(Color::BLACK),
+            line_width: 1.0,
+            line_cap: paint::Cap::Butt,
+            line_join: paint::Join::Miter,
+            miter_limit: 10.0,
+            line_dash: Vec::new(),
+            line_dash_offset: 0.0,
+            global_alpha: 1.0,
+            blend_mode: BlendMode::SrcOver,
+            font: Font::from_typeface(typeface, DEFAULT_FONT_SIZE),
+            text_align: CanvasTextAlign::Left,
+            text_baseline: CanvasTextBaseline::Alphabetic,
+        }
+    }
+}
+
+/// Arguments of a draw command
+struct CommandArgs<'a> {
+    values: &'a [JsValue],
+    index: usize,
+}
+
+impl<'a> CommandArgs<'a> {
+    fn len(&self) -> usize {
+        self.values.len() - self.index
+    }
+
+    fn next(&mut self) -> Result<&'a JsValue, Error> {
+        let value = self.values.get(self.index).ok_or_else(|| anyhow!("missing argument"))?;
+        self.index += 1;
+        Ok(value)
+    }
+
+    fn f32(&mut self) -> Result<f32, Error> {
+        let value = self.next()?;
+        value.as_number().map(|v| v as f32).ok_or_else(|| anyhow!("number expected"))
+    }
+
+    fn string(&mut self) -> Result<&'a str, Error> {
+        self.next()?.as_str().ok_or_else(|| anyhow!("string expected"))
+    }
+
+    fn bool_or(&mut self, default: bool) -> bool {
+        match self.values.get(self.index) {
+            Some(JsValue::Bool(b)) => {
+                self.index += 1;
+                *b
+            }
+            _ => default,
+        }
+    }
+
+    fn point(&mut self) -> Result<Point, Error> {
+        Ok(Point::new(self.f32()?, self.f32()?))
+    }
+
+    fn rect(&mut self) -> Result<Rect, Error> {
+        Ok(Rect::from_xywh(self.f32()?, self.f32()?, self.f32()?, self.f32()?))
+    }
+}
+
+/// 2D context like html canvas, draw commands are executed on a raster surface
+pub struct Context2D {
+    surface: Surface,
+    state: DrawState,
+    saved_states: Vec<DrawState>,
+    /// Current path in the current user space
+    path: Path,
+    /// Keep images alive, images in `IMG_MANAGER` are weakly cached
+    images: HashMap<String, Image>,
+}
+
+impl Context2D {
+    pub fn new(width: i32, height: i32) -> Option<Self> {
+        let surface = surfaces::raster_n32_premul((width.max(1), height.max(1)))?;
+        Some(Self {
+            surface,
+            state: DrawState::default(),
+            saved_states: Vec::new(),
+            path: Path::new(),
+            images: HashMap::new(),
+        })
+    }
+
+    pub fn width(&self) -> i32 {
+        self.surface.width()
+    }
+
+    pub fn height(&self) -> i32 {
+        self.surface.height()
+    }
+
+    pub fn snapshot(&mut self) -> Image {
+        self.surface.image_snapshot()
+    }
+
+    /// Read RGBA8 pixels of the rect, pixels out of the surface are transparent
+    pub fn read_pixels(&mut self, x: i32, y: i32, width: i32, height: i32) -> Vec<u8> {
+        let width = width.max(0);
+        let height = height.max(0);
+        let info = ImageInfo::new((width, height), ColorType::RGBA8888, AlphaType::Unpremul, ColorSpace::new_srgb());
+        let row_bytes = width as usize * 4;
+        let mut bytes = vec![0u8; row_bytes * height as usize];
+        if width > 0 && height > 0 {
+            self.surface.read_pixels(&info, &mut bytes, row_bytes, (x, y));
+        }
+        bytes
+    }
+
+    pub fn measure_text(&self, text: &str) -> f32 {
+        self.state.font.measure_str(text, None).0
+    }
+
+    /// Execute command like `["fillRect", 0, 0, 10, 10]`
+    pub fn execute(&mut self, command: &[JsValue]) -> Result<(), Error> {
+        let Some(JsValue::String(op)) = command.first() else {
+            return Err(anyhow!("invalid command"));
+        };
+        let op = op.as_str();
+        let mut args = CommandArgs { values: command, index: 1 };
+        match op {
+            "save" => {
+                self.saved_states.push(self.state.clone());
+                self.surface.canvas().save();
+            }
+            "restore" => {
+                if let Some(state) = self.saved_states.pop() {
+                    self.state = state;
+                    self.update_matrix(|c| {
+                        c.restore();
+                    });
+                }
+            }
+            "translate" => {
+                let (x, y) = (args.f32()?, args.f32()?);
+                self.update_matrix(|c| {
+                    c.translate((x, y));
+                });
+            }
+            "scale" => {
+                let (x, y) = (args.f32()?, args.f32()?);
+                self.update_matrix(|c| {
+                    c.scale((x, y));
+                });
+            }
+            "rotate" => {
+                let angle = args.f32()?;
+                self.update_matrix(|c| {
+                    c.rotate(angle.to_degrees(), None);
+                });
+            }
+            "transform" | "setTransform" => {
+                let m = transform_matrix(&mut args)?;
+                let reset = op == "setTransform";
+                self.update_matrix(|c| {
+                    if reset {
+                        c.reset_matrix();
+                    }
+                    c.concat(&m);
+                });
+            }
+            "resetTransform" => {
+                self.update_matrix(|c| {
+                    c.reset_matrix();
+                });
+            }
+            "beginPath" => self.path = Path::new(),
+            "closePath" => {
+                self.path.close();
+            }
+            "moveTo" => {
+                self.path.move_to(args.point()?);
+            }
+            "lineTo" => {
+                self.path.line_to(args.point()?);
+            }
+            "bezierCurveTo" => {
+                self.path.cubic_to(args.point()?, args.point()?, args.point()?);
+            }
+            "quadraticCurveTo" => {
+                self.path.quad_to(args.point()?, args.point()?);
+            }
+            "arc" => {
+                let (x, y, r) = (args.f32()?, args.f32()?, args.f32()?);
+                let (start, end) = (args.f32()?, args.f32()?);
+                let ccw = args.bool_or(false);
+                self.add_ellipse(x, y, r, r, 0.0, start, end, ccw);
+            }
+            "ellipse" => {
+                let (x, y, rx, ry) = (args.f32()?, args.f32()?, args.f32()?, args.f32()?);
+                let (rotation, start, end) = (args.f32()?, args.f32()?, args.f32()?);
+                let ccw = args.bool_or(false);
+                self.add_ellipse(x, y, rx, ry, rotation, start, end, ccw);
+            }
+            "arcTo" => {
+                let (p1, p2, r) = (args.point()?, args.point()?, args.f32()?);
+                self.path.arc_to_tangent(p1, p2, r);
+            }
+            "rect" => {
+                self.path.add_rect(args.rect()?, None);
+            }
+            "fill" => {
+                let fill_type = parse_fill_rule(args.string().unwrap_or("nonzero"));
+                self.path.set_fill_type(fill_type);
+                let paint = self.fill_paint();
+                let path = self.path.clone();
+                self.surface.canvas().draw_path(&path, &paint);
+            }
+            "stroke" => {
+                let paint = self.stroke_paint();
+                let path = self.path.clone();
+                self.surface.canvas().draw_path(&path, &paint);
+            }
+            "clip" => {
+                let fill_type = parse_fill_rule(args.string().unwrap_or("nonzero"));
+                let mut path = self.path.clone();
+                path.set_fill_type(fill_type);
+                self.surface.canvas().clip_path(&path, ClipOp::Intersect, true);
+            }
+            "fillRect" => {
+                let paint = self.fill_paint();
+                self.surface.canvas().draw_rect(args.rect()?, &paint);
+            }
+            "strokeRect" => {
+                let paint = self.stroke_paint();
+                self.surface.canvas().draw_rect(args.rect()?, &paint);
+            }
+            "clearRect" => {
+                let mut paint = Paint::default();
+                paint.set_blend_mode(BlendMode::Clear);
+                self.surface.canvas().draw_rect(args.rect()?, &paint);
+            }
+            "fillText" | "strokeText" => {
+                let text = args.string()?;
+                let position = args.point()?;
+                let paint = if op == "fillText" { self.fill_paint() } else { self.stroke_paint() };
+                self.draw_text(text, position, &paint);
+            }
+            "drawImage" => {
+                let src = args.string()?;
+                self.draw_image(src, &mut args)?;
+            }
+            "putImageData" => {
+                let (width, height) = (args.f32()? as i32, args.f32()? as i32);
+                let data: Vec<u8> = match args.next()? {
+                    JsValue::Array(values) => values.iter().map(|v| v.as_number().unwrap_or(0.0) as u8).collect(),
+                    _ => return Err(anyhow!("array expected")),
+                };
+                let position = args.point()?;
+                let image = rgba_to_skia_image(width, height, &data).ok_or_else(|| anyhow!("invalid image data"))?;
+                // image data is not affected by transform, clip and compositing
+                let canvas = self.surface.canvas();
+                canvas.save();
+                canvas.reset_matrix();
+                let mut paint = Paint::default();
+                paint.set_blend_mode(BlendMode::Src);
+                canvas.draw_image(&image, position, Some(&paint));
+                canvas.restore();
+            }
+            "fillStyle" => self.state.fill_style = parse_fill_style(&mut args)?,
+            "strokeStyle" => self.state.stroke_style = parse_fill_style(&mut args)?,
+            "lineWidth" => self.state.line_width = args.f32()?,
+            "lineCap" => {
+                self.state.line_cap = match args.string()? {
+                    "round" => paint::Cap::Round,
+                    "square" => paint::Cap::Square,
+                    _ => paint::Cap::Butt,
+                };
+            }
+            "lineJoin" => {
+                self.state.line_join = match args.string()? {
+                    "round" => paint::Join::Round,
+                    "bevel" => paint::Join::Bevel,
+                    _ => paint::Join::Miter,
+                };
+            }
+            "miterLimit" => self.state.miter_limit = args.f32()?,
+            "lineDash" => {
+                let mut dash = Vec::with_capacity(args.len());
+                while args.len() > 0 {
+                    dash.push(args.f32()?);
+                }
+                self.state.line_dash = dash;
+            }
+            "lineDashOffset" => self.state.line_dash_offset = args.f32()?,
+            "globalAlpha" => self.state.global_alpha = args.f32()?.clamp(0.0, 1.0),
+            "globalCompositeOperation" => {
+                if let Some(mode) = parse_blend_mode(args.string()?) {
+                    self.state.blend_mode = mode;
+                }
+            }
+            "font" => {
+                if let Some(font) = parse_font(args.string()?) {
+                    self.state.font = font;
+                }
+            }
+            "textAlign" => {
+                self.state.text_align = match args.string()? {
+                    "center" => CanvasTextAlign::Center,
+                    "right" | "end" => CanvasTextAlign::Right,
+                    _ => CanvasTextAlign::Left,
+                };
+            }
+            "textBaseline" => {
+                self.state.text_baseline = match args.string()? {
+                    "top" | "hanging" => CanvasTextBaseline::Top,
+                    "middle" => CanvasTextBaseline::Middle,
+                    "bottom" | "ideographic" => CanvasTextBaseline::Bottom,
+                    _ => CanvasTextBaseline::Alphabetic,
+                };
+            }
+            _ => return Err(anyhow!("unknown command:{}", op)),
+        }
+        Ok(())
+    }
+
+    /// Change the transform, the current path is kept at the same place on surface
+    fn update_matrix<F: FnOnce(&skia_safe::Canvas)>(&mut self, callback: F) {
+        let canvas = self.surface.canvas();
+        let old_matrix = canvas.local_to_device_as_3x3();
+        callback(canvas);
+        let new_matrix = canvas.local_to_device_as_3x3();
+        if !self.path.is_empty() && old_matrix != new_matrix {
+            if let Some(inverse) = new_matrix.invert() {
+                self.path = self.path.with_transform(&old_matrix).with_transform(&inverse);
+            }
+        }
+    }
+
+    fn add_ellipse(&mut self, x: f32, y: f32, rx: f32, ry: f32, rotation: f32, start: f32, end: f32, ccw: bool) {
+        let sweep = arc_sweep(start, end, ccw);
+        let oval = Rect::from_xywh(x - rx, y - ry, rx * 2.0, ry * 2.0);
+        let mut arc = Path::new();
+        // skia could not draw a full circle by one arc
+        let half = sweep.to_degrees() / 2.0;
+        arc.arc_to(oval, start.to_degrees(), half, true);
+        arc.arc_to(oval, start.to_degrees() + half, half, false);
+        if rotation != 0.0 {
+            arc = arc.with_transform(&Matrix::rotate_deg_pivot(rotation.to_degrees(), (x, y)));
+        }
+        // connect the current point to the start of arc
+        self.path.add_path(&arc, (0.0, 0.0), AddPathMode::Extend);
+    }
+
+    fn apply_common(&self, paint: &mut Paint, style: &FillStyle) {
+        paint.set_anti_alias(true);
+        paint.set_blend_mode(self.state.blend_mode);
+        match style {
+            FillStyle::Color(color) => {
+                paint.set_color(*color);
+                paint.set_alpha_f(color.a() as f32 / 255.0 * self.state.global_alpha);
+            }
+            FillStyle::Shader(shader) => {
+                paint.set_shader(Some(shader.clone()));
+                paint.set_alpha_f(self.state.global_alpha);
+            }
+        }
+    }
+
+    fn fill_paint(&self) -> Paint {
+        let mut paint = Paint::default();
+        self.apply_common(&mut paint, &self.state.fill_style);
+        paint
+    }
+
+    fn stroke_paint(&self) -> Paint {
+        let mut paint = Paint::default();
+        self.apply_common(&mut paint, &self.state.stroke_style);
+        paint.set_style(PaintStyle::Stroke);
+        paint.set_stroke_width(self.state.line_width);
+        paint.set_stroke_cap(self.state.line_cap);
+        paint.set_stroke_join(self.state.line_join);
+        paint.set_stroke_miter(self.state.miter_limit);
+        if !self.state.line_dash.is_empty() {
+            let mut intervals = self.state.line_dash.clone();
+            // odd number of segments is repeated like html canvas
+            if intervals.len() % 2 == 1 {
+                intervals.extend_from_slice(&self.state.line_dash);
+            }
+            paint.set_path_effect(PathEffect::dash(&intervals, self.state.line_dash_offset));
+        }
+        paint
+    }
+
+    fn draw_text(&mut self, text: &str, position: Point, paint: &Paint) {
+        let font = &self.state.font;
+        let width = font.measure_str(text, None).0;
+        let (_, metrics) = font.metrics();
+        let x = match self.state.text_align {
+            CanvasTextAlign::Left => position.x,
+            CanvasTextAlign::Center => position.x - width / 2.0,
+            CanvasTextAlign::Right => position.x - width,
+        };
+        let y = match self.state.text_baseline {
+            CanvasTextBaseline::Alphabetic => position.y,
+            CanvasTextBaseline::Top => position.y - metrics.ascent,
+            CanvasTextBaseline::Middle => position.y - (metrics.ascent + metrics.descent) / 2.0,
+            CanvasTextBaseline::Bottom => position.y - metrics.descent,
+        };
+        let font = font.clone();
+        self.surface.canvas().draw_str(text, (x, y), &font, paint);
+    }
+
+    /// Arguments are `dx, dy`, `dx, dy, dw, dh` or `sx, sy, sw, sh, dx, dy, dw, dh`
+    fn draw_image(&mut self, src: &str, args: &mut CommandArgs) -> Result<(), Error> {
+        let image = match self.images.get(src) {
+            Some(img) => img.clone(),
+            None => {
+                let img = IMG_MANAGER.with(|im| im.get_img(src)).ok_or_else(|| anyhow!("failed to load image:{}", src))?;
+                self.images.insert(src.to_string(), img.clone());
+                img
+            }
+        };
+        let (img_width, img_height) = (image.width() as f32, image.height() as f32);
+        let (src_rect, dst_rect) = match args.len() {
+            2 => {
+                let p = args.point()?;
+                (Rect::from_wh(img_width, img_height), Rect::from_xywh(p.x, p.y, img_width, img_height))
+            }
+            4 => (Rect::from_wh(img_width, img_height), args.rect()?),
+            8 => (args.rect()?, args.rect()?),
+            _ => return Err(anyhow!("invalid arguments of drawImage")),
+        };
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        paint.set_blend_mode(self.state.blend_mode);
+        paint.set_alpha_f(self.state.global_alpha);
+        self.surface.canvas().draw_image_rect(&image, Some((&src_rect, SrcRectConstraint::Fast)), dst_rect, &paint);
+        Ok(())
+    }
+}
+
+/// Drawing surface for charts and custom visualisations, commands are batched by js and executed by `Context2D`
+pub struct CanvasElement {
+    element: ElementRef,
+    context: Option<Context2D>,
+    snapshot: Option<Image>,
+}
+
+impl CanvasElement {
+    pub fn set_width(&mut self, width: i32) {
+        let height = self.get_context().height();
+        self.resize(width, height);
+    }
+
+    pub fn set_height(&mut self, height: i32) {
+        let width = self.get_context().width();
+        self.resize(width, height);
+    }
+
+    /// Resizing clears the content and resets the drawing state like html canvas
+    pub fn resize(&mut self, width: i32, height: i32) {
+        self.context = Context2D::new(width, height);
+        set_intrinsic_size(&mut self.element, width as f32, height as f32);
+        self.update_snapshot();
+    }
+
+    pub fn get_context(&mut self) -> &mut Context2D {
+        self.context.get_or_insert_with(|| Context2D::new(DEFAULT_WIDTH, DEFAULT_HEIGHT).unwrap())
+    }
+
+    /// Execute batched commands, e.g. `[["fillStyle", "#F00"], ["fillRect", 0, 0, 10, 10]]`
+    pub fn draw_commands(&mut self, commands: JsValue) -> Result<(), Error> {
+        let JsValue::Array(commands) = commands else {
+            return Err(anyhow!("array expected"));
+        };
+        let context = self.get_context();
+        let mut result = Ok(());
+        for command in commands {
+            let r = match command {
+                JsValue::Array(c) => context.execute(&c),
+                _ => Err(anyhow!("invalid command")),
+            };
+            // a bad command should not stop the rest of the batch
+            if let Err(e) = r {
+                result = Err(e);
+            }
+        }
+        self.update_snapshot();
+        result
+    }
+
+    fn update_snapshot(&mut self) {
+        self.snapshot = Some(self.get_context().snapshot());
+        self.element.mark_dirty(false);
+    }
+}
+
+impl ElementBackend for CanvasElement {
+    fn create(mut element: ElementRef) -> Self {
+        set_intrinsic_size(&mut element, DEFAULT_WIDTH as f32, DEFAULT_HEIGHT as f32);
+        Self {
+            element,
+            context: Context2D::new(DEFAULT_WIDTH, DEFAULT_HEIGHT),
+            snapshot: None,
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        "Canvas"
+    }
+
+    fn draw(&self, canvas: &skia_safe::Canvas) {
+        if let Some(img) = &self.snapshot {
+            let rect = content_rect(&self.element).to_skia_rect();
+            let mut paint = Paint::default();
+            paint.set_anti_alias(true);
+            canvas.draw_image_rect(img, None, rect, &paint);
+        }
+    }
+
+    fn set_property(&mut self, p: &str, v: JsValue) {
+        js_call!("width", i32, self, set_width, p, v);
+        js_call!("height", i32, self, set_height, p, v);
+    }
+
+    fn get_property(&mut self, property_name: &str) -> Result<Option<JsValue>, Error> {
+        match property_name {
+            "width" => Ok(Some(JsValue::Int(self.get_context().width()))),
+            "height" => Ok(Some(JsValue::Int(self.get_context().height()))),
+            _ => Ok(None),
+        }
+    }
+
+    fn get_accessibility_role(&self) -> Role {
+        Role::Canvas
+    }
+}
+
+/// Matrix of `transform(a, b, c, d, e, f)`
+fn transform_matrix(args: &mut CommandArgs) -> Result<Matrix, Error> {
+    let (a, b, c, d, e, f) = (args.f32()?, args.f32()?, args.f32()?, args.f32()?, args.f32()?, args.f32()?);
+    Ok(Matrix::new_all(a, c, e, b, d, f, 0.0, 0.0, 1.0))
+}
+
+/// Sweep angle in radians of `arc(x, y, r, start, end, counterclockwise)`
+fn arc_sweep(start: f32, end: f32, ccw: bool) -> f32 {
+    let full = 2.0 * PI;
+    if !ccw {
+        if end - start >= full { full } else { (end - start).rem_euclid(full) }
+    } else if start - end >= full {
+        -full
+    } else {
+        -(start - end).rem_euclid(full)
+    }
+}
+
+fn parse_fill_rule(rule: &str) -> PathFillType {
+    match rule {
+        "evenodd" => PathFillType::EvenOdd,
+        _ => PathFillType::Winding,
+    }
+}
+
+/// Color string, or gradient like `["linear", x0, y0, x1, y1, offset, color, ...]`
+/// and `["radial", x0, y0, r0, x1, y1, r1, offset, color, ...]`
+fn parse_fill_style(args: &mut CommandArgs) -> Result<FillStyle, Error> {
+    match args.next()? {
+        JsValue::String(color) => {
+            parse_css_color(color).map(FillStyle::Color).ok_or_else(|| anyhow!("invalid color:{}", color))
+        }
+        JsValue::Array(gradient) => {
+            let mut args = CommandArgs { values: gradient, index: 0 };
+            let kind = args.string()?;
+            let geometry = match kind {
+                "linear" => vec![args.f32()?, args.f32()?, args.f32()?, args.f32()?],
+                "radial" => vec![args.f32()?, args.f32()?, args.f32()?, args.f32()?, args.f32()?, args.f32()?],
+                _ => return Err(anyhow!("invalid gradient:{}", kind)),
+            };
+            let mut positions = Vec::new();
+            let mut colors = Vec::new();
+            while args.len() > 0 {
+                positions.push(args.f32()?.clamp(0.0, 1.0));
+                let color = args.string()?;
+                colors.push(parse_css_color(color).ok_or_else(|| anyhow!("invalid color:{}", color))?);
+            }
+            if colors.is_empty() {
+                return Ok(FillStyle::Color(Color::TRANSPARENT));
+            }
+            let g = &geometry;
+            let shader = if kind == "linear" {
+                Shader::linear_gradient(((g[0], g[1]), (g[2], g[3])), colors.as_slice(), Some(positions.as_slice()), TileMode::Clamp, None, None)
+            } else {
+                Shader::two_point_conical_gradient((g[0], g[1]), g[2], (g[3], g[4]), g[5], colors.as_slice(), Some(positions.as_slice()), TileMode::Clamp, None, None)
+            };
+            shader.map(FillStyle::Shader).ok_or_else(|| anyhow!("invalid gradient"))
+        }
+        _ => Err(anyhow!("invalid style")),
+    }
+}
+
+fn parse_blend_mode(op: &str) -> Option<BlendMode> {
+    let mode = match op {
+        "source-over" => BlendMode::SrcOver,
+        "source-in" => BlendMode::SrcIn,
+        "source-out" => BlendMode::SrcOut,
+        "source-atop" => BlendMode::SrcATop,
+        "destination-over" => BlendMode::DstOver,
+        "destination-in" => BlendMode::DstIn,
+        "destination-out" => BlendMode::DstOut,
+        "destination-atop" => BlendMode::DstATop,
+        "lighter" => BlendMode::Plus,
+        "copy" => BlendMode::Src,
+        "xor" => BlendMode::Xor,
+        "multiply" => BlendMode::Multiply,
+        "screen" => BlendMode::Screen,
+        "darken" => BlendMode::Darken,
+        "lighten" => BlendMode::Lighten,
+        _ => return None,
+    };
+    Some(mode)
+}
+
+/// Parse css font like `italic bold 16px sans-serif`
+fn parse_font(font: &str) -> Option<Font> {
+    let mut weight = Weight::NORMAL;
+    let mut slant = Slant::Upright;
+    let mut size = None;
+    let mut parts = font.split_whitespace();
+    while size.is_none() {
+        let part = parts.next()?;
+        match part {
+            "italic" | "oblique" => slant = Slant::Italic,
+            "bold" => weight = Weight::BOLD,
+            "normal" => {}
+            _ => {
+                if let Some(px) = part.strip_suffix("px") {
+                    size = Some(px.parse::<f32>().ok()?);
+                } else if let Ok(w) = part.parse::<i32>() {
+                    weight = Weight::from(w);
+                } else {
+                    return None;
+                }
+            }
+        }
+    }
+    let family = parts.collect::<Vec<_>>().join(" ");
+    let family = family.split(',').next().unwrap_or("").trim().trim_matches(|c| c == '"' || c == '\'');
+    let style = FontStyle::new(weight, Width::NORMAL, slant);
+    let typeface = FONT_MGR.with(|fm| fm.match_family_style(family, style))
+        .unwrap_or_else(|| DEFAULT_TYPE_FACE.with(|t| t.clone()));
+    Some(Font::from_typeface(typeface, size?))
+}
+
+#[test]
+fn test_context_2d() {
+    let mut ctx = Context2D::new(20, 20).unwrap();
+    let commands = vec![
+        vec![JsValue::String("fillStyle".to_string()), JsValue::String("#FF0000".to_string())],
+        vec![JsValue::String("translate".to_string()), JsValue::Int(5), JsValue::Int(5)],
+        vec![JsValue::String("fillRect".to_string()), JsValue::Int(0), JsValue::Int(0), JsValue::Int(10), JsValue::Int(10)],
+        vec![JsValue::String("clearRect".to_string()), JsValue::Int(0), JsValue::Int(0), JsValue::Int(2), JsValue::Int(2)],
+    ];
+    for c in &commands {
+        ctx.execute(c).unwrap();
+    }
+    let pixel = |ctx: &mut Context2D, x, y| ctx.read_pixels(x, y, 1, 1);
+    assert_eq!(vec![255, 0, 0, 255], pixel(&mut ctx, 10, 10));
+    assert_eq!(vec![0, 0, 0, 0], pixel(&mut ctx, 2, 2));
+    assert_eq!(vec![0, 0, 0, 0], pixel(&mut ctx, 6, 6));
+    assert!(ctx.execute(&[JsValue::String("unknown".to_string())]).is_err());
+}
+
+#[test]
+fn test_arc_sweep() {
+    assert_eq!(PI, arc_sweep(0.0, PI, false));
+    assert_eq!(-PI, arc_sweep(0.0, PI, true));
+    assert_eq!(2.0 * PI, arc_sweep(0.0, 3.0 * PI, false));
+    assert!((arc_sweep(PI * 1.5, 0.0, false) - PI / 2.0).abs() < 1e-5);
+}
@@ -0,0 +1,201 @@
+use accesskit::{Action, DefaultActionVerb, NodeBuilder, Role, Toggled};
+use anyhow::Error;
+use quick_js::JsValue;
+use skia_safe::{Canvas, Path, PaintStyle};
+
+use crate::base::{CheckedChangeDetail, ElementEvent};
+use crate::element::control::{accent_paint, content_rect, emit_keyboard_click, is_activate_key, outline_paint, set_intrinsic_size, thumb_paint, track_paint};
+use crate::element::{ElementBackend, ElementRef};
+use crate::event::{AcceptClickEvent, CheckedChangeBind, KeyDownEvent};
+use crate::js_call;
+
+pub struct Checkbox {
+    element: ElementRef,
+    checked: bool,
+    /// Neither checked nor unchecked, e.g. a "select all" checkbox with some items selected
+    indeterminate: bool,
+    disabled: bool,
+}
+
+impl Checkbox {
+    pub fn set_checked(&mut self, checked: bool) {
+        self.checked = checked;
+        self.indeterminate = false;
+        self.element.mark_dirty(false);
+    }
+
+    pub fn is_checked(&self) -> bool {
+        self.checked
+    }
+
+    pub fn set_indeterminate(&mut self, indeterminate: bool) {
+        self.indeterminate = indeterminate;
+        self.element.mark_dirty(false);
+    }
+
+    pub fn set_disabled(&mut self, disabled: bool) {
+        self.disabled = disabled;
+        self.element.mark_dirty(false);
+    }
+
+    /// Toggle by user, change event is emitted
+    pub fn toggle(&mut self) {
+        if self.disabled {
+            return;
+        }
+        self.set_checked(!self.checked);
+        self.element.emit_checked_change(CheckedChangeDetail {
+            checked: self.checked,
+        });
+    }
+}
+
+impl ElementBackend for Checkbox {
+    fn create(mut element: ElementRef) -> Self {
+        set_intrinsic_size(&mut element, 16.0, 16.0);
+        Self {
+            element,
+            checked: false,
+            indeterminate: false,
+            disabled: false,
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        "Checkbox"
+    }
+
+    fn draw(&self, canvas: &Canvas) {
+        let rect = content_rect(&self.element);
+        let size = rect.width.min(rect.height);
+        if size <= 0.0 {
+            return;
+        }
+        let box_rect = skia_safe::Rect::from_xywh(
+            (rect.width - size) / 2.0,
+            (rect.height - size) / 2.0,
+            size,
+            size,
+        );
+        let radius = size / 6.0;
+        if !self.checked && !self.indeterminate {
+            let mut paint = outline_paint(&self.element, self.disabled);
+            paint.set_style(PaintStyle::Stroke);
+            paint.set_stroke_width(1.0);
+            canvas.draw_round_rect(box_rect.with_inset((0.5, 0.5)), radius, radius, &paint);
+            if self.disabled {
+                canvas.draw_round_rect(box_rect, radius, radius, &track_paint(&self.element, true));
+            }
+            return;
+        }
+        canvas.draw_round_rect(box_rect, radius, radius, &accent_paint(&self.element, self.disabled));
+
+        let mut mark = thumb_paint(&self.element, self.disabled);
+        mark.set_style(PaintStyle::Stroke);
+        mark.set_stroke_width((size / 8.0).max(1.5));
+        let (x, y) = (box_rect.left, box_rect.top);
+        let mut path = Path::new();
+        if self.indeterminate {
+            path.move_to((x + size * 0.25, y + size * 0.5));
+            path.line_to((x + size * 0.75, y + size * 0.5));
+        } else {
+            path.move_to((x + size * 0.22, y + size * 0.52));
+            path.line_to((x + size * 0.42, y + size * 0.72));
+            path.line_to((x + size * 0.78, y + size * 0.3));
+        }
+        canvas.draw_path(&path, &mark);
+    }
+
+    fn set_property(&mut self, p: &str, v: JsValue) {
+        js_call!("checked", bool, self, set_checked, p, v);
+        js_call!("indeterminate", bool, self, set_indeterminate, p, v);
+        js_call!("disabled", bool, self, set_disabled, p, v);
+    }
+
+    fn get_property(&mut self, property_name: &str) -> Result<Option<JsValue>, Error> {
+        match property_name {
+            "checked" => Ok(Some(JsValue::Bool(self.checked))),
+            _ => Ok(None),
+        }
+    }
+
+    fn handle_event_default_behavior(&mut self, event_type: &str, event: &mut ElementEvent) -> bool {
+        let mut activated = false;
+        KeyDownEvent::try_match(event_type, event, |d| {
+            activated = is_activate_key(d);
+        });
+        if activated {
+            emit_keyboard_click(&mut self.element);
+            return true;
+        }
+        event.accept_click(|_| {
+            self.toggle();
+        })
+    }
+
+    fn is_focusable(&self) -> bool {
+        !self.disabled
+    }
+
+    fn get_accessibility_role(&self) -> Role {
+        Role::CheckBox
+    }
+
+    fn build_accessibility_node(&self, builder: &mut NodeBuilder) {
+        builder.set_toggled(if self.indeterminate {
+            Toggled::Mixed
+        } else if self.checked {
+            Toggled::True
+        } else {
+            Toggled::False
+        });
+        if self.disabled {
+            builder.set_disabled();
+        } else {
+            builder.set_default_action_verb(DefaultActionVerb::Click);
+            builder.add_action(Action::Focus);
+            builder.add_action(Action::Default);
+        }
+    }
+}
+
+#[test]
+fn test_checkbox_toggle() {
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use winit::keyboard::NamedKey;
+    use crate::element::control::create_key_down_event;
+    use crate::event::KEY_MOD_SHIFT;
+    use crate::event_loop::run_pending_callbacks;
+
+    let mut el = ElementRef::new(Checkbox::create);
+    let changes = Rc::new(Cell::new(0));
+    {
+        let changes = changes.clone();
+        el.bind_checked_change(move |_, d| {
+            assert!(d.checked);
+            changes.set(changes.get() + 1);
+        });
+    }
+    let checkbox = el.get_backend_mut_as::<Checkbox>();
+    checkbox.set_indeterminate(true);
+    checkbox.toggle();
+    assert!(checkbox.is_checked());
+    assert!(!checkbox.indeterminate);
+    run_pending_callbacks();
+    assert_eq!(1, changes.get());
+
+    // space is handled as clicking
+    let mut el = ElementRef::new(Checkbox::create);
+    let mut event = create_key_down_event(&el, NamedKey::Space, 0);
+    assert!(el.get_backend_mut_as::<Checkbox>().handle_event_default_behavior("keydown", &mut event));
+    run_pending_callbacks();
+    assert!(el.get_backend_as::<Checkbox>().is_checked());
+    let mut event = create_key_down_event(&el, NamedKey::Enter, KEY_MOD_SHIFT);
+    assert!(!el.get_backend_mut_as::<Checkbox>().handle_event_default_behavior("keydown", &mut event));
+
+    let checkbox = el.get_backend_mut_as::<Checkbox>();
+    checkbox.set_disabled(true);
+    checkbox.toggle();
+    assert!(checkbox.is_checked());
+}
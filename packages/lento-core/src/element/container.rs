@@ -36,10 +36,7 @@ impl ElementBackend for Container {
         };
         child.set_parent(Some(ele.clone()));
         self.children.insert(pos as usize, child);
-
-        ele.with_window(|win| {
-            win.mark_dirty(true);
-        });
+        ele.mark_dirty(true);
     }
 
     fn remove_child_view(&mut self, position: u32) {
@@ -55,5 +52,9 @@ impl ElementBackend for Container {
         self.children.clone()
     }
 
+    fn accepts_children(&self) -> bool {
+        true
+    }
+
 }
 
@@ -0,0 +1,114 @@
+use skia_safe::{Color, Paint};
+use winit::keyboard::NamedKey;
+use yoga::{Context, MeasureMode, Node, NodeRef, Size};
+
+use crate::base::{MouseDetail, MouseEventType, Rect};
+use crate::element::ElementRef;
+use crate::event::{ClickEventBind, KeyEventDetail};
+
+/// Size of form controls when width or height is not specified by style
+struct IntrinsicSize {
+    width: f32,
+    height: f32,
+}
+
+extern "C" fn measure_control(node_ref: NodeRef, _width: f32, _mode: MeasureMode, _height: f32, _height_mode: MeasureMode) -> Size {
+    if let Some(ctx) = Node::get_context(&node_ref) {
+        if let Some(size) = ctx.downcast_ref::<IntrinsicSize>() {
+            return Size {
+                width: size.width,
+                height: size.height,
+            };
+        }
+    }
+    Size {
+        width: 0.0,
+        height: 0.0,
+    }
+}
+
+pub fn set_intrinsic_size(element: &mut ElementRef, width: f32, height: f32) {
+    element.layout.set_measure_func(Some(measure_control));
+    element.layout.set_context(Some(Context::new(IntrinsicSize { width, height })));
+    element.mark_dirty(true);
+}
+
+/// Content box relative to the drawing origin of backend
+pub fn content_rect(element: &ElementRef) -> Rect {
+    let bounds = element.get_content_bounds();
+    Rect::new(0.0, 0.0, bounds.width, bounds.height)
+}
+
+fn control_paint(color: Color, disabled: bool) -> Paint {
+    let alpha = if disabled { color.a() / 3 } else { color.a() };
+    let mut paint = Paint::default();
+    paint.set_anti_alias(true);
+    paint.set_color(color.with_a(alpha));
+    paint
+}
+
+/// Paint of the outline of unchecked controls, which follows the `color` style
+pub fn outline_paint(element: &ElementRef, disabled: bool) -> Paint {
+    control_paint(element.layout.computed_style.color, disabled)
+}
+
+/// Paint of the checked or filled part, which follows the `checkedColor` style or the `color` style if not set
+pub fn accent_paint(element: &ElementRef, disabled: bool) -> Paint {
+    let color = element.layout.control.checked_color.unwrap_or(element.layout.computed_style.color);
+    control_paint(color, disabled)
+}
+
+/// Paint of the unfilled track, which follows the `trackColor` style or a translucent accent color if not set
+pub fn track_paint(element: &ElementRef, disabled: bool) -> Paint {
+    if let Some(color) = element.layout.control.track_color {
+        return control_paint(color, disabled);
+    }
+    let mut paint = accent_paint(element, disabled);
+    let alpha = paint.color().a() / 4;
+    paint.set_color(paint.color().with_a(alpha));
+    paint
+}
+
+/// Paint of thumbs and marks drawn on the accent color, e.g. the check mark, which follows the `thumbColor` style
+pub fn thumb_paint(element: &ElementRef, disabled: bool) -> Paint {
+    let color = element.layout.control.thumb_color.unwrap_or(Color::WHITE);
+    control_paint(color, disabled)
+}
+
+/// Space or Enter without modifiers
+pub fn is_activate_key(detail: &KeyEventDetail) -> bool {
+    detail.modifiers == 0 && (detail.named_key == Some(NamedKey::Enter) || detail.named_key == Some(NamedKey::Space))
+}
+
+/// Emit click event at the center of element, so that keyboard activation is handled like clicking
+pub fn emit_keyboard_click(element: &mut ElementRef) {
+    let bounds = element.get_origin_bounds();
+    let (width, height) = element.get_size();
+    element.emit_click(MouseDetail {
+        event_type: MouseEventType::MouseClick,
+        button: 0,
+        offset_x: width / 2.0,
+        offset_y: height / 2.0,
+        frame_x: bounds.x + width / 2.0,
+        frame_y: bounds.y + height / 2.0,
+        screen_x: 0.0,
+        screen_y: 0.0,
+    });
+}
+
+#[cfg(test)]
+pub fn create_key_down_event(target: &ElementRef, named_key: NamedKey, modifiers: u32) -> crate::base::ElementEvent {
+    let detail = KeyEventDetail {
+        modifiers,
+        ctrl_key: false,
+        alt_key: false,
+        meta_key: false,
+        shift_key: false,
+        named_key: Some(named_key),
+        key: None,
+        key_str: None,
+        repeat: false,
+        pressed: true,
+    };
+    crate::base::ElementEvent::new("keydown", detail, target.clone())
+}
@@ -1,21 +1,27 @@
 use crate::string::StringUtils;
 
-#[derive(PartialEq, Eq)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub enum EditOpType {
     Insert,
     Delete,
 }
 
+#[derive(Clone, Debug)]
 pub struct EditOp {
     pub caret: usize,
     pub op: EditOpType,
     pub content: String,
+    /// Ops of the same non-zero batch are undone and redone together
+    pub batch: u32,
 }
 
 pub struct EditHistory {
     max_history: usize,
     history: Vec<EditOp>,
     history_ptr: usize,
+    /// Batch of recording ops, zero if not batching
+    batch: u32,
+    last_batch: u32,
 }
 
 impl EditHistory {
@@ -23,7 +29,17 @@ impl EditHistory {
         EditHistory {
             history: Vec::new(),
             history_ptr: 0,
-            max_history: 20,
+            max_history: 100,
+            batch: 0,
+            last_batch: 0,
+        }
+    }
+
+    pub fn set_max_history(&mut self, max_history: usize) {
+        self.max_history = max_history.max(1);
+        while self.history.len() > self.max_history {
+            self.history.remove(0);
+            self.history_ptr = self.history_ptr.saturating_sub(1);
         }
     }
 
@@ -33,6 +49,7 @@ impl EditHistory {
                 caret,
                 op: EditOpType::Insert,
                 content: content.to_string(),
+                batch: self.batch,
             });
         }
     }
@@ -43,6 +60,7 @@ impl EditHistory {
                 caret,
                 op: EditOpType::Delete,
                 content: content.to_string(),
+                batch: self.batch,
             });
         }
     }
@@ -59,6 +77,7 @@ impl EditHistory {
                     caret: prev_op.caret,
                     op: EditOpType::Delete,
                     content: prev_op.content.to_string(),
+                    batch: prev_op.batch,
                 }
             }
             EditOpType::Delete => {
@@ -66,18 +85,65 @@ impl EditHistory {
                     caret: prev_op.caret,
                     op: EditOpType::Insert,
                     content: prev_op.content.to_string(),
+                    batch: prev_op.batch,
                 }
             }
         };
         Some(op)
     }
 
+    /// Return the undone op to apply again
+    pub fn redo(&mut self) -> Option<EditOp> {
+        let op = self.history.get(self.history_ptr)?.clone();
+        self.history_ptr += 1;
+        Some(op)
+    }
+
+    /// Ops recorded until end_batch are undone as a whole, e.g. replace all
+    pub fn begin_batch(&mut self) {
+        self.last_batch += 1;
+        self.batch = self.last_batch;
+    }
+
+    pub fn end_batch(&mut self) {
+        self.batch = 0;
+    }
+
+    /// Undo the last op, or all ops of its batch, return ops to apply in order
+    pub fn undo_batch(&mut self) -> Vec<EditOp> {
+        let mut ops = Vec::new();
+        while let Some(op) = self.undo() {
+            let batch = op.batch;
+            ops.push(op);
+            if batch == 0 || self.history_ptr == 0 || self.history[self.history_ptr - 1].batch != batch {
+                break;
+            }
+        }
+        ops
+    }
+
+    /// Redo the next op, or all ops of its batch, return ops to apply in order
+    pub fn redo_batch(&mut self) -> Vec<EditOp> {
+        let mut ops = Vec::new();
+        while let Some(op) = self.redo() {
+            let batch = op.batch;
+            ops.push(op);
+            if batch == 0 || self.history.get(self.history_ptr).map(|o| o.batch) != Some(batch) {
+                break;
+            }
+        }
+        ops
+    }
+
     fn merge_input(&mut self, op_caret: usize, content: &str) -> bool {
         if self.history_ptr == 0 || self.history_ptr != self.history.len() {
             return false;
         }
         let last_op = unsafe { self.history.get_unchecked_mut(self.history_ptr - 1) };
-        if last_op.op == EditOpType::Insert && last_op.caret + last_op.content.chars_count() == op_caret {
+        if last_op.batch != self.batch {
+            return false;
+        }
+        if last_op.op == EditOpType::Insert && last_op.caret + last_op.content.graphemes_count() == op_caret {
             last_op.content.push_str(content);
             true
         } else {
@@ -90,13 +156,13 @@ impl EditHistory {
             return false;
         }
         let last_op = unsafe { self.history.get_unchecked_mut(self.history_ptr - 1) };
-        if last_op.op != EditOpType::Delete {
+        if last_op.op != EditOpType::Delete || last_op.batch != self.batch {
             return false;
         }
-        if last_op.caret + last_op.content.chars_count() == op_caret {
+        if last_op.caret + last_op.content.graphemes_count() == op_caret {
             last_op.content.push_str(content);
             true
-        } else if op_caret + content.chars_count() == last_op.caret {
+        } else if op_caret + content.graphemes_count() == last_op.caret {
             last_op.caret = op_caret;
             let mut content = content.to_string();
             content.push_str(&last_op.content);
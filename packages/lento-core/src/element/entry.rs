@@ -1,23 +1,37 @@
 use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
+use std::sync::{Arc, Mutex};
 use std::string::ToString;
+use std::time::{Duration, Instant};
+use accesskit::{Action, ActionData, ActionRequest, NodeBuilder, Role};
 use anyhow::Error;
-use clipboard::{ClipboardContext, ClipboardProvider};
 use quick_js::JsValue;
-use skia_safe::{Canvas, Font, Paint};
-use skia_safe::textlayout::{TextAlign};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use skia_safe::{Canvas, Color, Font, Paint};
+use unicode_segmentation::UnicodeSegmentation;
+use skia_safe::textlayout::{TextAlign, TextDirection};
 use winit::keyboard::NamedKey;
 use winit::window::CursorIcon;
-use crate::base::{CaretDetail, ElementEvent, MouseDetail, MouseEventType, Rect, TextChangeDetail, TextUpdateDetail};
+use crate::base::{BeforeInputDetail, CaretDetail, ElementEvent, MouseDetail, MouseEventType, Rect, TextChangeDetail, TextUpdateDetail};
 use crate::element::{ElementBackend, ElementRef};
-use crate::element::text::{AtomOffset, Text as Label};
+use crate::color::parse_hex_color;
+use crate::element::text::{parse_align, AtomOffset, Text as Label};
+use crate::element::text::highlighter::{RegexHighlighter, SyntaxHighlighter};
+use crate::element::text::search::SearchQuery;
+use crate::element::text::skia_text_paragraph::SkiaTextParagraph;
 use crate::number::DeNan;
-use crate::{js_call, match_event, match_event_type, timer};
+use crate::{async_runtime, js_call, js_call_rust, match_event, match_event_type, timer};
 use crate::app::AppEvent;
-use crate::element::edit_history::{EditHistory, EditOpType};
-use crate::element::text::text_paragraph::Line;
-use crate::event::{CaretEventBind, KEY_MOD_CTRL, KEY_MOD_SHIFT, KeyDownEvent, KeyEventDetail};
-use crate::event_loop::send_event;
+use crate::element::edit_history::{EditHistory, EditOp, EditOpType};
+use crate::element::keymap::{EditCommand, Keymap, Movement, Shortcut};
+use crate::element::text::text_paragraph::{Line, TextParams};
+use crate::event::{BeforeInputEvent, CaretEventBind, KEY_MOD_SHIFT, KeyDownEvent, KeyEventDetail};
+use crate::event_loop::{create_event_loop_callback, send_event};
+use crate::ext::ext_spellcheck::{get_dictionary_version, get_spell_checker};
+use crate::js::js_value_util::SerializeToJsValue;
+use crate::ext::ext_clipboard::{clipboard_get_text, clipboard_set_text};
 use crate::string::StringUtils;
 use crate::timer::TimerHandle;
 
@@ -25,6 +39,54 @@ const COPY_KEY: &str = "\x03";
 const PASTE_KEY: &str = "\x16";
 const KEY_BACKSPACE: &str = "\x08";
 const KEY_ENTER: &str = "\x0D";
+const MULTI_CLICK_INTERVAL: Duration = Duration::from_millis(500);
+const PASSWORD_MASK: char = '•';
+const DEFAULT_TAB_SIZE: usize = 4;
+const BRACKET_PAIRS: [(char, char); 3] = [('(', ')'), ('[', ']'), ('{', '}')];
+/// Atoms scanned at most on each side of caret to find the matched bracket
+const BRACKET_SCAN_LIMIT: usize = 10000;
+/// Delay of spell check after editing
+const SPELL_CHECK_DELAY: u64 = 300;
+
+/// Style of placeholder, unset fields follow the text of entry
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaceholderStyle {
+    /// hex color
+    pub color: Option<String>,
+    pub font_size: Option<f32>,
+    pub line_height: Option<f32>,
+    /// "left", "center" or "right"
+    pub align: Option<String>,
+}
+
+/// Restrict chars could be input
+pub enum InputFilter {
+    /// digits only
+    Numeric,
+    /// regex matched against each inserted char
+    Pattern(Regex),
+}
+
+impl InputFilter {
+    pub fn parse(filter: &str) -> Result<Self, Error> {
+        if filter == "numeric" {
+            Ok(InputFilter::Numeric)
+        } else {
+            Ok(InputFilter::Pattern(Regex::new(filter)?))
+        }
+    }
+
+    pub fn accept(&self, c: char) -> bool {
+        match self {
+            InputFilter::Numeric => c.is_ascii_digit(),
+            InputFilter::Pattern(regex) => {
+                let mut buf = [0u8; 4];
+                regex.is_match(c.encode_utf8(&mut buf))
+            }
+        }
+    }
+}
 
 pub struct Entry {
     base: Label,
@@ -41,17 +103,89 @@ pub struct Entry {
     element: ElementRef,
     vertical_caret_moving_coord_x: f32,
     edit_history: EditHistory,
+    /// composing text of input method, inserted after caret but not a part of value
+    preedit_text: String,
+    /// cursor offset in preedit text, None means hidden
+    preedit_cursor: Option<usize>,
+    keymap: Keymap,
+    /// (time, frame_x, frame_y) of last mousedown, for double and triple click
+    last_mouse_down: Option<(Instant, f32, f32)>,
+    click_count: u32,
+    password: bool,
+    placeholder: String,
+    placeholder_color: Color,
+    placeholder_style: PlaceholderStyle,
+    max_length: Option<usize>,
+    read_only: bool,
+    disabled: bool,
+    input_filter: Option<InputFilter>,
+    /// Tab inserts tab char, new line is auto indented, brackets are matched and current line is highlighted
+    code_mode: bool,
+    /// Columns of a tab stop, tabs are expanded to it in code mode and indented with spaces otherwise
+    tab_size: usize,
+    /// (bracket near caret, matched bracket)
+    matched_brackets: Option<(AtomOffset, AtomOffset)>,
+    current_line_color: Color,
+    bracket_color: Color,
+    spell_check: bool,
+    /// Misspelled byte ranges of checked lines, filled by the checking thread
+    spell_check_cache: Arc<Mutex<HashMap<String, Vec<(usize, usize)>>>>,
+    /// Dictionary version of the cache
+    spell_check_version: u32,
+    spell_check_timer: Option<TimerHandle>,
 }
 
 pub type TextChangeHandler = dyn FnMut(&str);
 
+#[derive(Serialize)]
+pub struct MisspelledWord {
+    pub start: AtomOffset,
+    pub end: AtomOffset,
+    pub word: String,
+}
+
 impl Entry {
 
     pub fn get_text(&self) -> String {
-        self.base.get_text()
+        let text = self.base.get_text();
+        if self.preedit_text.is_empty() {
+            return text;
+        }
+        let start = text.grapheme_byte_index(self.caret);
+        let end = text.grapheme_byte_index(self.caret + self.preedit_text.graphemes_count());
+        let mut result = text[..start].to_string();
+        result.push_str(&text[end..]);
+        result
+    }
+
+    pub fn set_preedit(&mut self, text: &str, cursor: Option<(usize, usize)>) {
+        if self.preedit_text.is_empty() && !text.is_empty() && self.base.get_selection().is_some() {
+            // composition replaces the selected text
+            self.insert_text("", self.caret, true);
+        }
+        self.clear_preedit();
+        if !text.is_empty() {
+            self.base.insert_text(self.caret, text);
+            self.preedit_text = text.to_string();
+            self.preedit_cursor = cursor.and_then(|(_, end)| text.get(..end)).map(|s| s.graphemes_count());
+        }
+        self.element.mark_dirty(true);
     }
 
-    pub fn with_paragraph<R, F: FnOnce(&mut Vec<Line>) -> R>(&self, callback: F) -> R {
+    fn clear_preedit(&mut self) {
+        if !self.preedit_text.is_empty() {
+            let len = self.preedit_text.graphemes_count();
+            self.base.delete_text(self.caret, self.caret + len);
+            self.preedit_text.clear();
+        }
+        self.preedit_cursor = None;
+    }
+
+    fn is_composing(&self) -> bool {
+        !self.preedit_text.is_empty()
+    }
+
+    pub fn with_paragraph<R, F: FnOnce(&mut [Line]) -> R>(&self, callback: F) -> R {
         self.base.with_lines_mut(callback)
     }
 
@@ -60,6 +194,8 @@ impl Entry {
         if text != old_text {
             self.base.set_text(text);
             self.update_caret_value(self.base.get_atom_count() - 1, false);
+            self.update_misspelled();
+            self.schedule_spell_check();
         }
     }
 
@@ -92,31 +228,608 @@ impl Entry {
         self.update_caret_value(atom_offset, false);
     }
 
-    fn move_caret(&mut self, delta: isize) {
-        let max_atom_offset = (self.base.get_atom_count() - 1) as isize;
-        let new_atom_offset = (self.caret as isize + delta).clamp(0, max_atom_offset) as AtomOffset;
-        self.update_caret_value(new_atom_offset, false);
+    pub fn set_password(&mut self, password: bool) {
+        self.password = password;
+        self.base.set_mask(if password { Some(PASSWORD_MASK) } else { None });
+        self.schedule_spell_check();
+    }
+
+    pub fn set_placeholder(&mut self, placeholder: String) {
+        self.placeholder = placeholder;
+        self.element.mark_dirty(false);
+    }
+
+    pub fn set_placeholder_color(&mut self, color: String) {
+        if let Some(c) = parse_hex_color(color.trim_start_matches('#')) {
+            self.placeholder_color = c;
+            self.element.mark_dirty(false);
+        }
+    }
+
+    pub fn set_placeholder_style(&mut self, style: PlaceholderStyle) {
+        if let Some(color) = &style.color {
+            self.set_placeholder_color(color.clone());
+        }
+        self.placeholder_style = style;
+        self.element.mark_dirty(false);
+    }
+
+    fn get_placeholder_params(&self) -> TextParams {
+        let mut params = self.base.get_text_params().clone();
+        params.mask = None;
+        params.highlighter = None;
+        params.paint.set_color(self.placeholder_color);
+        let style = &self.placeholder_style;
+        if let Some(font_size) = style.font_size {
+            params.font.set_size(font_size);
+        }
+        if let Some(line_height) = style.line_height {
+            params.line_height = Some(line_height);
+        }
+        if let Some(align) = &style.align {
+            params.align = parse_align(align);
+        }
+        params
+    }
+
+    /// Negative value means unlimited
+    pub fn set_max_length(&mut self, max_length: i32) {
+        self.max_length = if max_length >= 0 { Some(max_length as usize) } else { None };
+    }
+
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+
+    pub fn set_disabled(&mut self, disabled: bool) {
+        self.disabled = disabled;
+        self.element.set_cursor(if disabled { CursorIcon::Default } else { CursorIcon::Text });
+        if disabled {
+            self.base.unselect();
+            self.handle_blur();
+        }
+    }
+
+    /// "numeric" for digits only, other value is treated as regex for each char, empty value to remove filter
+    pub fn set_input_filter(&mut self, filter: String) {
+        if filter.is_empty() {
+            self.input_filter = None;
+            return;
+        }
+        match InputFilter::parse(&filter) {
+            Ok(f) => self.input_filter = Some(f),
+            Err(e) => eprintln!("invalid input filter:{:?}", e),
+        }
+    }
+
+    fn is_input_allowed(&self) -> bool {
+        !self.read_only && !self.disabled
+    }
+
+    /// Apply input filter and max length
+    fn filter_input(&self, input: &str) -> String {
+        let mut text: String = match &self.input_filter {
+            Some(f) => input.chars().filter(|c| f.accept(*c)).collect(),
+            None => input.to_string(),
+        };
+        if let Some(max_length) = self.max_length {
+            let selected = self.base.get_selection().map(|(start, end)| end - start).unwrap_or(0);
+            let remaining = max_length.saturating_sub(self.get_text().graphemes_count() - selected);
+            if text.graphemes_count() > remaining {
+                text = text.grapheme_substring(0, remaining).to_string();
+            }
+        }
+        text
+    }
+
+    /// Insert text at caret, beforeinput is emitted first if someone listen to it
+    fn request_input(&mut self, input: &str) {
+        if !self.is_input_allowed() {
+            return;
+        }
+        let text = self.filter_input(input);
+        if text.is_empty() && !input.is_empty() {
+            return;
+        }
+        if self.element.has_event_listener("beforeinput") {
+            let event = ElementEvent::new("beforeinput", self.create_before_input_detail(text), self.element.clone());
+            self.element.emit_event("beforeinput", event);
+        } else {
+            self.insert_text(&text, self.caret, true);
+        }
+    }
+
+    /// Insert text without emitting beforeinput, e.g. text rewritten in beforeinput handler
+    pub fn insert(&mut self, text: String) {
+        if !self.is_input_allowed() {
+            return;
+        }
+        let text = self.filter_input(&text);
+        self.insert_text(&text, self.caret, true);
+    }
+
+    pub fn set_code_mode(&mut self, code_mode: bool) {
+        self.code_mode = code_mode;
+        self.base.set_tab_size(if code_mode { Some(self.tab_size) } else { None });
+        self.update_matched_brackets();
+    }
+
+    pub fn set_tab_size(&mut self, tab_size: usize) {
+        self.tab_size = usize::max(tab_size, 1);
+        if self.code_mode {
+            self.base.set_tab_size(Some(self.tab_size));
+        }
+    }
+
+    pub fn set_highlighter(&mut self, highlighter: Option<Rc<dyn SyntaxHighlighter>>) {
+        self.base.set_highlighter(highlighter);
+    }
+
+    /// Rules are (regex, hex color) pairs, empty rules to remove highlighter
+    pub fn set_syntax_rules(&mut self, rules: Vec<(String, String)>) {
+        if rules.is_empty() {
+            self.set_highlighter(None);
+            return;
+        }
+        match RegexHighlighter::new(&rules) {
+            Ok(h) => self.set_highlighter(Some(Rc::new(h))),
+            Err(e) => eprintln!("invalid syntax rules:{:?}", e),
+        }
+    }
+
+    /// Select the match after caret, or the match before caret if backward
+    pub fn goto_next_match(&mut self, backward: bool) -> Option<(AtomOffset, AtomOffset)> {
+        let offset = match self.base.get_selection() {
+            Some((start, end)) => if backward { start } else { end },
+            None => self.caret,
+        };
+        let index = self.base.find_match_index(offset, backward)?;
+        let (start, end) = self.base.set_active_match(Some(index))?;
+        self.select_range(start, end);
+        Some((start, end))
+    }
+
+    /// "next" or "previous"
+    pub fn goto_match(&mut self, direction: String) {
+        self.goto_next_match(direction == "previous");
+    }
+
+    /// Replace the selected match and select the next one, only select the next one if no match is selected
+    pub fn replace_match(&mut self, replacement: String) {
+        if !self.is_input_allowed() {
+            return;
+        }
+        let Some((start, end)) = self.base.get_active_match().filter(|m| Some(*m) == self.base.get_selection()) else {
+            self.goto_next_match(false);
+            return;
+        };
+        let Some(search) = self.base.get_search() else { return };
+        let replacement = search.expand_replacement(&self.base.get_text(), (start, end), &replacement);
+        self.edit_history.begin_batch();
+        self.insert_text(&replacement, start, true);
+        self.edit_history.end_batch();
+        self.goto_next_match(false);
+    }
+
+    /// Replace all matches, which could be undone at once, return count of replaced matches
+    pub fn replace_all(&mut self, replacement: String) -> usize {
+        if !self.is_input_allowed() {
+            return 0;
+        }
+        let Some(search) = self.base.get_search() else { return 0 };
+        let text = self.base.get_text();
+        let matches = self.base.get_search_matches();
+        let replacements: Vec<String> = matches.iter()
+            .map(|m| search.expand_replacement(&text, *m, &replacement))
+            .collect();
+        self.base.unselect();
+        self.edit_history.begin_batch();
+        // replace from the end so that offsets of remaining matches are kept
+        for (&(start, end), replacement) in matches.iter().zip(replacements).rev() {
+            self.edit_history.record_delete(start, text.grapheme_substring(start, end - start));
+            self.base.delete_text(start, end);
+            if !replacement.is_empty() {
+                self.edit_history.record_input(start, &replacement);
+                self.base.insert_text(start, &replacement);
+            }
+        }
+        self.edit_history.end_batch();
+        if !matches.is_empty() {
+            let caret = usize::min(self.caret, self.base.get_atom_count() - 1);
+            self.update_caret_value(caret, false);
+            self.emit_text_change();
+        }
+        matches.len()
+    }
+
+    /// Underline misspelled words with the loaded dictionary, which is disabled for password
+    pub fn set_spell_check(&mut self, spell_check: bool) {
+        self.spell_check = spell_check;
+        self.schedule_spell_check();
     }
 
-    fn move_caret_vertical(&mut self, is_up: bool) {
-        let (current_row, current_col) = self.base.get_location_by_atom_offset(self.caret);
-        let line_height = self.with_paragraph(|ps| {
-            let p = unsafe { ps.get_unchecked_mut(current_row) };
-            p.get_soft_line_height(current_col)
+    fn is_spell_check_enabled(&self) -> bool {
+        self.spell_check && !self.password
+    }
+
+    fn schedule_spell_check(&mut self) {
+        if !self.is_spell_check_enabled() {
+            self.spell_check_timer = None;
+            self.base.set_misspelled(Vec::new());
+            return;
+        }
+        let mut el = self.element.clone();
+        self.spell_check_timer = Some(timer::set_timeout(move || {
+            el.get_backend_mut_as::<Entry>().check_spelling();
+        }, SPELL_CHECK_DELAY));
+    }
+
+    /// Check again with the loaded dictionary, called when a dictionary is loaded or unloaded
+    pub(crate) fn handle_dictionary_change(&mut self) {
+        if self.spell_check_version != get_dictionary_version() {
+            self.schedule_spell_check();
+        }
+    }
+
+    /// Check lines which are not in cache on a worker thread, only changed lines are checked after editing
+    fn check_spelling(&mut self) {
+        let version = get_dictionary_version();
+        let Some(spell_checker) = get_spell_checker().filter(|_| self.is_spell_check_enabled()) else {
+            self.spell_check_version = version;
+            self.base.set_misspelled(Vec::new());
+            return;
+        };
+        let lines: HashSet<String> = self.with_paragraph(|lines| {
+            lines.iter().map(|l| l.get_text().to_string()).collect()
+        });
+        let unchecked: Vec<String> = {
+            let mut cache = self.spell_check_cache.lock().unwrap();
+            if self.spell_check_version != version {
+                cache.clear();
+                self.spell_check_version = version;
+            }
+            cache.retain(|line, _| lines.contains(line));
+            lines.into_iter().filter(|l| !cache.contains_key(l)).collect()
+        };
+        if unchecked.is_empty() {
+            self.update_misspelled();
+            return;
+        }
+        let cache = self.spell_check_cache.clone();
+        let mut el = self.element.clone();
+        let callback = create_event_loop_callback(move || {
+            let entry = el.get_backend_mut_as::<Entry>();
+            if entry.spell_check_version == get_dictionary_version() {
+                entry.update_misspelled();
+            } else {
+                entry.schedule_spell_check();
+            }
+        });
+        async_runtime::spawn(async move {
+            let _ = tokio::task::spawn_blocking(move || {
+                for line in unchecked {
+                    let misspelled = spell_checker.find_misspelled(&line);
+                    cache.lock().unwrap().insert(line, misspelled);
+                }
+            }).await;
+            callback.call();
+        });
+    }
+
+    /// Underline misspelled words of checked lines
+    fn update_misspelled(&mut self) {
+        if !self.is_spell_check_enabled() {
+            return;
+        }
+        let cache = self.spell_check_cache.clone();
+        let cache = cache.lock().unwrap();
+        let misspelled = self.with_paragraph(|lines| {
+            let mut result = Vec::new();
+            let mut line_offset = 0;
+            for line in lines.iter() {
+                let text = line.get_text();
+                for (start, end) in cache.get(text).into_iter().flatten() {
+                    let begin = line_offset + text[..*start].graphemes_count();
+                    result.push((begin, begin + text[*start..*end].graphemes_count()));
+                }
+                line_offset += line.atom_count;
+            }
+            result
         });
+        self.base.set_misspelled(misspelled);
+    }
 
-        let (caret_coord, _) = self.base.get_caret_offset_coordinate(self.caret);
-        if self.vertical_caret_moving_coord_x <= 0.0 {
-            let (caret_start, _) = self.base.get_caret_offset_coordinate(self.caret);
-            self.vertical_caret_moving_coord_x = caret_start.0
+    /// Misspelled word at caret, for suggestions
+    pub fn get_misspelled_word(&self) -> Option<MisspelledWord> {
+        let (start, end) = *self.base.get_misspelled().iter().find(|(start, end)| *start <= self.caret && self.caret <= *end)?;
+        let word = self.get_text().grapheme_substring(start, end - start).to_string();
+        Some(MisspelledWord { start, end, word })
+    }
+
+    /// New line with the indent of current line, indented further after open bracket
+    fn get_new_line_text(&self) -> String {
+        let (row, col) = self.base.get_location_by_atom_offset(self.caret);
+        let line_text = self.with_paragraph(|lines| lines[row].get_text().to_string());
+        let before_caret = line_text.grapheme_substring(0, col);
+        let mut result = "\n".to_string();
+        result.extend(before_caret.chars().take_while(|c| *c == ' ' || *c == '\t'));
+        if before_caret.trim_end().ends_with(|c| BRACKET_PAIRS.iter().any(|(open, _)| *open == c)) {
+            result.push('\t');
         }
-        let new_coord_y = if is_up {
-            caret_coord.1 - line_height
+        result
+    }
+
+    fn update_matched_brackets(&mut self) {
+        let matched = if self.code_mode {
+            [Some(self.caret), self.caret.checked_sub(1)].into_iter().flatten()
+                .find_map(|pos| self.find_matched_bracket_around(pos).map(|m| (pos, m)))
         } else {
-            caret_coord.1 + line_height
+            None
+        };
+        if matched != self.matched_brackets {
+            self.matched_brackets = matched;
+            self.element.mark_dirty(false);
+        }
+    }
+
+    /// Only lines within BRACKET_SCAN_LIMIT atoms around offset are scanned
+    fn find_matched_bracket_around(&self, offset: AtomOffset) -> Option<AtomOffset> {
+        let (row, col) = self.base.get_location_by_atom_offset(offset);
+        self.base.with_lines_mut(|lines| {
+            let line_start = offset - col;
+            let (mut begin_row, mut begin) = (row, line_start);
+            while begin_row > 0 && offset - begin < BRACKET_SCAN_LIMIT {
+                begin_row -= 1;
+                begin -= lines[begin_row].atom_count;
+            }
+            let (mut end_row, mut end) = (row, line_start + lines.get(row)?.atom_count);
+            while end_row + 1 < lines.len() && end - offset < BRACKET_SCAN_LIMIT {
+                end_row += 1;
+                end += lines[end_row].atom_count;
+            }
+            let chars: Vec<char> = lines[begin_row..=end_row].iter().flat_map(|l| atom_chars(l.get_text())).collect();
+            find_matched_bracket(&chars, offset - begin).map(|m| begin + m)
+        })
+    }
+
+    pub fn set_current_line_color(&mut self, color: String) {
+        if let Some(color) = parse_hex_color(&color) {
+            self.current_line_color = color;
+            self.element.mark_dirty(false);
+        }
+    }
+
+    pub fn set_bracket_color(&mut self, color: String) {
+        if let Some(color) = parse_hex_color(&color) {
+            self.bracket_color = color;
+            self.element.mark_dirty(false);
+        }
+    }
+
+    pub fn set_key_binding(&mut self, binding: (String, String)) {
+        let (shortcut, command) = binding;
+        let shortcut = match Shortcut::parse(&shortcut) {
+            Some(s) => s,
+            None => {
+                eprintln!("invalid shortcut:{}", shortcut);
+                return;
+            }
         };
-        let new_coord = (self.vertical_caret_moving_coord_x, new_coord_y);
-        self.update_caret_by_offset_coordinate(new_coord.0, new_coord.1, true);
+        if command.is_empty() {
+            self.keymap.unbind(&shortcut);
+        } else if let Some(cmd) = EditCommand::parse(&command) {
+            self.keymap.bind(shortcut, cmd);
+        } else {
+            eprintln!("invalid edit command:{}", command);
+        }
+    }
+
+    pub fn get_keymap_mut(&mut self) -> &mut Keymap {
+        &mut self.keymap
+    }
+
+    /// Return false if the command is not applicable, e.g. insert new line in single line mode
+    pub fn execute_command(&mut self, command: EditCommand) -> bool {
+        match command {
+            EditCommand::Move(movement) => self.move_by(movement, false),
+            EditCommand::Select(movement) => self.move_by(movement, true),
+            EditCommand::DeleteBackward => self.delete_by(Movement::Backward),
+            EditCommand::DeleteForward => self.delete_by(Movement::Forward),
+            EditCommand::DeleteWordBackward => self.delete_by(Movement::WordLeft),
+            EditCommand::DeleteWordForward => self.delete_by(Movement::WordRight),
+            EditCommand::SelectAll => self.select_all(),
+            EditCommand::Copy => {
+                self.copy_selection();
+            }
+            EditCommand::Cut => {
+                if self.copy_selection() {
+                    self.handle_input("");
+                }
+            }
+            EditCommand::Paste => {
+                match clipboard_get_text() {
+                    Ok(text) => self.handle_input(&text),
+                    Err(e) => println!("failed to read clipboard:{:?}", e),
+                }
+            }
+            EditCommand::Undo => {
+                if self.is_input_allowed() {
+                    self.undo();
+                }
+            }
+            EditCommand::Redo => {
+                if self.is_input_allowed() {
+                    self.redo();
+                }
+            }
+            EditCommand::InsertNewLine => {
+                if !self.multiple_line {
+                    return false;
+                }
+                if self.code_mode {
+                    let text = self.get_new_line_text();
+                    self.handle_input(&text);
+                } else {
+                    self.handle_input("\n");
+                }
+            }
+            EditCommand::FindNext => return self.goto_next_match(false).is_some(),
+            EditCommand::FindPrevious => return self.goto_next_match(true).is_some(),
+            EditCommand::InsertTab => {
+                if !self.multiple_line {
+                    return false;
+                }
+                if self.code_mode {
+                    self.handle_input("\t");
+                } else {
+                    // tab chars are not expanded outside code mode, indent to the next tab stop with spaces
+                    let (_, column) = self.base.get_location_by_atom_offset(self.caret);
+                    let spaces = self.tab_size - column % self.tab_size;
+                    self.handle_input(&" ".repeat(spaces));
+                }
+            }
+        }
+        true
+    }
+
+    fn move_by(&mut self, movement: Movement, extend_selection: bool) {
+        let is_vertical = matches!(movement, Movement::Up | Movement::Down | Movement::PageUp | Movement::PageDown);
+        let selection = self.base.get_selection();
+        if extend_selection {
+            let anchor = match selection {
+                Some((start, end)) => if self.caret == start { end } else { start },
+                None => self.caret,
+            };
+            let target = self.get_movement_target(movement);
+            self.selecting_begin = Some(anchor);
+            self.update_caret_value(target, is_vertical);
+            self.selecting_begin = None;
+        } else {
+            // collapse selection like native text fields
+            let rtl = self.base.get_direction() == TextDirection::RTL;
+            let target = match (selection, movement) {
+                (Some((start, _)), Movement::Backward) => start,
+                (Some((_, end)), Movement::Forward) => end,
+                (Some((start, end)), Movement::Left) => if rtl { end } else { start },
+                (Some((start, end)), Movement::Right) => if rtl { start } else { end },
+                _ => self.get_movement_target(movement),
+            };
+            if selection.is_some() {
+                self.base.unselect();
+            }
+            self.update_caret_value(target, is_vertical);
+        }
+    }
+
+    fn get_movement_target(&mut self, movement: Movement) -> AtomOffset {
+        let max_atom_offset = self.base.get_atom_count() - 1;
+        match movement {
+            Movement::Backward => self.caret.saturating_sub(1),
+            Movement::Forward => usize::min(self.caret + 1, max_atom_offset),
+            Movement::Left => self.base.get_visual_caret_target(self.caret, false),
+            Movement::Right => self.base.get_visual_caret_target(self.caret, true),
+            Movement::WordLeft => {
+                let chars = atom_chars(&self.get_text());
+                prev_word_boundary(&chars, self.caret)
+            }
+            Movement::WordRight => {
+                let chars = atom_chars(&self.get_text());
+                usize::min(next_word_boundary(&chars, self.caret), max_atom_offset)
+            }
+            Movement::LineStart => {
+                let (row, _) = self.base.get_location_by_atom_offset(self.caret);
+                self.base.get_atom_offset_by_location((row, 0))
+            }
+            Movement::LineEnd => {
+                let (row, _) = self.base.get_location_by_atom_offset(self.caret);
+                self.base.get_atom_offset_by_location((row, usize::MAX))
+            }
+            Movement::DocumentStart => 0,
+            Movement::DocumentEnd => max_atom_offset,
+            Movement::Up | Movement::Down | Movement::PageUp | Movement::PageDown => {
+                if !self.multiple_line {
+                    return match movement {
+                        Movement::Up | Movement::PageUp => 0,
+                        _ => max_atom_offset,
+                    };
+                }
+                let (current_row, current_col) = self.base.get_location_by_atom_offset(self.caret);
+                let line_height = self.with_paragraph(|ps| {
+                    let p = unsafe { ps.get_unchecked_mut(current_row) };
+                    p.get_soft_line_height(current_col)
+                });
+                let page_height = f32::max(self.element.layout.get_layout_height().de_nan(0.0), line_height);
+                let delta = match movement {
+                    Movement::Up => -line_height,
+                    Movement::Down => line_height,
+                    Movement::PageUp => -page_height,
+                    _ => page_height,
+                };
+                let (caret_coord, _) = self.base.get_caret_offset_coordinate(self.caret);
+                if self.vertical_caret_moving_coord_x <= 0.0 {
+                    self.vertical_caret_moving_coord_x = caret_coord.0;
+                }
+                let position = (self.vertical_caret_moving_coord_x, caret_coord.1 + delta);
+                self.base.get_atom_offset_by_coordinate(position)
+            }
+        }
+    }
+
+    fn delete_by(&mut self, movement: Movement) {
+        if self.base.get_selection().is_none() {
+            let target = self.get_movement_target(movement);
+            let start = AtomOffset::min(self.caret, target);
+            let end = AtomOffset::max(self.caret, target);
+            if start == end {
+                return;
+            }
+            self.base.select(start, end);
+        }
+        self.handle_input("");
+    }
+
+    fn select_all(&mut self) {
+        let end = self.base.get_atom_count() - 1;
+        self.base.unselect();
+        self.update_caret_value(end, false);
+        self.base.set_selection((0, end));
+    }
+
+    /// Return false if nothing selected
+    fn copy_selection(&mut self) -> bool {
+        if self.password {
+            return false;
+        }
+        if let Some(sel) = self.base.get_selection_text() {
+            if let Err(e) = clipboard_set_text(sel) {
+                println!("failed to write clipboard:{:?}", e);
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    fn select_range(&mut self, start: AtomOffset, end: AtomOffset) {
+        self.selecting_begin = None;
+        self.base.unselect();
+        self.update_caret_value(end, false);
+        self.base.set_selection((start, end));
+    }
+
+    fn update_click_count(&mut self, event: &MouseDetail) -> u32 {
+        let now = Instant::now();
+        let is_multi_click = match self.last_mouse_down {
+            Some((time, x, y)) => {
+                now.duration_since(time) < MULTI_CLICK_INTERVAL
+                    && f32::abs(event.frame_x - x) < 4.0
+                    && f32::abs(event.frame_y - y) < 4.0
+            }
+            None => false,
+        };
+        self.click_count = if is_multi_click { u32::min(self.click_count + 1, 3) } else { 1 };
+        self.last_mouse_down = Some((now, event.frame_x, event.frame_y));
+        self.click_count
     }
 
     fn update_caret_by_offset_coordinate(&mut self, x: f32, y: f32, is_kb_vertical: bool) {
@@ -152,19 +865,24 @@ impl Entry {
         }
     }
 
-    fn emit_caret_change(&mut self) {
-        let mut ele = self.element.clone();
-        let origin_bounds = self.element.get_origin_bounds();
-        let (border_top, _, _, border_left) = self.element.get_padding();
-
-        let caret = self.caret;
+    /// Bounds of the caret at `caret` relative to the content box and to the element
+    fn get_caret_rect(&self, caret: usize) -> (Rect, Rect) {
+        // content is painted inside both border and padding, the IME candidate window should follow it
+        let (border_top, _, _, border_left) = self.element.get_border_width();
+        let (padding_top, _, _, padding_left) = self.element.get_padding();
         let (start, end) = self.base.get_caret_offset_coordinate(caret);
-        // bounds relative to entry
         let bounds = Rect::new(start.0, start.1, 1.0, end.1 - start.1);
-        let origin_bounds = bounds
-            .translate(origin_bounds.x + border_left, origin_bounds.y + border_top);
+        let element_bounds = bounds.translate(border_left + padding_left, border_top + padding_top);
+        (bounds, element_bounds)
+    }
 
-        ele.emit_caret_change(CaretDetail::new(caret, origin_bounds, bounds));
+    fn emit_caret_change(&mut self) {
+        let mut ele = self.element.clone();
+        let (bounds, element_bounds) = self.get_caret_rect(self.caret);
+        let origin_bounds = self.element.get_origin_bounds();
+        let origin_bounds = element_bounds.translate(origin_bounds.x, origin_bounds.y);
+        ele.emit_caret_change(CaretDetail::new(self.caret, origin_bounds, bounds));
+        self.update_matched_brackets();
     }
 
     fn caret_tick(caret_visible: Rc<Cell<bool>>, mut context: ElementRef) {
@@ -181,6 +899,7 @@ impl Entry {
     }
 
     fn handle_blur(&mut self) {
+        self.clear_preedit();
         self.focusing = false;
         self.caret_timer_handle = None;
         self.caret_visible.set(false);
@@ -202,10 +921,26 @@ impl Entry {
     }
 
     fn handle_mouse_event(&mut self, event: &MouseDetail) {
+        if self.disabled {
+            return;
+        }
         match event.event_type {
             MouseEventType::MouseDown => {
+                let click_count = self.update_click_count(event);
                 self.update_caret_by_offset_coordinate(event.offset_x, event.offset_y, false);
-                self.begin_select();
+                match click_count {
+                    1 => self.begin_select(),
+                    2 => {
+                        let chars = atom_chars(&self.get_text());
+                        let (start, end) = word_range_at(&chars, self.caret);
+                        self.select_range(start, end);
+                    }
+                    _ => {
+                        let start = self.get_movement_target(Movement::LineStart);
+                        let end = self.get_movement_target(Movement::LineEnd);
+                        self.select_range(start, end);
+                    }
+                }
             }
             MouseEventType::MouseMove => {
                 if self.selecting_begin.is_some() {
@@ -219,97 +954,56 @@ impl Entry {
         }
     }
 
-    fn handle_key_down(&mut self, event: &KeyEventDetail) {
-        if event.modifiers == 0 {
-            if let Some(nk) = &event.named_key {
-                match nk {
-                    NamedKey::Backspace => {
-                        if self.base.get_selection().is_none() {
-                            if self.caret > 0 {
-                                self.base.select(self.caret - 1, self.caret);
-                            }
-                        }
-                        self.handle_input("");
-                    },
-                    NamedKey::Enter => {
-                        if self.multiple_line {
-                            self.handle_input("\n");
-                        }
-                    },
-                    NamedKey::ArrowLeft => {
-                        self.move_caret(-1);
-                    },
-                    NamedKey::ArrowRight => {
-                        self.move_caret(1);
-                    },
-                    NamedKey::ArrowUp => {
-                        self.move_caret_vertical(true);
-                    },
-                    NamedKey::ArrowDown => {
-                        self.move_caret_vertical(false);
-                    }
-                    NamedKey::Space => {
-                        self.handle_input(" ");
-                    },
-                    NamedKey::Tab => {
-                        //TODO use \t?
-                        self.handle_input("   ");
-                    },
-                    _ => {}
-                }
+    /// Return false if the key is not handled, e.g. Tab for focus navigation
+    fn handle_key_down(&mut self, event: &KeyEventDetail) -> bool {
+        if self.is_composing() {
+            // keys are handled by input method
+            return true;
+        }
+        if let Some(command) = self.keymap.lookup(event) {
+            return self.execute_command(command);
+        }
+        if event.modifiers == 0 || event.modifiers == KEY_MOD_SHIFT {
+            if event.named_key == Some(NamedKey::Space) {
+                self.handle_input(" ");
+                return true;
             } else if let Some(text) = &event.key_str {
                 self.handle_input(&text);
-            }
-        } else if event.modifiers == KEY_MOD_SHIFT {
-            if let Some(text) = &event.key_str {
-                self.handle_input(&text);
-            }
-        } else if event.modifiers == KEY_MOD_CTRL {
-            if let Some(text) = &event.key_str {
-                match text.as_str() {
-                    "c" | "x" => {
-                        if let Some(sel) = self.base.get_selection_text() {
-                            let sel=  sel.to_string();
-                            if text == "x" {
-                                self.handle_input("");
-                            }
-                            let mut ctx: ClipboardContext = ClipboardProvider::new().unwrap();
-                            ctx.set_contents(sel).unwrap();
-                        }
-                    },
-                    "v" => {
-                        let mut ctx: ClipboardContext = ClipboardProvider::new().unwrap();
-                        if let Ok(text) = ctx.get_contents() {
-                            self.handle_input(&text);
-                        }
-                    }
-                    "a" => {
-                        self.base.set_selection((0, self.get_text().chars().count()))
-                    }
-                    "z" => {
-                        self.undo();
-                    }
-                    _ => {}
-                }
+                return true;
             }
         }
+        false
     }
 
     fn undo(&mut self) {
-        if let Some(op) = &self.edit_history.undo() {
-            match op.op {
-                EditOpType::Insert => {
-                    self.insert_text(op.content.as_str(), op.caret, false);
-                }
-                EditOpType::Delete => {
-                    self.base.select(op.caret, op.caret + op.content.chars_count());
-                    self.insert_text("", op.caret, false);
-                }
+        for op in &self.edit_history.undo_batch() {
+            self.apply_edit_op(op);
+        }
+    }
+
+    fn redo(&mut self) {
+        for op in &self.edit_history.redo_batch() {
+            self.apply_edit_op(op);
+        }
+    }
+
+    fn apply_edit_op(&mut self, op: &EditOp) {
+        match op.op {
+            EditOpType::Insert => {
+                self.base.unselect();
+                self.insert_text(op.content.as_str(), op.caret, false);
+            }
+            EditOpType::Delete => {
+                self.base.select(op.caret, op.caret + op.content.graphemes_count());
+                self.insert_text("", op.caret, false);
             }
         }
     }
 
     fn handle_focus(&mut self) {
+        if self.disabled {
+            return;
+        }
         self.focusing = true;
         // self.emit_caret_change();
         self.caret_visible.set(true);
@@ -329,23 +1023,54 @@ impl Entry {
         }
     }
 
-    fn insert_text(&mut self, input: &str, caret: usize, record_history: bool) {
+    /// The default behavior runs in a later macro task, the target range is captured now
+    /// so that text typed in the meantime doesn't move it
+    fn create_before_input_detail(&self, data: String) -> BeforeInputDetail {
+        let input_type = if data.is_empty() { "deleteContent" } else { "insertText" };
+        let (range_start, range_end) = self.base.get_selection().unwrap_or((self.caret, self.caret));
+        BeforeInputDetail {
+            input_type: input_type.to_string(),
+            data,
+            range_start,
+            range_end,
+        }
+    }
+
+    /// Replace the atoms in [start, end) with `input`, the range is clamped to the current text
+    fn replace_range(&mut self, input: &str, start: usize, end: usize) {
+        let max = self.base.get_atom_count().saturating_sub(1);
+        let (start, end) = (usize::min(start, max), usize::min(end, max));
+        if start < end {
+            self.base.select(start, end);
+        } else {
+            self.base.unselect();
+        }
+        self.insert_text(input, start, true);
+    }
+
+    fn insert_text(&mut self, input: &str, mut caret: usize, record_history: bool) {
         if let Some((text, begin, end)) = self.base.get_selection_data() {
             if record_history {
                 self.edit_history.record_delete(begin, &text);
             }
             self.base.delete_selected_text();
             self.update_caret_value(begin, false);
+            caret = begin;
         }
         if !input.is_empty() {
             if record_history {
                 self.edit_history.record_input(caret, input);
             }
+            // input may be merged into the grapheme before caret, e.g. combining marks
+            let atom_count = self.base.get_atom_count();
             self.base.insert_text(caret, input);
             //TODO maybe update caret twice?
-            self.update_caret_value(caret + input.chars_count(), false);
+            self.update_caret_value(caret + self.base.get_atom_count() - atom_count, false);
         }
+        self.emit_text_change();
+    }
 
+    fn emit_text_change(&mut self) {
         // emit text update
         let mut event = ElementEvent::new("textupdate", TextUpdateDetail {
             value: self.base.get_text().to_string()
@@ -357,6 +1082,9 @@ impl Entry {
             value: self.base.get_text().to_string(),
         }, self.element.clone());
         self.element.emit_event("textchange", event);
+        self.update_matched_brackets();
+        self.update_misspelled();
+        self.schedule_spell_check();
     }
 
 }
@@ -378,11 +1106,33 @@ impl ElementBackend for Entry {
             caret_timer_handle: None,
             selecting_begin: None,
             focusing: false,
-            align: TextAlign::Left,
+            align: TextAlign::Start,
             multiple_line: false,
             element: ele,
             vertical_caret_moving_coord_x: 0.0,
             edit_history: EditHistory::new(),
+            preedit_text: String::new(),
+            preedit_cursor: None,
+            keymap: Keymap::default(),
+            last_mouse_down: None,
+            click_count: 0,
+            password: false,
+            placeholder: String::new(),
+            placeholder_color: parse_hex_color("7F7F7F").unwrap(),
+            placeholder_style: PlaceholderStyle::default(),
+            max_length: None,
+            read_only: false,
+            disabled: false,
+            input_filter: None,
+            code_mode: false,
+            tab_size: DEFAULT_TAB_SIZE,
+            matched_brackets: None,
+            current_line_color: parse_hex_color("FFFFFF10").unwrap(),
+            bracket_color: parse_hex_color("808080").unwrap(),
+            spell_check: false,
+            spell_check_cache: Arc::new(Mutex::new(HashMap::new())),
+            spell_check_version: 0,
+            spell_check_timer: None,
         }
     }
 
@@ -390,6 +1140,10 @@ impl ElementBackend for Entry {
         "Entry"
     }
 
+    fn search(&mut self, query: &SearchQuery) -> Result<(), Error> {
+        self.base.search(query)
+    }
+
     fn handle_style_changed(&mut self, key: &str) {
         self.base.handle_style_changed(key)
     }
@@ -399,12 +1153,65 @@ impl ElementBackend for Entry {
         let mut paint = Paint::default();
         paint.set_color(self.base.get_color());
 
-        let (start, end) = self.base.get_caret_offset_coordinate(self.caret);
         canvas.save();
+        if self.code_mode && self.focusing {
+            let (row, _) = self.base.get_location_by_atom_offset(self.caret);
+            if let Some((top, height)) = self.base.get_line_vertical_bounds(row) {
+                let mut line_paint = Paint::default();
+                line_paint.set_color(self.current_line_color);
+                let width = self.element.get_content_bounds().width;
+                canvas.draw_rect(skia_safe::Rect::from_xywh(0.0, top, width, height), &line_paint);
+            }
+        }
         self.base.draw(canvas);
-        if self.focusing && self.caret_visible.get() {
-            paint.set_stroke_width(2.0);
-            canvas.draw_line(start, end, &paint);
+        if let Some((begin, end)) = self.matched_brackets {
+            let mut bracket_paint = Paint::default();
+            bracket_paint.set_color(self.bracket_color);
+            bracket_paint.set_style(skia_safe::paint::Style::Stroke);
+            for offset in [begin, end] {
+                if let Some(bounds) = self.base.get_atom_bounds(offset) {
+                    canvas.draw_rect(bounds, &bracket_paint);
+                }
+            }
+        }
+        if !self.placeholder.is_empty() && !self.is_composing() && self.base.get_atom_count() <= 1 {
+            let params = self.get_placeholder_params();
+            // aligned placeholder of single line entry needs a width too
+            let width = if self.multiple_line || self.placeholder_style.align.is_some() {
+                let (_, border_right, _, border_left) = self.element.get_border_width();
+                let (_, padding_right, _, padding_left) = self.element.get_padding();
+                self.element.layout.get_layout_width() - border_left - border_right - padding_left - padding_right
+            } else {
+                f32::NAN
+            };
+            let mut paragraph = SkiaTextParagraph::new(self.placeholder.clone(), &params);
+            paragraph.layout(width);
+            // canvas is translated to the content box, inside border and padding
+            paragraph.paint(canvas, (0.0, 0.0));
+        }
+        if self.is_composing() {
+            // underline preedit text
+            let preedit_len = self.preedit_text.graphemes_count();
+            paint.set_stroke_width(1.0);
+            for offset in self.caret..self.caret + preedit_len {
+                let (_, begin) = self.base.get_caret_offset_coordinate(offset);
+                let (_, end) = self.base.get_caret_offset_coordinate(offset + 1);
+                if begin.1 == end.1 {
+                    canvas.draw_line(begin, end, &paint);
+                }
+            }
+        }
+        let caret = match (self.is_composing(), self.preedit_cursor) {
+            (false, _) => Some(self.caret),
+            (true, Some(c)) => Some(self.caret + c),
+            (true, None) => None,
+        };
+        if let Some(caret) = caret {
+            if self.focusing && self.caret_visible.get() {
+                let (start, end) = self.base.get_caret_offset_coordinate(caret);
+                paint.set_stroke_width(2.0);
+                canvas.draw_line(start, end, &paint);
+            }
         }
         canvas.restore();
     }
@@ -417,22 +1224,70 @@ impl ElementBackend for Entry {
         js_call!("multipleline", bool, self, set_multiple_line, p, v);
         js_call!("selection", (usize, usize), label, set_selection, p, v);
         js_call!("caret", usize, self, set_caret, p, v);
+        js_call!("keybinding", (String, String), self, set_key_binding, p, v);
+        js_call!("password", bool, self, set_password, p, v);
+        js_call!("placeholder", String, self, set_placeholder, p, v);
+        js_call!("placeholdercolor", String, self, set_placeholder_color, p, v);
+        js_call_rust!("placeholderstyle", PlaceholderStyle, self, set_placeholder_style, p, v);
+        js_call!("maxlength", i32, self, set_max_length, p, v);
+        js_call!("readonly", bool, self, set_read_only, p, v);
+        js_call!("disabled", bool, self, set_disabled, p, v);
+        js_call!("inputfilter", String, self, set_input_filter, p, v);
+        js_call!("inserttext", String, self, insert, p, v);
+        js_call!("codemode", bool, self, set_code_mode, p, v);
+        js_call!("tabsize", usize, self, set_tab_size, p, v);
+        js_call!("syntaxrules", Vec<(String, String)>, self, set_syntax_rules, p, v);
+        js_call!("currentlinecolor", String, self, set_current_line_color, p, v);
+        js_call!("bracketcolor", String, self, set_bracket_color, p, v);
+        js_call!("searchcolor", String, label, set_search_color, p, v);
+        js_call!("activesearchcolor", String, label, set_active_search_color, p, v);
+        js_call!("gotomatch", String, self, goto_match, p, v);
+        js_call!("replacematch", String, self, replace_match, p, v);
+        js_call!("replaceall", String, self, replace_all, p, v);
+        js_call!("spellcheck", bool, self, set_spell_check, p, v);
     }
 
     fn get_property(&mut self, property_name: &str) -> Result<Option<JsValue>, Error> {
+        if property_name == "text" {
+            return Ok(Some(JsValue::String(self.get_text())));
+        } else if property_name == "misspelledword" {
+            return match self.get_misspelled_word() {
+                Some(w) => Ok(Some(w.to_js_value()?)),
+                None => Ok(Some(JsValue::Null)),
+            };
+        }
         self.base.get_property(property_name)
     }
 
     fn handle_input(&mut self, input: &str) {
         //println!("on input:{}", input);
-        self.insert_text(input, self.caret, true);
+        self.clear_preedit();
+        self.request_input(input);
+    }
+
+    fn handle_ime_preedit(&mut self, text: &str, cursor: Option<(usize, usize)>) {
+        self.set_preedit(text, cursor);
+    }
+
+    fn is_editable(&self) -> bool {
+        self.is_input_allowed()
+    }
+
+    fn get_caret_bounds(&self) -> Option<Rect> {
+        let caret = self.caret + self.preedit_cursor.unwrap_or(0);
+        Some(self.get_caret_rect(caret).1)
     }
 
     fn handle_event_default_behavior(&mut self, event_type: &str, event: &mut ElementEvent) -> bool {
+        let mut handled = false;
         KeyDownEvent::try_match(event_type, event, |d| {
-            self.handle_key_down(d)
-        })
-            || self.base.handle_event_default_behavior(event_type, event)
+            handled = self.handle_key_down(d);
+        });
+        BeforeInputEvent::try_match(event_type, event, |d| {
+            self.replace_range(&d.data, d.range_start, d.range_end);
+            handled = true;
+        });
+        handled || self.base.handle_event_default_behavior(event_type, event)
     }
 
     fn handle_origin_bounds_change(&mut self, bounds: &Rect) {
@@ -446,6 +1301,170 @@ impl ElementBackend for Entry {
         match_event_type!(event, MouseDetail, self, handle_mouse_event);
     }
 
+    fn is_focusable(&self) -> bool {
+        !self.disabled
+    }
+
+    fn get_accessibility_role(&self) -> Role {
+        if self.password {
+            Role::PasswordInput
+        } else if self.multiple_line {
+            Role::MultilineTextInput
+        } else {
+            Role::TextInput
+        }
+    }
+
+    fn build_accessibility_node(&self, builder: &mut NodeBuilder) {
+        if self.password {
+            builder.set_value(self.get_text().graphemes(true).map(|_| PASSWORD_MASK).collect::<String>());
+        } else {
+            builder.set_value(self.get_text());
+        }
+        if !self.placeholder.is_empty() {
+            builder.set_placeholder(self.placeholder.clone());
+        }
+        if self.disabled {
+            builder.set_disabled();
+            return;
+        }
+        if self.read_only {
+            builder.set_read_only();
+        }
+        builder.add_action(Action::Focus);
+        builder.add_action(Action::SetValue);
+    }
+
+    fn handle_accessibility_action(&mut self, request: &ActionRequest) -> bool {
+        if request.action == Action::SetValue {
+            if let Some(ActionData::Value(value)) = &request.data {
+                self.base.set_selection((0, self.get_text().graphemes_count()));
+                self.handle_input(value);
+            }
+            return true;
+        }
+        false
+    }
+
+}
+
+#[derive(PartialEq)]
+enum CharKind {
+    Space,
+    LineBreak,
+    Word,
+    Punctuation,
+}
+
+fn char_kind(c: char) -> CharKind {
+    if c == '\n' {
+        CharKind::LineBreak
+    } else if c.is_whitespace() {
+        CharKind::Space
+    } else if c.is_alphanumeric() || c == '_' {
+        CharKind::Word
+    } else {
+        CharKind::Punctuation
+    }
+}
+
+fn is_blank(c: char) -> bool {
+    matches!(char_kind(c), CharKind::Space | CharKind::LineBreak)
+}
+
+/// Offset of the bracket matched with the bracket at offset
+fn find_matched_bracket(chars: &[char], offset: usize) -> Option<usize> {
+    let c = *chars.get(offset)?;
+    for (open, close) in BRACKET_PAIRS {
+        let forward = if c == open {
+            true
+        } else if c == close {
+            false
+        } else {
+            continue;
+        };
+        let mut depth = 0;
+        let mut idx = offset;
+        loop {
+            if chars[idx] == open {
+                depth += if forward { 1 } else { -1 };
+            } else if chars[idx] == close {
+                depth += if forward { -1 } else { 1 };
+            }
+            if depth == 0 {
+                return Some(idx);
+            }
+            if forward {
+                idx += 1;
+                if idx >= chars.len() {
+                    return None;
+                }
+            } else {
+                idx = idx.checked_sub(1)?;
+            }
+        }
+    }
+    None
+}
+
+/// First char of every atom, so that word boundaries are computed in atom offsets
+fn atom_chars(text: &str) -> Vec<char> {
+    text.graphemes(true).filter_map(|g| g.chars().next()).collect()
+}
+
+/// Start of the word before offset, blanks are skipped
+fn prev_word_boundary(chars: &[char], offset: usize) -> usize {
+    let mut i = usize::min(offset, chars.len());
+    while i > 0 && is_blank(chars[i - 1]) {
+        i -= 1;
+    }
+    if i > 0 {
+        let kind = char_kind(chars[i - 1]);
+        while i > 0 && char_kind(chars[i - 1]) == kind {
+            i -= 1;
+        }
+    }
+    i
+}
+
+/// End of the word after offset, blanks are skipped
+fn next_word_boundary(chars: &[char], offset: usize) -> usize {
+    let len = chars.len();
+    let mut i = offset;
+    while i < len && is_blank(chars[i]) {
+        i += 1;
+    }
+    if i < len {
+        let kind = char_kind(chars[i]);
+        while i < len && char_kind(chars[i]) == kind {
+            i += 1;
+        }
+    }
+    i
+}
+
+/// Range of the word, spaces or punctuations at offset
+fn word_range_at(chars: &[char], offset: usize) -> (usize, usize) {
+    if chars.is_empty() {
+        return (0, 0);
+    }
+    let mut idx = usize::min(offset, chars.len() - 1);
+    if chars[idx] == '\n' && idx > 0 {
+        idx -= 1;
+    }
+    let kind = char_kind(chars[idx]);
+    if kind == CharKind::LineBreak {
+        return (idx, idx);
+    }
+    let mut start = idx;
+    while start > 0 && char_kind(chars[start - 1]) == kind {
+        start -= 1;
+    }
+    let mut end = idx + 1;
+    while end < chars.len() && char_kind(chars[end]) == kind {
+        end += 1;
+    }
+    (start, end)
 }
 
 #[test]
@@ -462,14 +1481,334 @@ pub fn test_edit_history() {
     entry.handle_input(text2);
     assert_eq!(text_all, entry.get_text());
     // delete text2
-    entry.base.select(text1.chars_count(), text1.chars_count() + text2.chars_count());
+    entry.base.select(text1.graphemes_count(), text1.graphemes_count() + text2.graphemes_count());
     entry.handle_input("");
     assert_eq!(text1, entry.get_text());
     // undo
     entry.undo();
     assert_eq!(text_all, entry.get_text());
-    assert_eq!(text_all.chars_count(), entry.caret);
+    assert_eq!(text_all.graphemes_count(), entry.caret);
     entry.undo();
     assert_eq!("", entry.get_text());
     assert_eq!(0, entry.caret);
 }
+
+#[test]
+pub fn test_preedit() {
+    let mut el = ElementRef::new(Entry::create);
+    let entry = el.get_backend_mut_as::<Entry>();
+    entry.handle_input("ab");
+    entry.set_caret(1);
+    entry.set_preedit("ni", Some((2, 2)));
+    assert_eq!("ab", entry.get_text());
+    assert_eq!("anib", entry.base.get_text());
+    assert_eq!(Some(2), entry.preedit_cursor);
+    entry.set_preedit("", None);
+    entry.handle_input("你");
+    assert_eq!("a你b", entry.get_text());
+    assert_eq!(2, entry.caret);
+}
+
+#[test]
+pub fn test_caret_bounds_inside_border_and_padding() {
+    use crate::style::StyleProp;
+    let mut el = ElementRef::new(Entry::create);
+    el.set_style_props(vec![
+        StyleProp::parse("BorderLeft", "2 #ccc").unwrap(),
+        StyleProp::parse("BorderTop", "3 #ccc").unwrap(),
+        StyleProp::parse("PaddingLeft", "5").unwrap(),
+        StyleProp::parse("PaddingTop", "7").unwrap(),
+    ]);
+    el.calculate_layout(200.0, 100.0);
+    let entry = el.get_backend_mut_as::<Entry>();
+    entry.handle_input("ab");
+    let (bounds, _) = entry.get_caret_rect(entry.caret);
+    let ime_bounds = ElementBackend::get_caret_bounds(entry).unwrap();
+    assert_eq!(bounds.x + 2.0 + 5.0, ime_bounds.x);
+    assert_eq!(bounds.y + 3.0 + 7.0, ime_bounds.y);
+    assert_eq!(bounds.height, ime_bounds.height);
+}
+
+#[test]
+pub fn test_before_input_target_range() {
+    let mut el = ElementRef::new(Entry::create);
+    let entry = el.get_backend_mut_as::<Entry>();
+    entry.handle_input("hello");
+    let detail = entry.create_before_input_detail("!".to_string());
+    assert_eq!((5, 5), (detail.range_start, detail.range_end));
+    // more text is typed before the default behavior of beforeinput runs
+    entry.handle_input(" world");
+    let mut event = ElementEvent::new("beforeinput", detail, el.clone());
+    let entry = el.get_backend_mut_as::<Entry>();
+    assert!(entry.handle_event_default_behavior("beforeinput", &mut event));
+    assert_eq!("hello! world", entry.get_text());
+    assert_eq!(6, entry.caret);
+
+    // replace selection
+    entry.base.select(7, 12);
+    let detail = entry.create_before_input_detail("there".to_string());
+    assert_eq!((7, 12), (detail.range_start, detail.range_end));
+    entry.base.unselect();
+    let mut event = ElementEvent::new("beforeinput", detail, el.clone());
+    let entry = el.get_backend_mut_as::<Entry>();
+    entry.handle_event_default_behavior("beforeinput", &mut event);
+    assert_eq!("hello! there", entry.get_text());
+}
+
+#[test]
+pub fn test_placeholder_style() {
+    let mut el = ElementRef::new(Entry::create);
+    let entry = el.get_backend_mut_as::<Entry>();
+    entry.set_password(true);
+    entry.set_placeholder_style(PlaceholderStyle {
+        color: Some("#FF0000".to_string()),
+        font_size: Some(20.0),
+        line_height: None,
+        align: Some("center".to_string()),
+    });
+    let params = entry.get_placeholder_params();
+    assert_eq!(Color::from_rgb(255, 0, 0), params.paint.color());
+    assert_eq!(20.0, params.font.size());
+    assert_eq!(TextAlign::Center, params.align);
+    // placeholder is never masked
+    assert_eq!(None, params.mask);
+    assert_eq!(entry.base.get_text_params().line_height, params.line_height);
+}
+
+#[cfg(test)]
+fn key_event(named_key: Option<NamedKey>, key: String, key_str: Option<String>, modifiers: u32) -> KeyEventDetail {
+    use crate::event::{KEY_MOD_ALT, KEY_MOD_CTRL, KEY_MOD_META};
+    KeyEventDetail {
+        modifiers,
+        ctrl_key: modifiers & KEY_MOD_CTRL != 0,
+        alt_key: modifiers & KEY_MOD_ALT != 0,
+        meta_key: modifiers & KEY_MOD_META != 0,
+        shift_key: modifiers & KEY_MOD_SHIFT != 0,
+        named_key,
+        key: Some(key),
+        key_str,
+        repeat: false,
+        pressed: true,
+    }
+}
+
+#[cfg(test)]
+fn named_key_event(key: NamedKey, modifiers: u32) -> KeyEventDetail {
+    let name = crate::event::named_key_to_str(&key).to_string();
+    key_event(Some(key), name, None, modifiers)
+}
+
+#[cfg(test)]
+fn char_key_event(key: &str, modifiers: u32) -> KeyEventDetail {
+    key_event(None, key.to_string(), Some(key.to_string()), modifiers)
+}
+
+#[test]
+pub fn test_keymap() {
+    use crate::element::keymap::primary_modifier;
+    use crate::event::{KEY_MOD_ALT, KEY_MOD_CTRL};
+    let word = if cfg!(target_os = "macos") { KEY_MOD_ALT } else { KEY_MOD_CTRL };
+    let primary = primary_modifier();
+    let mut el = ElementRef::new(Entry::create);
+    let entry = el.get_backend_mut_as::<Entry>();
+    for key in ["h", "i", " ", "a", "l", "l"] {
+        entry.handle_key_down(&char_key_event(key, 0));
+    }
+    assert_eq!("hi all", entry.get_text());
+
+    entry.handle_key_down(&named_key_event(NamedKey::Home, 0));
+    assert_eq!(0, entry.caret);
+    entry.handle_key_down(&named_key_event(NamedKey::ArrowRight, word));
+    assert_eq!(2, entry.caret);
+    entry.handle_key_down(&named_key_event(NamedKey::ArrowRight, word | KEY_MOD_SHIFT));
+    assert_eq!(Some((2, 6)), entry.base.get_selection());
+    entry.handle_key_down(&named_key_event(NamedKey::ArrowLeft, KEY_MOD_SHIFT));
+    assert_eq!(Some((2, 5)), entry.base.get_selection());
+    // collapse to selection start
+    entry.handle_key_down(&named_key_event(NamedKey::ArrowLeft, 0));
+    assert_eq!(None, entry.base.get_selection());
+    assert_eq!(2, entry.caret);
+
+    entry.handle_key_down(&named_key_event(NamedKey::Delete, 0));
+    assert_eq!("hiall", entry.get_text());
+    entry.handle_key_down(&named_key_event(NamedKey::End, 0));
+    entry.handle_key_down(&named_key_event(NamedKey::Backspace, word));
+    assert_eq!("", entry.get_text());
+
+    entry.handle_key_down(&char_key_event("z", primary));
+    assert_eq!("hiall", entry.get_text());
+    entry.handle_key_down(&char_key_event("z", primary));
+    assert_eq!("hi all", entry.get_text());
+    entry.handle_key_down(&char_key_event("Z", primary | KEY_MOD_SHIFT));
+    assert_eq!("hiall", entry.get_text());
+    entry.handle_key_down(&char_key_event("Z", primary | KEY_MOD_SHIFT));
+    assert_eq!("", entry.get_text());
+    // nothing to redo
+    entry.handle_key_down(&char_key_event("Z", primary | KEY_MOD_SHIFT));
+    assert_eq!("", entry.get_text());
+
+    // custom binding
+    entry.set_key_binding(("Ctrl+B".to_string(), "moveDocumentStart".to_string()));
+    entry.handle_input("abc");
+    entry.handle_key_down(&char_key_event("b", KEY_MOD_CTRL));
+    assert_eq!(0, entry.caret);
+}
+
+#[test]
+pub fn test_word_boundary() {
+    let chars: Vec<char> = "foo.bar  baz\nqux".chars().collect();
+    assert_eq!(3, next_word_boundary(&chars, 0));
+    assert_eq!(12, next_word_boundary(&chars, 7));
+    assert_eq!(9, prev_word_boundary(&chars, 12));
+    assert_eq!(13, prev_word_boundary(&chars, 16));
+    assert_eq!((4, 7), word_range_at(&chars, 5));
+    assert_eq!((7, 9), word_range_at(&chars, 8));
+}
+
+#[test]
+pub fn test_input_modes() {
+    let mut el = ElementRef::new(Entry::create);
+    let entry = el.get_backend_mut_as::<Entry>();
+    entry.set_input_filter("numeric".to_string());
+    entry.set_max_length(4);
+    entry.handle_input("1a2b3");
+    assert_eq!("123", entry.get_text());
+    entry.handle_input("456");
+    assert_eq!("1234", entry.get_text());
+    entry.handle_input("x");
+    assert_eq!("1234", entry.get_text());
+
+    entry.set_input_filter("[a-f]".to_string());
+    entry.set_max_length(-1);
+    entry.handle_input("abcxyz");
+    assert_eq!("1234abc", entry.get_text());
+
+    entry.set_read_only(true);
+    entry.handle_input("d");
+    entry.handle_key_down(&named_key_event(NamedKey::Backspace, 0));
+    assert_eq!("1234abc", entry.get_text());
+    entry.set_read_only(false);
+
+    entry.set_password(true);
+    entry.execute_command(EditCommand::SelectAll);
+    assert!(!entry.copy_selection());
+    assert_eq!("1234abc", entry.get_text());
+}
+
+#[test]
+pub fn test_grapheme_editing() {
+    let mut el = ElementRef::new(Entry::create);
+    let entry = el.get_backend_mut_as::<Entry>();
+    // flag, family(ZWJ sequence), a with combining ring above
+    entry.handle_input("\u{1F1FA}\u{1F1F8}\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F466}a\u{30A}");
+    assert_eq!(3, entry.caret);
+    entry.handle_key_down(&named_key_event(NamedKey::ArrowLeft, 0));
+    assert_eq!(2, entry.caret);
+    entry.handle_key_down(&named_key_event(NamedKey::Backspace, 0));
+    assert_eq!("\u{1F1FA}\u{1F1F8}a\u{30A}", entry.get_text());
+    assert_eq!(1, entry.caret);
+    entry.handle_key_down(&named_key_event(NamedKey::Delete, 0));
+    assert_eq!("\u{1F1FA}\u{1F1F8}", entry.get_text());
+    // combining mark is merged into the grapheme before caret
+    entry.handle_input("e");
+    entry.handle_input("\u{301}");
+    assert_eq!(2, entry.caret);
+    entry.handle_key_down(&named_key_event(NamedKey::ArrowLeft, KEY_MOD_SHIFT));
+    assert_eq!(Some("e\u{301}".to_string()), entry.base.get_selection_text());
+}
+
+#[test]
+pub fn test_code_mode() {
+    let chars: Vec<char> = "f(a[1], {b})".chars().collect();
+    assert_eq!(Some(11), find_matched_bracket(&chars, 1));
+    assert_eq!(Some(3), find_matched_bracket(&chars, 5));
+    assert_eq!(Some(8), find_matched_bracket(&chars, 10));
+    assert_eq!(None, find_matched_bracket(&chars, 0));
+
+    let mut el = ElementRef::new(Entry::create);
+    let entry = el.get_backend_mut_as::<Entry>();
+    entry.set_multiple_line(true);
+    entry.set_code_mode(true);
+    entry.handle_input("\tif x {");
+    entry.execute_command(EditCommand::InsertNewLine);
+    entry.execute_command(EditCommand::InsertTab);
+    assert_eq!("\tif x {\n\t\t\t", entry.get_text());
+    entry.handle_input("}");
+    assert_eq!(Some((entry.caret - 1, 6)), entry.matched_brackets);
+
+    // brackets are searched in lines around caret only
+    let lines = "a\n".repeat(BRACKET_SCAN_LIMIT);
+    entry.set_text(format!("(\n{}b)", lines));
+    assert_eq!(None, entry.matched_brackets);
+    entry.set_text(format!("(\n{}b)", &lines[..20]));
+    assert_eq!(Some((entry.caret - 1, 0)), entry.matched_brackets);
+}
+
+#[test]
+pub fn test_insert_tab_outside_code_mode() {
+    let mut el = ElementRef::new(Entry::create);
+    let entry = el.get_backend_mut_as::<Entry>();
+    entry.set_multiple_line(true);
+    entry.execute_command(EditCommand::InsertTab);
+    assert_eq!("    ", entry.get_text());
+    entry.handle_input("ab");
+    entry.execute_command(EditCommand::InsertTab);
+    assert_eq!("    ab  ", entry.get_text());
+
+
+    let mut el = ElementRef::new(Entry::create);
+    let entry = el.get_backend_mut_as::<Entry>();
+    entry.set_multiple_line(true);
+    entry.set_tab_size(2);
+    entry.handle_input("a");
+    entry.execute_command(EditCommand::InsertTab);
+    assert_eq!("a ", entry.get_text());
+
+    // single line entries leave tab to focus navigation
+    entry.set_multiple_line(false);
+    assert!(!entry.execute_command(EditCommand::InsertTab));
+}
+
+#[test]
+pub fn test_search_replace() {
+    let mut el = ElementRef::new(Entry::create);
+    let entry = el.get_backend_mut_as::<Entry>();
+    entry.handle_input("foo bar Foo");
+    entry.search(&SearchQuery { query: "foo".to_string(), ignore_case: true, regex: false }).unwrap();
+    assert_eq!(vec![(0, 3), (8, 11)], entry.base.get_search_matches());
+    assert_eq!(Some((0, 3)), entry.goto_next_match(false));
+    assert_eq!(Some((0, 3)), entry.base.get_selection());
+
+    // replace and undo at once
+    entry.replace_match("baz".to_string());
+    assert_eq!("baz bar Foo", entry.get_text());
+    assert_eq!(Some((8, 11)), entry.base.get_selection());
+    entry.undo();
+    assert_eq!("foo bar Foo", entry.get_text());
+
+    entry.search(&SearchQuery { query: "(\\w)o+".to_string(), ignore_case: false, regex: true }).unwrap();
+    assert_eq!(2, entry.replace_all("<$1>".to_string()));
+    assert_eq!("<f> bar <F>", entry.get_text());
+    entry.undo();
+    assert_eq!("foo bar Foo", entry.get_text());
+    entry.redo();
+    assert_eq!("<f> bar <F>", entry.get_text());
+
+    // invalid regex is reported to the caller and keeps the previous search
+    assert!(entry.search(&SearchQuery { query: "(".to_string(), ignore_case: false, regex: true }).is_err());
+    assert!(entry.base.get_search().is_some());
+}
+
+#[test]
+pub fn test_spell_check_ranges() {
+    let mut el = ElementRef::new(Entry::create);
+    let entry = el.get_backend_mut_as::<Entry>();
+    entry.set_multiple_line(true);
+    entry.handle_input("ok\n\u{1F1FA}\u{1F1F8} wrold");
+    entry.spell_check = true;
+    entry.spell_check_cache.lock().unwrap().insert("\u{1F1FA}\u{1F1F8} wrold".to_string(), vec![(9, 14)]);
+    entry.update_misspelled();
+    assert_eq!(&vec![(5, 10)], entry.base.get_misspelled());
+    entry.set_caret(7);
+    assert_eq!("wrold", entry.get_misspelled_word().unwrap().word);
+}
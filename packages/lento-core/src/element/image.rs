@@ -1,6 +1,7 @@
 use std::fs::File;
 use std::io::Cursor;
 
+use accesskit::Role;
 use anyhow::Error;
 use base64::Engine;
 use base64::prelude::*;
@@ -140,4 +141,7 @@ impl ElementBackend for Image {
         canvas.restore();
     }
 
-}
\ No newline at end of file
+    fn get_accessibility_role(&self) -> Role {
+        Role::Image
+    }
+}
@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+
+use crate::event::{named_key_to_str, KeyEventDetail, KEY_MOD_ALT, KEY_MOD_CTRL, KEY_MOD_META, KEY_MOD_SHIFT};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Movement {
+    /// Previous grapheme in logical order
+    Backward,
+    /// Next grapheme in logical order
+    Forward,
+    /// Visual movement, which is different from logical movement in rtl text
+    Left,
+    Right,
+    Up,
+    Down,
+    WordLeft,
+    WordRight,
+    LineStart,
+    LineEnd,
+    PageUp,
+    PageDown,
+    DocumentStart,
+    DocumentEnd,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EditCommand {
+    /// Move caret and collapse selection
+    Move(Movement),
+    /// Move caret and extend selection
+    Select(Movement),
+    DeleteBackward,
+    DeleteForward,
+    DeleteWordBackward,
+    DeleteWordForward,
+    SelectAll,
+    Copy,
+    Cut,
+    Paste,
+    Undo,
+    Redo,
+    InsertNewLine,
+    InsertTab,
+    /// Select next match of search
+    FindNext,
+    FindPrevious,
+}
+
+macro_rules! movement_names {
+    ($($name: expr => $movement: ident,)*) => {
+        impl Movement {
+            fn parse(str: &str) -> Option<Self> {
+                match str {
+                    $($name => Some(Movement::$movement),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+movement_names!(
+    "Backward" => Backward,
+    "Forward" => Forward,
+    "Left" => Left,
+    "Right" => Right,
+    "Up" => Up,
+    "Down" => Down,
+    "WordLeft" => WordLeft,
+    "WordRight" => WordRight,
+    "LineStart" => LineStart,
+    "LineEnd" => LineEnd,
+    "PageUp" => PageUp,
+    "PageDown" => PageDown,
+    "DocumentStart" => DocumentStart,
+    "DocumentEnd" => DocumentEnd,
+);
+
+impl EditCommand {
+    /// Parse command name, e.g. "moveWordLeft", "selectLineEnd", "deleteBackward", "redo"
+    pub fn parse(str: &str) -> Option<Self> {
+        let cmd = match str {
+            "deleteBackward" => EditCommand::DeleteBackward,
+            "deleteForward" => EditCommand::DeleteForward,
+            "deleteWordBackward" => EditCommand::DeleteWordBackward,
+            "deleteWordForward" => EditCommand::DeleteWordForward,
+            "selectAll" => EditCommand::SelectAll,
+            "copy" => EditCommand::Copy,
+            "cut" => EditCommand::Cut,
+            "paste" => EditCommand::Paste,
+            "undo" => EditCommand::Undo,
+            "redo" => EditCommand::Redo,
+            "insertNewLine" => EditCommand::InsertNewLine,
+            "insertTab" => EditCommand::InsertTab,
+            "findNext" => EditCommand::FindNext,
+            "findPrevious" => EditCommand::FindPrevious,
+            _ => {
+                return if let Some(m) = str.strip_prefix("move") {
+                    Movement::parse(m).map(EditCommand::Move)
+                } else if let Some(m) = str.strip_prefix("select") {
+                    Movement::parse(m).map(EditCommand::Select)
+                } else {
+                    None
+                }
+            }
+        };
+        Some(cmd)
+    }
+}
+
+/// Key with modifiers, key is the name of named key(e.g. "ArrowLeft") or the lowercase character
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Shortcut {
+    pub key: String,
+    pub modifiers: u32,
+}
+
+impl Shortcut {
+    pub fn new(key: &str, modifiers: u32) -> Self {
+        let key = if key.chars().count() == 1 {
+            key.to_lowercase()
+        } else {
+            key.to_string()
+        };
+        Self { key, modifiers }
+    }
+
+    /// Parse shortcut like "Ctrl+Shift+Z", "Mod" is Meta on macOS and Ctrl on other platforms
+    pub fn parse(str: &str) -> Option<Self> {
+        let mut modifiers = 0;
+        let mut parts: Vec<&str> = str.split('+').map(|p| p.trim()).collect();
+        let key = parts.pop()?;
+        if key.is_empty() {
+            return None;
+        }
+        for p in parts {
+            modifiers |= match p.to_lowercase().as_str() {
+                "ctrl" | "control" => KEY_MOD_CTRL,
+                "shift" => KEY_MOD_SHIFT,
+                "alt" | "option" => KEY_MOD_ALT,
+                "meta" | "cmd" | "super" => KEY_MOD_META,
+                "mod" => primary_modifier(),
+                _ => return None,
+            };
+        }
+        Some(Self::new(key, modifiers))
+    }
+
+    pub fn from_key_event(event: &KeyEventDetail) -> Option<Self> {
+        let key = match &event.named_key {
+            Some(nk) => named_key_to_str(nk),
+            None => event.key_str.as_ref()?.as_str(),
+        };
+        Some(Self::new(key, event.modifiers))
+    }
+}
+
+/// Meta on macOS and Ctrl on other platforms
+pub fn primary_modifier() -> u32 {
+    if cfg!(target_os = "macos") {
+        KEY_MOD_META
+    } else {
+        KEY_MOD_CTRL
+    }
+}
+
+#[derive(Clone)]
+pub struct Keymap {
+    bindings: HashMap<Shortcut, EditCommand>,
+}
+
+impl Keymap {
+    pub fn new() -> Self {
+        Self {
+            bindings: HashMap::new(),
+        }
+    }
+
+    pub fn bind(&mut self, shortcut: Shortcut, command: EditCommand) {
+        self.bindings.insert(shortcut, command);
+    }
+
+    pub fn unbind(&mut self, shortcut: &Shortcut) {
+        self.bindings.remove(shortcut);
+    }
+
+    pub fn lookup(&self, event: &KeyEventDetail) -> Option<EditCommand> {
+        let shortcut = Shortcut::from_key_event(event)?;
+        self.bindings.get(&shortcut).copied()
+    }
+
+    fn bind_movement(&mut self, key: &str, modifiers: u32, movement: Movement) {
+        self.bind(Shortcut::new(key, modifiers), EditCommand::Move(movement));
+        self.bind(Shortcut::new(key, modifiers | KEY_MOD_SHIFT), EditCommand::Select(movement));
+    }
+}
+
+impl Default for Keymap {
+    /// Key bindings of native text fields on current platform
+    fn default() -> Self {
+        use EditCommand::*;
+        let mut km = Keymap::new();
+        let primary = primary_modifier();
+        let is_mac = cfg!(target_os = "macos");
+        // word navigation is Option+Arrow on macOS
+        let word = if is_mac { KEY_MOD_ALT } else { KEY_MOD_CTRL };
+
+        km.bind_movement("ArrowLeft", 0, Movement::Left);
+        km.bind_movement("ArrowRight", 0, Movement::Right);
+        km.bind_movement("ArrowUp", 0, Movement::Up);
+        km.bind_movement("ArrowDown", 0, Movement::Down);
+        km.bind_movement("ArrowLeft", word, Movement::WordLeft);
+        km.bind_movement("ArrowRight", word, Movement::WordRight);
+        km.bind_movement("Home", 0, Movement::LineStart);
+        km.bind_movement("End", 0, Movement::LineEnd);
+        km.bind_movement("PageUp", 0, Movement::PageUp);
+        km.bind_movement("PageDown", 0, Movement::PageDown);
+        km.bind_movement("Home", KEY_MOD_CTRL, Movement::DocumentStart);
+        km.bind_movement("End", KEY_MOD_CTRL, Movement::DocumentEnd);
+        if is_mac {
+            km.bind_movement("ArrowLeft", KEY_MOD_META, Movement::LineStart);
+            km.bind_movement("ArrowRight", KEY_MOD_META, Movement::LineEnd);
+            km.bind_movement("ArrowUp", KEY_MOD_META, Movement::DocumentStart);
+            km.bind_movement("ArrowDown", KEY_MOD_META, Movement::DocumentEnd);
+        }
+
+        km.bind(Shortcut::new("Backspace", 0), DeleteBackward);
+        km.bind(Shortcut::new("Backspace", KEY_MOD_SHIFT), DeleteBackward);
+        km.bind(Shortcut::new("Delete", 0), DeleteForward);
+        km.bind(Shortcut::new("Backspace", word), DeleteWordBackward);
+        km.bind(Shortcut::new("Delete", word), DeleteWordForward);
+
+        km.bind(Shortcut::new("a", primary), SelectAll);
+        km.bind(Shortcut::new("c", primary), Copy);
+        km.bind(Shortcut::new("x", primary), Cut);
+        km.bind(Shortcut::new("v", primary), Paste);
+        km.bind(Shortcut::new("Insert", KEY_MOD_CTRL), Copy);
+        km.bind(Shortcut::new("Delete", KEY_MOD_SHIFT), Cut);
+        km.bind(Shortcut::new("Insert", KEY_MOD_SHIFT), Paste);
+        km.bind(Shortcut::new("z", primary), Undo);
+        km.bind(Shortcut::new("z", primary | KEY_MOD_SHIFT), Redo);
+        if !is_mac {
+            km.bind(Shortcut::new("y", KEY_MOD_CTRL), Redo);
+        }
+
+        km.bind(Shortcut::new("Enter", 0), InsertNewLine);
+        km.bind(Shortcut::new("Enter", KEY_MOD_SHIFT), InsertNewLine);
+        km.bind(Shortcut::new("Tab", 0), InsertTab);
+        km.bind(Shortcut::new("F3", 0), FindNext);
+        km.bind(Shortcut::new("F3", KEY_MOD_SHIFT), FindPrevious);
+        km.bind(Shortcut::new("g", primary), FindNext);
+        km.bind(Shortcut::new("g", primary | KEY_MOD_SHIFT), FindPrevious);
+        km
+    }
+}
+
+#[test]
+fn test_parse() {
+    assert_eq!(Some(Shortcut::new("z", KEY_MOD_CTRL | KEY_MOD_SHIFT)), Shortcut::parse("Ctrl+Shift+Z"));
+    assert_eq!(Some(Shortcut::new("Home", 0)), Shortcut::parse("Home"));
+    assert_eq!(None, Shortcut::parse("Hyper+A"));
+    assert_eq!(Some(EditCommand::Select(Movement::WordLeft)), EditCommand::parse("selectWordLeft"));
+    assert_eq!(Some(EditCommand::Redo), EditCommand::parse("redo"));
+    assert_eq!(None, EditCommand::parse("moveSomewhere"));
+}
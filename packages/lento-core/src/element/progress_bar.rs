@@ -0,0 +1,131 @@
+use std::time::Instant;
+
+use accesskit::{NodeBuilder, Role};
+use anyhow::Error;
+use quick_js::JsValue;
+use skia_safe::Canvas;
+
+use crate::element::control::{accent_paint, content_rect, set_intrinsic_size, track_paint};
+use crate::element::{ElementBackend, ElementRef};
+use crate::js_call;
+use crate::timer::{set_interval, TimerHandle};
+
+/// Duration of the indicator moving across the bar in indeterminate mode
+const INDETERMINATE_PERIOD_MILLIS: u128 = 1500;
+
+const INDETERMINATE_FRAME_MILLIS: u64 = 16;
+
+pub struct ProgressBar {
+    element: ElementRef,
+    value: f32,
+    max: f32,
+    /// Progress is unknown, an animated indicator is drawn
+    indeterminate: bool,
+    animation_start: Instant,
+    /// Repaints the indicator while in indeterminate mode
+    animation_timer: Option<TimerHandle>,
+}
+
+impl ProgressBar {
+    pub fn set_value(&mut self, value: f32) {
+        self.value = value.clamp(0.0, self.max);
+        self.element.mark_dirty(false);
+    }
+
+    pub fn get_value(&self) -> f32 {
+        self.value
+    }
+
+    pub fn set_max(&mut self, max: f32) {
+        self.max = max.max(0.0);
+        self.value = self.value.min(self.max);
+        self.element.mark_dirty(false);
+    }
+
+    pub fn set_indeterminate(&mut self, indeterminate: bool) {
+        if self.indeterminate == indeterminate {
+            return;
+        }
+        self.indeterminate = indeterminate;
+        self.animation_timer = if indeterminate {
+            self.animation_start = Instant::now();
+            let element = self.element.clone();
+            Some(set_interval(move || {
+                element.clone().mark_dirty(false);
+            }, INDETERMINATE_FRAME_MILLIS))
+        } else {
+            None
+        };
+        self.element.mark_dirty(false);
+    }
+}
+
+impl ElementBackend for ProgressBar {
+    fn create(mut element: ElementRef) -> Self {
+        set_intrinsic_size(&mut element, 160.0, 6.0);
+        Self {
+            element,
+            value: 0.0,
+            max: 1.0,
+            indeterminate: false,
+            animation_start: Instant::now(),
+            animation_timer: None,
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        "ProgressBar"
+    }
+
+    fn draw(&self, canvas: &Canvas) {
+        let rect = content_rect(&self.element);
+        if rect.width <= 0.0 || rect.height <= 0.0 {
+            return;
+        }
+        let radius = rect.height / 2.0;
+        let track = rect.to_skia_rect();
+        canvas.draw_round_rect(track, radius, radius, &track_paint(&self.element, false));
+
+        let mut indicator = track;
+        if self.indeterminate {
+            let elapsed = self.animation_start.elapsed().as_millis() % INDETERMINATE_PERIOD_MILLIS;
+            let phase = elapsed as f32 / INDETERMINATE_PERIOD_MILLIS as f32;
+            let indicator_width = rect.width * 0.3;
+            indicator.left = -indicator_width + (rect.width + indicator_width) * phase;
+            indicator.right = indicator.left + indicator_width;
+        } else if self.max > 0.0 {
+            indicator.right = rect.width * self.value / self.max;
+        } else {
+            return;
+        }
+        canvas.save();
+        canvas.clip_rect(track, None, true);
+        canvas.draw_round_rect(indicator, radius, radius, &accent_paint(&self.element, false));
+        canvas.restore();
+    }
+
+    fn set_property(&mut self, p: &str, v: JsValue) {
+        js_call!("value", f32, self, set_value, p, v);
+        js_call!("max", f32, self, set_max, p, v);
+        js_call!("indeterminate", bool, self, set_indeterminate, p, v);
+    }
+
+    fn get_property(&mut self, property_name: &str) -> Result<Option<JsValue>, Error> {
+        match property_name {
+            "value" => Ok(Some(JsValue::Float(self.value as f64))),
+            _ => Ok(None),
+        }
+    }
+
+    fn get_accessibility_role(&self) -> Role {
+        Role::ProgressIndicator
+    }
+
+    fn build_accessibility_node(&self, builder: &mut NodeBuilder) {
+        if !self.indeterminate {
+            builder.set_numeric_value(self.value as f64);
+            builder.set_min_numeric_value(0.0);
+            builder.set_max_numeric_value(self.max as f64);
+        }
+    }
+}
@@ -0,0 +1,317 @@
+use accesskit::{Action, DefaultActionVerb, NodeBuilder, Role, Toggled};
+use anyhow::Error;
+use quick_js::JsValue;
+use skia_safe::{Canvas, PaintStyle};
+use winit::keyboard::NamedKey;
+
+use crate::base::{CheckedChangeDetail, ElementEvent, SelectionChangeDetail};
+use crate::element::container::Container;
+use crate::element::control::{accent_paint, content_rect, emit_keyboard_click, is_activate_key, outline_paint, set_intrinsic_size};
+use crate::element::{ElementBackend, ElementRef};
+use crate::event::{AcceptClickEvent, CheckedChangeBind, KeyDownEvent, SelectionChangeBind};
+use crate::js_call;
+
+pub struct Radio {
+    element: ElementRef,
+    value: String,
+    checked: bool,
+    disabled: bool,
+}
+
+impl Radio {
+    pub fn set_value(&mut self, value: String) {
+        self.value = value;
+    }
+
+    pub fn get_value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn set_checked(&mut self, checked: bool) {
+        self.checked = checked;
+        self.element.mark_dirty(false);
+    }
+
+    pub fn is_checked(&self) -> bool {
+        self.checked
+    }
+
+    pub fn set_disabled(&mut self, disabled: bool) {
+        self.disabled = disabled;
+        self.element.mark_dirty(false);
+    }
+
+    /// Check by user, other radios in the same group are unchecked
+    pub fn select(&mut self) {
+        if self.disabled || self.checked {
+            return;
+        }
+        match find_group(&self.element) {
+            Some(mut group) => {
+                group.get_backend_mut_as::<RadioGroup>().select_radio(&self.element);
+            }
+            None => {
+                self.set_checked(true);
+                self.element.emit_checked_change(CheckedChangeDetail {
+                    checked: true,
+                });
+            }
+        }
+    }
+}
+
+impl ElementBackend for Radio {
+    fn create(mut element: ElementRef) -> Self {
+        set_intrinsic_size(&mut element, 16.0, 16.0);
+        Self {
+            element,
+            value: String::new(),
+            checked: false,
+            disabled: false,
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        "Radio"
+    }
+
+    fn draw(&self, canvas: &Canvas) {
+        let rect = content_rect(&self.element);
+        let radius = rect.width.min(rect.height) / 2.0;
+        if radius <= 0.0 {
+            return;
+        }
+        let center = (rect.width / 2.0, rect.height / 2.0);
+        let mut paint = if self.checked {
+            accent_paint(&self.element, self.disabled)
+        } else {
+            outline_paint(&self.element, self.disabled)
+        };
+        paint.set_style(PaintStyle::Stroke);
+        paint.set_stroke_width(1.0);
+        canvas.draw_circle(center, radius - 0.5, &paint);
+        if self.checked {
+            canvas.draw_circle(center, radius / 2.0, &accent_paint(&self.element, self.disabled));
+        }
+    }
+
+    fn set_property(&mut self, p: &str, v: JsValue) {
+        js_call!("value", String, self, set_value, p, v);
+        js_call!("checked", bool, self, set_checked, p, v);
+        js_call!("disabled", bool, self, set_disabled, p, v);
+    }
+
+    fn get_property(&mut self, property_name: &str) -> Result<Option<JsValue>, Error> {
+        match property_name {
+            "checked" => Ok(Some(JsValue::Bool(self.checked))),
+            "value" => Ok(Some(JsValue::String(self.value.clone()))),
+            _ => Ok(None),
+        }
+    }
+
+    fn handle_event_default_behavior(&mut self, event_type: &str, event: &mut ElementEvent) -> bool {
+        let mut activated = false;
+        let mut direction = 0;
+        KeyDownEvent::try_match(event_type, event, |d| {
+            activated = is_activate_key(d);
+            if d.modifiers == 0 {
+                direction = match d.named_key {
+                    Some(NamedKey::ArrowDown) | Some(NamedKey::ArrowRight) => 1,
+                    Some(NamedKey::ArrowUp) | Some(NamedKey::ArrowLeft) => -1,
+                    _ => 0,
+                };
+            }
+        });
+        if activated {
+            emit_keyboard_click(&mut self.element);
+            return true;
+        }
+        if direction != 0 {
+            if let Some(mut group) = find_group(&self.element) {
+                group.get_backend_mut_as::<RadioGroup>().move_selection(&self.element, direction);
+                return true;
+            }
+        }
+        event.accept_click(|_| {
+            self.select();
+        })
+    }
+
+    fn is_focusable(&self) -> bool {
+        !self.disabled
+    }
+
+    fn get_accessibility_role(&self) -> Role {
+        Role::RadioButton
+    }
+
+    fn build_accessibility_node(&self, builder: &mut NodeBuilder) {
+        builder.set_toggled(if self.checked { Toggled::True } else { Toggled::False });
+        if self.disabled {
+            builder.set_disabled();
+        } else {
+            builder.set_default_action_verb(DefaultActionVerb::Click);
+            builder.add_action(Action::Focus);
+            builder.add_action(Action::Default);
+        }
+    }
+}
+
+/// Container in which only one radio could be checked
+pub struct RadioGroup {
+    element: ElementRef,
+    base: Container,
+    value: Option<String>,
+}
+
+impl RadioGroup {
+    pub fn set_value(&mut self, value: String) {
+        for mut radio in self.get_radios() {
+            let radio = radio.get_backend_mut_as::<Radio>();
+            radio.set_checked(radio.get_value() == value);
+        }
+        self.value = Some(value);
+    }
+
+    pub fn get_value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+
+    /// Radios inside the group, nested groups are excluded
+    pub fn get_radios(&self) -> Vec<ElementRef> {
+        let mut result = Vec::new();
+        collect_radios(&self.element, &mut result);
+        result
+    }
+
+    fn select_radio(&mut self, target: &ElementRef) {
+        let mut value = None;
+        for mut radio in self.get_radios() {
+            let checked = &radio == target;
+            let radio = radio.get_backend_mut_as::<Radio>();
+            radio.set_checked(checked);
+            if checked {
+                value = Some(radio.get_value().to_string());
+            }
+        }
+        if let Some(value) = value {
+            self.value = Some(value.clone());
+            self.element.emit_selection_change(SelectionChangeDetail { value });
+        }
+    }
+
+    /// Check and focus the next enabled radio, wrap around at the end
+    fn move_selection(&mut self, from: &ElementRef, direction: isize) {
+        let radios: Vec<ElementRef> = self.get_radios().into_iter()
+            .filter(|r| r == from || !r.get_backend_as::<Radio>().disabled)
+            .collect();
+        let Some(pos) = radios.iter().position(|r| r == from) else {
+            return;
+        };
+        let len = radios.len() as isize;
+        let mut next = radios[(pos as isize + direction).rem_euclid(len) as usize].clone();
+        if &next == from {
+            return;
+        }
+        self.select_radio(&next);
+        let _ = next.focus();
+    }
+}
+
+impl ElementBackend for RadioGroup {
+    fn create(element: ElementRef) -> Self {
+        Self {
+            base: Container::create(element.clone()),
+            element,
+            value: None,
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        "RadioGroup"
+    }
+
+    fn set_property(&mut self, p: &str, v: JsValue) {
+        js_call!("value", String, self, set_value, p, v);
+    }
+
+    fn get_property(&mut self, property_name: &str) -> Result<Option<JsValue>, Error> {
+        match property_name {
+            "value" => Ok(Some(match &self.value {
+                Some(v) => JsValue::String(v.clone()),
+                None => JsValue::Null,
+            })),
+            _ => Ok(None),
+        }
+    }
+
+    fn add_child_view(&mut self, child: ElementRef, position: Option<u32>) {
+        self.base.add_child_view(child, position);
+    }
+
+    fn remove_child_view(&mut self, position: u32) {
+        self.base.remove_child_view(position);
+    }
+
+    fn get_children(&self) -> Vec<ElementRef> {
+        self.base.get_children()
+    }
+
+    fn accepts_children(&self) -> bool {
+        true
+    }
+
+    fn get_accessibility_role(&self) -> Role {
+        Role::RadioGroup
+    }
+}
+
+fn find_group(radio: &ElementRef) -> Option<ElementRef> {
+    let mut parent = radio.get_parent();
+    while let Some(p) = parent {
+        if p.get_backend().get_name() == "RadioGroup" {
+            return Some(p);
+        }
+        parent = p.get_parent();
+    }
+    None
+}
+
+fn collect_radios(element: &ElementRef, result: &mut Vec<ElementRef>) {
+    for child in element.get_children() {
+        match child.get_backend().get_name() {
+            "Radio" => result.push(child),
+            "RadioGroup" => {}
+            _ => collect_radios(&child, result),
+        }
+    }
+}
+
+#[test]
+fn test_radio_group_arrow_navigation() {
+    use crate::element::control::create_key_down_event;
+
+    let mut group = ElementRef::new(RadioGroup::create);
+    let mut radios = Vec::new();
+    for value in ["a", "b", "c"] {
+        let mut radio = ElementRef::new(Radio::create);
+        radio.get_backend_mut_as::<Radio>().set_value(value.to_string());
+        group.add_child_view(radio.clone(), None);
+        radios.push(radio);
+    }
+    radios[1].get_backend_mut_as::<Radio>().set_disabled(true);
+    group.get_backend_mut_as::<RadioGroup>().set_value("a".to_string());
+
+    let key_down = |radio: &mut ElementRef, key: NamedKey| {
+        let mut event = create_key_down_event(radio, key, 0);
+        assert!(radio.get_backend_mut_as::<Radio>().handle_event_default_behavior("keydown", &mut event));
+        group.get_backend_as::<RadioGroup>().get_value().unwrap().to_string()
+    };
+    // disabled radios are skipped
+    assert_eq!("c", key_down(&mut radios[0], NamedKey::ArrowDown));
+    assert!(!radios[0].get_backend_as::<Radio>().is_checked());
+    assert!(radios[2].get_backend_as::<Radio>().is_checked());
+    // wrap around
+    assert_eq!("a", key_down(&mut radios[2], NamedKey::ArrowRight));
+    assert_eq!("c", key_down(&mut radios[0], NamedKey::ArrowUp));
+}
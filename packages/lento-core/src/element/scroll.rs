@@ -1,21 +1,33 @@
+use std::collections::VecDeque;
 use std::str::FromStr;
+use std::time::{Duration, Instant};
 
+use accesskit::{NodeBuilder, Role};
 use quick_js::JsValue;
-use skia_safe::{Canvas, Paint};
+use skia_safe::{Canvas, Color, Paint};
 use yoga::Direction::LTR;
 
-use crate::{backend_as_api, js_call};
+use crate::{backend_as_api, js_call, js_call_rust};
 use crate::base::{CaretDetail, ElementEvent, Rect};
-use crate::color::parse_hex_color;
 use crate::element::{ElementBackend, ElementRef};
 use crate::element::container::Container;
 use crate::element::scroll::ScrollBarStrategy::{Always, Auto, Never};
-use crate::event::{AcceptCaretEvent, AcceptMouseDownEvent, AcceptMouseMoveEvent, AcceptMouseUpEvent, AcceptTouchCancelEvent, AcceptTouchEndEvent, AcceptTouchMoveEvent, AcceptTouchStartEvent, MouseDownEvent, MouseWheelDetail};
+use crate::element::scroll_animation::{is_fling_velocity, momentum_end, nearest_snap_point, ScrollBehavior, FRAME_INTERVAL};
+use crate::event::{AcceptCaretEvent, AcceptMouseDownEvent, AcceptMouseLeaveEvent, AcceptMouseMoveEvent, AcceptMouseUpEvent, AcceptScroll, AcceptTouchCancelEvent, AcceptTouchEndEvent, AcceptTouchMoveEvent, AcceptTouchStartEvent, MouseDownEvent, MouseWheelDetail};
 use crate::js::js_runtime::FromJsValue;
+use crate::style::{ScrollbarMode, ScrollbarStyle};
+use crate::timer::{set_timeout, TimerHandle};
 
-const BACKGROUND_COLOR: &str = "1E1F22";
+/// Overlay bars keep visible for this duration(ms) after scrolling, then fade out
+const OVERLAY_VISIBLE_DURATION: f32 = 1000.0;
 
-const INDICATOR_COLOR: &str = "444446";
+const OVERLAY_FADE_DURATION: f32 = 300.0;
+
+/// Touch moves in this duration before releasing are used to calculate the fling velocity
+const VELOCITY_SAMPLE_DURATION: Duration = Duration::from_millis(100);
+
+/// Wheel scrolling is considered ended after this delay(ms), then it snaps
+const WHEEL_SNAP_DELAY: u64 = 150;
 
 pub enum ScrollBarStrategy {
     Never,
@@ -48,7 +60,6 @@ impl FromJsValue for ScrollBarStrategy {
 backend_as_api!(ScrollBackend, Scroll, as_scroll, as_scroll_mut);
 
 pub struct Scroll {
-    scroll_bar_size: f32,
     element: ElementRef,
     base: Container,
     vertical_bar_strategy: ScrollBarStrategy,
@@ -65,6 +76,23 @@ pub struct Scroll {
     is_x_overflow: bool,
     real_content_width: f32,
     real_content_height: f32,
+
+    /// (time, frame_x, frame_y) of recent touch moves
+    touch_samples: VecDeque<(Instant, f32, f32)>,
+    snap_points_x: Vec<f32>,
+    snap_points_y: Vec<f32>,
+    /// Snap to the start of children
+    snap_children_x: bool,
+    snap_children_y: bool,
+    snap_timer: Option<TimerHandle>,
+
+    /// Thumb under the mouse, true for the vertical one
+    hovered_thumb: Option<bool>,
+    /// Thumb being dragged, true for the vertical one
+    pressed_thumb: Option<bool>,
+    /// Last time overlay bars were shown by scrolling or hovering
+    bar_active_time: Option<Instant>,
+    fade_timer: Option<TimerHandle>,
 }
 
 impl Scroll {
@@ -83,6 +111,109 @@ impl Scroll {
         self.element.set_scroll_top(top);
     }
 
+    /// Scroll offsets to settle at when scrolling ends
+    pub fn set_snap_points_x(&mut self, points: Vec<f32>) {
+        self.snap_points_x = points;
+    }
+
+    pub fn set_snap_points_y(&mut self, points: Vec<f32>) {
+        self.snap_points_y = points;
+    }
+
+    /// Snap to the start of children, "x", "y", "both" or "none"
+    pub fn set_snap_children(&mut self, axis: String) {
+        let axis = axis.to_lowercase();
+        self.snap_children_x = axis == "x" || axis == "both";
+        self.snap_children_y = axis == "y" || axis == "both";
+    }
+
+    fn snap_points(&self, vertical: bool) -> Vec<f32> {
+        let (mut points, snap_children) = if vertical {
+            (self.snap_points_y.clone(), self.snap_children_y)
+        } else {
+            (self.snap_points_x.clone(), self.snap_children_x)
+        };
+        if snap_children {
+            let content_bounds = self.element.get_content_bounds();
+            for child in self.element.get_children() {
+                let bounds = child.get_bounds();
+                points.push(if vertical {
+                    bounds.y - content_bounds.y
+                } else {
+                    bounds.x - content_bounds.x
+                });
+            }
+        }
+        points
+    }
+
+    fn snap_target(&self, position: (f32, f32)) -> Option<(f32, f32)> {
+        let points_x = self.snap_points(false);
+        let points_y = self.snap_points(true);
+        if points_x.is_empty() && points_y.is_empty() {
+            return None;
+        }
+        Some((nearest_snap_point(&points_x, position.0), nearest_snap_point(&points_y, position.1)))
+    }
+
+    fn snap(&mut self) {
+        if let Some((left, top)) = self.snap_target(self.element.get_scroll_target()) {
+            self.element.scroll_to_with_behavior(left, top, ScrollBehavior::Smooth);
+        }
+    }
+
+    fn schedule_snap(&mut self) {
+        if self.snap_points_x.is_empty() && self.snap_points_y.is_empty()
+            && !self.snap_children_x && !self.snap_children_y {
+            return;
+        }
+        let mut element = self.element.clone();
+        self.snap_timer = Some(set_timeout(move || {
+            element.as_scroll_mut().snap();
+        }, WHEEL_SNAP_DELAY));
+    }
+
+    fn record_touch(&mut self, frame_x: f32, frame_y: f32) {
+        let now = Instant::now();
+        self.touch_samples.push_back((now, frame_x, frame_y));
+        while let Some((time, _, _)) = self.touch_samples.front() {
+            if now.duration_since(*time) > VELOCITY_SAMPLE_DURATION {
+                self.touch_samples.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Scroll velocity(px per ms) of the recent touch moves
+    fn touch_velocity(&self) -> (f32, f32) {
+        let now = Instant::now();
+        let mut samples = self.touch_samples.iter()
+            .filter(|(time, _, _)| now.duration_since(*time) <= VELOCITY_SAMPLE_DURATION);
+        let (Some(first), Some(last)) = (samples.next(), self.touch_samples.back()) else {
+            return (0.0, 0.0);
+        };
+        let duration = last.0.duration_since(first.0).as_secs_f32() * 1000.0;
+        if duration <= 0.0 {
+            return (0.0, 0.0);
+        }
+        // Content moves with the finger, so scrolling is in the opposite direction
+        let vx = if self.is_x_overflow { (first.1 - last.1) / duration } else { 0.0 };
+        let vy = if self.is_y_overflow { (first.2 - last.2) / duration } else { 0.0 };
+        (vx, vy)
+    }
+
+    fn release_touch(&mut self) {
+        let velocity = self.touch_velocity();
+        self.touch_samples.clear();
+        let position = (self.element.get_scroll_left(), self.element.get_scroll_top());
+        if let Some((left, top)) = self.snap_target(momentum_end(position, velocity)) {
+            self.element.scroll_to_with_behavior(left, top, ScrollBehavior::Smooth);
+        } else if is_fling_velocity(velocity) {
+            self.element.fling(velocity);
+        }
+    }
+
     fn layout_content(&mut self) {
         let (width, height) = self.get_body_view_size();
         //TODO fix ltr
@@ -94,11 +225,21 @@ impl Scroll {
         }
     }
 
+    fn bar_style(&self) -> &ScrollbarStyle {
+        &self.element.layout.scrollbar
+    }
+
+    fn is_overlay(&self) -> bool {
+        self.bar_style().mode == ScrollbarMode::Overlay
+    }
+
     fn get_body_view_size(&self) -> (f32, f32) {
         let (mut width, mut height) = self.element.get_size();
-        // let (body_width, body_height) = self.body.get_size();
-        width -= self.vertical_bar_rect.width;
-        height -= self.horizontal_bar_rect.height;
+        // Overlay bars are drawn over the content
+        if !self.is_overlay() {
+            width -= self.vertical_bar_rect.width;
+            height -= self.horizontal_bar_rect.height;
+        }
 
         width = f32::max(0.0, width);
         height = f32::max(0.0, height);
@@ -107,12 +248,22 @@ impl Scroll {
     }
 
     fn handle_default_mouse_wheel(&mut self, detail: &MouseWheelDetail) -> bool {
-        if self.is_y_overflow {
-            self.element.set_scroll_top(self.element.get_scroll_top() - 40.0 * detail.rows);
-            true
+        let delta_x = if self.is_x_overflow { -detail.delta_x } else { 0.0 };
+        let delta_y = if self.is_y_overflow { -detail.delta_y } else { 0.0 };
+        if delta_x == 0.0 && delta_y == 0.0 {
+            return false;
+        }
+        if detail.precise {
+            // Touchpads report continuous deltas, which is smooth already
+            let left = self.element.get_scroll_left();
+            let top = self.element.get_scroll_top();
+            self.element.scroll_to_with_behavior(left + delta_x, top + delta_y, ScrollBehavior::Instant);
         } else {
-            false
+            let (left, top) = self.element.get_scroll_target();
+            self.element.scroll_to_with_behavior(left + delta_x, top + delta_y, ScrollBehavior::Smooth);
         }
+        self.schedule_snap();
+        true
     }
 
     fn handle_caret_change(&mut self, detail: &CaretDetail) {
@@ -139,7 +290,7 @@ impl Scroll {
 
 
     fn update_vertical_bar_rect(&mut self, is_visible: bool, container_width: f32, container_height: f32) {
-        let bar_size = self.scroll_bar_size;
+        let bar_size = self.bar_style().width;
         self.vertical_bar_rect = if is_visible {
             let bar_height = if self.horizontal_bar_rect.is_empty() {
                 container_height
@@ -167,7 +318,7 @@ impl Scroll {
     }
 
     fn update_horizontal_bar_rect(&mut self, is_visible: bool, width: f32, height: f32) {
-        let bar_size = self.scroll_bar_size;
+        let bar_size = self.bar_style().width;
         self.horizontal_bar_rect = if is_visible {
             let bar_width = if self.vertical_bar_rect.is_empty() {
                 width
@@ -237,6 +388,101 @@ impl Scroll {
         self.horizontal_move_begin = None;
     }
 
+    /// Position relative to the content box, which is the coordinate of bar rects
+    fn to_local_position(&self, frame_x: f32, frame_y: f32) -> (f32, f32) {
+        let bounds = self.element.get_origin_bounds();
+        let (border_top, _, _, border_left) = self.element.get_border_width();
+        let (padding_top, _, _, padding_left) = self.element.get_padding();
+        (frame_x - bounds.x - border_left - padding_left, frame_y - bounds.y - border_top - padding_top)
+    }
+
+    /// Returns the thumb at the position, true for the vertical one
+    fn thumb_at(&self, x: f32, y: f32) -> Option<bool> {
+        if self.bar_alpha() <= 0.0 {
+            None
+        } else if self.calculate_vertical_indicator_rect().contains_point(x, y) {
+            Some(true)
+        } else if self.calculate_horizontal_indicator_rect().contains_point(x, y) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    fn set_hovered_thumb(&mut self, thumb: Option<bool>) {
+        if self.hovered_thumb != thumb {
+            self.hovered_thumb = thumb;
+            self.activate_bars();
+        }
+    }
+
+    /// Show overlay bars, they fade out later
+    fn activate_bars(&mut self) {
+        self.element.mark_dirty(false);
+        if self.is_overlay() {
+            self.bar_active_time = Some(Instant::now());
+            self.request_fade_frame();
+        }
+    }
+
+    fn request_fade_frame(&mut self) {
+        let elapsed = self.bar_active_elapsed();
+        let delay = if elapsed < OVERLAY_VISIBLE_DURATION {
+            (OVERLAY_VISIBLE_DURATION - elapsed) as u64
+        } else {
+            FRAME_INTERVAL
+        };
+        let mut element = self.element.clone();
+        self.fade_timer = Some(set_timeout(move || {
+            let scroll = element.as_scroll_mut();
+            scroll.element.mark_dirty(false);
+            if scroll.hovered_thumb.is_none() && scroll.pressed_thumb.is_none() && scroll.bar_alpha() > 0.0 {
+                scroll.request_fade_frame();
+            }
+        }, delay));
+    }
+
+    fn bar_active_elapsed(&self) -> f32 {
+        self.bar_active_time.map(|t| t.elapsed().as_secs_f32() * 1000.0).unwrap_or(f32::MAX)
+    }
+
+    /// Opacity of bars, overlay bars fade out when idle
+    fn bar_alpha(&self) -> f32 {
+        if !self.is_overlay() || self.hovered_thumb.is_some() || self.pressed_thumb.is_some() {
+            return 1.0;
+        }
+        let elapsed = self.bar_active_elapsed();
+        if elapsed <= OVERLAY_VISIBLE_DURATION {
+            1.0
+        } else {
+            (1.0 - (elapsed - OVERLAY_VISIBLE_DURATION) / OVERLAY_FADE_DURATION).max(0.0)
+        }
+    }
+
+    fn thumb_color(&self, vertical: bool) -> Color {
+        let style = self.bar_style();
+        if self.pressed_thumb == Some(vertical) {
+            style.thumb_active_color
+        } else if self.hovered_thumb == Some(vertical) {
+            style.thumb_hover_color
+        } else {
+            style.thumb_color
+        }
+    }
+
+    fn draw_bar(&self, canvas: &Canvas, bar_rect: &Rect, thumb_rect: &Rect, vertical: bool, alpha: f32) {
+        let radius = self.bar_style().radius;
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        paint.set_color(self.bar_style().track_color);
+        paint.set_alpha_f(paint.alpha_f() * alpha);
+        canvas.draw_rect(bar_rect.to_skia_rect(), &paint);
+
+        paint.set_color(self.thumb_color(vertical));
+        paint.set_alpha_f(paint.alpha_f() * alpha);
+        canvas.draw_round_rect(thumb_rect.to_skia_rect(), radius, radius, &paint);
+    }
+
 
 }
 
@@ -246,7 +492,6 @@ impl ElementBackend for Scroll {
         let mut base = Container::create(ele.clone());
 
         let inst = Self {
-            scroll_bar_size: 14.0,
             element: ele.clone(),
             base,
             horizontal_bar_strategy: Auto,
@@ -259,6 +504,16 @@ impl ElementBackend for Scroll {
             vertical_bar_rect: Rect::empty(),
             real_content_height: 0.0,
             horizontal_move_begin: None,
+            touch_samples: VecDeque::new(),
+            snap_points_x: Vec::new(),
+            snap_points_y: Vec::new(),
+            snap_children_x: false,
+            snap_children_y: false,
+            snap_timer: None,
+            hovered_thumb: None,
+            pressed_thumb: None,
+            bar_active_time: None,
+            fade_timer: None,
         };
         inst
     }
@@ -326,54 +581,98 @@ impl ElementBackend for Scroll {
         self.base.get_children()
     }
 
+    fn accepts_children(&self) -> bool {
+        true
+    }
+
     fn set_property(&mut self, p: &str, v: JsValue) {
         js_call!("scroll_y", ScrollBarStrategy, self, set_scroll_y, p, v);
         js_call!("scroll_x", ScrollBarStrategy, self, set_scroll_x, p, v);
+        js_call!("snap_children", String, self, set_snap_children, p, v);
+        js_call_rust!("snap_points_x", Vec<f32>, self, set_snap_points_x, p, v);
+        js_call_rust!("snap_points_y", Vec<f32>, self, set_snap_points_y, p, v);
+    }
+
+    fn handle_style_changed(&mut self, key: &str) {
+        match key {
+            "ScrollbarMode" | "ScrollbarWidth" => {
+                let bounds = self.element.get_origin_bounds();
+                self.handle_origin_bounds_change(&bounds);
+                self.element.mark_dirty(true);
+            }
+            _ => {}
+        }
+    }
+
+    fn get_accessibility_role(&self) -> Role {
+        Role::ScrollView
+    }
+
+    fn build_accessibility_node(&self, builder: &mut NodeBuilder) {
+        builder.set_scroll_x(self.element.get_scroll_left() as f64);
+        builder.set_scroll_y(self.element.get_scroll_top() as f64);
     }
 
     fn handle_event_default_behavior(&mut self, _event_type: &str, event: &mut ElementEvent) -> bool {
         let is_target_self = &event.context.target == &self.element;
         event.accept_touch_start(|d| {
             let touch = unsafe { d.touches.get_unchecked(0) };
+            self.element.stop_scroll_animation();
+            self.touch_samples.clear();
+            self.record_touch(touch.frame_x, touch.frame_y);
             self.begin_scroll_x(-touch.frame_x);
             self.begin_scroll_y(-touch.frame_y);
         }) || event.accept_touch_move(|d| {
             let touch = unsafe { d.touches.get_unchecked(0) };
+            self.record_touch(touch.frame_x, touch.frame_y);
             self.update_scroll_x(-touch.frame_x, false);
             self.update_scroll_y(-touch.frame_y, false);
         }) || event.accept_touch_end(|_| {
             self.end_scroll();
+            self.release_touch();
         }) || event.accept_touch_cancel(|_| {
             self.end_scroll();
+            self.touch_samples.clear();
         }) || event.accept_mouse_down(|d| {
+            // Overlay thumbs are hit before children, see `is_foreground_at`
             if !is_target_self {
                 return;
             }
-            let is_in_vertical_bar = self.vertical_bar_rect.contains_point(d.offset_x, d.offset_y);
-            if is_in_vertical_bar {
-                let indicator_rect = self.calculate_vertical_indicator_rect();
-                if indicator_rect.contains_point(d.offset_x, d.offset_y) {
-                    self.begin_scroll_y(d.frame_y);
-                } else {
-                    //TODO scroll page
-                }
-                return;
-            }
-            let is_in_horizontal_bar = self.horizontal_bar_rect.contains_point(d.offset_x, d.offset_y);
-            if is_in_horizontal_bar {
-                let indicator_rect = self.calculate_horizontal_indicator_rect();
-                if indicator_rect.contains_point(d.offset_x, d.offset_y) {
-                    self.begin_scroll_x(d.frame_x);
-                } else {
-                    //TODO scroll page
-                }
-                return;
+            let (x, y) = self.to_local_position(d.frame_x, d.frame_y);
+            match self.thumb_at(x, y) {
+                Some(true) => self.begin_scroll_y(d.frame_y),
+                Some(false) => self.begin_scroll_x(d.frame_x),
+                //TODO scroll page
+                None => return,
             }
-        }) || event.accept_mouse_up(|d| {
+            self.pressed_thumb = self.thumb_at(x, y);
+            self.element.stop_scroll_animation();
+            self.activate_bars();
+        }) || event.accept_mouse_up(|_| {
+            let is_dragging_bar = self.pressed_thumb.is_some();
             self.end_scroll();
+            if is_dragging_bar {
+                self.pressed_thumb = None;
+                self.activate_bars();
+                self.snap();
+            }
         }) || event.accept_mouse_move(|d| {
-            self.update_scroll_x(d.frame_x, true);
-            self.update_scroll_y(d.frame_y, true);
+            if self.pressed_thumb.is_some() {
+                self.update_scroll_x(d.frame_x, true);
+                self.update_scroll_y(d.frame_y, true);
+            } else {
+                let (x, y) = self.to_local_position(d.frame_x, d.frame_y);
+                let thumb = self.thumb_at(x, y);
+                self.set_hovered_thumb(thumb);
+            }
+        }) || event.accept_mouse_leave(|_| {
+            if is_target_self {
+                self.set_hovered_thumb(None);
+            }
+        }) || event.accept_scroll(|_| {
+            if is_target_self {
+                self.activate_bars();
+            }
         }) || event.accept_caret_change(|d| {
             self.handle_caret_change(d);
         }) || if let Some(e) = event.detail.raw().downcast_ref::<MouseWheelDetail>() {
@@ -383,22 +682,23 @@ impl ElementBackend for Scroll {
         }
     }
 
-    fn draw(&self, canvas: &Canvas) {
-        let mut paint = Paint::default();
-        paint.set_color(parse_hex_color(BACKGROUND_COLOR).unwrap());
-
-        let mut indicator_paint = Paint::default();
-        indicator_paint.set_color(parse_hex_color(INDICATOR_COLOR).unwrap());
+    fn is_foreground_at(&self, x: f32, y: f32) -> bool {
+        // Overlay thumbs are over children, which should not get mouse events there
+        self.is_overlay() && self.thumb_at(x, y).is_some()
+    }
 
+    fn draw_foreground(&self, canvas: &Canvas) {
+        let alpha = self.bar_alpha();
+        if alpha <= 0.0 {
+            return;
+        }
         if !self.vertical_bar_rect.is_empty() {
-            canvas.draw_rect(self.vertical_bar_rect.to_skia_rect(), &paint);
-            let v_indicator_rect = self.calculate_vertical_indicator_rect();
-            canvas.draw_rect(v_indicator_rect.to_skia_rect(), &indicator_paint);
+            let thumb_rect = self.calculate_vertical_indicator_rect();
+            self.draw_bar(canvas, &self.vertical_bar_rect, &thumb_rect, true, alpha);
         }
         if !self.horizontal_bar_rect.is_empty() {
-            canvas.draw_rect(self.horizontal_bar_rect.to_skia_rect(), &paint);
-            let h_indicator_rect = self.calculate_horizontal_indicator_rect();
-            canvas.draw_rect(h_indicator_rect.to_skia_rect(), &indicator_paint);
+            let thumb_rect = self.calculate_horizontal_indicator_rect();
+            self.draw_bar(canvas, &self.horizontal_bar_rect, &thumb_rect, false, alpha);
         }
     }
 }
@@ -426,4 +726,108 @@ impl Indicator {
     fn get_indicator_end(&self) -> f32 {
         self.get_indicator_offset() + self.get_indicator_size()
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+fn create_test_scroll(mode: &str) -> (ElementRef, ElementRef) {
+    use crate::style::StyleProp;
+    let mut scroll = ElementRef::new(Scroll::create);
+    scroll.set_style_props(vec![
+        StyleProp::parse("width", "100").unwrap(),
+        StyleProp::parse("height", "100").unwrap(),
+        StyleProp::parse("scrollbarMode", mode).unwrap(),
+    ]);
+    let mut content = ElementRef::new(Container::create);
+    content.set_style_props(vec![StyleProp::parse("height", "400").unwrap()]);
+    scroll.add_child_view(content.clone(), None);
+    scroll.calculate_layout(100.0, 100.0);
+    (scroll, content)
+}
+
+#[test]
+fn test_scrollbar_style() {
+    use crate::style::StyleProp;
+    let mut scroll = ElementRef::new(Scroll::create);
+    assert!(StyleProp::parse("scrollbarMode", "floating").is_none());
+    scroll.set_style_props(vec![
+        StyleProp::parse("scrollbarMode", "Overlay").unwrap(),
+        StyleProp::parse("scrollbarWidth", "8").unwrap(),
+        StyleProp::parse("scrollbarRadius", "4").unwrap(),
+        StyleProp::parse("scrollbarTrackColor", "#000000").unwrap(),
+        StyleProp::parse("scrollbarThumbColor", "#ff0000").unwrap(),
+        StyleProp::parse("scrollbarThumbHoverColor", "#00ff00").unwrap(),
+        StyleProp::parse("scrollbarThumbActiveColor", "#0000ff").unwrap(),
+    ]);
+    let style = scroll.layout.scrollbar.clone();
+    assert_eq!(ScrollbarMode::Overlay, style.mode);
+    assert_eq!(8.0, style.width);
+    assert_eq!(4.0, style.radius);
+    assert_eq!(Color::from_rgb(0, 0, 0), style.track_color);
+    assert_eq!(Color::from_rgb(255, 0, 0), style.thumb_color);
+    assert_eq!(Color::from_rgb(0, 255, 0), style.thumb_hover_color);
+    assert_eq!(Color::from_rgb(0, 0, 255), style.thumb_active_color);
+
+    // removed properties fall back to defaults
+    scroll.set_style_props(Vec::new());
+    let style = scroll.layout.scrollbar.clone();
+    assert_eq!(ScrollbarMode::Classic, style.mode);
+    assert_eq!(ScrollbarStyle::DEFAULT_WIDTH, style.width);
+    assert_eq!(ScrollbarStyle::DEFAULT_THUMB_COLOR, style.thumb_color);
+}
+
+#[test]
+fn test_thumb_at() {
+    let (mut scroll, _) = create_test_scroll("classic");
+    // content is 400 high in a 100 high view, the thumb is 25 high
+    let bar_x = 100.0 - ScrollbarStyle::DEFAULT_WIDTH / 2.0;
+    assert_eq!(Some(true), scroll.as_scroll().thumb_at(bar_x, 10.0));
+    assert_eq!(None, scroll.as_scroll().thumb_at(bar_x, 50.0));
+    assert_eq!(None, scroll.as_scroll().thumb_at(50.0, 10.0));
+
+    scroll.set_scroll_top(300.0);
+    assert_eq!(None, scroll.as_scroll().thumb_at(bar_x, 10.0));
+    assert_eq!(Some(true), scroll.as_scroll().thumb_at(bar_x, 90.0));
+}
+
+#[test]
+fn test_overlay_bar_alpha() {
+    let (mut scroll, _) = create_test_scroll("overlay");
+    let bar_x = 100.0 - ScrollbarStyle::DEFAULT_WIDTH / 2.0;
+    let set_elapsed = |scroll: &mut ElementRef, ms: u64| {
+        scroll.as_scroll_mut().bar_active_time = Some(Instant::now() - Duration::from_millis(ms));
+    };
+    // idle overlay bars are hidden and can't be hit
+    assert_eq!(0.0, scroll.as_scroll().bar_alpha());
+    assert_eq!(None, scroll.as_scroll().thumb_at(bar_x, 10.0));
+
+    set_elapsed(&mut scroll, 500);
+    assert_eq!(1.0, scroll.as_scroll().bar_alpha());
+    assert_eq!(Some(true), scroll.as_scroll().thumb_at(bar_x, 10.0));
+
+    set_elapsed(&mut scroll, (OVERLAY_VISIBLE_DURATION + OVERLAY_FADE_DURATION / 2.0) as u64);
+    assert!((scroll.as_scroll().bar_alpha() - 0.5).abs() < 0.05);
+
+    set_elapsed(&mut scroll, (OVERLAY_VISIBLE_DURATION + OVERLAY_FADE_DURATION) as u64 + 100);
+    assert_eq!(0.0, scroll.as_scroll().bar_alpha());
+
+    // hovered bars don't fade
+    scroll.as_scroll_mut().hovered_thumb = Some(true);
+    assert_eq!(1.0, scroll.as_scroll().bar_alpha());
+
+    // classic bars are always visible
+    let (scroll, _) = create_test_scroll("classic");
+    assert_eq!(1.0, scroll.as_scroll().bar_alpha());
+}
+
+#[test]
+fn test_overlay_thumb_hit_test() {
+    use crate::frame::hit_test_root;
+    let (mut scroll, content) = create_test_scroll("overlay");
+    let bar_x = 100.0 - ScrollbarStyle::DEFAULT_WIDTH / 2.0;
+    // the hidden thumb doesn't cover the content
+    assert!(hit_test_root(&scroll, (bar_x, 10.0)) == Some(content.clone()));
+
+    scroll.as_scroll_mut().bar_active_time = Some(Instant::now());
+    assert!(hit_test_root(&scroll, (bar_x, 10.0)) == Some(scroll.clone()));
+    assert!(hit_test_root(&scroll, (bar_x, 50.0)) == Some(content.clone()));
+    assert!(hit_test_root(&scroll, (50.0, 10.0)) == Some(content));
+}
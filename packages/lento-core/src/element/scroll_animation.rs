@@ -0,0 +1,171 @@
+use std::time::Instant;
+
+use crate::timer::TimerHandle;
+
+/// Distance of one wheel line
+pub const LINE_SCROLL_DISTANCE: f32 = 40.0;
+
+pub const FRAME_INTERVAL: u64 = 16;
+
+const SMOOTH_SCROLL_DURATION: f32 = 200.0;
+
+/// Time constant(ms) of momentum decay, the velocity falls to 1/e after it
+const MOMENTUM_TIME_CONSTANT: f32 = 325.0;
+
+/// Momentum stops below this velocity(px per ms)
+const MIN_MOMENTUM_VELOCITY: f32 = 0.02;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScrollBehavior {
+    Instant,
+    Smooth,
+}
+
+impl ScrollBehavior {
+    pub fn parse(behavior: Option<&str>) -> Self {
+        match behavior {
+            Some("smooth") => ScrollBehavior::Smooth,
+            _ => ScrollBehavior::Instant,
+        }
+    }
+}
+
+enum Motion {
+    /// Eased movement to a target position
+    Smooth { from: (f32, f32), to: (f32, f32) },
+    /// Decaying movement after a fling, velocity in px per ms
+    Momentum { from: (f32, f32), velocity: (f32, f32) },
+}
+
+pub struct ScrollAnimation {
+    motion: Motion,
+    start_time: Instant,
+    /// Position applied by the last frame, the animation is interrupted if the scroll position is changed by others
+    pub position: (f32, f32),
+    pub timer: Option<TimerHandle>,
+}
+
+impl ScrollAnimation {
+    pub fn smooth(from: (f32, f32), to: (f32, f32)) -> Self {
+        Self::new(Motion::Smooth { from, to }, from)
+    }
+
+    pub fn momentum(from: (f32, f32), velocity: (f32, f32)) -> Self {
+        Self::new(Motion::Momentum { from, velocity }, from)
+    }
+
+    fn new(motion: Motion, position: (f32, f32)) -> Self {
+        Self {
+            motion,
+            start_time: Instant::now(),
+            position,
+            timer: None,
+        }
+    }
+
+    /// Position where the animation ends
+    pub fn target(&self) -> (f32, f32) {
+        match self.motion {
+            Motion::Smooth { to, .. } => to,
+            Motion::Momentum { from, velocity } => momentum_end(from, velocity),
+        }
+    }
+
+    /// Returns the position at `now` and whether the animation is finished
+    pub fn step(&self, now: Instant) -> ((f32, f32), bool) {
+        let elapsed = now.duration_since(self.start_time).as_secs_f32() * 1000.0;
+        match self.motion {
+            Motion::Smooth { from, to } => {
+                let progress = (elapsed / SMOOTH_SCROLL_DURATION).min(1.0);
+                let eased = ease_out_cubic(progress);
+                let position = (
+                    from.0 + (to.0 - from.0) * eased,
+                    from.1 + (to.1 - from.1) * eased,
+                );
+                (position, progress >= 1.0)
+            }
+            Motion::Momentum { from, velocity } => {
+                let decay = (-elapsed / MOMENTUM_TIME_CONSTANT).exp();
+                let distance = MOMENTUM_TIME_CONSTANT * (1.0 - decay);
+                let position = (from.0 + velocity.0 * distance, from.1 + velocity.1 * distance);
+                let speed = f32::max(velocity.0.abs(), velocity.1.abs()) * decay;
+                (position, speed < MIN_MOMENTUM_VELOCITY)
+            }
+        }
+    }
+}
+
+pub fn is_fling_velocity(velocity: (f32, f32)) -> bool {
+    f32::max(velocity.0.abs(), velocity.1.abs()) >= MIN_MOMENTUM_VELOCITY
+}
+
+/// Position where a fling with `velocity` stops
+pub fn momentum_end(from: (f32, f32), velocity: (f32, f32)) -> (f32, f32) {
+    (
+        from.0 + velocity.0 * MOMENTUM_TIME_CONSTANT,
+        from.1 + velocity.1 * MOMENTUM_TIME_CONSTANT,
+    )
+}
+
+/// The nearest snap point, or `value` if there is no snap point
+pub fn nearest_snap_point(points: &[f32], value: f32) -> f32 {
+    points.iter().copied().fold(None, |nearest: Option<f32>, p| {
+        match nearest {
+            Some(n) if (n - value).abs() <= (p - value).abs() => Some(n),
+            _ => Some(p),
+        }
+    }).unwrap_or(value)
+}
+
+/// Distance to scroll for aligning a range(`start`, `len`) in a viewport by `align`,
+/// which is one of "start", "center", "end" and "nearest"
+pub fn align_delta(start: f32, len: f32, view_start: f32, view_len: f32, align: &str) -> f32 {
+    let end = start + len;
+    let view_end = view_start + view_len;
+    match align {
+        "start" => start - view_start,
+        "center" => (start + end) / 2.0 - (view_start + view_end) / 2.0,
+        "end" => end - view_end,
+        _ => {
+            if start < view_start && end > view_end {
+                0.0
+            } else if start < view_start {
+                start - view_start
+            } else if end > view_end {
+                f32::min(end - view_end, start - view_start)
+            } else {
+                0.0
+            }
+        }
+    }
+}
+
+fn ease_out_cubic(t: f32) -> f32 {
+    1.0 - (1.0 - t).powi(3)
+}
+
+#[test]
+fn test_scroll_animation() {
+    use std::time::Duration;
+    let animation = ScrollAnimation::smooth((0.0, 0.0), (0.0, 100.0));
+    let (position, finished) = animation.step(animation.start_time + Duration::from_millis(100));
+    assert!(!finished);
+    assert!(position.1 > 50.0 && position.1 < 100.0);
+    let (position, finished) = animation.step(animation.start_time + Duration::from_millis(300));
+    assert!(finished);
+    assert_eq!((0.0, 100.0), position);
+
+    let animation = ScrollAnimation::momentum((0.0, 0.0), (0.0, 2.0));
+    let (position, finished) = animation.step(animation.start_time + Duration::from_secs(10));
+    assert!(finished);
+    assert!((position.1 - animation.target().1).abs() < 1.0);
+
+    assert_eq!(30.0, nearest_snap_point(&[0.0, 30.0, 60.0], 40.0));
+    assert_eq!(40.0, nearest_snap_point(&[], 40.0));
+
+    assert_eq!(0.0, align_delta(10.0, 10.0, 0.0, 100.0, "nearest"));
+    assert_eq!(20.0, align_delta(110.0, 10.0, 0.0, 100.0, "nearest"));
+    assert_eq!(-10.0, align_delta(-10.0, 10.0, 0.0, 100.0, "nearest"));
+    assert_eq!(110.0, align_delta(110.0, 10.0, 0.0, 100.0, "start"));
+    assert_eq!(65.0, align_delta(110.0, 10.0, 0.0, 100.0, "center"));
+}
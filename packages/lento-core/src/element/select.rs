@@ -0,0 +1,929 @@
+use std::time::{Duration, Instant};
+
+use accesskit::{Action, DefaultActionVerb, NodeBuilder, Role};
+use anyhow::Error;
+use ordered_float::OrderedFloat;
+use quick_js::JsValue;
+use serde::{Deserialize, Serialize};
+use skia_safe::{Canvas, Color, Path};
+use winit::keyboard::NamedKey;
+use yoga::{Edge, StyleUnit};
+
+use crate::base::{ElementEvent, Rect, SelectionChangeDetail, Size};
+use crate::element::container::Container;
+use crate::element::control::outline_paint;
+use crate::element::entry::Entry;
+use crate::element::scroll::Scroll;
+use crate::element::text::Text;
+use crate::element::{ElementBackend, ElementRef};
+use crate::event::{AcceptMouseDownEvent, ClickEventBind, KeyDownEvent, KeyDownEventBind, KeyEventDetail, MouseEnterEventBind, SelectionChangeBind, TextChangeBind};
+use crate::ext::ext_frame::{create_frame, frame_close, frame_set_popup, FrameAttrs};
+use crate::frame::FrameWeak;
+use crate::{create_element, js_call, js_call_rust, set_style};
+
+const ARROW_WIDTH: f32 = 20.0;
+const ROW_HEIGHT: f32 = 28.0;
+const GROUP_HEADER_HEIGHT: f32 = 24.0;
+const MAX_POPUP_HEIGHT: f32 = 300.0;
+/// Rows moved by PageUp and PageDown
+const PAGE_ROWS: isize = 10;
+/// Typed characters are joined for searching if they are typed within the timeout
+const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_millis(1000);
+/// The press which closes the popup should not open it again
+const REOPEN_DELAY: Duration = Duration::from_millis(200);
+
+const POPUP_COLOR: &str = "#DFE1E5";
+const POPUP_DISABLED_COLOR: &str = "#6F737A";
+const POPUP_BACKGROUND: &str = "#2B2D30";
+const POPUP_ACTIVE_BACKGROUND: &str = "#2E436E";
+const POPUP_BORDER_COLOR: &str = "#43454A";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelectOption {
+    pub value: String,
+    /// Text to display, value is displayed if absent
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub disabled: bool,
+    /// Label of option group, adjacent options with the same group are displayed under one header
+    #[serde(default)]
+    pub group: Option<String>,
+}
+
+impl SelectOption {
+    pub fn get_label(&self) -> &str {
+        self.label.as_deref().unwrap_or(&self.value)
+    }
+}
+
+/// Drop-down list of options, or a combobox with an `Entry` when editable
+pub struct Select {
+    element: ElementRef,
+    base: Container,
+    label_element: ElementRef,
+    entry_element: Option<ElementRef>,
+    options: Vec<SelectOption>,
+    selected: Option<usize>,
+    placeholder: String,
+    disabled: bool,
+    popup: Option<FrameWeak>,
+    popup_body: Option<ElementRef>,
+    /// Rows of options in popup, (option index, row)
+    popup_rows: Vec<(usize, ElementRef)>,
+    /// Highlighted option in popup
+    active: Option<usize>,
+    popup_closed_time: Option<Instant>,
+    type_ahead: String,
+    type_ahead_time: Instant,
+}
+
+impl Select {
+    pub fn set_options(&mut self, options: Vec<SelectOption>) {
+        let value = self.selected.map(|i| self.options[i].value.clone());
+        self.options = options;
+        self.selected = value.and_then(|v| self.options.iter().position(|o| o.value == v));
+        self.update_label();
+        if self.popup.is_some() {
+            self.refresh_popup();
+        }
+    }
+
+    pub fn set_value(&mut self, value: String) {
+        self.selected = self.options.iter().position(|o| o.value == value);
+        if let Some(entry) = &mut self.entry_element {
+            entry.get_backend_mut_as::<Entry>().set_text(value);
+        }
+        self.update_label();
+    }
+
+    /// Text of entry in editable mode, otherwise value of the selected option
+    pub fn get_value(&self) -> Option<String> {
+        match &self.entry_element {
+            Some(entry) => Some(entry.get_backend_as::<Entry>().get_text()),
+            None => self.selected.map(|i| self.options[i].value.clone()),
+        }
+    }
+
+    pub fn set_placeholder(&mut self, placeholder: String) {
+        if let Some(entry) = &mut self.entry_element {
+            entry.get_backend_mut_as::<Entry>().set_placeholder(placeholder.clone());
+        }
+        self.placeholder = placeholder;
+        self.update_label();
+    }
+
+    pub fn set_disabled(&mut self, disabled: bool) {
+        self.disabled = disabled;
+        if let Some(entry) = &mut self.entry_element {
+            entry.get_backend_mut_as::<Entry>().set_disabled(disabled);
+        }
+        if disabled {
+            self.close_popup();
+        }
+        self.element.mark_dirty(false);
+    }
+
+    /// Replace the label with an entry, so that text not in options could be input
+    pub fn set_editable(&mut self, editable: bool) {
+        if editable == self.entry_element.is_some() {
+            return;
+        }
+        self.close_popup();
+        self.base.remove_child_view(0);
+        if editable {
+            let mut entry = create_element!(Entry, {
+                flex => "1",
+            });
+            let entry_backend = entry.get_backend_mut_as::<Entry>();
+            entry_backend.set_placeholder(self.placeholder.clone());
+            entry_backend.set_disabled(self.disabled);
+            if let Some(i) = self.selected {
+                entry_backend.set_text(self.options[i].value.clone());
+            }
+            let mut select = self.element.clone();
+            entry.bind_key_down(move |ctx, detail| {
+                if select.get_backend_mut_as::<Select>().handle_entry_key_down(detail) {
+                    ctx.prevent_default = true;
+                }
+            });
+            let mut select = self.element.clone();
+            entry.bind_text_change(move |_ctx, _detail| {
+                select.get_backend_mut_as::<Select>().handle_entry_text_change();
+            });
+            self.base.add_child_view(entry.clone(), None);
+            self.entry_element = Some(entry);
+        } else {
+            self.entry_element = None;
+            self.base.add_child_view(self.label_element.clone(), None);
+            self.update_label();
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.popup.is_some()
+    }
+
+    pub fn open_popup(&mut self) {
+        if self.disabled || self.popup.is_some() {
+            return;
+        }
+        if self.get_visible_options().is_empty() {
+            return;
+        }
+        let Some(owner) = self.element.get_frame() else {
+            return;
+        };
+        let Some(window_position) = owner.upgrade_mut(|f| f.get_window_position()) else {
+            return;
+        };
+
+        let mut body = ElementRef::new(SelectPopup::create);
+        body.get_backend_mut_as::<SelectPopup>().select = Some(self.element.clone());
+        let border = format!("1 {}", POPUP_BORDER_COLOR);
+        set_style!(body, {
+            color => POPUP_COLOR,
+            backgroundColor => POPUP_BACKGROUND,
+            borderTop => &border,
+            borderRight => &border,
+            borderBottom => &border,
+            borderLeft => &border,
+        });
+        self.popup_body = Some(body.clone());
+        let height = self.update_popup_rows();
+
+        let bounds = self.element.get_origin_bounds();
+        let frame = match create_frame(FrameAttrs {
+            width: Some(bounds.width),
+            height: Some(height),
+            title: None,
+            resizable: Some(false),
+            decorations: Some(false),
+            override_redirect: Some(true),
+            position: Some((window_position.x + bounds.x, window_position.y + bounds.bottom())),
+            visible: None,
+        }) {
+            Ok(f) => f,
+            Err(_) => {
+                self.handle_popup_closed();
+                return;
+            }
+        };
+        if frame_set_popup(frame.clone(), owner).is_err() {
+            self.handle_popup_closed();
+            let _ = frame_close(frame);
+            return;
+        }
+        let mut select = self.element.clone();
+        frame.upgrade_mut(|f| {
+            f.bind_event_listener("close", move |_ctx, _detail: &mut ()| {
+                select.get_backend_mut_as::<Select>().handle_popup_closed();
+            });
+            f.set_body(body.clone());
+        });
+        self.popup = Some(frame);
+        self.reset_active();
+        self.element.mark_dirty(false);
+    }
+
+    /// Replace rows of popup with the visible options, returns the height of popup
+    fn update_popup_rows(&mut self) -> f32 {
+        let Some(mut body) = self.popup_body.clone() else {
+            return 0.0;
+        };
+        for _ in 0..body.get_children().len() {
+            body.remove_child_view(0);
+        }
+        let options = self.get_visible_options();
+        let mut height = 2.0;
+        let mut last_group = None;
+        let mut rows = Vec::with_capacity(options.len());
+        for index in options {
+            let option = &self.options[index];
+            if option.group.is_some() && option.group != last_group {
+                let mut header = create_element!(Text, {
+                    height => &GROUP_HEADER_HEIGHT.to_string(),
+                    paddingLeft => "6",
+                    color => POPUP_DISABLED_COLOR,
+                });
+                header.get_backend_mut_as::<Text>().set_text(option.group.clone().unwrap_or_default());
+                body.add_child_view(header, None);
+                height += GROUP_HEADER_HEIGHT;
+            }
+            last_group = option.group.clone();
+            let row = self.create_row(index);
+            body.add_child_view(row.clone(), None);
+            rows.push((index, row));
+            height += ROW_HEIGHT;
+        }
+        self.popup_rows = rows;
+        self.active = None;
+        height.min(MAX_POPUP_HEIGHT)
+    }
+
+    /// Highlight the selected option if it is visible, otherwise the first enabled one
+    fn reset_active(&mut self) {
+        let active = self.selected
+            .filter(|i| self.popup_rows.iter().any(|(index, _)| index == i))
+            .or_else(|| self.find_enabled_row(-1, 1));
+        self.set_active(active);
+    }
+
+    pub fn close_popup(&mut self) {
+        if let Some(popup) = self.popup.take() {
+            self.handle_popup_closed();
+            let _ = frame_close(popup);
+        }
+    }
+
+    pub fn toggle_popup(&mut self) {
+        if self.popup.is_some() {
+            self.close_popup();
+        } else {
+            self.open_popup();
+        }
+    }
+
+    /// Select option by user, change event is emitted if the value is changed
+    pub fn choose(&mut self, index: usize) {
+        let Some(option) = self.options.get(index) else {
+            return;
+        };
+        if option.disabled {
+            return;
+        }
+        let old_value = self.get_value();
+        self.selected = Some(index);
+        if let Some(entry) = &mut self.entry_element {
+            entry.get_backend_mut_as::<Entry>().set_text(option.value.clone());
+        }
+        self.update_label();
+        self.close_popup();
+        self.emit_change_if_needed(old_value);
+    }
+
+    fn emit_change_if_needed(&mut self, old_value: Option<String>) {
+        let value = self.get_value();
+        if value != old_value {
+            self.element.emit_selection_change(SelectionChangeDetail {
+                value: value.unwrap_or_default(),
+            });
+        }
+    }
+
+    fn handle_popup_closed(&mut self) {
+        self.popup = None;
+        self.popup_body = None;
+        self.popup_rows.clear();
+        self.active = None;
+        self.popup_closed_time = Some(Instant::now());
+        self.element.mark_dirty(false);
+    }
+
+    fn create_row(&self, index: usize) -> ElementRef {
+        let option = &self.options[index];
+        let mut row = create_element!(Container, {});
+        let mut label = create_element!(Text, {});
+        label.get_backend_mut_as::<Text>().set_text(option.get_label().to_string());
+        row.add_child_view(label, None);
+        style_row(&mut row, option.disabled, false);
+        if !option.disabled {
+            let mut select = self.element.clone();
+            row.bind_mouse_enter(move |_ctx, _detail| {
+                select.get_backend_mut_as::<Select>().set_active(Some(index));
+            });
+            let mut select = self.element.clone();
+            row.bind_click(move |_ctx, _detail| {
+                select.get_backend_mut_as::<Select>().choose(index);
+            });
+        }
+        row
+    }
+
+    /// Options matching the text of entry in editable mode, otherwise all options
+    fn get_visible_options(&self) -> Vec<usize> {
+        let filter = match &self.entry_element {
+            Some(entry) => {
+                let text = entry.get_backend_as::<Entry>().get_text().to_lowercase();
+                let is_selected_text = self.selected.map(|i| self.options[i].value.to_lowercase() == text).unwrap_or(false);
+                if is_selected_text { String::new() } else { text }
+            }
+            None => String::new(),
+        };
+        (0..self.options.len())
+            .filter(|i| filter.is_empty() || self.options[*i].get_label().to_lowercase().contains(&filter))
+            .collect()
+    }
+
+    fn update_label(&mut self) {
+        let (text, color) = match self.selected {
+            Some(i) => (self.options[i].get_label().to_string(), None),
+            None => (self.placeholder.clone(), Some(POPUP_DISABLED_COLOR)),
+        };
+        let label = self.label_element.get_backend_mut_as::<Text>();
+        label.set_text(text);
+        match color {
+            Some(c) => {
+                set_style!(self.label_element, {
+                    flex => "1",
+                    color => c,
+                });
+            }
+            None => {
+                set_style!(self.label_element, {
+                    flex => "1",
+                });
+            }
+        }
+        self.element.mark_dirty(true);
+    }
+
+    fn set_active(&mut self, active: Option<usize>) {
+        if self.active == active {
+            return;
+        }
+        let old_active = self.active;
+        self.active = active;
+        for (index, row) in &mut self.popup_rows {
+            if Some(*index) == old_active || Some(*index) == active {
+                style_row(row, self.options[*index].disabled, Some(*index) == active);
+            }
+        }
+        self.scroll_active_into_view();
+    }
+
+    fn scroll_active_into_view(&mut self) {
+        let (Some(body), Some(active)) = (&mut self.popup_body, self.active) else {
+            return;
+        };
+        let Some((_, row)) = self.popup_rows.iter().find(|(i, _)| *i == active) else {
+            return;
+        };
+        let row_bounds = row.get_bounds();
+        let viewport_height = body.get_content_bounds().height;
+        let scroll_top = body.get_scroll_top();
+        if row_bounds.y < scroll_top {
+            body.set_scroll_top(row_bounds.y);
+        } else if row_bounds.bottom() > scroll_top + viewport_height {
+            body.set_scroll_top(row_bounds.bottom() - viewport_height);
+        }
+    }
+
+    /// Option index of the next enabled row after the row at `from` in the direction of `delta`
+    fn find_enabled_row(&self, from: isize, delta: isize) -> Option<usize> {
+        let len = self.popup_rows.len() as isize;
+        let mut pos = from;
+        loop {
+            pos += delta;
+            if pos < 0 || pos >= len {
+                return None;
+            }
+            let index = self.popup_rows[pos as usize].0;
+            if !self.options[index].disabled {
+                return Some(index);
+            }
+        }
+    }
+
+    fn move_active(&mut self, delta: isize) {
+        let pos = self.active
+            .and_then(|a| self.popup_rows.iter().position(|(i, _)| *i == a))
+            .map(|p| p as isize);
+        let next = match pos {
+            Some(p) => {
+                // move back to the nearest enabled option if the target is disabled
+                let step = delta.signum();
+                let target = (p + delta).clamp(0, self.popup_rows.len() as isize - 1);
+                self.find_enabled_row(target + step, -step)
+            }
+            None => self.find_enabled_row(-1, 1),
+        };
+        if next.is_some() {
+            self.set_active(next);
+        }
+    }
+
+    /// Select the next or previous enabled option without opening popup
+    fn select_adjacent(&mut self, delta: isize) {
+        let len = self.options.len() as isize;
+        let mut index = self.selected.map(|i| i as isize).unwrap_or(if delta > 0 { -1 } else { len });
+        loop {
+            index += delta;
+            if index < 0 || index >= len {
+                return;
+            }
+            if !self.options[index as usize].disabled {
+                break;
+            }
+        }
+        let old_value = self.get_value();
+        self.selected = Some(index as usize);
+        self.update_label();
+        self.emit_change_if_needed(old_value);
+    }
+
+    /// Find the next option starting with the typed text
+    fn type_ahead(&mut self, text: &str) {
+        if self.type_ahead_time.elapsed() > TYPE_AHEAD_TIMEOUT {
+            self.type_ahead.clear();
+        }
+        self.type_ahead_time = Instant::now();
+        self.type_ahead.push_str(&text.to_lowercase());
+        let current = if self.popup.is_some() { self.active } else { self.selected };
+        // typing the same character repeatedly cycles through options starting with it
+        let first_char = self.type_ahead.chars().next().unwrap_or_default();
+        let repeated = self.type_ahead.chars().all(|c| c == first_char);
+        let prefix = if repeated { first_char.to_string() } else { self.type_ahead.clone() };
+        let start = match current {
+            Some(c) if repeated => c + 1,
+            Some(c) => c,
+            None => 0,
+        };
+        let len = self.options.len();
+        let found = (0..len).map(|i| (start + i) % len).find(|i| {
+            let option = &self.options[*i];
+            !option.disabled && option.get_label().to_lowercase().starts_with(&prefix)
+        });
+        if let Some(index) = found {
+            if self.popup.is_some() {
+                if self.popup_rows.iter().any(|(i, _)| *i == index) {
+                    self.set_active(Some(index));
+                }
+            } else {
+                let old_value = self.get_value();
+                self.selected = Some(index);
+                self.update_label();
+                self.emit_change_if_needed(old_value);
+            }
+        }
+    }
+
+    /// Keys of closed select and opened popup, return true if handled
+    fn handle_key(&mut self, detail: &KeyEventDetail) -> bool {
+        if self.disabled {
+            return false;
+        }
+        let is_open = self.popup.is_some();
+        let editable = self.entry_element.is_some();
+        let alt = detail.alt_key && !detail.ctrl_key && !detail.meta_key;
+        if detail.modifiers != 0 && !alt && !(detail.shift_key && detail.key_str.is_some()) {
+            return false;
+        }
+        match detail.named_key {
+            Some(NamedKey::ArrowDown) if alt || (editable && !is_open) => self.open_popup(),
+            Some(NamedKey::ArrowUp) if alt && is_open => self.close_popup(),
+            Some(NamedKey::ArrowDown) if is_open => self.move_active(1),
+            Some(NamedKey::ArrowUp) if is_open => self.move_active(-1),
+            Some(NamedKey::PageDown) if is_open => self.move_active(PAGE_ROWS),
+            Some(NamedKey::PageUp) if is_open => self.move_active(-PAGE_ROWS),
+            Some(NamedKey::Home) if is_open && !editable => self.move_active(-(self.options.len() as isize)),
+            Some(NamedKey::End) if is_open && !editable => self.move_active(self.options.len() as isize),
+            Some(NamedKey::ArrowDown) if !editable => self.select_adjacent(1),
+            Some(NamedKey::ArrowUp) if !editable => self.select_adjacent(-1),
+            Some(NamedKey::Enter) if is_open => match self.active {
+                Some(active) => self.choose(active),
+                None => self.close_popup(),
+            },
+            Some(NamedKey::Enter) if editable => {
+                // commit the input text
+                let old_value = self.selected.map(|i| self.options[i].value.clone());
+                self.emit_change_if_needed(old_value);
+            }
+            Some(NamedKey::Enter) | Some(NamedKey::Space) if !editable => self.open_popup(),
+            Some(NamedKey::Escape) | Some(NamedKey::Tab) if is_open => self.close_popup(),
+            _ => {
+                match &detail.key_str {
+                    Some(text) if !editable && !alt => self.type_ahead(text),
+                    _ => return false,
+                }
+            }
+        }
+        true
+    }
+
+    fn handle_entry_key_down(&mut self, detail: &KeyEventDetail) -> bool {
+        match detail.named_key {
+            Some(NamedKey::ArrowDown) | Some(NamedKey::ArrowUp) | Some(NamedKey::Enter) | Some(NamedKey::Escape) => self.handle_key(detail),
+            _ => false,
+        }
+    }
+
+    fn handle_entry_text_change(&mut self) {
+        if self.popup.is_some() {
+            self.refresh_popup();
+        } else {
+            self.open_popup();
+        }
+    }
+
+    /// Filter rows of the opened popup in place, the popup is closed if nothing matches
+    fn refresh_popup(&mut self) {
+        if self.get_visible_options().is_empty() {
+            self.close_popup();
+            return;
+        }
+        let height = self.update_popup_rows();
+        self.reset_active();
+        let width = self.element.get_origin_bounds().width;
+        if let Some(popup) = &self.popup {
+            popup.upgrade_mut(|f| f.resize(Size { width, height }));
+        }
+    }
+
+    fn draw_arrow(&self, canvas: &Canvas) {
+        let bounds = self.element.get_content_bounds();
+        let (_, padding_right, _, _) = self.element.get_padding();
+        // draw origin is the content box, the arrow is in the right padding
+        let center_x = bounds.width + padding_right / 2.0;
+        let center_y = bounds.height / 2.0;
+        let mut path = Path::new();
+        path.move_to((center_x - 4.0, center_y - 2.0));
+        path.line_to((center_x + 4.0, center_y - 2.0));
+        path.line_to((center_x, center_y + 3.0));
+        path.close();
+        canvas.draw_path(&path, &outline_paint(&self.element, self.disabled));
+    }
+}
+
+impl ElementBackend for Select {
+    fn create(mut element: ElementRef) -> Self {
+        let mut base = Container::create(element.clone());
+        element.layout.set_padding(Edge::Left, StyleUnit::Point(OrderedFloat(4.0)));
+        element.layout.set_padding(Edge::Right, StyleUnit::Point(OrderedFloat(ARROW_WIDTH)));
+        element.layout.set_border(Edge::Top, 1.0);
+        element.layout.set_border(Edge::Right, 1.0);
+        element.layout.set_border(Edge::Bottom, 1.0);
+        element.layout.set_border(Edge::Left, 1.0);
+        let color = Color::from_rgb(128, 128, 128);
+        element.layout.border_color = [color, color, color, color];
+
+        let label_element = create_element!(Text, {
+            flex => "1",
+        });
+        base.add_child_view(label_element.clone(), None);
+        Self {
+            element,
+            base,
+            label_element,
+            entry_element: None,
+            options: Vec::new(),
+            selected: None,
+            placeholder: String::new(),
+            disabled: false,
+            popup: None,
+            popup_body: None,
+            popup_rows: Vec::new(),
+            active: None,
+            popup_closed_time: None,
+            type_ahead: String::new(),
+            type_ahead_time: Instant::now(),
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        "Select"
+    }
+
+    fn draw(&self, canvas: &Canvas) {
+        self.draw_arrow(canvas);
+    }
+
+    fn set_property(&mut self, p: &str, v: JsValue) {
+        js_call_rust!("options", Vec<SelectOption>, self, set_options, p, v);
+        js_call!("value", String, self, set_value, p, v);
+        js_call!("placeholder", String, self, set_placeholder, p, v);
+        js_call!("disabled", bool, self, set_disabled, p, v);
+        js_call!("editable", bool, self, set_editable, p, v);
+        js_call!("open", bool, self, set_open, p, v);
+    }
+
+    fn get_property(&mut self, property_name: &str) -> Result<Option<JsValue>, Error> {
+        match property_name {
+            "value" => Ok(Some(match self.get_value() {
+                Some(v) => JsValue::String(v),
+                None => JsValue::Null,
+            })),
+            "open" => Ok(Some(JsValue::Bool(self.is_open()))),
+            _ => Ok(None),
+        }
+    }
+
+    fn add_child_view(&mut self, _child: ElementRef, _position: Option<u32>) {
+        // children are managed by select
+    }
+
+    fn get_children(&self) -> Vec<ElementRef> {
+        self.base.get_children()
+    }
+
+    fn handle_event_default_behavior(&mut self, event_type: &str, event: &mut ElementEvent) -> bool {
+        let mut key_handled = false;
+        let is_key_down = KeyDownEvent::try_match(event_type, event, |d| {
+            key_handled = self.handle_key(d);
+        });
+        if is_key_down {
+            return key_handled;
+        }
+        event.accept_mouse_down(|d| {
+            if self.disabled || d.button != 1 {
+                return;
+            }
+            let just_closed = self.popup_closed_time.map(|t| t.elapsed() < REOPEN_DELAY).unwrap_or(false);
+            if !just_closed {
+                self.toggle_popup();
+            }
+        })
+    }
+
+    fn is_focusable(&self) -> bool {
+        !self.disabled && self.entry_element.is_none()
+    }
+
+    fn get_accessibility_role(&self) -> Role {
+        Role::ComboBox
+    }
+
+    fn build_accessibility_node(&self, builder: &mut NodeBuilder) {
+        if let Some(value) = self.get_value() {
+            let label = self.options.iter().find(|o| o.value == value).map(|o| o.get_label().to_string());
+            builder.set_value(label.unwrap_or(value));
+        }
+        builder.set_expanded(self.popup.is_some());
+        if self.disabled {
+            builder.set_disabled();
+        } else {
+            builder.set_default_action_verb(DefaultActionVerb::Open);
+            builder.add_action(Action::Focus);
+            builder.add_action(Action::Expand);
+            builder.add_action(Action::Collapse);
+        }
+    }
+
+    fn handle_accessibility_action(&mut self, request: &accesskit::ActionRequest) -> bool {
+        match request.action {
+            Action::Expand => self.open_popup(),
+            Action::Collapse => self.close_popup(),
+            _ => return false,
+        }
+        true
+    }
+}
+
+impl Select {
+    fn set_open(&mut self, open: bool) {
+        if open {
+            self.open_popup();
+        } else {
+            self.close_popup();
+        }
+    }
+}
+
+/// Body of popup frame. Keyboard input of owner frame is redirected to popup,
+/// so keys and text are passed back to the select.
+struct SelectPopup {
+    base: Scroll,
+    select: Option<ElementRef>,
+}
+
+impl SelectPopup {
+    fn get_entry(&self) -> Option<ElementRef> {
+        self.select.as_ref().and_then(|s| s.get_backend_as::<Select>().entry_element.clone())
+    }
+}
+
+impl ElementBackend for SelectPopup {
+    fn create(element: ElementRef) -> Self {
+        Self {
+            base: Scroll::create(element),
+            select: None,
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        "SelectPopup"
+    }
+
+    fn draw(&self, canvas: &Canvas) {
+        self.base.draw(canvas);
+    }
+
+    fn draw_foreground(&self, canvas: &Canvas) {
+        self.base.draw_foreground(canvas);
+    }
+
+    fn handle_style_changed(&mut self, key: &str) {
+        self.base.handle_style_changed(key);
+    }
+
+    fn handle_origin_bounds_change(&mut self, bounds: &Rect) {
+        self.base.handle_origin_bounds_change(bounds);
+    }
+
+    fn handle_input(&mut self, input: &str) {
+        if let Some(mut entry) = self.get_entry() {
+            entry.handle_input(input);
+        }
+    }
+
+    fn handle_ime_preedit(&mut self, text: &str, cursor: Option<(usize, usize)>) {
+        if let Some(mut entry) = self.get_entry() {
+            entry.handle_ime_preedit(text, cursor);
+        }
+    }
+
+    fn is_editable(&self) -> bool {
+        self.get_entry().is_some()
+    }
+
+    fn add_child_view(&mut self, child: ElementRef, position: Option<u32>) {
+        self.base.add_child_view(child, position);
+    }
+
+    fn remove_child_view(&mut self, position: u32) {
+        self.base.remove_child_view(position);
+    }
+
+    fn get_children(&self) -> Vec<ElementRef> {
+        self.base.get_children()
+    }
+
+    fn handle_event_default_behavior(&mut self, event_type: &str, event: &mut ElementEvent) -> bool {
+        if let Some(mut select) = self.select.clone() {
+            let mut handled = false;
+            KeyDownEvent::try_match(event_type, event, |d| {
+                handled = select.get_backend_mut_as::<Select>().handle_key(d);
+            });
+            if handled {
+                return true;
+            }
+            if let (true, Some(mut entry)) = (event_type == "keydown", self.get_entry()) {
+                return entry.get_backend_mut().handle_event_default_behavior(event_type, event);
+            }
+        }
+        self.base.handle_event_default_behavior(event_type, event)
+    }
+
+    fn get_accessibility_role(&self) -> Role {
+        Role::ListBox
+    }
+}
+
+fn style_row(row: &mut ElementRef, disabled: bool, active: bool) {
+    let color = if disabled { POPUP_DISABLED_COLOR } else { POPUP_COLOR };
+    if active {
+        set_style!(row, {
+            height => &ROW_HEIGHT.to_string(),
+            justifyContent => "center",
+            paddingLeft => "6",
+            paddingRight => "6",
+            color => color,
+            backgroundColor => POPUP_ACTIVE_BACKGROUND,
+        });
+    } else {
+        set_style!(row, {
+            height => &ROW_HEIGHT.to_string(),
+            justifyContent => "center",
+            paddingLeft => "6",
+            paddingRight => "6",
+            color => color,
+        });
+    }
+}
+
+#[cfg(test)]
+fn create_test_options() -> Vec<SelectOption> {
+    ["apple", "banana", "blueberry", "cherry"].iter().enumerate().map(|(i, v)| SelectOption {
+        value: v.to_string(),
+        label: None,
+        disabled: i == 2,
+        group: None,
+    }).collect()
+}
+
+#[test]
+fn test_select_value() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use crate::event_loop::run_pending_callbacks;
+
+    let mut el = ElementRef::new(Select::create);
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    {
+        let changes = changes.clone();
+        el.bind_selection_change(move |_, d| changes.borrow_mut().push(d.value.clone()));
+    }
+    let select = el.get_backend_mut_as::<Select>();
+    select.set_options(create_test_options());
+    assert_eq!(None, select.get_value());
+    select.set_value("banana".to_string());
+    assert_eq!(Some(1), select.selected);
+
+    // disabled options could not be chosen
+    select.choose(2);
+    select.choose(3);
+    select.choose(3);
+    assert_eq!(Some("cherry".to_string()), select.get_value());
+    run_pending_callbacks();
+    assert_eq!(vec!["cherry".to_string()], *changes.borrow());
+
+    // selection is kept by value when options are changed
+    let mut options = create_test_options();
+    options.remove(0);
+    el.get_backend_mut_as::<Select>().set_options(options);
+    assert_eq!(Some(2), el.get_backend_as::<Select>().selected);
+}
+
+#[test]
+fn test_select_type_ahead() {
+    let mut el = ElementRef::new(Select::create);
+    let select = el.get_backend_mut_as::<Select>();
+    select.set_options(create_test_options());
+    select.type_ahead("c");
+    assert_eq!(Some(3), select.selected);
+    select.type_ahead("h");
+    assert_eq!(Some(3), select.selected);
+
+    // repeated characters cycle through enabled options
+    select.type_ahead_time -= TYPE_AHEAD_TIMEOUT * 2;
+    select.type_ahead("b");
+    assert_eq!(Some(1), select.selected);
+    select.type_ahead("b");
+    assert_eq!(Some(1), select.selected);
+    select.type_ahead_time -= TYPE_AHEAD_TIMEOUT * 2;
+    select.type_ahead("a");
+    assert_eq!(Some(0), select.selected);
+}
+
+#[test]
+fn test_select_filter() {
+    let mut el = ElementRef::new(Select::create);
+    let select = el.get_backend_mut_as::<Select>();
+    select.set_options(create_test_options());
+    select.set_editable(true);
+    select.set_value("cherry".to_string());
+    // text of the selected option doesn't filter
+    assert_eq!(vec![0, 1, 2, 3], select.get_visible_options());
+
+    let body = ElementRef::new(SelectPopup::create);
+    select.popup_body = Some(body.clone());
+    let mut entry = select.entry_element.clone().unwrap();
+    entry.get_backend_mut_as::<Entry>().set_text("B".to_string());
+    assert_eq!(vec![1, 2], select.get_visible_options());
+
+    // rows are replaced in the same popup
+    select.update_popup_rows();
+    select.reset_active();
+    assert_eq!(vec![1, 2], select.popup_rows.iter().map(|(i, _)| *i).collect::<Vec<_>>());
+    assert_eq!(2, body.get_children().len());
+    assert_eq!(Some(1), select.active);
+
+    entry.get_backend_mut_as::<Entry>().set_text("rr".to_string());
+    select.update_popup_rows();
+    select.reset_active();
+    assert_eq!(vec![2, 3], select.popup_rows.iter().map(|(i, _)| *i).collect::<Vec<_>>());
+    assert_eq!(2, body.get_children().len());
+    // the selected option is highlighted once it is visible
+    assert_eq!(Some(3), select.active);
+    assert!(body == select.popup_body.clone().unwrap());
+}
@@ -0,0 +1,264 @@
+use accesskit::{Action, ActionRequest, NodeBuilder, Role};
+use anyhow::Error;
+use quick_js::JsValue;
+use skia_safe::Canvas;
+use winit::keyboard::NamedKey;
+
+use crate::base::{ElementEvent, ValueChangeDetail};
+use crate::element::control::{accent_paint, content_rect, set_intrinsic_size, thumb_paint, track_paint};
+use crate::element::{ElementBackend, ElementRef};
+use crate::event::{AcceptMouseDownEvent, AcceptMouseMoveEvent, AcceptMouseUpEvent, KeyDownEvent, ValueChangeBind};
+use crate::js_call;
+
+const TRACK_HEIGHT: f32 = 4.0;
+
+const MAX_THUMB_RADIUS: f32 = 8.0;
+
+/// Steps moved by PageUp and PageDown
+const PAGE_STEPS: f32 = 10.0;
+
+pub struct Slider {
+    element: ElementRef,
+    min: f32,
+    max: f32,
+    /// Zero means continuous
+    step: f32,
+    value: f32,
+    disabled: bool,
+    dragging: bool,
+}
+
+impl Slider {
+    pub fn set_min(&mut self, min: f32) {
+        self.min = min;
+        self.value = snap_value(self.value, self.min, self.max, self.step);
+        self.element.mark_dirty(false);
+    }
+
+    pub fn set_max(&mut self, max: f32) {
+        self.max = max;
+        self.value = snap_value(self.value, self.min, self.max, self.step);
+        self.element.mark_dirty(false);
+    }
+
+    pub fn set_step(&mut self, step: f32) {
+        self.step = step.max(0.0);
+        self.value = snap_value(self.value, self.min, self.max, self.step);
+        self.element.mark_dirty(false);
+    }
+
+    pub fn set_value(&mut self, value: f32) {
+        self.value = snap_value(value, self.min, self.max, self.step);
+        self.element.mark_dirty(false);
+    }
+
+    pub fn get_value(&self) -> f32 {
+        self.value
+    }
+
+    pub fn set_disabled(&mut self, disabled: bool) {
+        self.disabled = disabled;
+        self.dragging = false;
+        self.element.mark_dirty(false);
+    }
+
+    /// Change value by user, change event is emitted if the value is changed
+    pub fn update_value(&mut self, value: f32) {
+        if self.disabled {
+            return;
+        }
+        let old_value = self.value;
+        self.set_value(value);
+        if self.value != old_value {
+            self.element.emit_value_change(ValueChangeDetail {
+                value: self.value,
+            });
+        }
+    }
+
+    fn get_key_step(&self) -> f32 {
+        if self.step > 0.0 {
+            self.step
+        } else {
+            (self.max - self.min) / 100.0
+        }
+    }
+
+    fn get_thumb_radius(&self) -> f32 {
+        let rect = content_rect(&self.element);
+        (rect.height / 2.0).min(MAX_THUMB_RADIUS)
+    }
+
+    fn get_ratio(&self) -> f32 {
+        if self.max > self.min {
+            (self.value - self.min) / (self.max - self.min)
+        } else {
+            0.0
+        }
+    }
+
+    fn update_value_by_frame_x(&mut self, frame_x: f32) {
+        let bounds = self.element.get_origin_content_bounds();
+        let radius = self.get_thumb_radius();
+        let track_width = bounds.width - radius * 2.0;
+        if track_width <= 0.0 {
+            return;
+        }
+        let ratio = ((frame_x - bounds.x - radius) / track_width).clamp(0.0, 1.0);
+        self.update_value(self.min + ratio * (self.max - self.min));
+    }
+
+    fn handle_key_down(&mut self, key: NamedKey) -> bool {
+        let step = self.get_key_step();
+        let value = match key {
+            NamedKey::ArrowLeft | NamedKey::ArrowDown => self.value - step,
+            NamedKey::ArrowRight | NamedKey::ArrowUp => self.value + step,
+            NamedKey::PageDown => self.value - step * PAGE_STEPS,
+            NamedKey::PageUp => self.value + step * PAGE_STEPS,
+            NamedKey::Home => self.min,
+            NamedKey::End => self.max,
+            _ => return false,
+        };
+        self.update_value(value);
+        true
+    }
+}
+
+impl ElementBackend for Slider {
+    fn create(mut element: ElementRef) -> Self {
+        set_intrinsic_size(&mut element, 160.0, 20.0);
+        Self {
+            element,
+            min: 0.0,
+            max: 100.0,
+            step: 1.0,
+            value: 0.0,
+            disabled: false,
+            dragging: false,
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        "Slider"
+    }
+
+    fn draw(&self, canvas: &Canvas) {
+        let rect = content_rect(&self.element);
+        let radius = self.get_thumb_radius();
+        let track_width = rect.width - radius * 2.0;
+        if track_width <= 0.0 {
+            return;
+        }
+        let center_y = rect.height / 2.0;
+        let track_height = TRACK_HEIGHT.min(rect.height);
+        let track = skia_safe::Rect::from_xywh(radius, center_y - track_height / 2.0, track_width, track_height);
+        let track_radius = track_height / 2.0;
+        canvas.draw_round_rect(track, track_radius, track_radius, &track_paint(&self.element, self.disabled));
+
+        let thumb_x = radius + track_width * self.get_ratio();
+        let mut filled = track;
+        filled.right = thumb_x;
+        let accent = accent_paint(&self.element, self.disabled);
+        canvas.draw_round_rect(filled, track_radius, track_radius, &accent);
+
+        canvas.draw_circle((thumb_x, center_y), radius, &accent);
+        canvas.draw_circle((thumb_x, center_y), radius / 2.0, &thumb_paint(&self.element, self.disabled));
+    }
+
+    fn set_property(&mut self, p: &str, v: JsValue) {
+        js_call!("min", f32, self, set_min, p, v);
+        js_call!("max", f32, self, set_max, p, v);
+        js_call!("step", f32, self, set_step, p, v);
+        js_call!("value", f32, self, set_value, p, v);
+        js_call!("disabled", bool, self, set_disabled, p, v);
+    }
+
+    fn get_property(&mut self, property_name: &str) -> Result<Option<JsValue>, Error> {
+        match property_name {
+            "value" => Ok(Some(JsValue::Float(self.value as f64))),
+            _ => Ok(None),
+        }
+    }
+
+    fn handle_event_default_behavior(&mut self, event_type: &str, event: &mut ElementEvent) -> bool {
+        if self.disabled {
+            return false;
+        }
+        let mut key = None;
+        KeyDownEvent::try_match(event_type, event, |d| {
+            if d.modifiers == 0 {
+                key = d.named_key;
+            }
+        });
+        if let Some(key) = key {
+            return self.handle_key_down(key);
+        }
+        event.accept_mouse_down(|d| {
+            self.dragging = true;
+            self.update_value_by_frame_x(d.frame_x);
+        }) || event.accept_mouse_move(|d| {
+            if self.dragging {
+                self.update_value_by_frame_x(d.frame_x);
+            }
+        }) || event.accept_mouse_up(|_| {
+            self.dragging = false;
+        })
+    }
+
+    fn is_focusable(&self) -> bool {
+        !self.disabled
+    }
+
+    fn get_accessibility_role(&self) -> Role {
+        Role::Slider
+    }
+
+    fn build_accessibility_node(&self, builder: &mut NodeBuilder) {
+        builder.set_numeric_value(self.value as f64);
+        builder.set_min_numeric_value(self.min as f64);
+        builder.set_max_numeric_value(self.max as f64);
+        if self.step > 0.0 {
+            builder.set_numeric_value_step(self.step as f64);
+        }
+        if self.disabled {
+            builder.set_disabled();
+        } else {
+            builder.add_action(Action::Focus);
+            builder.add_action(Action::Increment);
+            builder.add_action(Action::Decrement);
+        }
+    }
+
+    fn handle_accessibility_action(&mut self, request: &ActionRequest) -> bool {
+        match request.action {
+            Action::Increment => self.update_value(self.value + self.get_key_step()),
+            Action::Decrement => self.update_value(self.value - self.get_key_step()),
+            _ => return false,
+        }
+        true
+    }
+}
+
+/// Clamp value into [min, max] and round it to the nearest step
+fn snap_value(value: f32, min: f32, max: f32, step: f32) -> f32 {
+    if max <= min {
+        return min;
+    }
+    let value = if step > 0.0 {
+        let steps = ((value - min) / step).round();
+        min + steps * step
+    } else {
+        value
+    };
+    value.clamp(min, max)
+}
+
+#[test]
+fn test_snap_value() {
+    assert_eq!(0.0, snap_value(-5.0, 0.0, 100.0, 1.0));
+    assert_eq!(100.0, snap_value(120.0, 0.0, 100.0, 1.0));
+    assert_eq!(40.0, snap_value(37.6, 0.0, 100.0, 5.0));
+    assert_eq!(3.0, snap_value(3.4, 1.0, 10.0, 2.0));
+    assert_eq!(37.6, snap_value(37.6, 0.0, 100.0, 0.0));
+    assert_eq!(10.0, snap_value(5.0, 10.0, 10.0, 1.0));
+}
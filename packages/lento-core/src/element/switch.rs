@@ -0,0 +1,152 @@
+use accesskit::{Action, DefaultActionVerb, NodeBuilder, Role, Toggled};
+use anyhow::Error;
+use quick_js::JsValue;
+use skia_safe::Canvas;
+
+use crate::base::{CheckedChangeDetail, ElementEvent};
+use crate::element::control::{accent_paint, content_rect, emit_keyboard_click, is_activate_key, set_intrinsic_size, thumb_paint, track_paint};
+use crate::element::{ElementBackend, ElementRef};
+use crate::event::{AcceptClickEvent, CheckedChangeBind, KeyDownEvent};
+use crate::js_call;
+
+/// Toggle switch, an on/off control which takes effect immediately
+pub struct Switch {
+    element: ElementRef,
+    checked: bool,
+    disabled: bool,
+}
+
+impl Switch {
+    pub fn set_checked(&mut self, checked: bool) {
+        self.checked = checked;
+        self.element.mark_dirty(false);
+    }
+
+    pub fn is_checked(&self) -> bool {
+        self.checked
+    }
+
+    pub fn set_disabled(&mut self, disabled: bool) {
+        self.disabled = disabled;
+        self.element.mark_dirty(false);
+    }
+
+    /// Toggle by user, change event is emitted
+    pub fn toggle(&mut self) {
+        if self.disabled {
+            return;
+        }
+        self.set_checked(!self.checked);
+        self.element.emit_checked_change(CheckedChangeDetail {
+            checked: self.checked,
+        });
+    }
+}
+
+impl ElementBackend for Switch {
+    fn create(mut element: ElementRef) -> Self {
+        set_intrinsic_size(&mut element, 36.0, 20.0);
+        Self {
+            element,
+            checked: false,
+            disabled: false,
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        "Switch"
+    }
+
+    fn draw(&self, canvas: &Canvas) {
+        let rect = content_rect(&self.element);
+        if rect.width <= 0.0 || rect.height <= 0.0 {
+            return;
+        }
+        let height = rect.height.min(rect.width);
+        let track = skia_safe::Rect::from_xywh(0.0, (rect.height - height) / 2.0, rect.width, height);
+        let radius = height / 2.0;
+        let track_paint = if self.checked {
+            accent_paint(&self.element, self.disabled)
+        } else {
+            track_paint(&self.element, self.disabled)
+        };
+        canvas.draw_round_rect(track, radius, radius, &track_paint);
+
+        let thumb_radius = radius - 2.0;
+        let thumb_x = if self.checked {
+            track.right - radius
+        } else {
+            track.left + radius
+        };
+        canvas.draw_circle((thumb_x, track.center_y()), thumb_radius.max(0.0), &thumb_paint(&self.element, self.disabled));
+    }
+
+    fn set_property(&mut self, p: &str, v: JsValue) {
+        js_call!("checked", bool, self, set_checked, p, v);
+        js_call!("disabled", bool, self, set_disabled, p, v);
+    }
+
+    fn get_property(&mut self, property_name: &str) -> Result<Option<JsValue>, Error> {
+        match property_name {
+            "checked" => Ok(Some(JsValue::Bool(self.checked))),
+            _ => Ok(None),
+        }
+    }
+
+    fn handle_event_default_behavior(&mut self, event_type: &str, event: &mut ElementEvent) -> bool {
+        let mut activated = false;
+        KeyDownEvent::try_match(event_type, event, |d| {
+            activated = is_activate_key(d);
+        });
+        if activated {
+            emit_keyboard_click(&mut self.element);
+            return true;
+        }
+        event.accept_click(|_| {
+            self.toggle();
+        })
+    }
+
+    fn is_focusable(&self) -> bool {
+        !self.disabled
+    }
+
+    fn get_accessibility_role(&self) -> Role {
+        Role::Switch
+    }
+
+    fn build_accessibility_node(&self, builder: &mut NodeBuilder) {
+        builder.set_toggled(if self.checked { Toggled::True } else { Toggled::False });
+        if self.disabled {
+            builder.set_disabled();
+        } else {
+            builder.set_default_action_verb(DefaultActionVerb::Click);
+            builder.add_action(Action::Focus);
+            builder.add_action(Action::Default);
+        }
+    }
+}
+
+#[test]
+fn test_switch_toggle() {
+    use winit::keyboard::NamedKey;
+    use crate::element::control::create_key_down_event;
+    use crate::event_loop::run_pending_callbacks;
+
+    let mut el = ElementRef::new(Switch::create);
+    let switch = el.get_backend_mut_as::<Switch>();
+    switch.toggle();
+    assert!(switch.is_checked());
+    switch.toggle();
+    assert!(!switch.is_checked());
+
+    let mut event = create_key_down_event(&el, NamedKey::Enter, 0);
+    assert!(el.get_backend_mut_as::<Switch>().handle_event_default_behavior("keydown", &mut event));
+    run_pending_callbacks();
+    assert!(el.get_backend_as::<Switch>().is_checked());
+
+    let switch = el.get_backend_mut_as::<Switch>();
+    switch.set_disabled(true);
+    switch.toggle();
+    assert!(switch.is_checked());
+}
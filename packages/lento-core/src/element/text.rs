@@ -1,28 +1,37 @@
 pub mod skia_text_paragraph;
 pub mod text_paragraph;
+pub mod highlighter;
+pub mod search;
 mod simple_text_paragraph;
 
 use std::cell::RefCell;
+use std::ops::Range;
 use std::rc::Rc;
 
+use accesskit::{NodeBuilder, Role};
 use anyhow::Error;
 use quick_js::JsValue;
-use skia_safe::{Canvas, Color, Font, FontMgr, FontStyle, Paint, Typeface};
-use skia_safe::textlayout::{FontCollection, TextAlign};
-use yoga::{Context, MeasureMode, Node, NodeRef, Size};
+use skia_safe::{Canvas, Color, Font, FontMgr, FontStyle, Paint, PaintStyle, Typeface};
+use skia_safe::textlayout::{FontCollection, TextAlign, TextDirection};
+use yoga::{Context, Direction, MeasureMode, Node, NodeRef, Size};
 
-use crate::base::{ElementEvent, MouseDetail, MouseEventType, Rect, TextUpdateDetail};
+use crate::base::{CaretDetail, ElementEvent, MouseDetail, MouseEventType, Rect, TextUpdateDetail};
+use crate::event_loop::schedule_macro_task_unsafe;
+use crate::canvas_util::CanvasHelper;
 use crate::color::parse_hex_color;
 use crate::element::{ElementBackend, ElementRef};
-use crate::element::text::skia_text_paragraph::{SkiaTextParagraph};
-use crate::element::text::text_paragraph::{ParagraphData, Line, ParagraphRef, TextParams};
+use crate::element::text::highlighter::SyntaxHighlighter;
+use crate::element::text::search::{SearchQuery, TextSearch};
+use crate::element::text::text_paragraph::{ParagraphData, Line, OverflowWrap, ParagraphRef, TextParams, WordBreak};
 use crate::{js_call, match_event_type};
-use crate::event::{AcceptFocusShiftEvent, FocusShiftBind};
+use crate::event::{AcceptFocusShiftEvent, CaretEventBind, FocusShiftBind};
 use crate::number::DeNan;
 use crate::string::StringUtils;
 
 // zero-width space for caret
 const ZERO_WIDTH_WHITESPACE: &str = "\u{200B}";
+const WORD_JOINER: &str = "\u{2060}";
+const DEFAULT_ELLIPSIS: &str = "\u{2026}";
 
 pub type AtomOffset = usize;
 pub type RowOffset = usize;
@@ -31,7 +40,7 @@ pub type ColOffset = usize;
 
 #[repr(C)]
 pub struct Text {
-    text_params: TextParams,
+    text_params: Rc<TextParams>,
     selection_paint: Paint,
     paragraph_ref: ParagraphRef,
     last_width: f32,
@@ -39,6 +48,52 @@ pub struct Text {
     selection: Option<(AtomOffset, AtomOffset)>,
     element: ElementRef,
     selecting_begin: Option<AtomOffset>,
+    white_space: WhiteSpace,
+    /// Source text if white spaces are collapsed for display
+    source_text: Option<String>,
+    search: Option<TextSearch>,
+    /// Atom ranges matched by search, None if outdated
+    search_matches: RefCell<Option<Vec<(AtomOffset, AtomOffset)>>>,
+    /// Index of the active match
+    active_match: Option<usize>,
+    search_paint: Paint,
+    active_search_paint: Paint,
+    /// Atom ranges underlined as misspelled
+    misspelled: Vec<(AtomOffset, AtomOffset)>,
+    misspelled_paint: Paint,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum WhiteSpace {
+    /// Collapse white spaces and line breaks, wrap lines
+    Normal,
+    /// Collapse white spaces and line breaks, no wrap
+    NoWrap,
+    /// Preserve white spaces and line breaks, no wrap
+    Pre,
+    /// Preserve white spaces and line breaks, wrap lines
+    PreWrap,
+}
+
+impl WhiteSpace {
+    pub fn parse(str: &str) -> Option<Self> {
+        let ws = match str {
+            "normal" => WhiteSpace::Normal,
+            "nowrap" => WhiteSpace::NoWrap,
+            "pre" => WhiteSpace::Pre,
+            "pre-wrap" => WhiteSpace::PreWrap,
+            _ => return None,
+        };
+        Some(ws)
+    }
+
+    pub fn is_collapsed(&self) -> bool {
+        matches!(self, WhiteSpace::Normal | WhiteSpace::NoWrap)
+    }
+
+    pub fn is_wrap(&self) -> bool {
+        matches!(self, WhiteSpace::Normal | WhiteSpace::PreWrap)
+    }
 }
 
 thread_local! {
@@ -55,8 +110,11 @@ extern "C" fn measure_label(node_ref: NodeRef, width: f32, _mode: MeasureMode, _
             let mut height = 0f32;
             let mut text_width = 0f32;
             for p in p_list {
-                height += p.paragraph.height();
-                text_width = text_width.max(p.paragraph.max_intrinsic_width());
+                if p.hidden {
+                    continue;
+                }
+                height += p.height();
+                text_width = text_width.max(p.max_intrinsic_width());
             }
             // measure_time::print_time!("text len:{}, width:{}, height:{}", paragraph.paragraphs.len(), text_width, height);
             return Size {
@@ -76,21 +134,35 @@ impl Text {
     fn new(element: ElementRef) -> Self {
         let text_params = TextParams {
             font: DEFAULT_TYPE_FACE.with(|tf| Font::from_typeface(tf, 14.0)),
-            align: TextAlign::Left,
+            align: TextAlign::Start,
             paint: Paint::default(),
             line_height: None,
+            mask: None,
+            direction: TextDirection::LTR,
+            ellipsis: None,
+            word_break: WordBreak::Normal,
+            overflow_wrap: OverflowWrap::BreakWord,
+            tab_size: None,
+            highlighter: None,
         };
         let text = "".to_string();
 
+        let text_params = Rc::new(text_params);
         let paragraphs = Self::build_lines(&text, &text_params, true);
         let paragraph_props = ParagraphRef {
-            data: Rc::new(RefCell::new(ParagraphData {
-                lines: paragraphs,
-                text_wrap: true,
-            })),
+            data: Rc::new(RefCell::new(ParagraphData::new(paragraphs))),
         };
         let mut selection_paint = Paint::default();
         selection_paint.set_color(parse_hex_color("214283").unwrap());
+        let mut search_paint = Paint::default();
+        search_paint.set_color(parse_hex_color("614A19").unwrap());
+        let mut active_search_paint = Paint::default();
+        active_search_paint.set_color(parse_hex_color("A8731E").unwrap());
+        let mut misspelled_paint = Paint::default();
+        misspelled_paint.set_color(parse_hex_color("E5534B").unwrap());
+        misspelled_paint.set_style(PaintStyle::Stroke);
+        misspelled_paint.set_stroke_width(1.0);
+        misspelled_paint.set_anti_alias(true);
         Self {
             paragraph_ref: paragraph_props,
             selection_paint,
@@ -99,6 +171,15 @@ impl Text {
             last_width: 0.0,
             text_params,
             selecting_begin: None,
+            white_space: WhiteSpace::PreWrap,
+            source_text: None,
+            search: None,
+            search_matches: RefCell::new(None),
+            active_match: None,
+            search_paint,
+            active_search_paint,
+            misspelled: Vec::new(),
+            misspelled_paint,
         }
     }
 
@@ -106,7 +187,7 @@ impl Text {
         let old_text = self.get_text();
         if old_text != text {
             self.selection = None;
-            self.rebuild_lines(&text);
+            self.update_display_text(&text);
             self.mark_dirty(true);
 
             let mut event = ElementEvent::new("textupdate", TextUpdateDetail {
@@ -117,55 +198,89 @@ impl Text {
     }
 
 
+    /// Offsets of editing are display offsets, the edited display text replaces the source text
     pub fn insert_text(&mut self, caret: AtomOffset, text: &str) {
+        self.source_text = None;
         let (caret_row, caret_col) = self.get_location_by_atom_offset(caret);
         let new_text = {
-            let mut pi = self.paragraph_ref.data.borrow_mut();
-            let p = pi.lines.get(caret_row).unwrap();
+            let pi = self.paragraph_ref.data.borrow();
+            let p = &pi.lines()[caret_row];
             let mut new_text = p.get_text().to_string();
-            let insert_pos = new_text.byte_index(caret_col);
+            let insert_pos = new_text.grapheme_byte_index(caret_col);
             new_text.insert_str(insert_pos, text);
             new_text
         };
-        self.rebuild_line(caret_row, new_text);
+        self.rebuild_lines_in(caret_row..caret_row + 1, new_text);
     }
 
     pub fn delete_text(&mut self, begin: AtomOffset, end: AtomOffset) {
+        self.source_text = None;
         let (begin_row, begin_col) = self.get_location_by_atom_offset(begin);
         let (end_row, end_col) = self.get_location_by_atom_offset(end);
         let new_text = {
-            let mut pi = self.paragraph_ref.data.borrow_mut();
+            let pi = self.paragraph_ref.data.borrow();
             let mut new_text = String::new();
-            let begin_p = pi.lines.get_mut(begin_row).unwrap();
+            let begin_p = &pi.lines()[begin_row];
             if begin_col > 0 {
                 new_text.push_str(&begin_p.subtext(0, begin_col));
             }
-            let end_p = pi.lines.get_mut(end_row).unwrap();
+            let end_p = &pi.lines()[end_row];
             if end_col < end_p.get_text().len() {
                 new_text.push_str(&end_p.subtext(end_col, end_p.atom_count));
             }
             new_text
         };
-        self.rebuild_line(begin_row, new_text);
-        let delete_rows_count = end_row - begin_row;
-        let mut pi = self.paragraph_ref.data.borrow_mut();
-        for _ in 0..delete_rows_count {
-            pi.lines.remove(begin_row + 1);
+        self.rebuild_lines_in(begin_row..end_row + 1, new_text);
+    }
+
+    /// Vertical range of lines not clipped by ancestors, relative to the content box
+    fn get_visible_range(&self, origin_bounds: &Rect) -> (f32, f32) {
+        let (border_top, _, _, _) = self.element.get_border_width();
+        let (padding_top, _, _, _) = self.element.get_padding();
+        let content_top = origin_bounds.y + border_top + padding_top;
+        let mut top = origin_bounds.y;
+        let mut bottom = origin_bounds.y + origin_bounds.height;
+        let mut parent = self.element.get_parent();
+        while let Some(p) = parent {
+            let pb = p.get_origin_bounds();
+            top = f32::max(top, pb.y);
+            bottom = f32::min(bottom, pb.y + pb.height);
+            parent = p.get_parent();
         }
+        (top - content_top, bottom - content_top)
+    }
+
+    /// Lay out lines in the vertical range, returns true if any estimated height is changed
+    fn layout_visible_lines(&self, top: f32, bottom: f32) -> bool {
+        self.with_lines_mut(|lines| {
+            let mut line_top = 0.0;
+            let mut height_changed = false;
+            for line in lines {
+                if line.hidden || line_top > bottom {
+                    break;
+                }
+                let estimated_height = line.height();
+                if line_top + estimated_height >= top {
+                    let height = line.paragraph().height();
+                    height_changed |= height != estimated_height;
+                    line_top += height;
+                } else {
+                    line_top += estimated_height;
+                }
+            }
+            height_changed
+        })
     }
 
-    fn rebuild_line(&mut self, line: usize, new_text: String) {
+    /// Replace lines in rows with lines of new text
+    fn rebuild_lines_in(&mut self, rows: Range<RowOffset>, new_text: String) {
         {
             let mut pi = self.paragraph_ref.data.borrow_mut();
-            let is_ending = pi.lines.len() - 1 == line;
-            let mut ps = Self::build_lines(&new_text, &self.text_params, is_ending);
-            pi.lines.remove(line);
-            let mut idx = line;
-            for p in ps {
-                pi.lines.insert(idx, p);
-                idx += 1;
-            }
+            let is_ending = pi.lines().len() == rows.end;
+            let lines = Self::build_lines(&new_text, &self.text_params, is_ending);
+            pi.splice_lines(rows, lines);
         }
+        self.invalidate_search_matches();
         self.mark_dirty(true);
     }
 
@@ -230,18 +345,197 @@ impl Text {
         }
     }
 
+    fn update_display_text(&mut self, text: &str) {
+        if self.white_space.is_collapsed() {
+            self.source_text = Some(text.to_string());
+            self.rebuild_lines(&collapse_white_space(text));
+        } else {
+            self.source_text = None;
+            self.rebuild_lines(text);
+        }
+    }
+
+    pub fn set_white_space(&mut self, white_space: String) {
+        let Some(white_space) = WhiteSpace::parse(&white_space) else { return };
+        let text = self.get_text();
+        self.white_space = white_space;
+        self.paragraph_ref.data.borrow_mut().text_wrap = white_space.is_wrap();
+        self.selection = None;
+        self.update_display_text(&text);
+        self.mark_dirty(true);
+    }
+
+    /// "ellipsis" or "clip"
+    pub fn set_text_overflow(&mut self, text_overflow: String) {
+        let ellipsis = text_overflow == "ellipsis";
+        self.params_mut().ellipsis = if ellipsis { Some(DEFAULT_ELLIPSIS.to_string()) } else { None };
+        self.paragraph_ref.data.borrow_mut().ellipsis = ellipsis;
+        self.refresh_lines();
+        self.mark_dirty(true);
+    }
+
+    /// Zero or negative value means unlimited
+    pub fn set_max_lines(&mut self, max_lines: i32) {
+        self.paragraph_ref.data.borrow_mut().max_lines = if max_lines > 0 { Some(max_lines as usize) } else { None };
+        self.mark_dirty(true);
+    }
+
+    /// "normal", "break-all" or "keep-all"
+    pub fn set_word_break(&mut self, word_break: String) {
+        self.params_mut().word_break = WordBreak::parse(&word_break);
+        self.refresh_lines();
+        self.mark_dirty(true);
+    }
+
+    /// "normal", "break-word" or "anywhere"
+    pub fn set_overflow_wrap(&mut self, overflow_wrap: String) {
+        self.params_mut().overflow_wrap = OverflowWrap::parse(&overflow_wrap);
+        self.refresh_lines();
+        self.mark_dirty(true);
+    }
+
+    pub fn set_tab_size(&mut self, tab_size: Option<usize>) {
+        self.params_mut().tab_size = tab_size;
+        self.refresh_lines();
+        self.mark_dirty(true);
+    }
+
+    pub fn set_highlighter(&mut self, highlighter: Option<Rc<dyn SyntaxHighlighter>>) {
+        self.params_mut().highlighter = highlighter;
+        self.refresh_lines();
+        self.mark_dirty(false);
+    }
+
+    pub fn clear_search(&mut self) {
+        self.search = None;
+        self.invalidate_search_matches();
+        self.mark_dirty(false);
+    }
+
+    pub fn get_search(&self) -> Option<&TextSearch> {
+        self.search.as_ref()
+    }
+
+    pub fn get_search_matches(&self) -> Vec<(AtomOffset, AtomOffset)> {
+        self.update_search_matches();
+        self.search_matches.borrow().clone().unwrap_or_default()
+    }
+
+    fn update_search_matches(&self) {
+        let Some(search) = &self.search else { return };
+        if self.search_matches.borrow().is_none() {
+            let matches = search.find_all(&self.get_display_text());
+            self.search_matches.replace(Some(matches));
+        }
+    }
+
+    fn invalidate_search_matches(&mut self) {
+        self.search_matches.replace(None);
+        self.active_match = None;
+    }
+
+    /// Index of the first match after offset, or the last match before offset if backward, wrapping around
+    pub fn find_match_index(&self, offset: AtomOffset, backward: bool) -> Option<usize> {
+        self.update_search_matches();
+        let matches = self.search_matches.borrow();
+        let matches = matches.as_ref().filter(|m| !m.is_empty())?;
+        let index = if backward {
+            let idx = matches.partition_point(|(start, _)| *start < offset);
+            if idx == 0 { matches.len() - 1 } else { idx - 1 }
+        } else {
+            let idx = matches.partition_point(|(start, _)| *start < offset);
+            if idx == matches.len() { 0 } else { idx }
+        };
+        Some(index)
+    }
+
+    /// Highlight the match with active color, return its range
+    pub fn set_active_match(&mut self, index: Option<usize>) -> Option<(AtomOffset, AtomOffset)> {
+        let range = index.and_then(|i| self.get_search_matches().get(i).copied());
+        self.active_match = range.and(index);
+        self.mark_dirty(false);
+        range
+    }
+
+    pub fn get_active_match(&self) -> Option<(AtomOffset, AtomOffset)> {
+        let index = self.active_match?;
+        self.get_search_matches().get(index).copied()
+    }
+
+    /// Activate the match next to the active match and scroll it into view
+    pub fn goto_next_match(&mut self, backward: bool) -> Option<(AtomOffset, AtomOffset)> {
+        let offset = match self.get_active_match() {
+            Some((start, end)) => if backward { start } else { end },
+            None => if backward { self.get_atom_count() } else { 0 },
+        };
+        let index = self.find_match_index(offset, backward)?;
+        let range = self.set_active_match(Some(index))?;
+        self.scroll_range_into_view(range);
+        Some(range)
+    }
+
+    /// "next" or "previous"
+    pub fn goto_match(&mut self, direction: String) {
+        self.goto_next_match(direction == "previous");
+    }
+
+    pub fn set_search_color(&mut self, color: String) {
+        if let Some(color) = parse_hex_color(&color) {
+            self.search_paint.set_color(color);
+            self.mark_dirty(false);
+        }
+    }
+
+    pub fn set_active_search_color(&mut self, color: String) {
+        if let Some(color) = parse_hex_color(&color) {
+            self.active_search_paint.set_color(color);
+            self.mark_dirty(false);
+        }
+    }
+
+    /// Scroll the range into view of the enclosing Scroll, which scrolls by caret change events
+    pub fn scroll_range_into_view(&mut self, range: (AtomOffset, AtomOffset)) {
+        let (start, _) = self.get_caret_offset_coordinate(range.0);
+        let (_, end) = self.get_caret_offset_coordinate(range.1);
+        let left = f32::min(start.0, end.0);
+        let bounds = Rect::new(left, start.1, f32::max(1.0, (end.0 - start.0).abs()), end.1 - start.1);
+        let origin_bounds = self.element.get_origin_bounds();
+        let (border_top, _, _, border_left) = self.element.get_border_width();
+        let origin_bounds = bounds.translate(origin_bounds.x + border_left, origin_bounds.y + border_top);
+        self.element.emit_caret_change(CaretDetail::new(range.0, origin_bounds, bounds));
+    }
+
+    /// Underline ranges with squiggly line, ranges should be sorted
+    pub fn set_misspelled(&mut self, misspelled: Vec<(AtomOffset, AtomOffset)>) {
+        if self.misspelled != misspelled {
+            self.misspelled = misspelled;
+            self.mark_dirty(false);
+        }
+    }
+
+    pub fn get_misspelled(&self) -> &Vec<(AtomOffset, AtomOffset)> {
+        &self.misspelled
+    }
+
+    /// Text of lines, which differs from source text if white spaces are collapsed
+    fn get_display_text(&self) -> String {
+        let pi = self.paragraph_ref.data.borrow();
+        let mut text = String::with_capacity(pi.byte_len());
+        for line in pi.lines() {
+            text.push_str(line.get_text());
+        }
+        text
+    }
+
     pub fn get_text(&self) -> String {
-        self.with_lines_mut(|ps| {
-            let mut text = String::new();
-            for p in ps {
-                text.push_str(&p.get_text())
-            }
-            text
-        })
+        if let Some(text) = &self.source_text {
+            return text.clone();
+        }
+        self.get_display_text()
     }
 
     pub fn set_font_size(&mut self, size: f32) {
-        self.text_params.font.set_size(size);
+        self.params_mut().font.set_size(size);
         self.refresh_lines();
         self.mark_dirty(true);
     }
@@ -250,8 +544,25 @@ impl Text {
         &self.text_params.font
     }
 
+    pub fn get_text_params(&self) -> &TextParams {
+        &self.text_params
+    }
+
+    /// Lines share the params, copy on write
+    fn params_mut(&mut self) -> &mut TextParams {
+        Rc::make_mut(&mut self.text_params)
+    }
+
+    pub fn set_mask(&mut self, mask: Option<char>) {
+        if self.text_params.mask != mask {
+            self.params_mut().mask = mask;
+            self.refresh_lines();
+            self.mark_dirty(true);
+        }
+    }
+
     pub fn set_align(&mut self, align: TextAlign) {
-        self.text_params.align = align;
+        self.params_mut().align = align;
         self.refresh_lines();
         self.mark_dirty(false);
     }
@@ -260,14 +571,26 @@ impl Text {
         self.text_params.align
     }
 
+    pub fn set_direction(&mut self, direction: TextDirection) {
+        if self.text_params.direction != direction {
+            self.params_mut().direction = direction;
+            self.refresh_lines();
+            self.mark_dirty(false);
+        }
+    }
+
+    pub fn get_direction(&self) -> TextDirection {
+        self.text_params.direction
+    }
+
     pub fn get_color(&self) -> Color {
         self.text_params.paint.color()
     }
 
     pub fn rebuild_lines(&mut self, text: &str) {
         let paragraphs = Self::build_lines(text, &self.text_params, true);
-        let mut pi = self.paragraph_ref.data.borrow_mut();
-        pi.update_line(paragraphs);
+        self.paragraph_ref.data.borrow_mut().update_line(paragraphs);
+        self.invalidate_search_matches();
     }
 
     pub fn refresh_lines(&mut self) {
@@ -296,9 +619,9 @@ impl Text {
             let mut height = 0f32;
             let max_offset = p_list.len() - 1;
             for p in p_list {
-                height += p.paragraph.height();
+                height += p.height();
                 if paragraph_offset == max_offset || height > expected_offset.1 {
-                    let line_pos = (expected_offset.0, expected_offset.1 - (height - p.paragraph.height()));
+                    let line_pos = (expected_offset.0, expected_offset.1 - (height - p.height()));
                     let line_col = p.get_caret_by_coord(line_pos);
                     return (paragraph_offset, line_col);
                 }
@@ -317,51 +640,86 @@ impl Text {
             let mut current_row = 0;
             for p in p_list {
                 if current_row == caret_row {
-                    let gc = p.paragraph.get_char_bounds(caret_col).unwrap();
+                    let gc = p.paragraph().get_caret_rect(caret_col).unwrap();
                     let right = gc.left + padding_left;
                     let middle = (gc.top + gc.bottom) / 2.0 + padding_top + y_offset;
                     return ((right, middle - caret_height / 2.0), (right, middle + caret_height / 2.0));
                 }
-                y_offset += p.paragraph.height();
+                y_offset += p.height();
                 current_row += 1;
             }
             unreachable!()
         })
     }
 
-    pub fn get_location_by_atom_offset(&self, atom_offset: AtomOffset) -> (RowOffset, ColOffset) {
-        self.with_lines_mut(|ps| {
-            let mut line_atom_offset = 0;
-            let mut row = 0;
-            for p in ps {
-                let line_atom_end_offset = line_atom_offset + p.atom_count;
-                if line_atom_end_offset > atom_offset {
-                    let col = p.get_column_by_atom_offset(atom_offset - line_atom_offset);
-                    return Some((row, col));
+    /// Bounds of the grapheme at atom offset, relative to content box
+    pub fn get_atom_bounds(&self, atom_offset: AtomOffset) -> Option<skia_safe::Rect> {
+        let (row, col) = self.get_location_by_atom_offset(atom_offset);
+        let (top, _) = self.get_line_vertical_bounds(row)?;
+        self.with_lines_mut(|lines| {
+            let bounds = lines.get_mut(row)?.paragraph().get_char_bounds(col)?;
+            Some(bounds.with_offset((0.0, top)))
+        })
+    }
+
+    /// (top, height) of hard line, relative to content box
+    pub fn get_line_vertical_bounds(&self, row: RowOffset) -> Option<(f32, f32)> {
+        self.with_lines_mut(|lines| {
+            let mut top = 0.0;
+            for (idx, line) in lines.iter().enumerate() {
+                if idx == row {
+                    return Some((top, line.height()));
                 }
-                line_atom_offset = line_atom_end_offset;
-                row += 1;
+                top += line.height();
             }
             None
-        }).unwrap_or(self.get_max_caret())
+        })
+    }
+
+    pub fn get_location_by_atom_offset(&self, atom_offset: AtomOffset) -> (RowOffset, ColOffset) {
+        let location = self.paragraph_ref.data.borrow().get_location(atom_offset);
+        location.unwrap_or_else(|| self.get_max_caret())
     }
 
     pub fn get_atom_offset_by_location(&self, location: (RowOffset, ColOffset)) -> AtomOffset {
-        self.with_lines_mut(|ps| {
-            let (caret_row, caret_col) = location;
-            let mut row = 0;
-            let mut atom_offset = 0;
-            for p in ps {
-                let line_atom_count = p.atom_count;
-                if row == caret_row {
-                    let col = usize::min(caret_col, p.atom_count - 1);
-                    return atom_offset + col;
+        self.paragraph_ref.data.borrow().get_atom_offset(location)
+    }
+
+    /// Caret offset after moving one grapheme left or right in visual order
+    pub fn get_visual_caret_target(&self, atom_offset: AtomOffset, move_right: bool) -> AtomOffset {
+        let forward = move_right == (self.text_params.direction == TextDirection::LTR);
+        let logical_target = if forward {
+            usize::min(atom_offset + 1, self.get_atom_count() - 1)
+        } else {
+            atom_offset.saturating_sub(1)
+        };
+        let (row, col) = self.get_location_by_atom_offset(atom_offset);
+        let line_begin = self.get_atom_offset_by_location((row, 0));
+        let visual_col = self.with_lines_mut(|lines| {
+            let line = &mut lines[row];
+            if self.text_params.direction == TextDirection::LTR && !line.get_text().chars().any(is_rtl_char) {
+                return None;
+            }
+            let atom_count = line.atom_count;
+            let paragraph = line.paragraph();
+            let soft_line = paragraph.get_line_number(col);
+            let x = paragraph.get_caret_rect(col)?.left;
+            // the nearest caret position on the same soft line
+            let mut target: Option<(f32, ColOffset)> = None;
+            for c in 0..atom_count {
+                if c == col || paragraph.get_line_number(c) != soft_line {
+                    continue;
+                }
+                let Some(rect) = paragraph.get_caret_rect(c) else { continue };
+                let distance = if move_right { rect.left - x } else { x - rect.left };
+                if distance > 0.0 && target.map(|(d, _)| distance < d).unwrap_or(true) {
+                    target = Some((distance, c));
                 }
-                row += 1;
-                atom_offset += line_atom_count;
             }
-            atom_offset
-        })
+            // goto adjacent line at the visual edge
+            Some(target.map(|(_, c)| line_begin + c).unwrap_or(logical_target))
+        });
+        visual_col.unwrap_or(logical_target)
     }
 
     fn begin_select(&mut self, caret: AtomOffset) {
@@ -402,7 +760,7 @@ impl Text {
         }
     }
 
-    pub fn with_lines_mut<R, F: FnOnce(&mut Vec<Line>) -> R>(&self, callback: F) -> R {
+    pub fn with_lines_mut<R, F: FnOnce(&mut [Line]) -> R>(&self, callback: F) -> R {
         let layout = &self.element.layout;
         let content_width = layout.get_layout_width()
             - layout.get_layout_padding_left().de_nan(0.0)
@@ -414,21 +772,14 @@ impl Text {
     }
 
     pub fn get_atom_count(&self) -> AtomOffset {
-        self.with_lines_mut(|ps| {
-            let mut result = 0;
-            for p in ps {
-                result += p.atom_count;
-            }
-            result
-        })
+        self.paragraph_ref.data.borrow().atom_count()
     }
 
     pub fn get_max_caret(&self) -> (RowOffset, ColOffset) {
-        self.with_lines_mut(|ps| {
-            let max_row = ps.len() - 1;
-            let max_col = unsafe { ps.get_unchecked(max_row).atom_count - 1 };
-            (max_row, max_col)
-        })
+        let pi = self.paragraph_ref.data.borrow();
+        let lines = pi.lines();
+        let max_row = lines.len() - 1;
+        (max_row, lines[max_row].atom_count - 1)
     }
 
     pub fn get_line_height(&self) -> Option<f32> {
@@ -442,7 +793,7 @@ impl Text {
         }
     }
 
-    pub fn build_lines(text: &str, params: &TextParams, is_ending: bool) -> Vec<Line> {
+    pub fn build_lines(text: &str, params: &Rc<TextParams>, is_ending: bool) -> Vec<Line> {
         let mut lines: Vec<&str> = if text.is_empty() {
             vec![""]
         } else {
@@ -453,13 +804,7 @@ impl Text {
         }
         let mut result = Vec::new();
         for ln in lines {
-            // let p = SimpleTextParagraph::new(ln, params);
-            let p = SkiaTextParagraph::new(ln.to_string(), params);
-            result.push(Line {
-                atom_count: ln.trim_line_endings().chars().count() + 1,
-                paragraph: p,
-                paragraph_dirty: true,
-            })
+            result.push(Line::new(ln.to_string(), params.clone()));
         }
         result
     }
@@ -485,6 +830,44 @@ fn intersect_range(range1: (AtomOffset, AtomOffset), range2: (AtomOffset, AtomOf
     }
 }
 
+/// Fill bounds of graphemes in range, line_range is the atom range of line
+fn draw_range_background(canvas: &Canvas, line: &mut Line, line_range: (AtomOffset, AtomOffset), top: f32, range: (AtomOffset, AtomOffset), paint: &Paint) {
+    if let Some((begin, end)) = intersect_range(range, line_range) {
+        for offset in begin - line_range.0..end - line_range.0 {
+            if let Some(g) = line.paragraph().get_char_bounds(offset) {
+                canvas.draw_rect(&g.with_offset((0.0, top)), paint);
+            }
+        }
+    }
+}
+
+/// Squiggly underline of graphemes in range, line_range is the atom range of line
+fn draw_range_squiggly_line(canvas: &Canvas, line: &mut Line, line_range: (AtomOffset, AtomOffset), top: f32, range: (AtomOffset, AtomOffset), paint: &Paint) {
+    let Some((begin, end)) = intersect_range(range, line_range) else { return };
+    // (left, right, bottom) of the graphemes on the same soft line
+    let mut segment: Option<(f32, f32, f32)> = None;
+    for offset in begin - line_range.0..end - line_range.0 {
+        let Some(g) = line.paragraph().get_char_bounds(offset) else { continue };
+        segment = match segment {
+            Some((left, right, bottom)) if bottom == g.bottom => Some((left.min(g.left), right.max(g.right), bottom)),
+            Some((left, right, bottom)) => {
+                canvas.draw_squiggly_line(left, right, top + bottom - 1.5, paint);
+                Some((g.left, g.right, g.bottom))
+            }
+            None => Some((g.left, g.right, g.bottom)),
+        };
+    }
+    if let Some((left, right, bottom)) = segment {
+        canvas.draw_squiggly_line(left, right, top + bottom - 1.5, paint);
+    }
+}
+
+fn ranges_to_js_value(ranges: &[(AtomOffset, AtomOffset)]) -> JsValue {
+    JsValue::Array(ranges.iter().map(|(start, end)| {
+        JsValue::Array(vec![JsValue::Int(*start as i32), JsValue::Int(*end as i32)])
+    }).collect())
+}
+
 impl ElementBackend for Text {
     fn create(mut ele: ElementRef) -> Self {
         let mut label = Self::new(ele.clone());
@@ -497,10 +880,21 @@ impl ElementBackend for Text {
         "Text"
     }
 
+    fn search(&mut self, query: &SearchQuery) -> Result<(), Error> {
+        self.search = if query.query.is_empty() {
+            None
+        } else {
+            Some(TextSearch::new(query)?)
+        };
+        self.invalidate_search_matches();
+        self.mark_dirty(false);
+        Ok(())
+    }
+
     fn handle_style_changed(&mut self, key: &str) {
         if key == "color" {
             let color = self.element.layout.computed_style.color;
-            self.text_params.paint.set_color(color);
+            self.params_mut().paint.set_color(color);
             self.refresh_lines();
             self.mark_dirty(false);
         }
@@ -514,53 +908,78 @@ impl ElementBackend for Text {
         //     paint.set_color(parse_hex_color("ccc").unwrap());
         //     canvas.draw_rect(clip_r, &paint);
         // }
+        self.update_search_matches();
+        let search_matches = self.search_matches.borrow();
+        let search_matches = search_matches.as_deref().unwrap_or(&[]);
         self.with_lines_mut(|p_list| {
             let mut top = 0.0;
             let mut line_atom_offset = 0;
             for p in p_list {
-                let p_height = p.paragraph.height();
+                if p.hidden {
+                    break;
+                }
                 let p_top = top;
-                let p_bottom = top + p_height;
                 let p_atom_begin = line_atom_offset;
                 let p_atom_count = p.atom_count;
                 let p_atom_end = p_atom_begin + p_atom_count;
-
-                top += p_height;
                 line_atom_offset += p_atom_count;
                 if let Some(cp) = clip_rect {
-                    if p_bottom < cp.top {
+                    // off-screen lines are not laid out, use the estimated height
+                    let estimated_height = p.height();
+                    if top + estimated_height < cp.top {
+                        top += estimated_height;
                         continue;
                     } else if p_top > cp.bottom {
                         break;
                     }
                 }
+                top += p.paragraph().height();
+                let p_range = (p_atom_begin, p_atom_end);
+                let first_match = search_matches.partition_point(|(_, end)| *end <= p_atom_begin);
+                for (i, m) in search_matches.iter().enumerate().skip(first_match) {
+                    if m.0 >= p_atom_end {
+                        break;
+                    }
+                    let paint = if self.active_match == Some(i) { &self.active_search_paint } else { &self.search_paint };
+                    draw_range_background(canvas, p, p_range, p_top, *m, paint);
+                }
                 if let Some(si_range) = self.selection {
-                    let p_range = (p_atom_begin, p_atom_end);
-                    if let Some((begin, end)) = intersect_range(si_range, p_range) {
-                        let begin = begin - p_atom_begin;
-                        let end = end - p_atom_begin;
-                        for offset in begin..end {
-                            if let Some(g) = p.paragraph.get_char_bounds(offset) {
-                                let bounds = g.with_offset((0.0, p_top));
-                                canvas.draw_rect(&bounds, &self.selection_paint);
-                            }
-                        }
+                    draw_range_background(canvas, p, p_range, p_top, si_range, &self.selection_paint);
+                }
+                p.paragraph().paint(canvas, (0.0, p_top));
+                let first_misspelled = self.misspelled.partition_point(|(_, end)| *end <= p_atom_begin);
+                for m in &self.misspelled[first_misspelled..] {
+                    if m.0 >= p_atom_end {
+                        break;
                     }
+                    draw_range_squiggly_line(canvas, p, p_range, p_top, *m, &self.misspelled_paint);
                 }
-                p.paragraph.paint(canvas, (0.0, p_top));
             }
         });
     }
 
     fn set_property(&mut self, p: &str, v: JsValue) {
         js_call!("text", String, self, set_text, p, v);
+        js_call!("searchcolor", String, self, set_search_color, p, v);
+        js_call!("activesearchcolor", String, self, set_active_search_color, p, v);
+        js_call!("gotomatch", String, self, goto_match, p, v);
         js_call!("fontsize", f32, self, set_font_size, p, v);
         js_call!("align", TextAlign, self, set_align, p, v);
+        js_call!("textoverflow", String, self, set_text_overflow, p, v);
+        js_call!("maxlines", i32, self, set_max_lines, p, v);
+        js_call!("whitespace", String, self, set_white_space, p, v);
+        js_call!("wordbreak", String, self, set_word_break, p, v);
+        js_call!("overflowwrap", String, self, set_overflow_wrap, p, v);
     }
 
     fn get_property(&mut self, property_name: &str) -> Result<Option<JsValue>, Error> {
         match property_name {
             "text" => Ok(Some(JsValue::String(self.get_text().to_string()))),
+            "searchmatches" => Ok(Some(ranges_to_js_value(&self.get_search_matches()))),
+            "activematch" => Ok(Some(match self.active_match {
+                Some(i) => JsValue::Int(i as i32),
+                None => JsValue::Null,
+            })),
             _ => {
                 Ok(None)
             }
@@ -574,19 +993,40 @@ impl ElementBackend for Text {
     }
 
     fn handle_origin_bounds_change(&mut self, bounds: &Rect) {
-        let mut pi = self.paragraph_ref.data.borrow_mut();
-        //TODO check font/color changed?
-        if bounds.width != self.last_width {
-            pi.lines.iter_mut().for_each(|p| {
-                p.paragraph_dirty = true;
-            });
-            self.last_width = bounds.width;
+        let direction = match self.element.layout.get_layout_direction() {
+            Direction::RTL => TextDirection::RTL,
+            _ => TextDirection::LTR,
+        };
+        self.set_direction(direction);
+        {
+            let mut pi = self.paragraph_ref.data.borrow_mut();
+            //TODO check font/color changed?
+            if bounds.width != self.last_width {
+                pi.invalidate_layout();
+                self.last_width = bounds.width;
+            }
+        }
+        let (top, bottom) = self.get_visible_range(bounds);
+        if self.layout_visible_lines(top, bottom) {
+            // the estimated heights are replaced by actual heights, lay out again after this pass
+            let mut element = self.element.clone();
+            unsafe {
+                schedule_macro_task_unsafe(move || element.mark_dirty(true));
+            }
         }
     }
 
     fn handle_event(&mut self, _event_type: &str, event: &mut ElementEvent) {
         match_event_type!(event, MouseDetail, self, handle_mouse_event);
     }
+
+    fn get_accessibility_role(&self) -> Role {
+        Role::Label
+    }
+
+    fn build_accessibility_node(&self, builder: &mut NodeBuilder) {
+        builder.set_name(self.get_text());
+    }
 }
 
 pub fn parse_align(align: &str) -> TextAlign {
@@ -594,8 +1034,40 @@ pub fn parse_align(align: &str) -> TextAlign {
         "left" => TextAlign::Left,
         "right" => TextAlign::Right,
         "center" => TextAlign::Center,
-        _ => TextAlign::Left,
+        "start" => TextAlign::Start,
+        "end" => TextAlign::End,
+        _ => TextAlign::Start,
+    }
+}
+
+/// Collapse sequences of white spaces and line breaks into one space
+pub fn collapse_white_space(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut last_is_space = false;
+    for c in text.chars() {
+        let is_space = c == ' ' || c == '\t' || c == '\n' || c == '\r';
+        if !is_space {
+            result.push(c);
+        } else if !last_is_space {
+            result.push(' ');
+        }
+        last_is_space = is_space;
     }
+    result
+}
+
+/// Whether char is a CJK ideograph, kana or hangul syllable, which could be broken between each other
+pub fn is_cjk_char(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xAC00..=0xD7AF | 0xF900..=0xFAFF | 0x20000..=0x2FA1F
+    )
+}
+
+/// Whether char is from right-to-left scripts, e.g. Hebrew, Arabic
+pub fn is_rtl_char(c: char) -> bool {
+    matches!(c as u32,
+        0x0590..=0x08FF | 0xFB1D..=0xFDFF | 0xFE70..=0xFEFF | 0x10800..=0x10FFF | 0x1E800..=0x1EFFF
+    )
 }
 
 #[test]
@@ -615,4 +1087,138 @@ pub fn test_get_caret_by_char_offset() {
     text.set_text("abc".to_string());
     assert_eq!((0, 2), text.get_location_by_atom_offset(2));
     assert_eq!((0, 3), text.get_location_by_atom_offset(3));
-}
\ No newline at end of file
+}
+#[test]
+pub fn test_grapheme_atoms() {
+    let mut el = ElementRef::new(Text::create);
+    let text = el.get_backend_mut_as::<Text>();
+    // woman technologist(ZWJ sequence), e with combining acute, hangul jamo
+    text.set_text("\u{1F469}\u{200D}\u{1F4BB}e\u{301}\u{1100}\u{1161}\u{11A8}\nx".to_string());
+    assert_eq!(6, text.get_atom_count());
+    assert_eq!((1, 0), text.get_location_by_atom_offset(4));
+    text.select(1, 2);
+    assert_eq!(Some("e\u{301}".to_string()), text.get_selection_text());
+    text.delete_text(0, 1);
+    assert_eq!("e\u{301}\u{1100}\u{1161}\u{11A8}\nx", text.get_text());
+    text.insert_text(1, "\u{1F1EF}\u{1F1F5}");
+    assert_eq!("e\u{301}\u{1F1EF}\u{1F1F5}\u{1100}\u{1161}\u{11A8}\nx", text.get_text());
+    assert_eq!(6, text.get_atom_count());
+}
+
+#[test]
+pub fn test_bidi_caret() {
+    let mut el = ElementRef::new(Text::create);
+    let text = el.get_backend_mut_as::<Text>();
+    // "abc " followed by hebrew "שלום"
+    text.set_text("abc \u{5E9}\u{5DC}\u{5D5}\u{5DD}".to_string());
+    // hebrew is laid out from right to left
+    assert!(text.get_caret_offset_coordinate(5).0.0 > text.get_caret_offset_coordinate(6).0.0);
+    assert_eq!(5, text.get_visual_caret_target(6, true));
+    assert_eq!(6, text.get_visual_caret_target(5, false));
+    assert_eq!(1, text.get_visual_caret_target(2, false));
+    assert_eq!(TextAlign::End, parse_align("end"));
+}
+
+#[test]
+pub fn test_text_overflow() {
+    assert_eq!(" a b c ", collapse_white_space("\n a \t b\r\n\nc  "));
+    let mut el = ElementRef::new(Text::create);
+    let text = el.get_backend_mut_as::<Text>();
+    text.set_white_space("normal".to_string());
+    text.set_text("a\nb".to_string());
+    assert_eq!("a\nb", text.get_text());
+    // displayed as "a b"
+    assert_eq!(4, text.get_atom_count());
+
+    text.set_white_space("pre".to_string());
+    text.set_max_lines(1);
+    text.set_text_overflow("ellipsis".to_string());
+    let hidden: Vec<bool> = text.with_lines_mut(|lines| lines.iter().map(|l| l.hidden).collect());
+    assert_eq!(vec![false, true], hidden);
+}
+
+#[test]
+pub fn test_edit_collapsed_text() {
+    let mut el = ElementRef::new(Text::create);
+    let text = el.get_backend_mut_as::<Text>();
+    text.set_white_space("normal".to_string());
+    text.set_text("a\n\nb".to_string());
+    assert_eq!("a\n\nb", text.get_text());
+    // offsets are in the displayed "a b"
+    text.insert_text(1, "x");
+    assert_eq!("ax b", text.get_text());
+    text.delete_text(0, 1);
+    assert_eq!("x b", text.get_text());
+    text.select(0, 1);
+    assert_eq!(Some("x".to_string()), text.get_selection_text());
+}
+
+#[test]
+pub fn test_line_breaking() {
+    let line_count = |word_break: &str, overflow_wrap: &str, content: &str| {
+        let mut el = ElementRef::new(Text::create);
+        let text = el.get_backend_mut_as::<Text>();
+        text.set_word_break(word_break.to_string());
+        text.set_overflow_wrap(overflow_wrap.to_string());
+        text.set_text(content.to_string());
+        text.with_lines_mut(|lines| {
+            let line = &mut lines[0];
+            line.set_layout_width(40.0);
+            line.paragraph().line_count()
+        })
+    };
+    let cjk = "\u{4F60}\u{597D}\u{4E16}\u{754C}\u{4F60}\u{597D}\u{4E16}\u{754C}";
+    // CJK text is broken between ideographs unless keep-all is set
+    assert!(line_count("normal", "break-word", cjk) > 1);
+    let cjk_words = format!("{} {}", &cjk[..12], &cjk[12..]);
+    assert!(line_count("keep-all", "normal", &cjk_words) <= 2);
+    assert!(line_count("normal", "break-word", &cjk_words) > 2);
+
+    // overlong words are broken by break-word and overflow if normal
+    let word = "abcdefghijklmnopqrstuvwxyz0123456789";
+    assert!(line_count("normal", "break-word", word) > 1);
+    assert_eq!(1, line_count("normal", "normal", word));
+    assert!(is_cjk_char('\u{AC00}'));
+    assert!(!is_cjk_char('a'));
+}
+
+#[test]
+pub fn test_layout_visible_lines() {
+    let mut el = ElementRef::new(Text::create);
+    let text = el.get_backend_mut_as::<Text>();
+    text.set_text("line\n".repeat(1000));
+    text.layout_visible_lines(0.0, 50.0);
+    let laid_out: Vec<bool> = text.with_lines_mut(|lines| lines.iter().map(|l| l.is_laid_out()).collect());
+    assert!(laid_out[0]);
+    assert!(!laid_out[999]);
+    // heights are already actual
+    assert!(!text.layout_visible_lines(0.0, 50.0));
+    text.insert_text(5, "two ");
+    text.delete_text(0, 2);
+    assert_eq!(format!("ne\ntwo line\n{}", "line\n".repeat(998)), text.get_text());
+}
+
+#[test]
+pub fn test_line_index() {
+    let mut el = ElementRef::new(Text::create);
+    let text = el.get_backend_mut_as::<Text>();
+    // lines end with a newline atom or the end of text
+    text.set_text("ab\ncd\nef".to_string());
+    assert_eq!(9, text.get_atom_count());
+    assert_eq!((1, 1), text.get_location_by_atom_offset(4));
+    assert_eq!(4, text.get_atom_offset_by_location((1, 1)));
+
+    // following lines are shifted
+    text.insert_text(4, "x\ny");
+    assert_eq!("ab\ncx\nyd\nef", text.get_text());
+    assert_eq!(12, text.get_atom_count());
+    assert_eq!((2, 2), text.get_location_by_atom_offset(8));
+    assert_eq!((3, 1), text.get_location_by_atom_offset(10));
+
+    text.delete_text(1, 7);
+    assert_eq!("ad\nef", text.get_text());
+    assert_eq!(6, text.get_atom_count());
+    assert_eq!((1, 2), text.get_location_by_atom_offset(5));
+    assert_eq!((1, 2), text.get_location_by_atom_offset(100));
+    assert_eq!(6, text.get_atom_offset_by_location((5, 0)));
+}
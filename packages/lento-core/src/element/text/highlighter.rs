@@ -0,0 +1,82 @@
+use anyhow::{anyhow, Error};
+use regex::Regex;
+use skia_safe::Color;
+
+use crate::color::parse_hex_color;
+
+/// Color of a range in line text
+#[derive(Debug, Clone, PartialEq)]
+pub struct HighlightSpan {
+    /// Start byte offset
+    pub start: usize,
+    /// End byte offset
+    pub end: usize,
+    pub color: Color,
+}
+
+/// Tokenizer which colors text line by line
+pub trait SyntaxHighlighter {
+    /// Spans of the line, sorted and not overlapped
+    fn highlight_line(&self, line: &str) -> Vec<HighlightSpan>;
+}
+
+/// Highlight matches of rules, the former rule wins if matches overlap
+pub struct RegexHighlighter {
+    rules: Vec<(Regex, Color)>,
+}
+
+impl RegexHighlighter {
+    /// Rules are (regex, hex color) pairs
+    pub fn new(rules: &[(String, String)]) -> Result<Self, Error> {
+        let mut result = Vec::with_capacity(rules.len());
+        for (pattern, color) in rules {
+            let color = parse_hex_color(color.trim_start_matches('#'))
+                .ok_or_else(|| anyhow!("invalid color:{}", color))?;
+            result.push((Regex::new(pattern)?, color));
+        }
+        Ok(Self { rules: result })
+    }
+}
+
+impl SyntaxHighlighter for RegexHighlighter {
+    fn highlight_line(&self, line: &str) -> Vec<HighlightSpan> {
+        let mut matches = Vec::new();
+        for (idx, (regex, color)) in self.rules.iter().enumerate() {
+            for m in regex.find_iter(line) {
+                if !m.is_empty() {
+                    matches.push((m.start(), idx, m.end(), *color));
+                }
+            }
+        }
+        matches.sort_by_key(|(start, idx, _, _)| (*start, *idx));
+        let mut spans: Vec<HighlightSpan> = Vec::new();
+        for (start, idx, end, color) in matches {
+            if spans.last().map(|s| s.end > start).unwrap_or(false) {
+                continue;
+            }
+            // a former rule matched inside the span takes precedence
+            let covered = self.rules[..idx].iter().any(|(regex, _)| {
+                regex.find_at(line, start).map(|m| m.start() < end && !m.is_empty()).unwrap_or(false)
+            });
+            if covered {
+                continue;
+            }
+            spans.push(HighlightSpan { start, end, color });
+        }
+        spans
+    }
+}
+
+#[test]
+fn test_regex_highlighter() {
+    let rules = [
+        ("//.*".to_string(), "#808080".to_string()),
+        ("\\b(fn|let)\\b".to_string(), "#CC7832".to_string()),
+    ];
+    let highlighter = RegexHighlighter::new(&rules).unwrap();
+    let spans = highlighter.highlight_line("let a = 1; // let b");
+    assert_eq!(2, spans.len());
+    assert_eq!((0, 3), (spans[0].start, spans[0].end));
+    assert_eq!((11, 19), (spans[1].start, spans[1].end));
+    assert!(RegexHighlighter::new(&[("(".to_string(), "#fff".to_string())]).is_err());
+}
@@ -0,0 +1,100 @@
+use anyhow::Error;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::element::text::AtomOffset;
+use crate::string::StringUtils;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchQuery {
+    pub query: String,
+    #[serde(default)]
+    pub ignore_case: bool,
+    /// Treat query as regular expression, captures could be referenced in replacement as $1
+    #[serde(default)]
+    pub regex: bool,
+}
+
+pub struct TextSearch {
+    regex: Regex,
+    is_regex: bool,
+}
+
+impl TextSearch {
+    pub fn new(query: &SearchQuery) -> Result<Self, Error> {
+        let pattern = if query.regex {
+            query.query.clone()
+        } else {
+            regex::escape(&query.query)
+        };
+        let regex = RegexBuilder::new(&pattern)
+            .case_insensitive(query.ignore_case)
+            .multi_line(true)
+            .build()?;
+        Ok(Self {
+            regex,
+            is_regex: query.regex,
+        })
+    }
+
+    /// Atom ranges of non-empty matches, partial graphemes are extended to whole graphemes
+    pub fn find_all(&self, text: &str) -> Vec<(AtomOffset, AtomOffset)> {
+        let mut boundaries = text.grapheme_indices(true).map(|(i, _)| i);
+        let mut next_boundary = boundaries.next();
+        let mut atom = 0;
+        let mut result = Vec::new();
+        for m in self.regex.find_iter(text) {
+            if m.is_empty() {
+                continue;
+            }
+            while next_boundary.map(|b| b <= m.start()).unwrap_or(false) {
+                next_boundary = boundaries.next();
+                atom += 1;
+            }
+            // atom is the count of graphemes beginning at or before start
+            let start = atom - 1;
+            while next_boundary.map(|b| b < m.end()).unwrap_or(false) {
+                next_boundary = boundaries.next();
+                atom += 1;
+            }
+            if result.last().map(|(_, end)| *end > start).unwrap_or(false) {
+                continue;
+            }
+            result.push((start, atom));
+        }
+        result
+    }
+
+    /// Replacement of the match at range, captures are expanded in regex mode
+    pub fn expand_replacement(&self, text: &str, range: (AtomOffset, AtomOffset), replacement: &str) -> String {
+        if !self.is_regex {
+            return replacement.to_string();
+        }
+        let start = text.grapheme_byte_index(range.0);
+        match self.regex.captures_at(text, start) {
+            Some(caps) => {
+                let mut dst = String::new();
+                caps.expand(replacement, &mut dst);
+                dst
+            }
+            None => replacement.to_string(),
+        }
+    }
+}
+
+#[test]
+fn test_find_all() {
+    let search = TextSearch::new(&SearchQuery { query: "ab".to_string(), ignore_case: true, regex: false }).unwrap();
+    assert_eq!(vec![(0, 2), (3, 5)], search.find_all("ab\nAB"));
+
+    let search = TextSearch::new(&SearchQuery { query: "a.".to_string(), ignore_case: false, regex: false }).unwrap();
+    assert_eq!(vec![(4, 6)], search.find_all("axA.a."));
+
+    // grapheme offsets
+    let search = TextSearch::new(&SearchQuery { query: "(\\w)x".to_string(), ignore_case: false, regex: true }).unwrap();
+    let text = "e\u{301}\u{1F1EF}\u{1F1F5}bx";
+    assert_eq!(vec![(2, 4)], search.find_all(text));
+    assert_eq!("[b]", search.expand_replacement(text, (2, 4), "[$1]"));
+}
@@ -1,23 +1,52 @@
 use skia_safe::{Canvas, Font, Paint, Point, Rect};
-use skia_safe::textlayout::{Paragraph as SkParagraph, ParagraphBuilder, ParagraphStyle, StrutStyle, TextAlign, TextStyle};
-use crate::element::text::{FONT_COLLECTION, FONT_MGR, ZERO_WIDTH_WHITESPACE};
-use crate::element::text::text_paragraph::TextParams;
+use skia_safe::textlayout::{Paragraph as SkParagraph, ParagraphBuilder, ParagraphStyle, StrutStyle, TextDirection, TextStyle};
+use crate::element::text::{is_cjk_char, FONT_COLLECTION, FONT_MGR, WORD_JOINER, ZERO_WIDTH_WHITESPACE};
+use crate::element::text::text_paragraph::{TextParams, WordBreak};
 use crate::string::StringUtils;
+use unicode_segmentation::UnicodeSegmentation;
 
 pub struct SkiaTextParagraph {
     text: String,
     paragraph: SkParagraph,
+    params: TextParams,
+    /// Utf16 offset of every atom in rendered text, None if text is rendered as is
+    atom_utf16_offsets: Option<Vec<usize>>,
+    max_lines: Option<usize>,
+    /// Append ellipsis even if text is not overflow, e.g. following lines are hidden
+    truncated: bool,
 }
 
 impl SkiaTextParagraph {
     pub fn new(text: String, params: &TextParams) -> Self {
-        let paragraph = Self::build_paragraph(&text, params);
+        let (rendered_text, atom_utf16_offsets) = Self::build_rendered_text(&text, params);
+        let paragraph = Self::build_paragraph(&rendered_text, params, None, false);
         Self {
             paragraph,
             text,
+            params: params.clone(),
+            atom_utf16_offsets,
+            max_lines: None,
+            truncated: false,
         }
     }
 
+    /// Limit lines of paragraph, return true if paragraph is rebuilt and should be laid out again
+    pub fn set_clamp(&mut self, max_lines: Option<usize>, truncated: bool) -> bool {
+        if self.max_lines == max_lines && self.truncated == truncated {
+            return false;
+        }
+        self.max_lines = max_lines;
+        self.truncated = truncated;
+        let (rendered_text, _) = Self::build_rendered_text(&self.text, &self.params);
+        self.paragraph = Self::build_paragraph(&rendered_text, &self.params, max_lines, truncated);
+        true
+    }
+
+    /// Count of soft lines
+    pub fn line_count(&self) -> usize {
+        self.paragraph.line_number()
+    }
+
     pub fn get_text(&self) -> &str {
         &self.text
     }
@@ -34,17 +63,38 @@ impl SkiaTextParagraph {
         self.paragraph.max_intrinsic_width()
     }
 
-    pub fn get_char_bounds(&mut self, char_offset: usize) -> Option<Rect> {
-        let gc = self.paragraph.get_glyph_info_at_utf16_offset(char_offset);
+    /// Width of the longest unbreakable run, available after layout
+    pub fn min_intrinsic_width(&self) -> f32 {
+        self.paragraph.min_intrinsic_width()
+    }
+
+    /// Bounds of the grapheme at atom offset
+    pub fn get_char_bounds(&mut self, atom_offset: usize) -> Option<Rect> {
+        let gc = self.paragraph.get_glyph_info_at_utf16_offset(self.atom_to_utf16(atom_offset));
         gc.map(|g| g.grapheme_layout_bounds)
     }
 
+    /// Caret line of the grapheme at atom offset, caret is on the right side of rtl grapheme
+    pub fn get_caret_rect(&mut self, atom_offset: usize) -> Option<Rect> {
+        let gi = self.paragraph.get_glyph_info_at_utf16_offset(self.atom_to_utf16(atom_offset))?;
+        let bounds = gi.grapheme_layout_bounds;
+        let x = if gi.text_direction == TextDirection::RTL { bounds.right } else { bounds.left };
+        Some(Rect::new(x, bounds.top, x, bounds.bottom))
+    }
+
+    /// Soft line number of the grapheme at atom offset
+    pub fn get_line_number(&self, atom_offset: usize) -> Option<usize> {
+        self.paragraph.get_line_number_at_utf16_offset(self.atom_to_utf16(atom_offset))
+    }
+
+    /// Atom offset at coordinate
     pub fn get_char_offset_at_coordinate(&self, coord: (f32, f32)) -> usize {
-        self.paragraph.get_glyph_position_at_coordinate(coord).position as usize
+        let utf16_offset = self.paragraph.get_glyph_position_at_coordinate(coord).position as usize;
+        self.utf16_to_atom(utf16_offset)
     }
 
-    pub fn get_soft_line_height(&self, char_offset: usize) -> f32 {
-        let ln = self.paragraph.get_line_number_at_utf16_offset(char_offset).unwrap();
+    pub fn get_soft_line_height(&self, atom_offset: usize) -> f32 {
+        let ln = self.paragraph.get_line_number_at_utf16_offset(self.atom_to_utf16(atom_offset)).unwrap();
         let lm = self.paragraph.get_line_metrics_at(ln).unwrap();
         lm.height as f32
     }
@@ -53,8 +103,89 @@ impl SkiaTextParagraph {
         self.paragraph.paint(canvas, p)
     }
 
-    pub fn build_paragraph(text: &str, params: &TextParams) -> SkParagraph {
-        let mut text = text.trim_line_endings().to_string();
+    fn atom_to_utf16(&self, atom_offset: usize) -> usize {
+        match &self.atom_utf16_offsets {
+            Some(offsets) => offsets.get(atom_offset).or(offsets.last()).copied().unwrap_or(0),
+            None => self.text.trim_line_endings().grapheme_utf16_index(atom_offset),
+        }
+    }
+
+    fn utf16_to_atom(&self, utf16_offset: usize) -> usize {
+        match &self.atom_utf16_offsets {
+            Some(offsets) => offsets.partition_point(|o| *o <= utf16_offset).saturating_sub(1),
+            None => self.text.trim_line_endings().utf16_grapheme_index(utf16_offset),
+        }
+    }
+
+    /// Text passed to skia and utf16 offsets of atoms in it if they are different from the source text
+    fn build_rendered_text(text: &str, params: &TextParams) -> (String, Option<Vec<usize>>) {
+        let text = text.trim_line_endings();
+        if let Some(mask) = params.mask {
+            let count = text.graphemes_count();
+            let offsets = (0..=count).map(|i| i * mask.len_utf16()).collect();
+            return (std::iter::repeat(mask).take(count).collect(), Some(offsets));
+        }
+        if params.word_break == WordBreak::BreakAll {
+            // zero-width space between graphemes gives break opportunity everywhere
+            let mut rendered = String::with_capacity(text.len() * 2);
+            let mut offsets = vec![0];
+            let mut utf16_offset = 0;
+            for g in text.graphemes(true) {
+                rendered.push_str(g);
+                rendered.push_str(ZERO_WIDTH_WHITESPACE);
+                utf16_offset += g.encode_utf16().count() + 1;
+                offsets.push(utf16_offset);
+            }
+            return (rendered, Some(offsets));
+        }
+        if params.word_break == WordBreak::KeepAll && text.chars().any(is_cjk_char) {
+            // word joiner between CJK graphemes removes the break opportunities between them
+            let mut rendered = String::with_capacity(text.len() * 2);
+            let mut offsets = vec![0];
+            let mut utf16_offset = 0;
+            let mut prev_cjk = false;
+            for g in text.graphemes(true) {
+                let cjk = g.chars().next().map(is_cjk_char).unwrap_or(false);
+                if cjk && prev_cjk {
+                    rendered.push_str(WORD_JOINER);
+                    utf16_offset += 1;
+                    *offsets.last_mut().unwrap() = utf16_offset;
+                }
+                rendered.push_str(g);
+                utf16_offset += g.encode_utf16().count();
+                offsets.push(utf16_offset);
+                prev_cjk = cjk;
+            }
+            return (rendered, Some(offsets));
+        }
+        if let (Some(tab_size), true) = (params.tab_size, text.contains('\t')) {
+            let mut rendered = String::with_capacity(text.len());
+            let mut offsets = vec![0];
+            let mut column = 0;
+            let mut utf16_offset = 0;
+            for g in text.graphemes(true) {
+                if g == "\t" {
+                    let spaces = tab_size - column % tab_size;
+                    rendered.extend(std::iter::repeat(' ').take(spaces));
+                    column += spaces;
+                    utf16_offset += spaces;
+                } else {
+                    rendered.push_str(g);
+                    column += 1;
+                    utf16_offset += g.encode_utf16().count();
+                }
+                offsets.push(utf16_offset);
+            }
+            return (rendered, Some(offsets));
+        }
+        (text.to_string(), None)
+    }
+
+    fn build_paragraph(rendered_text: &str, params: &TextParams, max_lines: Option<usize>, truncated: bool) -> SkParagraph {
+        let mut text = rendered_text.to_string();
+        if let (true, Some(ellipsis)) = (truncated, &params.ellipsis) {
+            text.push_str(ellipsis);
+        }
         text.push_str(ZERO_WIDTH_WHITESPACE);
         let mut font_collection = FONT_COLLECTION.with(|f| f.clone());
         FONT_MGR.with(|fm| {
@@ -62,6 +193,13 @@ impl SkiaTextParagraph {
         });
         let mut paragraph_style = ParagraphStyle::new();
         paragraph_style.set_text_align(params.align);
+        paragraph_style.set_text_direction(params.direction);
+        if let Some(max_lines) = max_lines {
+            paragraph_style.set_max_lines(max_lines);
+            if let Some(ellipsis) = &params.ellipsis {
+                paragraph_style.set_ellipsis(ellipsis);
+            }
+        }
 
         if let Some(line_height) = params.line_height {
             let mut strut_style = StrutStyle::default();
@@ -78,7 +216,27 @@ impl SkiaTextParagraph {
         text_style.set_font_size(params.font.size());
 
         pb.push_style(&text_style);
-        pb.add_text(&text);
+        let spans = match (&params.highlighter, params.mask) {
+            (Some(highlighter), None) => highlighter.highlight_line(rendered_text),
+            _ => Vec::new(),
+        };
+        let mut pos = 0;
+        for span in spans {
+            if span.start < pos || span.end > rendered_text.len()
+                || !text.is_char_boundary(span.start) || !text.is_char_boundary(span.end) {
+                continue;
+            }
+            pb.add_text(&text[pos..span.start]);
+            let mut span_style = text_style.clone();
+            let mut paint = params.paint.clone();
+            paint.set_color(span.color);
+            span_style.set_foreground_paint(&paint);
+            pb.push_style(&span_style);
+            pb.add_text(&text[span.start..span.end]);
+            pb.pop();
+            pos = span.end;
+        }
+        pb.add_text(&text[pos..]);
         pb.build()
     }
 }
\ No newline at end of file
@@ -1,28 +1,51 @@
 use std::cell::RefCell;
+use std::ops::Range;
 use std::rc::Rc;
 use skia_safe::{Font, Paint};
-use skia_safe::textlayout::TextAlign;
-use crate::element::text::{AtomOffset, ColOffset};
+use skia_safe::textlayout::{TextAlign, TextDirection};
+use crate::element::text::{AtomOffset, ColOffset, RowOffset};
+use crate::element::text::highlighter::SyntaxHighlighter;
 use crate::element::text::simple_text_paragraph::SimpleTextParagraph;
 use crate::element::text::skia_text_paragraph::SkiaTextParagraph;
 use crate::string::StringUtils;
 
+/// Text with more lines is laid out lazily
+const EAGER_LAYOUT_LINES: usize = 1000;
+
 #[derive(Clone)]
 pub struct ParagraphRef {
     pub data: Rc<RefCell<ParagraphData>>,
 }
 
 pub struct ParagraphData {
-    pub lines: Vec<Line>,
+    lines: Vec<Line>,
+    /// Atom and byte offsets of the start of each line, followed by the end of text
+    line_starts: Vec<(AtomOffset, usize)>,
     pub text_wrap: bool,
+    /// Max soft lines to display, lines beyond it are hidden
+    pub max_lines: Option<usize>,
+    /// Truncate text with ellipsis instead of clipping, also applies to single line of no-wrap text
+    pub ellipsis: bool,
 }
 
 pub struct Line {
-    /// Atom count; \r\n is treated as one atom
+    /// Atom count, an atom is an extended grapheme cluster, \r\n is treated as one atom
     pub atom_count: AtomOffset,
-    pub paragraph: SkiaTextParagraph,
+    text: String,
+    params: Rc<TextParams>,
+    /// Built on first access, so that off-screen lines of large text are cheap
+    paragraph: Option<SkiaTextParagraph>,
     // pub paragraph: SimpleTextParagraph,
     pub paragraph_dirty: bool,
+    /// Hidden by max lines
+    pub hidden: bool,
+    layout_width: f32,
+    max_lines: Option<usize>,
+    truncated: bool,
+    /// Height of laid out paragraph, estimated before laid out
+    height: f32,
+    /// Max intrinsic width of laid out paragraph, estimated before laid out
+    intrinsic_width: f32,
 }
 
 #[derive(Clone)]
@@ -31,48 +54,287 @@ pub struct TextParams {
     pub paint: Paint,
     pub line_height: Option<f32>,
     pub align: TextAlign,
+    /// Base direction of paragraphs
+    pub direction: TextDirection,
+    /// Render every char as mask, e.g. password
+    pub mask: Option<char>,
+    /// Text displayed at the end of truncated text, e.g. "…"
+    pub ellipsis: Option<String>,
+    pub word_break: WordBreak,
+    pub overflow_wrap: OverflowWrap,
+    /// Expand tab to spaces until next tab stop, None to render tab as is
+    pub tab_size: Option<usize>,
+    pub highlighter: Option<Rc<dyn SyntaxHighlighter>>,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum WordBreak {
+    /// Break at word boundaries, words longer than the line are broken anywhere
+    Normal,
+    /// Break between any two graphemes
+    BreakAll,
+    /// Never break between CJK chars, they are wrapped like words of other scripts
+    KeepAll,
+}
+
+impl WordBreak {
+    pub fn parse(str: &str) -> Self {
+        match str {
+            "break-all" => WordBreak::BreakAll,
+            "keep-all" => WordBreak::KeepAll,
+            _ => WordBreak::Normal,
+        }
+    }
+}
+
+/// How words longer than the line are handled
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum OverflowWrap {
+    /// Overlong words overflow the line
+    Normal,
+    /// Overlong words are broken between graphemes, the default to keep text inside its element
+    BreakWord,
+}
+
+impl OverflowWrap {
+    pub fn parse(str: &str) -> Self {
+        match str {
+            "normal" => OverflowWrap::Normal,
+            // "anywhere" only differs from "break-word" in min-content size, which is not measured
+            _ => OverflowWrap::BreakWord,
+        }
+    }
 }
 
 impl ParagraphData {
-    pub fn update_line(&mut self, line: Vec<Line>) {
-        self.lines = line;
-    }
-
-    pub fn get_line(&mut self, width: f32) -> &mut Vec<Line> {
-        self.lines.iter_mut().for_each(|it| {
-            if it.paragraph_dirty {
-                let layout_width = if self.text_wrap {
-                    width
-                } else {
-                    f32::NAN
-                };
-                it.paragraph.layout(layout_width);
-                it.paragraph_dirty = false;
+    pub fn new(lines: Vec<Line>) -> Self {
+        let mut data = Self {
+            lines: Vec::new(),
+            line_starts: vec![(0, 0)],
+            text_wrap: true,
+            max_lines: None,
+            ellipsis: false,
+        };
+        data.update_line(lines);
+        data
+    }
+
+    pub fn update_line(&mut self, lines: Vec<Line>) {
+        self.lines = Vec::new();
+        self.line_starts = vec![(0, 0)];
+        self.splice_lines(0..0, lines);
+    }
+
+    /// Replace lines in rows, the starts of the following lines are shifted
+    pub fn splice_lines(&mut self, rows: Range<RowOffset>, lines: Vec<Line>) {
+        let (old_end_atoms, old_end_bytes) = self.line_starts[rows.end];
+        let (mut atoms, mut bytes) = self.line_starts[rows.start];
+        let mut starts = Vec::with_capacity(lines.len());
+        for line in &lines {
+            starts.push((atoms, bytes));
+            atoms += line.atom_count;
+            bytes += line.text.len();
+        }
+        for (start_atoms, start_bytes) in &mut self.line_starts[rows.end..] {
+            *start_atoms = *start_atoms - old_end_atoms + atoms;
+            *start_bytes = *start_bytes - old_end_bytes + bytes;
+        }
+        self.line_starts.splice(rows.clone(), starts);
+        self.lines.splice(rows, lines);
+    }
+
+    /// Lines without laying out
+    pub fn lines(&self) -> &[Line] {
+        &self.lines
+    }
+
+    /// Mark all lines to be laid out again
+    pub fn invalidate_layout(&mut self) {
+        self.lines.iter_mut().for_each(|it| it.paragraph_dirty = true);
+    }
+
+    pub fn atom_count(&self) -> AtomOffset {
+        self.line_starts[self.lines.len()].0
+    }
+
+    pub fn byte_len(&self) -> usize {
+        self.line_starts[self.lines.len()].1
+    }
+
+    /// Row and column of atom offset, None if it's beyond the end of text
+    pub fn get_location(&self, atom_offset: AtomOffset) -> Option<(RowOffset, ColOffset)> {
+        let row = self.line_starts[1..].partition_point(|(atoms, _)| *atoms <= atom_offset);
+        let line = self.lines.get(row)?;
+        Some((row, line.get_column_by_atom_offset(atom_offset - self.line_starts[row].0)))
+    }
+
+    pub fn get_atom_offset(&self, (row, col): (RowOffset, ColOffset)) -> AtomOffset {
+        match self.lines.get(row) {
+            Some(line) => self.line_starts[row].0 + usize::min(col, line.atom_count - 1),
+            None => self.atom_count(),
+        }
+    }
+
+    pub fn get_line(&mut self, width: f32) -> &mut [Line] {
+        // no-wrap text with ellipsis is laid out with actual width and truncated to one line
+        let layout_width = if self.text_wrap || self.ellipsis {
+            width
+        } else {
+            f32::NAN
+        };
+        let line_max_lines = if self.text_wrap { None } else if self.ellipsis { Some(1) } else { None };
+        // lines of large text are laid out when accessed, e.g. painted
+        let eager = self.lines.len() <= EAGER_LAYOUT_LINES;
+        let mut remaining = self.max_lines;
+        let lines_count = self.lines.len();
+        for (idx, it) in self.lines.iter_mut().enumerate() {
+            if remaining == Some(0) {
+                it.hidden = true;
+                continue;
+            }
+            it.hidden = false;
+            it.set_layout_width(layout_width);
+            let max_lines = match (remaining, line_max_lines) {
+                (Some(r), Some(m)) => Some(usize::min(r, m)),
+                (r, m) => r.or(m),
+            };
+            it.set_clamp(max_lines, it.truncated);
+            if let Some(r) = remaining {
+                let mut soft_lines = it.paragraph().line_count();
+                // the last visible line is ended with ellipsis if following lines are hidden
+                let truncated = self.ellipsis && soft_lines >= r && idx + 1 < lines_count;
+                if truncated != it.truncated {
+                    it.set_clamp(max_lines, truncated);
+                    soft_lines = it.paragraph().line_count();
+                }
+                remaining = Some(r.saturating_sub(soft_lines));
+            } else if eager {
+                it.paragraph();
             }
-        });
+        }
 
         return &mut self.lines;
     }
 }
 
 impl Line {
-    pub fn get_caret_by_coord(&self, coord: (f32, f32)) -> usize {
-        let col = self.paragraph.get_char_offset_at_coordinate(coord);
+    pub fn new(text: String, params: Rc<TextParams>) -> Self {
+        let atom_count = text.trim_line_endings().graphemes_count() + 1;
+        let (intrinsic_width, _) = params.font.measure_str(text.trim_line_endings(), None);
+        let mut line = Self {
+            atom_count,
+            text,
+            params,
+            paragraph: None,
+            paragraph_dirty: true,
+            hidden: false,
+            layout_width: f32::NAN,
+            max_lines: None,
+            truncated: false,
+            height: 0.0,
+            intrinsic_width,
+        };
+        line.estimate_height();
+        line
+    }
+
+    /// Paragraph of the line, built and laid out if needed
+    pub fn paragraph(&mut self) -> &mut SkiaTextParagraph {
+        if self.paragraph.is_none() {
+            let mut paragraph = SkiaTextParagraph::new(self.text.clone(), &self.params);
+            paragraph.set_clamp(self.max_lines, self.truncated);
+            self.paragraph = Some(paragraph);
+            self.paragraph_dirty = true;
+        }
+        let paragraph = self.paragraph.as_mut().unwrap();
+        if self.paragraph_dirty {
+            paragraph.layout(self.layout_width);
+            if self.params.overflow_wrap == OverflowWrap::Normal && paragraph.min_intrinsic_width() > self.layout_width {
+                // skia always breaks overlong words, widen the layout so that they overflow instead
+                let min_width = paragraph.min_intrinsic_width();
+                paragraph.layout(min_width);
+            }
+            self.height = paragraph.height();
+            self.intrinsic_width = paragraph.max_intrinsic_width();
+            self.paragraph_dirty = false;
+        }
+        paragraph
+    }
+
+    pub fn is_laid_out(&self) -> bool {
+        self.paragraph.is_some() && !self.paragraph_dirty
+    }
+
+    pub fn set_layout_width(&mut self, layout_width: f32) {
+        let changed = if layout_width.is_nan() { !self.layout_width.is_nan() } else { layout_width != self.layout_width };
+        if changed {
+            self.layout_width = layout_width;
+            self.paragraph_dirty = true;
+            if self.paragraph.is_none() {
+                self.estimate_height();
+            }
+        }
+    }
+
+    fn set_clamp(&mut self, max_lines: Option<usize>, truncated: bool) {
+        if self.max_lines == max_lines && self.truncated == truncated {
+            return;
+        }
+        self.max_lines = max_lines;
+        self.truncated = truncated;
+        if let Some(p) = &mut self.paragraph {
+            if p.set_clamp(max_lines, truncated) {
+                self.paragraph_dirty = true;
+            }
+        }
+    }
+
+    fn estimate_height(&mut self) {
+        let line_height = match self.params.line_height {
+            Some(h) => h,
+            None => {
+                let (_, metrics) = self.params.font.metrics();
+                metrics.descent - metrics.ascent + metrics.leading
+            }
+        };
+        let soft_lines = if self.layout_width.is_nan() || self.layout_width <= 0.0 {
+            1.0
+        } else {
+            f32::max(1.0, (self.intrinsic_width / self.layout_width).ceil())
+        };
+        self.height = line_height * soft_lines;
+    }
+
+    /// Display height, zero if hidden, estimated if not laid out
+    pub fn height(&self) -> f32 {
+        if self.hidden {
+            0.0
+        } else {
+            self.height
+        }
+    }
+
+    /// Estimated if not laid out
+    pub fn max_intrinsic_width(&self) -> f32 {
+        self.intrinsic_width
+    }
+
+    pub fn get_caret_by_coord(&mut self, coord: (f32, f32)) -> usize {
+        let col = self.paragraph().get_char_offset_at_coordinate(coord);
         usize::min(col, self.atom_count - 1)
     }
     pub fn get_column_by_atom_offset(&self, atom_offset: AtomOffset) -> usize {
         AtomOffset::min(atom_offset, self.atom_count - 1)
     }
     pub fn subtext(&self, start: ColOffset, end: ColOffset) -> &str {
-        let text = self.paragraph.get_text();
-        text.substring(start, end - start)
+        self.text.grapheme_substring(start, end - start)
     }
 
     pub fn get_text(&self) -> &str {
-        self.paragraph.get_text()
+        &self.text
     }
 
-    pub fn get_soft_line_height(&self, char_offset: usize) -> f32 {
-        self.paragraph.get_soft_line_height(char_offset)
+    pub fn get_soft_line_height(&mut self, char_offset: usize) -> f32 {
+        self.paragraph().get_soft_line_height(char_offset)
     }
-}
\ No newline at end of file
+}
@@ -1,5 +1,6 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
+use accesskit::Role;
 use anyhow::Error;
 use quick_js::JsValue;
 use skia_safe::{Canvas, Color, Font, Paint};
@@ -10,8 +11,9 @@ use crate::color::parse_hex_color;
 use crate::element::container::Container;
 use crate::element::{ElementBackend, ElementRef};
 use crate::element::entry::Entry;
+use crate::element::text::search::SearchQuery;
 use crate::event::{TextUpdateEventBind};
-use crate::{create_element, js_call_rust, set_style, tree};
+use crate::{create_element, js_call, js_call_rust, set_style, tree};
 use crate::element::scroll::Scroll;
 
 pub struct TextEdit {
@@ -20,6 +22,7 @@ pub struct TextEdit {
     entry_element: ElementRef,
     base: Scroll,
     line_number_font: Rc<RefCell<Font>>,
+    line_numbers: Rc<Cell<bool>>,
 }
 
 impl TextEdit {
@@ -32,6 +35,13 @@ impl TextEdit {
         self.entry_element.get_backend_as::<Entry>()
     }
 
+    pub fn set_line_numbers(&mut self, line_numbers: bool) {
+        self.line_numbers.set(line_numbers);
+        let lines = self.get_entry().with_paragraph(|lines| lines.len());
+        update_gutter_width(&mut self.container_element, &self.line_number_font.borrow(), line_numbers, lines);
+        self.element.mark_dirty(false);
+    }
+
 }
 
 impl ElementBackend for TextEdit {
@@ -58,14 +68,13 @@ impl ElementBackend for TextEdit {
         ]);
         base.add_child_view(container_ele.clone(), None);
 
+        let line_numbers = Rc::new(Cell::new(true));
         let mut update_line_number_width = {
             let mut container_ele = container_ele.clone();
             let line_number_font = line_number_font.clone();
-            move |lines: i32| {
-                let line_number_width = line_number_font.borrow().size() * lines.to_string().len() as f32;
-                set_style!(container_ele, {
-                    paddingLeft => &line_number_width.to_string(),
-                });
+            let line_numbers = line_numbers.clone();
+            move |lines: usize| {
+                update_gutter_width(&mut container_ele, &line_number_font.borrow(), line_numbers.get(), lines);
             }
         };
         update_line_number_width(1);
@@ -86,6 +95,7 @@ impl ElementBackend for TextEdit {
             element,
             line_number_font,
             container_element: container_ele,
+            line_numbers,
         }
     }
 
@@ -93,12 +103,24 @@ impl ElementBackend for TextEdit {
         "TextEdit"
     }
 
+    fn search(&mut self, query: &SearchQuery) -> Result<(), Error> {
+        self.get_entry_mut().search(query)
+    }
+
     fn handle_style_changed(&mut self, key: &str) {
+        self.base.handle_style_changed(key);
         self.get_entry_mut().handle_style_changed(key);
     }
 
+    fn draw_foreground(&self, canvas: &Canvas) {
+        self.base.draw_foreground(canvas);
+    }
+
     fn draw(&self, canvas: &Canvas) {
         self.base.draw(canvas);
+        if !self.line_numbers.get() {
+            return;
+        }
         canvas.save();
         // canvas.translate((0.0, -self.element.get_scroll_top()));
         let mut paint = Paint::default();
@@ -112,7 +134,7 @@ impl ElementBackend for TextEdit {
             let height = self.element.get_size().1;
             let mut line_number = 0;
             for p in lines {
-                let p_height = p.paragraph.height();
+                let p_height = p.height();
                 let y_start = line_start;
                 let y_end = y_start + p_height;
                 line_start += p_height;
@@ -133,6 +155,7 @@ impl ElementBackend for TextEdit {
     fn set_property(&mut self, property_name: &str, property_value: JsValue) {
         let base = &mut self.base;
         js_call_rust!("scroll_to_top", f32, base, scroll_to_top, property_name, property_value);
+        js_call!("linenumbers", bool, self, set_line_numbers, property_name, property_value);
         self.get_entry_mut().set_property(property_name, property_value);
     }
 
@@ -160,6 +183,10 @@ impl ElementBackend for TextEdit {
         self.base.get_children()
     }
 
+    fn accepts_children(&self) -> bool {
+        true
+    }
+
     fn remove_child_view(&mut self, position: u32) {
         self.base.remove_child_view(position)
     }
@@ -167,6 +194,19 @@ impl ElementBackend for TextEdit {
     fn handle_event_default_behavior(&mut self,event_type: &str, event: &mut ElementEvent) -> bool {
         self.base.handle_event_default_behavior(event_type, event)
     }
-}
 
+    fn get_accessibility_role(&self) -> Role {
+        Role::ScrollView
+    }
+}
 
+fn update_gutter_width(container: &mut ElementRef, font: &Font, line_numbers: bool, lines: usize) {
+    let width = if line_numbers {
+        font.size() * lines.to_string().len() as f32
+    } else {
+        0.0
+    };
+    set_style!(container, {
+        paddingLeft => &width.to_string(),
+    });
+}
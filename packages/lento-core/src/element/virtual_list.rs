@@ -0,0 +1,622 @@
+use std::collections::HashMap;
+
+use accesskit::{NodeBuilder, Role};
+use anyhow::Error;
+use quick_js::JsValue;
+use serde::{Deserialize, Serialize};
+use skia_safe::Canvas;
+
+use crate::base::{ElementEvent, Rect};
+use crate::element::container::Container;
+use crate::element::scroll::Scroll;
+use crate::element::{ElementBackend, ElementRef};
+use crate::{create_element, js_call, js_call_rust, set_style};
+
+const DEFAULT_ESTIMATED_ITEM_HEIGHT: f32 = 30.0;
+
+const DEFAULT_OVERSCAN: f32 = 200.0;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ItemsChange {
+    pub index: usize,
+    pub count: usize,
+}
+
+/// Detail of `rangechange` event, items in [start, end) should be bound
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct RangeChangeDetail {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Heights and offsets of items, unmeasured items use the estimated height
+struct ItemSizes {
+    fixed_height: Option<f32>,
+    estimated_height: f32,
+    measured: Vec<Option<f32>>,
+    /// offsets[i] is the top of item i, offsets[len] is the total height
+    offsets: Vec<f32>,
+}
+
+impl ItemSizes {
+    fn new() -> Self {
+        Self {
+            fixed_height: None,
+            estimated_height: DEFAULT_ESTIMATED_ITEM_HEIGHT,
+            measured: Vec::new(),
+            offsets: vec![0.0],
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.measured.len()
+    }
+
+    fn item_height(&self, index: usize) -> f32 {
+        match self.fixed_height {
+            Some(h) => h,
+            None => self.measured[index].unwrap_or(self.estimated_height),
+        }
+    }
+
+    fn offset(&self, index: usize) -> f32 {
+        self.offsets[index.min(self.len())]
+    }
+
+    fn total_height(&self) -> f32 {
+        self.offsets[self.len()]
+    }
+
+    /// Index of the item containing y, clamped to the last item
+    fn index_at(&self, y: f32) -> usize {
+        let len = self.len();
+        if len == 0 {
+            return 0;
+        }
+        let pos = self.offsets[1..].partition_point(|bottom| *bottom <= y);
+        pos.min(len - 1)
+    }
+
+    fn rebuild_offsets(&mut self) {
+        let mut offsets = Vec::with_capacity(self.len() + 1);
+        let mut top = 0.0;
+        offsets.push(top);
+        for i in 0..self.len() {
+            top += self.item_height(i);
+            offsets.push(top);
+        }
+        self.offsets = offsets;
+    }
+
+    fn set_len(&mut self, len: usize) {
+        self.measured.resize(len, None);
+        self.rebuild_offsets();
+    }
+
+    fn insert(&mut self, index: usize, count: usize) {
+        let index = index.min(self.len());
+        self.measured.splice(index..index, std::iter::repeat(None).take(count));
+        self.rebuild_offsets();
+    }
+
+    fn remove(&mut self, index: usize, count: usize) {
+        let start = index.min(self.len());
+        let end = (index + count).min(self.len());
+        self.measured.drain(start..end);
+        self.rebuild_offsets();
+    }
+
+    /// Return true if the height is changed
+    fn set_measured(&mut self, index: usize, height: f32) -> bool {
+        if self.fixed_height.is_some() || self.measured[index] == Some(height) {
+            return false;
+        }
+        self.measured[index] = Some(height);
+        true
+    }
+}
+
+/// Scroll view which only keeps views of visible items.
+///
+/// Views are provided by js for each index in the range of `rangechange` event,
+/// `add_child_view(view, Some(index))` binds a view to an item and `remove_child_view(index)` unbinds it.
+/// Each bound view is wrapped in a positioned slot so that the style of the view is kept.
+pub struct VirtualList {
+    element: ElementRef,
+    base: Scroll,
+    content: ElementRef,
+    sizes: ItemSizes,
+    overscan: f32,
+    /// slots of bound items, keyed by item index
+    slots: HashMap<usize, ElementRef>,
+    /// detached slots for reusing
+    recycled_slots: Vec<ElementRef>,
+    range: RangeChangeDetail,
+    /// Scroll offset to restore after the content is laid out
+    pending_scroll_top: Option<f32>,
+}
+
+impl VirtualList {
+    pub fn set_item_count(&mut self, count: usize) {
+        let anchor = self.get_anchor();
+        let removed: Vec<usize> = self.slots.keys().filter(|i| **i >= count).cloned().collect();
+        for index in removed {
+            self.release_slot(index);
+        }
+        self.sizes.set_len(count);
+        self.restore_anchor(anchor);
+        self.update_layout(true);
+    }
+
+    pub fn get_item_count(&self) -> usize {
+        self.sizes.len()
+    }
+
+    /// Insert items before index, the visible items keep their position if the index is above them
+    pub fn insert_items(&mut self, change: ItemsChange) {
+        let ItemsChange { index, count } = change;
+        let (mut anchor, delta) = self.get_anchor();
+        if index <= anchor && self.element.get_scroll_top() > 0.0 {
+            anchor += count;
+        }
+        self.shift_slots(index, count as isize);
+        self.sizes.insert(index, count);
+        self.restore_anchor((anchor, delta));
+        self.update_layout(true);
+    }
+
+    pub fn remove_items(&mut self, change: ItemsChange) {
+        let ItemsChange { index, count } = change;
+        let (mut anchor, mut delta) = self.get_anchor();
+        if anchor >= index + count {
+            anchor -= count;
+        } else if anchor >= index {
+            anchor = index;
+            delta = 0.0;
+        }
+        for i in index..index + count {
+            self.release_slot(i);
+        }
+        self.shift_slots(index + count, -(count as isize));
+        self.sizes.remove(index, count);
+        self.restore_anchor((anchor, delta));
+        self.update_layout(true);
+    }
+
+    /// Use fixed height for all items, zero or negative value means items are measured
+    pub fn set_item_height(&mut self, height: f32) {
+        self.sizes.fixed_height = if height > 0.0 { Some(height) } else { None };
+        self.sizes.rebuild_offsets();
+        self.update_layout(true);
+    }
+
+    /// Height of items not measured yet
+    pub fn set_estimated_item_height(&mut self, height: f32) {
+        if height > 0.0 {
+            self.sizes.estimated_height = height;
+            self.sizes.rebuild_offsets();
+            self.update_layout(true);
+        }
+    }
+
+    /// Extra distance above and below the viewport in which items are bound
+    pub fn set_overscan(&mut self, overscan: f32) {
+        self.overscan = overscan.max(0.0);
+        self.update_range(false);
+    }
+
+    pub fn scroll_to_index(&mut self, index: usize) {
+        let top = self.sizes.offset(index);
+        self.element.set_scroll_top(top);
+    }
+
+    pub fn get_range(&self) -> RangeChangeDetail {
+        self.range
+    }
+
+    /// The first visible item and the distance from its top to the scroll offset
+    fn get_anchor(&self) -> (usize, f32) {
+        let scroll_top = self.pending_scroll_top.unwrap_or(self.element.get_scroll_top());
+        let anchor = self.sizes.index_at(scroll_top);
+        (anchor, scroll_top - self.sizes.offset(anchor))
+    }
+
+    fn restore_anchor(&mut self, (anchor, delta): (usize, f32)) {
+        let scroll_top = self.sizes.offset(anchor) + delta;
+        self.pending_scroll_top = if scroll_top != self.element.get_scroll_top() {
+            Some(scroll_top)
+        } else {
+            None
+        };
+    }
+
+    fn shift_slots(&mut self, from: usize, distance: isize) {
+        let moved: Vec<usize> = self.slots.keys().filter(|i| **i >= from).cloned().collect();
+        let mut moved_slots = Vec::with_capacity(moved.len());
+        for index in moved {
+            let slot = self.slots.remove(&index).unwrap();
+            moved_slots.push(((index as isize + distance) as usize, slot));
+        }
+        self.slots.extend(moved_slots);
+    }
+
+    fn acquire_slot(&mut self, index: usize) -> ElementRef {
+        if let Some(slot) = self.slots.get(&index) {
+            return slot.clone();
+        }
+        let slot = match self.recycled_slots.pop() {
+            Some(s) => s,
+            None => create_element!(Container, {}),
+        };
+        self.content.add_child_view(slot.clone(), None);
+        self.slots.insert(index, slot.clone());
+        self.position_slot(index);
+        slot
+    }
+
+    fn release_slot(&mut self, index: usize) {
+        if let Some(mut slot) = self.slots.remove(&index) {
+            if !slot.get_children().is_empty() {
+                slot.remove_child_view(0);
+            }
+            if let Some(pos) = self.content.get_children().iter().position(|c| c == &slot) {
+                self.content.remove_child_view(pos as u32);
+            }
+            self.recycled_slots.push(slot);
+        }
+    }
+
+    fn position_slot(&mut self, index: usize) {
+        let top = self.sizes.offset(index).to_string();
+        let slot = self.slots.get_mut(&index).unwrap();
+        match self.sizes.fixed_height {
+            Some(h) => {
+                set_style!(slot, {
+                    position => "absolute",
+                    left => "0",
+                    right => "0",
+                    top => &top,
+                    height => &h.to_string(),
+                });
+            }
+            None => {
+                set_style!(slot, {
+                    position => "absolute",
+                    left => "0",
+                    right => "0",
+                    top => &top,
+                });
+            }
+        }
+    }
+
+    fn update_layout(&mut self, force_range_event: bool) {
+        let height = self.sizes.total_height().to_string();
+        set_style!(self.content, {
+            height => &height,
+        });
+        let indexes: Vec<usize> = self.slots.keys().cloned().collect();
+        for index in indexes {
+            self.position_slot(index);
+        }
+        self.element.mark_dirty(true);
+        self.update_range(force_range_event);
+    }
+
+    /// Update the range of bound items and notify js if it's changed
+    fn update_range(&mut self, force: bool) {
+        let range = if self.sizes.len() == 0 {
+            RangeChangeDetail { start: 0, end: 0 }
+        } else {
+            let scroll_top = self.pending_scroll_top.unwrap_or(self.element.get_scroll_top());
+            let viewport_height = self.element.get_content_bounds().height;
+            let start = self.sizes.index_at(scroll_top - self.overscan);
+            let end = self.sizes.index_at(scroll_top + viewport_height + self.overscan) + 1;
+            RangeChangeDetail { start, end }
+        };
+        if !force && range == self.range {
+            return;
+        }
+        self.range = range;
+        let event = ElementEvent::new("rangechange", range, self.element.clone());
+        self.element.emit_event("rangechange", event);
+    }
+
+    /// Measure heights of bound items, return true if any height is changed
+    fn measure_items(&mut self) -> bool {
+        if self.sizes.fixed_height.is_some() {
+            return false;
+        }
+        let mut changed = false;
+        for (index, slot) in &self.slots {
+            if slot.get_children().is_empty() || *index >= self.sizes.len() {
+                continue;
+            }
+            let (_, height) = slot.get_size();
+            changed |= self.sizes.set_measured(*index, height);
+        }
+        changed
+    }
+}
+
+impl ElementBackend for VirtualList {
+    fn create(element: ElementRef) -> Self {
+        let mut base = Scroll::create(element.clone());
+        let content = create_element!(Container, {});
+        base.add_child_view(content.clone(), None);
+        Self {
+            element,
+            base,
+            content,
+            sizes: ItemSizes::new(),
+            overscan: DEFAULT_OVERSCAN,
+            slots: HashMap::new(),
+            recycled_slots: Vec::new(),
+            range: RangeChangeDetail { start: 0, end: 0 },
+            pending_scroll_top: None,
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        "VirtualList"
+    }
+
+    fn draw(&self, canvas: &Canvas) {
+        self.base.draw(canvas);
+    }
+
+    fn draw_foreground(&self, canvas: &Canvas) {
+        self.base.draw_foreground(canvas);
+    }
+
+    fn handle_style_changed(&mut self, key: &str) {
+        self.base.handle_style_changed(key);
+    }
+
+    fn set_property(&mut self, p: &str, v: JsValue) {
+        js_call!("itemcount", usize, self, set_item_count, p, v);
+        js_call!("itemheight", f32, self, set_item_height, p, v);
+        js_call!("estimateditemheight", f32, self, set_estimated_item_height, p, v);
+        js_call!("overscan", f32, self, set_overscan, p, v);
+        js_call!("scrolltoindex", usize, self, scroll_to_index, p, v);
+        js_call_rust!("insertitems", ItemsChange, self, insert_items, p, v);
+        js_call_rust!("removeitems", ItemsChange, self, remove_items, p, v);
+        self.base.set_property(p, v);
+    }
+
+    fn get_property(&mut self, property_name: &str) -> Result<Option<JsValue>, Error> {
+        match property_name {
+            "itemcount" => Ok(Some(JsValue::Int(self.get_item_count() as i32))),
+            "range" => {
+                let range = self.get_range();
+                Ok(Some(JsValue::Array(vec![JsValue::Int(range.start as i32), JsValue::Int(range.end as i32)])))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn handle_origin_bounds_change(&mut self, bounds: &Rect) {
+        self.base.handle_origin_bounds_change(bounds);
+        if self.measure_items() {
+            let anchor = self.get_anchor();
+            self.sizes.rebuild_offsets();
+            self.restore_anchor(anchor);
+            self.update_layout(false);
+            return;
+        }
+        if let Some(scroll_top) = self.pending_scroll_top.take() {
+            self.element.set_scroll_top(scroll_top);
+        }
+        self.update_range(false);
+    }
+
+    fn handle_event(&mut self, event_type: &str, event: &mut ElementEvent) {
+        if event_type == "scroll" && event.context.target == self.element {
+            self.update_range(false);
+        }
+    }
+
+    fn add_child_view(&mut self, child: ElementRef, position: Option<u32>) {
+        let Some(index) = position else {
+            return;
+        };
+        let index = index as usize;
+        if index >= self.sizes.len() {
+            return;
+        }
+        let mut slot = self.acquire_slot(index);
+        if !slot.get_children().is_empty() {
+            slot.remove_child_view(0);
+        }
+        slot.add_child_view(child, None);
+    }
+
+    fn remove_child_view(&mut self, position: u32) {
+        self.release_slot(position as usize);
+    }
+
+    fn get_children(&self) -> Vec<ElementRef> {
+        self.base.get_children()
+    }
+
+    fn handle_event_default_behavior(&mut self, event_type: &str, event: &mut ElementEvent) -> bool {
+        self.base.handle_event_default_behavior(event_type, event)
+    }
+
+    fn get_accessibility_role(&self) -> Role {
+        Role::List
+    }
+
+    fn build_accessibility_node(&self, builder: &mut NodeBuilder) {
+        self.base.build_accessibility_node(builder);
+    }
+}
+
+#[test]
+fn test_item_sizes() {
+    let mut sizes = ItemSizes::new();
+    sizes.estimated_height = 10.0;
+    sizes.set_len(100);
+    assert_eq!(1000.0, sizes.total_height());
+    assert_eq!(0, sizes.index_at(-5.0));
+    assert_eq!(2, sizes.index_at(25.0));
+    assert_eq!(3, sizes.index_at(30.0));
+    assert_eq!(99, sizes.index_at(5000.0));
+
+    assert!(sizes.set_measured(0, 50.0));
+    assert!(!sizes.set_measured(0, 50.0));
+    sizes.rebuild_offsets();
+    assert_eq!(60.0, sizes.offset(2));
+
+    sizes.insert(0, 2);
+    assert_eq!(102, sizes.len());
+    assert_eq!(70.0, sizes.offset(3));
+    sizes.remove(0, 3);
+    assert_eq!(99, sizes.len());
+    assert_eq!(990.0, sizes.total_height());
+
+    sizes.fixed_height = Some(20.0);
+    sizes.rebuild_offsets();
+    assert_eq!(40.0, sizes.offset(2));
+}
+
+#[cfg(test)]
+fn create_test_list(item_count: usize) -> ElementRef {
+    use crate::style::StyleProp;
+    let mut list = ElementRef::new(VirtualList::create);
+    list.set_style_props(vec![
+        StyleProp::parse("width", "100").unwrap(),
+        StyleProp::parse("height", "100").unwrap(),
+    ]);
+    list.calculate_layout(100.0, 100.0);
+    let vl = list.get_backend_mut_as::<VirtualList>();
+    vl.set_item_height(10.0);
+    vl.set_overscan(0.0);
+    vl.set_item_count(item_count);
+    list.calculate_layout(100.0, 100.0);
+    list
+}
+
+#[cfg(test)]
+fn bind_range(list: &mut ElementRef) -> Vec<ElementRef> {
+    let range = list.get_backend_as::<VirtualList>().get_range();
+    let mut views = Vec::new();
+    for index in range.start..range.end {
+        let view = ElementRef::new(Container::create);
+        list.add_child_view(view.clone(), Some(index as u32));
+        views.push(view);
+    }
+    list.calculate_layout(100.0, 100.0);
+    views
+}
+
+#[test]
+fn test_range_change() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use crate::event_loop::run_pending_callbacks;
+
+    let mut list = create_test_list(100);
+    let ranges = Rc::new(RefCell::new(Vec::new()));
+    {
+        let ranges = ranges.clone();
+        list.bind_event_listener("rangechange", move |_, d: &mut RangeChangeDetail| {
+            ranges.borrow_mut().push(*d);
+        });
+    }
+    run_pending_callbacks();
+    ranges.borrow_mut().clear();
+    // 10 items are visible, the item at the bottom edge is included
+    assert_eq!(RangeChangeDetail { start: 0, end: 11 }, list.get_backend_as::<VirtualList>().get_range());
+
+    list.set_scroll_top(200.0);
+    run_pending_callbacks();
+    assert_eq!(vec![RangeChangeDetail { start: 20, end: 31 }], *ranges.borrow());
+
+    // scrolling inside the same range doesn't notify
+    list.set_scroll_top(201.0);
+    run_pending_callbacks();
+    assert_eq!(1, ranges.borrow().len());
+
+    list.get_backend_mut_as::<VirtualList>().set_overscan(50.0);
+    run_pending_callbacks();
+    assert_eq!(RangeChangeDetail { start: 15, end: 36 }, ranges.borrow()[1]);
+
+    list.get_backend_mut_as::<VirtualList>().set_item_count(0);
+    run_pending_callbacks();
+    assert_eq!(RangeChangeDetail { start: 0, end: 0 }, ranges.borrow()[2]);
+}
+
+#[test]
+fn test_insert_items_above_viewport() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use crate::event_loop::run_pending_callbacks;
+
+    let mut list = create_test_list(100);
+    list.set_scroll_top(200.0);
+    run_pending_callbacks();
+    let views = bind_range(&mut list);
+    assert_eq!(0.0, views[0].get_origin_bounds().y);
+
+    let ranges = Rc::new(RefCell::new(Vec::new()));
+    {
+        let ranges = ranges.clone();
+        list.bind_event_listener("rangechange", move |_, d: &mut RangeChangeDetail| {
+            ranges.borrow_mut().push(*d);
+        });
+    }
+    list.get_backend_mut_as::<VirtualList>().insert_items(ItemsChange { index: 0, count: 5 });
+    run_pending_callbacks();
+    // bound items are shifted and the range follows them
+    assert_eq!(vec![RangeChangeDetail { start: 25, end: 36 }], *ranges.borrow());
+    assert!(list.get_backend_as::<VirtualList>().slots.get(&25) == views[0].get_parent().as_ref());
+
+    // the scroll offset is restored after layout, so the visible items don't move
+    list.calculate_layout(100.0, 100.0);
+    run_pending_callbacks();
+    assert_eq!(250.0, list.get_scroll_top());
+    assert_eq!(0.0, views[0].get_origin_bounds().y);
+    assert_eq!(1, ranges.borrow().len());
+
+    // inserting below the viewport keeps the scroll offset
+    list.get_backend_mut_as::<VirtualList>().insert_items(ItemsChange { index: 90, count: 5 });
+    list.calculate_layout(100.0, 100.0);
+    run_pending_callbacks();
+    assert_eq!(250.0, list.get_scroll_top());
+    assert_eq!(0.0, views[0].get_origin_bounds().y);
+}
+
+#[test]
+fn test_slot_recycling() {
+    let mut list = create_test_list(100);
+    let views = bind_range(&mut list);
+    let content = list.get_backend_as::<VirtualList>().content.clone();
+    assert_eq!(11, content.get_children().len());
+
+    // unbinding detaches the slot and keeps it for reusing
+    let slot = views[0].get_parent().unwrap();
+    list.remove_child_view(0);
+    assert!(views[0].get_parent().is_none());
+    assert!(slot.get_children().is_empty());
+    assert_eq!(10, content.get_children().len());
+    assert_eq!(1, list.get_backend_as::<VirtualList>().recycled_slots.len());
+
+    let view = ElementRef::new(Container::create);
+    list.add_child_view(view.clone(), Some(11));
+    assert!(view.get_parent() == Some(slot));
+    assert_eq!(11, content.get_children().len());
+    assert!(list.get_backend_as::<VirtualList>().recycled_slots.is_empty());
+
+    // binding another view to a bound item replaces the view in the same slot
+    let slot = views[1].get_parent().unwrap();
+    let view = ElementRef::new(Container::create);
+    list.add_child_view(view.clone(), Some(1));
+    assert!(view.get_parent() == Some(slot.clone()));
+    assert_eq!(1, slot.get_children().len());
+
+    // removed items release their slots
+    list.get_backend_mut_as::<VirtualList>().set_item_count(5);
+    assert_eq!(4, content.get_children().len());
+    assert_eq!(7, list.get_backend_as::<VirtualList>().recycled_slots.len());
+}
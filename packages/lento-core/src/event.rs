@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
 use winit::keyboard::{ModifiersState, NamedKey};
-use crate::base::{CaretDetail, MouseDetail, ScrollEventDetail, TextChangeDetail, TextUpdateDetail, TouchDetail};
+use crate::base::{BeforeInputDetail, CaretDetail, CheckedChangeDetail, MouseDetail, ScrollEventDetail, SelectionChangeDetail, TextChangeDetail, TextUpdateDetail, TouchDetail, ValueChangeDetail};
+use crate::data_transfer::DataTransfer;
 use crate::define_event;
+use crate::element::scroll_animation::LINE_SCROLL_DISTANCE;
 
 pub const KEY_MOD_CTRL: u32 = 0x1;
 pub const KEY_MOD_ALT: u32 = 0x1 << 1;
@@ -366,22 +368,31 @@ pub fn named_key_to_str(key: &NamedKey) -> &'static str {
     )
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
-pub struct DragStartEventDetail {
-
+pub struct DragEventDetail {
+    /// The offset in the X coordinate of the mouse pointer between that event and the padding edge of the target node.
+    pub offset_x: f32,
+    ///  The offset in the Y coordinate of the mouse pointer between that event and the padding edge of the target node.
+    pub offset_y: f32,
+    /// x-axis relative to frame(as clientX in web)
+    pub frame_x: f32,
+    /// y-axis relative to frame(as clientY in web)
+    pub frame_y: f32,
+    pub screen_x: f32,
+    pub screen_y: f32,
+    pub data_transfer: DataTransfer,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct DragOverEventDetail {
-
-}
+pub type DragStartEventDetail = DragEventDetail;
+pub type DragOverEventDetail = DragEventDetail;
+pub type DropEventDetail = DragEventDetail;
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct DropEventDetail {
-
+pub struct CompositionEventDetail {
+    /// composing text in compositionupdate, committed text in compositionend
+    pub data: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -389,6 +400,34 @@ pub struct DropEventDetail {
 pub struct MouseWheelDetail {
     pub cols: f32,
     pub rows: f32,
+    /// Distance in pixels, in the same direction as `cols`
+    pub delta_x: f32,
+    /// Distance in pixels, in the same direction as `rows`
+    pub delta_y: f32,
+    /// Whether the delta is reported in pixels, e.g. by touchpads
+    pub precise: bool,
+}
+
+impl MouseWheelDetail {
+    pub fn from_lines(cols: f32, rows: f32) -> Self {
+        Self {
+            cols,
+            rows,
+            delta_x: cols * LINE_SCROLL_DISTANCE,
+            delta_y: rows * LINE_SCROLL_DISTANCE,
+            precise: false,
+        }
+    }
+
+    pub fn from_pixels(delta_x: f32, delta_y: f32) -> Self {
+        Self {
+            cols: delta_x / LINE_SCROLL_DISTANCE,
+            rows: delta_y / LINE_SCROLL_DISTANCE,
+            delta_x,
+            delta_y,
+            precise: true,
+        }
+    }
 }
 
 define_event!(CaretEvent,       CaretEventBind,       "caretchange", bind_caret_change, emit_caret_change, AcceptCaretEvent,       accept_caret_change, CaretDetail);
@@ -398,6 +437,7 @@ define_event!(MouseClickEvent,  ClickEventBind,       "click",       bind_click,
 define_event!(MouseMoveEvent,   MouseMoveEventBind,   "mousemove",   bind_mouse_move,   emit_mouse_move,   AcceptMouseMoveEvent,   accept_mouse_move,   MouseDetail);
 define_event!(MouseEnterEvent,  MouseEnterEventBind,  "mouseenter",  bind_mouse_enter,  emit_mouse_enter,  AcceptMouseEnterEvent,  accept_mouse_enter,  MouseDetail);
 define_event!(MouseLeaveEvent,  MouseLeaveEventBind,  "mouseleave",  bind_mouse_leave,  emit_mouse_leave,  AcceptMouseLeaveEvent,  accept_mouse_leave,  MouseDetail);
+define_event!(ContextMenuEvent, ContextMenuEventBind, "contextmenu", bind_context_menu, emit_context_menu, AcceptContextMenuEvent, accept_context_menu, MouseDetail);
 define_event!(KeyDownEvent,     KeyDownEventBind,     "keydown",     bind_key_down,     emit_key_down,     AcceptKeyDownEvent,     accept_key_down,     KeyEventDetail);
 define_event!(KeyUpEvent,       KeyUpEventBind,       "keyup",       bind_key_up,       emit_key_up,       AcceptKeyUpEvent,       accept_key_up,       KeyEventDetail);
 define_event!(MouseWheelEvent,  MouseWheelEventBind,  "mousewheel",  bind_mouse_wheel,  emit_mouse_wheel,  AcceptMouseWheelEvent,  accept_mouse_wheel,  MouseWheelDetail);
@@ -409,9 +449,19 @@ define_event!(TouchCancelEvent, TouchCancelEventBind, "touchcancel", bind_touch_
 define_event!(FocusEvent,       FocusEventBind,       "focus",       bind_focus,        emit_focus,        AcceptFocusEvent,       accept_focus,        ());
 define_event!(BlurEvent,        BlurEventBind,        "blur",        bind_blur,         emit_blur,         AcceptBlurEvent,        accept_blur,         ());
 define_event!(FocusShiftEvent,  FocusShiftBind,       "focusshift",  bind_focus_shift,  emit_focus_shift,  AcceptFocusShiftEvent,  accept_focus_shift,  ());
+define_event!(BeforeInputEvent, BeforeInputBind,      "beforeinput", bind_before_input, emit_before_input, AcceptBeforeInput,      accept_before_input, BeforeInputDetail);
 define_event!(TextChangeEvent,  TextChangeBind,       "textchange",  bind_text_change,  emit_text_change,  AcceptTextChange,       accept_text_change,  TextChangeDetail);
 define_event!(ScrollEvent,      ScrollBind,           "scroll",      bind_scroll,       emit_scroll,       AcceptScroll,           accept_scroll,       ScrollEventDetail);
 define_event!(DragStartEvent,   DragStartBind,        "dragstart",   bind_drag_start,   emit_drag_start,   AccpetDragStart,        accept_drag_start,   DragStartEventDetail);
 define_event!(DragOverEvent,    DragOverBind,         "dragover",    bind_drag_over,    emit_drag_over,    AccpetDragOver,         accept_drag_over,    DragOverEventDetail);
 define_event!(DropEvent,        DropBind,             "drop",        bind_drop,         emit_drop,         AccpetDrop,             accept_drop,         DropEventDetail);
+define_event!(DragEnterEvent,   DragEnterBind,        "dragenter",   bind_drag_enter,   emit_drag_enter,   AcceptDragEnter,        accept_drag_enter,   DragEventDetail);
+define_event!(DragLeaveEvent,   DragLeaveBind,        "dragleave",   bind_drag_leave,   emit_drag_leave,   AcceptDragLeave,        accept_drag_leave,   DragEventDetail);
+define_event!(CompositionStartEvent,  CompositionStartBind,  "compositionstart",  bind_composition_start,  emit_composition_start,  AcceptCompositionStart,  accept_composition_start,  CompositionEventDetail);
+define_event!(CompositionUpdateEvent, CompositionUpdateBind, "compositionupdate", bind_composition_update, emit_composition_update, AcceptCompositionUpdate, accept_composition_update, CompositionEventDetail);
+define_event!(CompositionEndEvent,    CompositionEndBind,    "compositionend",    bind_composition_end,    emit_composition_end,    AcceptCompositionEnd,    accept_composition_end,    CompositionEventDetail);
+define_event!(DragEndEvent,     DragEndBind,          "dragend",     bind_drag_end,     emit_drag_end,     AcceptDragEnd,          accept_drag_end,     DragEventDetail);
+define_event!(CheckedChangeEvent,   CheckedChangeBind,   "change", bind_checked_change,   emit_checked_change,   AcceptCheckedChange,   accept_checked_change,   CheckedChangeDetail);
+define_event!(ValueChangeEvent,     ValueChangeBind,     "change", bind_value_change,     emit_value_change,     AcceptValueChange,     accept_value_change,     ValueChangeDetail);
+define_event!(SelectionChangeEvent, SelectionChangeBind, "change", bind_selection_change, emit_selection_change, AcceptSelectionChange, accept_selection_change, SelectionChangeDetail);
 
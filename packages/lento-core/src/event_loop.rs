@@ -14,6 +14,12 @@ thread_local! {
     pub static ACTIVE_EVENT_LOOP: Cell<*const ActiveEventLoop> = Cell::new(null_mut());
 }
 
+#[cfg(test)]
+thread_local! {
+    /// Callbacks sent in tests, which have no event loop
+    static PENDING_CALLBACKS: std::cell::RefCell<Vec<Box<dyn FnOnce() + Send + Sync>>> = std::cell::RefCell::new(Vec::new());
+}
+
 unsafe impl Sync for EventLoopProxyHolder {}
 unsafe impl Send for EventLoopProxyHolder {}
 
@@ -91,8 +97,7 @@ pub fn schedule_macro_task<F: FnOnce() + Sync + Send + 'static>(callback: F) {
 }
 
 pub fn run_on_event_loop<F: FnOnce() + 'static + Send + Sync>(callback: F) {
-    let proxy = get_event_proxy();
-    proxy.send_event(AppEvent::Callback(Box::new(callback))).unwrap();
+    send_event(AppEvent::Callback(Box::new(callback))).unwrap();
 }
 
 pub fn set_event_proxy(proxy: EventLoopProxy<AppEvent>) {
@@ -102,10 +107,25 @@ pub fn set_event_proxy(proxy: EventLoopProxy<AppEvent>) {
 }
 
 pub fn send_event(event: AppEvent) -> Result<(), EventLoopClosed<AppEvent>> {
+    #[cfg(test)]
+    if get_event_loop_proxy_internal().event_loop_proxy.is_none() {
+        if let AppEvent::Callback(callback) = event {
+            PENDING_CALLBACKS.with_borrow_mut(|c| c.push(callback));
+        }
+        return Ok(());
+    }
     let proxy = get_event_proxy();
     proxy.send_event(event)
 }
 
+/// Run callbacks sent without an event loop, including the ones sent by them
+#[cfg(test)]
+pub fn run_pending_callbacks() {
+    while let Some(callback) = PENDING_CALLBACKS.with_borrow_mut(|c| (!c.is_empty()).then(|| c.remove(0))) {
+        callback();
+    }
+}
+
 pub fn run_event_loop_task<F: FnOnce()>(event_loop: &ActiveEventLoop, callback: F) {
     ACTIVE_EVENT_LOOP.set(event_loop as *const ActiveEventLoop);
     callback();
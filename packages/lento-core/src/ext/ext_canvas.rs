@@ -0,0 +1,48 @@
+use anyhow::{anyhow, Error};
+use quick_js::JsValue;
+use serde::{Deserialize, Serialize};
+
+use crate::element::canvas::CanvasElement;
+use crate::element::ElementRef;
+
+#[derive(Deserialize)]
+pub struct ImageDataRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+#[derive(Serialize)]
+pub struct ImageData {
+    pub width: i32,
+    pub height: i32,
+    /// RGBA8 pixels
+    pub data: Vec<u8>,
+}
+
+fn with_canvas<R, F: FnOnce(&mut CanvasElement) -> R>(mut element: ElementRef, callback: F) -> Result<R, Error> {
+    if element.get_backend().get_name() != "Canvas" {
+        return Err(anyhow!("not a canvas"));
+    }
+    Ok(callback(element.get_backend_mut_as::<CanvasElement>()))
+}
+
+pub fn canvas_draw(element: ElementRef, commands: JsValue) -> Result<(), Error> {
+    with_canvas(element, |c| c.draw_commands(commands))?
+}
+
+pub fn canvas_get_image_data(element: ElementRef, rect: ImageDataRect) -> Result<ImageData, Error> {
+    with_canvas(element, |c| {
+        let data = c.get_context().read_pixels(rect.x, rect.y, rect.width, rect.height);
+        ImageData {
+            width: rect.width.max(0),
+            height: rect.height.max(0),
+            data,
+        }
+    })
+}
+
+pub fn canvas_measure_text(element: ElementRef, text: String) -> Result<f32, Error> {
+    with_canvas(element, |c| c.get_context().measure_text(&text))
+}
@@ -0,0 +1,255 @@
+use std::borrow::Cow;
+use std::io::Cursor;
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Error};
+use arboard::Clipboard;
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use image::{ImageFormat, RgbaImage};
+use skia_safe::{AlphaType, ColorSpace, ColorType, Image, ImageInfo};
+
+use crate::img_manager::{rgba_to_skia_image, IMG_MANAGER};
+
+/// Image in clipboard, pixels are RGBA8
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClipboardImage {
+    pub width: usize,
+    pub height: usize,
+    pub bytes: Vec<u8>,
+}
+
+impl ClipboardImage {
+    pub fn from_image(image: &Image) -> Result<Self, Error> {
+        let width = image.width();
+        let height = image.height();
+        let info = ImageInfo::new((width, height), ColorType::RGBA8888, AlphaType::Unpremul, ColorSpace::new_srgb());
+        let row_bytes = width as usize * 4;
+        let mut bytes = vec![0u8; row_bytes * height as usize];
+        if !image.read_pixels(&info, &mut bytes, row_bytes, (0, 0), skia_safe::image::CachingHint::Allow) {
+            return Err(anyhow!("failed to read image pixels"));
+        }
+        Ok(Self {
+            width: width as usize,
+            height: height as usize,
+            bytes,
+        })
+    }
+}
+
+pub trait ClipboardBackend: Send {
+    fn get_text(&mut self) -> Result<String, Error>;
+    fn set_text(&mut self, text: String) -> Result<(), Error>;
+    fn get_html(&mut self) -> Result<String, Error>;
+    fn set_html(&mut self, html: String, alt_text: Option<String>) -> Result<(), Error>;
+    fn get_image(&mut self) -> Result<ClipboardImage, Error>;
+    fn set_image(&mut self, image: ClipboardImage) -> Result<(), Error>;
+}
+
+pub struct SystemClipboard {
+    clipboard: Clipboard,
+}
+
+impl SystemClipboard {
+    pub fn new() -> Result<Self, Error> {
+        Ok(Self {
+            clipboard: Clipboard::new()?,
+        })
+    }
+}
+
+impl ClipboardBackend for SystemClipboard {
+    fn get_text(&mut self) -> Result<String, Error> {
+        Ok(self.clipboard.get_text()?)
+    }
+
+    fn set_text(&mut self, text: String) -> Result<(), Error> {
+        Ok(self.clipboard.set_text(text)?)
+    }
+
+    fn get_html(&mut self) -> Result<String, Error> {
+        Ok(self.clipboard.get().html()?)
+    }
+
+    fn set_html(&mut self, html: String, alt_text: Option<String>) -> Result<(), Error> {
+        Ok(self.clipboard.set_html(html, alt_text)?)
+    }
+
+    fn get_image(&mut self) -> Result<ClipboardImage, Error> {
+        let img = self.clipboard.get_image()?;
+        Ok(ClipboardImage {
+            width: img.width,
+            height: img.height,
+            bytes: img.bytes.into_owned(),
+        })
+    }
+
+    fn set_image(&mut self, image: ClipboardImage) -> Result<(), Error> {
+        Ok(self.clipboard.set_image(arboard::ImageData {
+            width: image.width,
+            height: image.height,
+            bytes: Cow::Owned(image.bytes),
+        })?)
+    }
+}
+
+/// In-memory clipboard, useful for testing
+#[derive(Default)]
+pub struct MemoryClipboard {
+    text: Option<String>,
+    html: Option<String>,
+    image: Option<ClipboardImage>,
+}
+
+impl MemoryClipboard {
+    fn clear(&mut self) {
+        self.text = None;
+        self.html = None;
+        self.image = None;
+    }
+}
+
+impl ClipboardBackend for MemoryClipboard {
+    fn get_text(&mut self) -> Result<String, Error> {
+        self.text.clone().ok_or_else(|| anyhow!("no text in clipboard"))
+    }
+
+    fn set_text(&mut self, text: String) -> Result<(), Error> {
+        self.clear();
+        self.text = Some(text);
+        Ok(())
+    }
+
+    fn get_html(&mut self) -> Result<String, Error> {
+        self.html.clone().ok_or_else(|| anyhow!("no html in clipboard"))
+    }
+
+    fn set_html(&mut self, html: String, alt_text: Option<String>) -> Result<(), Error> {
+        self.clear();
+        self.html = Some(html);
+        self.text = alt_text;
+        Ok(())
+    }
+
+    fn get_image(&mut self) -> Result<ClipboardImage, Error> {
+        self.image.clone().ok_or_else(|| anyhow!("no image in clipboard"))
+    }
+
+    fn set_image(&mut self, image: ClipboardImage) -> Result<(), Error> {
+        self.clear();
+        self.image = Some(image);
+        Ok(())
+    }
+}
+
+// The system clipboard must be kept alive, otherwise the content would be lost on some platforms
+static CLIPBOARD: Mutex<Option<Box<dyn ClipboardBackend>>> = Mutex::new(None);
+
+fn with_clipboard<R, F: FnOnce(&mut dyn ClipboardBackend) -> Result<R, Error>>(callback: F) -> Result<R, Error> {
+    let mut clipboard = CLIPBOARD.lock().map_err(|e| anyhow!("failed to lock clipboard:{}", e))?;
+    if clipboard.is_none() {
+        *clipboard = Some(Box::new(SystemClipboard::new()?));
+    }
+    callback(clipboard.as_mut().unwrap().as_mut())
+}
+
+pub fn clipboard_set_backend(backend: Box<dyn ClipboardBackend>) -> Result<(), Error> {
+    let mut clipboard = CLIPBOARD.lock().map_err(|e| anyhow!("failed to lock clipboard:{}", e))?;
+    *clipboard = Some(backend);
+    Ok(())
+}
+
+/// Use in-memory clipboard instead of system clipboard
+pub fn clipboard_use_memory() -> Result<(), Error> {
+    clipboard_set_backend(Box::new(MemoryClipboard::default()))
+}
+
+pub fn clipboard_get_text() -> Result<String, Error> {
+    with_clipboard(|c| c.get_text())
+}
+
+pub fn clipboard_set_text(text: String) -> Result<(), Error> {
+    with_clipboard(|c| c.set_text(text))
+}
+
+pub fn clipboard_get_html() -> Result<String, Error> {
+    with_clipboard(|c| c.get_html())
+}
+
+pub fn clipboard_set_html(html: String, alt_text: Option<String>) -> Result<(), Error> {
+    with_clipboard(|c| c.set_html(html, alt_text))
+}
+
+pub fn clipboard_get_image() -> Result<Image, Error> {
+    let img = with_clipboard(|c| c.get_image())?;
+    rgba_to_skia_image(img.width as i32, img.height as i32, &img.bytes)
+        .ok_or_else(|| anyhow!("invalid image data"))
+}
+
+pub fn clipboard_set_image(image: &Image) -> Result<(), Error> {
+    let image = ClipboardImage::from_image(image)?;
+    with_clipboard(|c| c.set_image(image))
+}
+
+/// Load image from src, must be called on the js thread because images are cached per thread
+pub fn clipboard_load_image(src: &str) -> Result<ClipboardImage, Error> {
+    let img = IMG_MANAGER.with(|im| im.load_img(src))?;
+    ClipboardImage::from_image(&img)
+}
+
+// Js Api
+
+pub async fn clipboard_read_text() -> Result<String, Error> {
+    tokio::task::spawn_blocking(clipboard_get_text).await?
+}
+
+pub async fn clipboard_write_text(text: String) -> Result<(), Error> {
+    tokio::task::spawn_blocking(move || clipboard_set_text(text)).await?
+}
+
+pub async fn clipboard_read_html() -> Result<String, Error> {
+    tokio::task::spawn_blocking(clipboard_get_html).await?
+}
+
+pub async fn clipboard_write_html(html: String, alt_text: Option<String>) -> Result<(), Error> {
+    tokio::task::spawn_blocking(move || clipboard_set_html(html, alt_text)).await?
+}
+
+/// Read image from clipboard as png data url, which could be used as image src
+pub async fn clipboard_read_image() -> Result<String, Error> {
+    tokio::task::spawn_blocking(move || {
+        let img = with_clipboard(|c| c.get_image())?;
+        let rgba = RgbaImage::from_raw(img.width as u32, img.height as u32, img.bytes)
+            .ok_or_else(|| anyhow!("invalid image data"))?;
+        let mut png = Vec::new();
+        rgba.write_to(&mut Cursor::new(&mut png), ImageFormat::Png)?;
+        Ok(format!("data:image/png;base64,{}", BASE64_STANDARD.encode(png)))
+    }).await?
+}
+
+/// Write image to clipboard, see `clipboard_load_image`
+pub async fn clipboard_write_image(image: ClipboardImage) -> Result<(), Error> {
+    tokio::task::spawn_blocking(move || with_clipboard(|c| c.set_image(image))).await?
+}
+
+#[test]
+fn test_memory_clipboard() {
+    clipboard_use_memory().unwrap();
+    assert!(clipboard_get_text().is_err());
+
+    clipboard_set_text("hello".to_string()).unwrap();
+    assert_eq!("hello", clipboard_get_text().unwrap());
+
+    clipboard_set_html("<b>hello</b>".to_string(), Some("hello".to_string())).unwrap();
+    assert_eq!("<b>hello</b>", clipboard_get_html().unwrap());
+    assert_eq!("hello", clipboard_get_text().unwrap());
+
+    let bytes = vec![255, 0, 0, 255, 0, 255, 0, 255];
+    let img = rgba_to_skia_image(2, 1, &bytes).unwrap();
+    clipboard_set_image(&img).unwrap();
+    assert!(clipboard_get_text().is_err());
+    let img = clipboard_get_image().unwrap();
+    assert_eq!((2, 1), (img.width(), img.height()));
+    let data = with_clipboard(|c| c.get_image()).unwrap();
+    assert_eq!(bytes, data.bytes);
+}
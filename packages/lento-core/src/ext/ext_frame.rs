@@ -1,5 +1,6 @@
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::hash::Hash;
 use std::rc::Rc;
 
 use anyhow::{anyhow, Error};
@@ -7,7 +8,7 @@ use quick_js::{JsValue, ResourceValue};
 use serde::{Deserialize, Serialize};
 use winit::dpi::{LogicalPosition, LogicalSize, Size};
 use winit::dpi::Position::Logical;
-use winit::event::WindowEvent;
+use winit::event::{ElementState, WindowEvent};
 #[cfg(feature = "x11")]
 use winit::platform::x11::WindowAttributesExtX11;
 use winit::window::{Window, WindowId};
@@ -17,21 +18,33 @@ use crate::element::{ElementBackend, ElementRef};
 use crate::{define_resource};
 use crate::frame::{FrameRef, FrameWeak};
 use crate::js::js_value_util::{FromJsValue, ToJsValue};
+use crate::timer::{set_timeout, TimerHandle};
 
 
 thread_local! {
     pub static FRAMES: RefCell<HashMap<i32, FrameRef>> = RefCell::new(HashMap::new());
     pub static WINDOW_TO_FRAME: RefCell<HashMap<WindowId, FrameWeak>> = RefCell::new(HashMap::new());
     pub static MODAL_TO_OWNERS: RefCell<HashMap<WindowId, WindowId>> = RefCell::new(HashMap::new());
+    pub static POPUP_TO_OWNER: RefCell<HashMap<WindowId, WindowId>> = RefCell::new(HashMap::new());
+    static INACTIVE_CHECK_TIMER: RefCell<Option<TimerHandle>> = RefCell::new(None);
 }
 
 pub const VIEW_TYPE_CONTAINER: i32 = 1;
 pub const VIEW_TYPE_LABEL: i32 = 2;
 pub const VIEW_TYPE_BUTTON: i32 = 3;
 pub const VIEW_TYPE_ENTRY: i32 = 4;
+pub const VIEW_TYPE_RADIO_GROUP: i32 = 5;
+pub const VIEW_TYPE_PROGRESS_BAR: i32 = 6;
 pub const VIEW_TYPE_SCROLL: i32 = 7;
 pub const VIEW_TYPE_TEXT_EDIT: i32 = 8;
 pub const VIEW_TYPE_IMAGE: i32 = 9;
+pub const VIEW_TYPE_VIRTUAL_LIST: i32 = 10;
+pub const VIEW_TYPE_CHECKBOX: i32 = 11;
+pub const VIEW_TYPE_RADIO: i32 = 12;
+pub const VIEW_TYPE_SWITCH: i32 = 13;
+pub const VIEW_TYPE_SLIDER: i32 = 14;
+pub const VIEW_TYPE_SELECT: i32 = 15;
+pub const VIEW_TYPE_CANVAS: i32 = 16;
 
 pub type ViewId = i32;
 
@@ -95,12 +108,88 @@ pub fn frame_set_modal(mut frame: FrameWeak, owner: FrameWeak) -> Result<(), Err
     Ok(())
 }
 
+/// Make frame a popup of owner. Popup receives keyboard input of owner and
+/// is closed when owner is pressed or closed. Each owner has at most one popup.
+pub fn frame_set_popup(frame: FrameWeak, owner: FrameWeak) -> Result<(), Error> {
+    let popup_id = frame.upgrade_mut(|f| f.get_window_id()).ok_or_else(|| anyhow!("frame is closed"))?;
+    let owner_id = owner.upgrade_mut(|o| o.get_window_id()).ok_or_else(|| anyhow!("owner is closed"))?;
+    close_popups(owner_id);
+    POPUP_TO_OWNER.with_borrow_mut(|m| m.insert(popup_id, owner_id));
+    Ok(())
+}
+
+fn find_popup(owner: WindowId) -> Option<WindowId> {
+    POPUP_TO_OWNER.with_borrow(|m| {
+        m.iter().find(|(_, o)| o == &&owner).map(|(p, _)| p.clone())
+    })
+}
+
+fn find_top_popup(window_id: WindowId) -> Option<WindowId> {
+    let mut top = None;
+    let mut current = window_id;
+    while let Some(p) = find_popup(current) {
+        top = Some(p);
+        current = p;
+    }
+    top
+}
+
+/// Popups which are not `window` or its owners, they are dismissed when `window` is pressed or activated
+fn popups_outside_chain<K: Copy + Eq + Hash>(popup_to_owner: &HashMap<K, K>, window: K) -> Vec<K> {
+    let mut chain = vec![window];
+    let mut current = window;
+    while let Some(owner) = popup_to_owner.get(&current) {
+        chain.push(*owner);
+        current = *owner;
+    }
+    popup_to_owner.keys().filter(|p| !chain.contains(p)).copied().collect()
+}
+
+fn close_popup(popup: WindowId) {
+    let frame = WINDOW_TO_FRAME.with_borrow(|m| m.get(&popup).cloned());
+    if let Some(f) = frame {
+        let _ = frame_close(f);
+    }
+}
+
+fn close_popups_outside(window_id: WindowId) {
+    let popups = POPUP_TO_OWNER.with_borrow(|m| popups_outside_chain(m, window_id));
+    for p in popups {
+        close_popup(p);
+    }
+}
+
+const INACTIVE_CHECK_DELAY: u64 = 50;
+
+/// Close all popups if no frame is focused, i.e. the app is deactivated
+fn close_popups_if_inactive() {
+    INACTIVE_CHECK_TIMER.with_borrow_mut(|t| t.take());
+    let focused = FRAMES.with_borrow(|m| m.values().any(|f| f.has_focus()));
+    if !focused {
+        let popups: Vec<WindowId> = POPUP_TO_OWNER.with_borrow(|m| m.keys().copied().collect());
+        for p in popups {
+            close_popup(p);
+        }
+    }
+}
+
+fn close_popups(owner: WindowId) {
+    if let Some(popup) = find_popup(owner) {
+        let frame = WINDOW_TO_FRAME.with_borrow(|m| m.get(&popup).cloned());
+        if let Some(f) = frame {
+            let _ = frame_close(f);
+        }
+    }
+}
+
 pub fn frame_close(frame: FrameWeak) -> Result<(), Error> {
     frame.upgrade_mut(|frame| {
         let window_id = frame.get_window_id();
         if frame.allow_close() {
+            close_popups(window_id);
             WINDOW_TO_FRAME.with_borrow_mut(|m| m.remove(&window_id));
             MODAL_TO_OWNERS.with_borrow_mut(|m| m.remove(&window_id));
+            POPUP_TO_OWNER.with_borrow_mut(|m| m.remove(&window_id));
             FRAMES.with_borrow_mut(|m| {
                 m.remove(&frame.get_id());
                 if m.is_empty() {
@@ -123,14 +212,39 @@ pub fn handle_window_event(window_id: WindowId, event: WindowEvent) {
         WindowEvent::Occluded(_) => {}
         WindowEvent::RedrawRequested => {}
         _ => {
-            let has_modal = MODAL_TO_OWNERS.with_borrow_mut(|m| {
-                m.iter().find(|(_, o)| o == &&window_id).is_some()
+            let modal = MODAL_TO_OWNERS.with_borrow_mut(|m| {
+                m.iter().find(|(_, o)| o == &&window_id).map(|(m, _)| m.clone())
             });
-            if has_modal {
+            if let Some(modal) = modal {
+                // keep keyboard focus inside modal frame
+                if let WindowEvent::Focused(true) = &event {
+                    let modal_frame = WINDOW_TO_FRAME.with_borrow(|m| m.get(&modal).cloned());
+                    if let Some(f) = modal_frame {
+                        f.upgrade_mut(|f| f.focus_window());
+                    }
+                }
                 return
             }
         }
     }
+    let window_id = match &event {
+        // popup frames may not be focused, so keyboard input is redirected to them
+        WindowEvent::KeyboardInput { .. } | WindowEvent::ModifiersChanged(_) | WindowEvent::Ime(_) => {
+            find_top_popup(window_id).unwrap_or(window_id)
+        }
+        // pressing or activating any other frame dismisses popups, including frames not owning them
+        WindowEvent::MouseInput { state: ElementState::Pressed, .. } | WindowEvent::Focused(true) => {
+            close_popups_outside(window_id);
+            window_id
+        }
+        WindowEvent::Focused(false) => {
+            // focus may move to another frame of the app, check it after the focus change
+            let timer = set_timeout(close_popups_if_inactive, INACTIVE_CHECK_DELAY);
+            INACTIVE_CHECK_TIMER.with_borrow_mut(|t| *t = Some(timer));
+            window_id
+        }
+        _ => window_id,
+    };
     let mut frame = WINDOW_TO_FRAME.with_borrow_mut(|m| {
         match m.get_mut(&window_id) {
             None => None,
@@ -189,4 +303,21 @@ impl FrameWeak {
 
 // Js Api
 
-define_resource!(FrameWeak);
\ No newline at end of file
+define_resource!(FrameWeak);
+
+#[test]
+fn test_popups_outside_chain() {
+    // 2 is a popup of 1, 3 is a submenu popup of 2, 5 is a popup of another frame 4
+    let popup_to_owner = HashMap::from([(2, 1), (3, 2), (5, 4)]);
+    let sorted = |mut v: Vec<i32>| {
+        v.sort();
+        v
+    };
+    // pressing the owner closes its popups and popups of other frames
+    assert_eq!(vec![2, 3, 5], sorted(popups_outside_chain(&popup_to_owner, 1)));
+    // pressing a menu keeps its parent menus open
+    assert_eq!(vec![5], sorted(popups_outside_chain(&popup_to_owner, 3)));
+    assert_eq!(vec![3, 5], sorted(popups_outside_chain(&popup_to_owner, 2)));
+    // pressing an unrelated frame closes all popups
+    assert_eq!(vec![2, 3, 5], sorted(popups_outside_chain(&popup_to_owner, 6)));
+}
@@ -0,0 +1,117 @@
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Error};
+use spellbook::Dictionary;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::element::entry::Entry;
+use crate::element::ElementRef;
+use crate::event_loop::run_on_event_loop;
+use crate::ext::ext_frame::FRAMES;
+
+static SPELL_CHECKER: Mutex<Option<Arc<SpellChecker>>> = Mutex::new(None);
+/// Increased when the dictionary is changed, checked text should be checked again
+static DICTIONARY_VERSION: AtomicU32 = AtomicU32::new(0);
+
+/// Spell checker of Hunspell dictionary
+pub struct SpellChecker {
+    dictionary: Dictionary,
+}
+
+impl SpellChecker {
+    pub fn new(aff: &str, dic: &str) -> Result<Self, Error> {
+        let dictionary = Dictionary::new(aff, dic).map_err(|e| anyhow!("invalid dictionary:{}", e))?;
+        Ok(Self { dictionary })
+    }
+
+    /// Load dictionary from the .dic file and the .aff file with the same name, e.g. "dict/en_US.dic"
+    pub fn load(path: &str) -> Result<Self, Error> {
+        let dic_path = Path::new(path).with_extension("dic");
+        let aff_path = dic_path.with_extension("aff");
+        let aff = fs::read_to_string(&aff_path).map_err(|e| anyhow!("failed to read {:?}:{}", aff_path, e))?;
+        let dic = fs::read_to_string(&dic_path).map_err(|e| anyhow!("failed to read {:?}:{}", dic_path, e))?;
+        Self::new(&aff, &dic)
+    }
+
+    pub fn check(&self, word: &str) -> bool {
+        self.dictionary.check(word)
+    }
+
+    pub fn suggest(&self, word: &str) -> Vec<String> {
+        let mut suggestions = Vec::new();
+        self.dictionary.suggest(word, &mut suggestions);
+        suggestions
+    }
+
+    /// Byte ranges of misspelled words, words with digits are ignored
+    pub fn find_misspelled(&self, text: &str) -> Vec<(usize, usize)> {
+        text.unicode_word_indices()
+            .filter(|(_, word)| !word.chars().any(|c| c.is_numeric()) && !self.check(word))
+            .map(|(start, word)| (start, start + word.len()))
+            .collect()
+    }
+}
+
+pub fn get_spell_checker() -> Option<Arc<SpellChecker>> {
+    SPELL_CHECKER.lock().unwrap().clone()
+}
+
+pub fn set_spell_checker(spell_checker: Option<SpellChecker>) {
+    *SPELL_CHECKER.lock().unwrap() = spell_checker.map(Arc::new);
+    DICTIONARY_VERSION.fetch_add(1, Ordering::SeqCst);
+    run_on_event_loop(|| {
+        let bodies: Vec<ElementRef> = FRAMES.with_borrow(|m| m.values().filter_map(|f| f.get_body()).collect());
+        for body in bodies {
+            notify_dictionary_change(body);
+        }
+    });
+}
+
+/// Let editable elements check again with the new dictionary
+fn notify_dictionary_change(mut element: ElementRef) {
+    if element.get_backend().get_name() == "Entry" {
+        element.get_backend_mut_as::<Entry>().handle_dictionary_change();
+    }
+    for child in element.get_children() {
+        notify_dictionary_change(child);
+    }
+}
+
+pub fn get_dictionary_version() -> u32 {
+    DICTIONARY_VERSION.load(Ordering::SeqCst)
+}
+
+// Js Api
+
+pub async fn spellcheck_load_dictionary(path: String) -> Result<(), Error> {
+    let spell_checker = tokio::task::spawn_blocking(move || SpellChecker::load(&path)).await??;
+    set_spell_checker(Some(spell_checker));
+    Ok(())
+}
+
+pub async fn spellcheck_unload_dictionary() -> Result<(), Error> {
+    set_spell_checker(None);
+    Ok(())
+}
+
+pub async fn spellcheck_suggest(word: String) -> Result<Vec<String>, Error> {
+    let Some(spell_checker) = get_spell_checker() else {
+        return Ok(Vec::new());
+    };
+    Ok(tokio::task::spawn_blocking(move || spell_checker.suggest(&word)).await?)
+}
+
+#[test]
+fn test_find_misspelled() {
+    let aff = "SET UTF-8\nTRY esianrtolcdugmphbyfvkwz\n\nSFX S Y 1\nSFX S 0 s .\n";
+    let dic = "3\nhello\nworld/S\nspell\n";
+    let spell_checker = SpellChecker::new(aff, dic).unwrap();
+    assert!(spell_checker.check("worlds"));
+    let text = "hello wrold, 42 worlds spel";
+    let misspelled: Vec<&str> = spell_checker.find_misspelled(text).into_iter().map(|(s, e)| &text[s..e]).collect();
+    assert_eq!(vec!["wrold", "spel"], misspelled);
+    assert!(spell_checker.suggest("wrold").contains(&"world".to_string()));
+}
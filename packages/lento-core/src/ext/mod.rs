@@ -16,4 +16,7 @@ mod audio_player;
 pub mod ext_base64;
 pub mod ext_shell;
 pub mod ext_dialog;
+pub mod ext_clipboard;
+pub mod ext_spellcheck;
+pub mod ext_canvas;
 pub mod promise;
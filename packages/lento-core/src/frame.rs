@@ -5,25 +5,30 @@ use std::ops::{Deref, DerefMut};
 use std::rc::Rc;
 use std::slice;
 use std::time::SystemTime;
+use accesskit::{Action, ActionRequest};
+use accesskit_winit::Adapter;
 use anyhow::{anyhow, Error};
 use measure_time::print_time;
 use quick_js::{JsValue, ResourceValue};
 use skia_bindings::SkClipOp;
-use skia_safe::{Canvas, Color, ColorType, ImageInfo};
+use skia_safe::{Canvas, Color, ColorType, IRect, ImageInfo, Paint, M44};
 use skia_window::skia_window::{RenderBackendType, SkiaWindow};
 use winit::dpi::{LogicalPosition, LogicalSize, Position, Size};
 use winit::event::{ElementState, Ime, Modifiers, MouseButton, MouseScrollDelta, TouchPhase, WindowEvent};
 use winit::event_loop::{ActiveEventLoop, EventLoopProxy};
 use winit::keyboard::{Key, NamedKey};
 use winit::window::{Cursor, CursorIcon, Window, WindowAttributes, WindowId};
+use yoga::Overflow;
+use crate::accessibility::{find_element_by_node_id, AccessibilityTree, FrameAccessibilityHandler};
 use crate::app::AppEvent;
-use crate::base::{ElementEvent, Event, EventContext, EventHandler, EventRegistration, MouseDetail, MouseEventType, Touch, TouchDetail, UnsafeFnOnce};
+use crate::base::{ElementEvent, Event, EventContext, EventHandler, EventRegistration, MouseDetail, MouseEventType, Rect, Touch, TouchDetail, UnsafeFnOnce};
 use crate::base::MouseEventType::{MouseClick, MouseUp};
 use crate::canvas_util::CanvasHelper;
 use crate::cursor::search_cursor;
-use crate::element::ElementRef;
-use crate::event::{build_modifier, CaretEventBind, ClickEventBind, DragOverEventDetail, DragStartEventDetail, DropEventDetail, FocusShiftBind, FocusEventBind, KEY_MOD_ALT, KEY_MOD_CTRL, KEY_MOD_META, KEY_MOD_SHIFT, KeyEventDetail, MouseDownEventBind, MouseEnterEventBind, MouseLeaveEventBind, MouseMoveEventBind, MouseUpEventBind, MouseWheelDetail, named_key_to_str, TouchCancelEventBind, TouchEndEventBind, TouchMoveEventBind, TouchStartEventBind};
-use crate::event_loop::{run_with_event_loop, send_event};
+use crate::data_transfer::{DataTransfer, DropEffect};
+use crate::element::{ElementRef, ScrollIntoViewOption};
+use crate::event::{build_modifier, CaretEventBind, ClickEventBind, CompositionEventDetail, ContextMenuEventBind, DragEventDetail, DragOverEvent, FocusShiftBind, FocusEventBind, KEY_MOD_ALT, KEY_MOD_CTRL, KEY_MOD_META, KEY_MOD_SHIFT, KeyDownEvent, KeyEventDetail, MouseDownEventBind, MouseEnterEventBind, MouseLeaveEventBind, MouseMoveEventBind, MouseUpEventBind, MouseWheelDetail, named_key_to_str, TouchCancelEventBind, TouchEndEventBind, TouchMoveEventBind, TouchStartEventBind};
+use crate::event_loop::{run_with_event_loop, schedule_macro_task_unsafe, send_event};
 use crate::ext::common::create_event_handler;
 use crate::ext::ext_frame::FRAMES;
 use crate::js::js_value_util::{FromJsValue, ToJsValue};
@@ -80,13 +85,22 @@ pub struct Frame {
     pressing: Option<(ElementRef, MouseDownInfo)>,
     touching: TouchingInfo,
     dragging: bool,
+    /// payload of the current drag session, which may come from the app or the OS
+    data_transfer: Option<DataTransfer>,
     last_drag_over: Option<ElementRef>,
     hover: Option<ElementRef>,
+    /// whether the last user interaction was from keyboard
+    keyboard_navigating: bool,
+    ime_composing: bool,
     modifiers: Modifiers,
     layout_dirty: bool,
     dirty: bool,
     event_registration: EventRegistration<FrameWeak>,
     attributes: WindowAttributes,
+    accessibility_adapter: Adapter,
+    accessibility_tree: AccessibilityTree,
+    /// Elements whose accessibility nodes are rebuilt in the next update
+    accessibility_dirty: HashMap<u32, ElementRef>,
 }
 
 pub type FrameEventHandler = EventHandler<FrameWeak>;
@@ -106,7 +120,7 @@ impl FrameRef {
         let id = NEXT_FRAME_ID.get();
         NEXT_FRAME_ID.set(id + 1);
 
-        let window = Self::create_window(attributes.clone());
+        let (window, accessibility_adapter) = Self::create_window(id, attributes.clone());
         let state = Frame {
             id,
             window,
@@ -122,13 +136,19 @@ impl FrameRef {
             pressing: None,
             focusing: None,
             hover: None,
+            keyboard_navigating: false,
+            ime_composing: false,
             modifiers: Modifiers::default(),
             layout_dirty: false,
             dirty: false,
             dragging: false,
+            data_transfer: None,
             last_drag_over: None,
             event_registration: EventRegistration::new(),
             attributes,
+            accessibility_adapter,
+            accessibility_tree: AccessibilityTree::new(),
+            accessibility_dirty: HashMap::new(),
             touching: TouchingInfo {
                 start_time: SystemTime::now(),
                 times: 0,
@@ -146,7 +166,10 @@ impl FrameRef {
     }
 
     pub fn resume(&mut self) {
-        self.window = Self::create_window(self.attributes.clone());
+        let (window, accessibility_adapter) = Self::create_window(self.id, self.attributes.clone());
+        self.window = window;
+        self.accessibility_adapter = accessibility_adapter;
+        self.accessibility_tree.reset();
     }
 
     pub fn as_weak(&self) -> FrameWeak {
@@ -189,6 +212,14 @@ impl FrameRef {
         Ok(self.event_registration.add_js_event_listener(&event_name, callback))
     }
 
+    /// Logical position of the content area on screen
+    pub fn get_window_position(&self) -> LogicalPosition<f32> {
+        let scale_factor = self.window.scale_factor();
+        self.window.inner_position()
+            .map(|p| p.to_logical::<f32>(scale_factor))
+            .unwrap_or(LogicalPosition::new(0.0, 0.0))
+    }
+
     pub fn set_visible(&mut self, visible: bool) -> Result<(), Error> {
         self.window.set_visible(visible);
         Ok(())
@@ -209,7 +240,73 @@ impl FrameRef {
         }
     }
 
+    fn handle_ime_preedit(&mut self, text: String, cursor: Option<(usize, usize)>) {
+        let mut focusing = match self.focusing.clone() {
+            Some(f) => f,
+            None => return,
+        };
+        if !text.is_empty() && !self.ime_composing {
+            self.ime_composing = true;
+            emit_composition_event(&mut focusing, "compositionstart", String::new());
+        }
+        if self.ime_composing {
+            emit_composition_event(&mut focusing, "compositionupdate", text.clone());
+        }
+        focusing.handle_ime_preedit(&text, cursor);
+        self.update_ime_cursor_area();
+        if text.is_empty() && self.ime_composing {
+            // An empty preedit is sent before commit, end composition if it's cancelled
+            let myself = self.as_weak();
+            unsafe {
+                schedule_macro_task_unsafe(move || {
+                    myself.upgrade_mut(|f| f.end_composition(""));
+                });
+            }
+        }
+    }
+
+    fn end_composition(&mut self, data: &str) {
+        if self.ime_composing {
+            self.ime_composing = false;
+            if let Some(focusing) = &mut self.focusing {
+                focusing.handle_ime_preedit("", None);
+                emit_composition_event(focusing, "compositionend", data.to_string());
+            }
+        }
+    }
+
+    /// Enable input method only when an editable element is focused
+    fn update_ime(&mut self) {
+        let editable = match &self.focusing {
+            Some(f) => f.get_backend().is_editable(),
+            None => false,
+        };
+        self.window.set_ime_allowed(editable);
+        if editable {
+            self.update_ime_cursor_area();
+        }
+    }
+
+    fn update_ime_cursor_area(&mut self) {
+        let focusing = match &self.focusing {
+            Some(f) => f,
+            None => return,
+        };
+        if let Some(caret_bounds) = focusing.get_backend().get_caret_bounds() {
+            let origin_bounds = focusing.get_origin_bounds();
+            let area = caret_bounds.translate(origin_bounds.x, origin_bounds.y);
+            self.window.set_ime_cursor_area(Position::Logical(LogicalPosition {
+                x: area.x as f64,
+                y: area.y as f64,
+            }), Size::Logical(LogicalSize {
+                width: area.width as f64,
+                height: area.height as f64,
+            }));
+        }
+    }
+
     pub fn handle_event(&mut self, event: WindowEvent) {
+        self.accessibility_adapter.process_event(self.window.winit_window(), &event);
         match event {
             WindowEvent::RedrawRequested => {
                 self.paint();
@@ -221,12 +318,17 @@ impl FrameRef {
             WindowEvent::Ime(ime) => {
                 match ime {
                     Ime::Enabled => {}
-                    Ime::Preedit(_, _) => {}
+                    Ime::Preedit(text, cursor) => {
+                        self.handle_ime_preedit(text, cursor);
+                    }
                     Ime::Commit(str) => {
                         println!("input:{}", str);
+                        self.end_composition(&str);
                         self.handle_input(&str);
                     }
-                    Ime::Disabled => {}
+                    Ime::Disabled => {
+                        self.end_composition("");
+                    }
                 }
             }
             WindowEvent::KeyboardInput {
@@ -249,6 +351,9 @@ impl FrameRef {
                 };
                 let mut modifiers = build_modifier(&self.modifiers.state());
                 let pressed = event.state == ElementState::Pressed;
+                if pressed {
+                    self.keyboard_navigating = true;
+                }
                 if pressed && named_key == Some(NamedKey::Control) {
                     modifiers |= KEY_MOD_CTRL;
                 }
@@ -274,10 +379,17 @@ impl FrameRef {
                     pressed: event.state == ElementState::Pressed,
                 };
 
+                let is_context_menu_key = pressed && named_key == Some(NamedKey::ContextMenu);
+                let window_pos = self.get_window_position();
                 if let Some(focusing) = &mut self.focusing {
                     let event_type = if detail.pressed { "keydown" } else { "keyup" };
                     let event = ElementEvent::new(event_type, detail, focusing.clone());
                     focusing.emit_event(event_type, event);
+                    if is_context_menu_key {
+                        let bounds = focusing.get_origin_bounds();
+                        let (frame_x, frame_y) = (bounds.x + bounds.width / 2.0, bounds.y + bounds.height / 2.0);
+                        emit_mouse_event(focusing, "contextmenu", MouseEventType::ContextMenu, 0, frame_x, frame_y, window_pos.x + frame_x, window_pos.y + frame_y);
+                    }
                 }
             }
             WindowEvent::MouseInput { button, state, .. } => {
@@ -308,18 +420,23 @@ impl FrameRef {
                 }
             }
             WindowEvent::MouseWheel {delta,..} => {
-                match delta {
-                    MouseScrollDelta::LineDelta(x, y) => {
-                        self.handle_mouse_wheel((x, y));
-                    }
-                    MouseScrollDelta::PixelDelta(_) => {}
-                }
+                let detail = mouse_wheel_detail(delta, self.window.scale_factor());
+                self.handle_mouse_wheel(detail);
                 // println!("delta:{:?}", delta);
             }
             WindowEvent::Touch(touch) => {
                 let loc = touch.location.to_logical(self.window.scale_factor());
                 self.emit_touch_event(touch.id, touch.phase, loc.x, loc.y);
             }
+            WindowEvent::HoveredFile(path) => {
+                self.handle_hovered_file(path.to_string_lossy().to_string());
+            }
+            WindowEvent::HoveredFileCancelled => {
+                self.handle_hovered_file_cancelled();
+            }
+            WindowEvent::DroppedFile(path) => {
+                self.handle_dropped_file(path.to_string_lossy().to_string());
+            }
             _ => (),
         }
     }
@@ -336,9 +453,9 @@ impl FrameRef {
         self.event_registration.remove_event_listener(&event_type, id)
     }
 
-    fn handle_mouse_wheel(&mut self, delta: (f32, f32)) {
+    fn handle_mouse_wheel(&mut self, detail: MouseWheelDetail) {
         if let Some(mut target_node) = self.get_node_by_point() {
-            let mut event = ElementEvent::new("mousewheel", MouseWheelDetail {cols: delta.0, rows: delta.1}, target_node.clone());
+            let mut event = ElementEvent::new("mousewheel", detail, target_node.clone());
             target_node.emit_event("mousewheel",event);
         }
 
@@ -351,24 +468,35 @@ impl FrameRef {
         let screen_y = self.cursor_root_position.y as f32;
         let mut target_node = self.get_node_by_point();
         let dragging = self.dragging;
+        if let Some(dt) = &self.data_transfer {
+            if dt.is_external() {
+                if dt.is_dropped() {
+                    self.data_transfer = None;
+                } else {
+                    self.handle_drag_move();
+                    return;
+                }
+            }
+        }
         if let Some((pressing, down_info)) = &mut self.pressing.clone() {
             if dragging {
-                if let Some(target) = &mut target_node {
-                    if target != pressing {
-                        let mut event = ElementEvent::new("dragover", DragOverEventDetail {}, target.clone());
-                        target.emit_event("dragover", event);
-                        self.last_drag_over = Some(target.clone());
-                    }
-                }
+                self.handle_drag_move();
             } else {
                 if pressing.is_draggable() && (
                     f32::abs(frame_x - down_info.frame_x) > 3.0
                     || f32::abs(frame_y - down_info.frame_y) > 3.0
                 ) {
-                    let event = ElementEvent::new("dragstart", DragStartEventDetail {}, pressing.clone());
-                    pressing.emit_event("dragstart", event);
+                    let data_transfer = DataTransfer::new(false);
+                    emit_drag_event(pressing, "dragstart", &data_transfer, frame_x, frame_y, screen_x, screen_y);
+                    // data could only be set by dragstart handlers
+                    let mut dt = data_transfer.clone();
+                    unsafe {
+                        schedule_macro_task_unsafe(move || dt.set_writable(false));
+                    }
                     //TODO check preventDefault?
                     self.window.set_cursor(Cursor::Icon(CursorIcon::Grabbing));
+                    self.data_transfer = Some(data_transfer);
+                    self.last_drag_over = None;
                     self.dragging = true;
                 } else {
                     self.update_cursor(pressing);
@@ -432,9 +560,13 @@ impl FrameRef {
             };
             match state {
                 ElementState::Pressed => {
+                    self.keyboard_navigating = false;
                     self.focus(node.clone());
                     self.pressing = Some((node.clone(), MouseDownInfo {button, frame_x, frame_y}));
                     emit_mouse_event(&mut node, e_type, event_type, button, frame_x, frame_y, screen_x, screen_y);
+                    if button == 2 {
+                        emit_mouse_event(&mut node, "contextmenu", MouseEventType::ContextMenu, button, frame_x, frame_y, screen_x, screen_y);
+                    }
                 }
                 ElementState::Released => {
                     if let Some(mut pressing) = self.pressing.clone() {
@@ -526,6 +658,7 @@ impl FrameRef {
                         && SystemTime::now().duration_since(self.touching.start_time).unwrap().as_millis() < 1000
                     {
                         let mut node = node.clone();
+                        self.keyboard_navigating = false;
                         self.focus(node.clone());
                         self.touching.click_timer_handle = Some(set_timeout(move || {
                             println!("clicked");
@@ -541,33 +674,291 @@ impl FrameRef {
         }
     }
 
-    fn focus(&mut self, mut node: ElementRef) {
+    fn focus(&mut self, node: ElementRef) {
+        let focus_visible = self.keyboard_navigating;
+        self.focus_with_visible(node, focus_visible);
+    }
+
+    fn focus_with_visible(&mut self, mut node: ElementRef, focus_visible: bool) {
         let focusing = Some(node.clone());
         if self.focusing != focusing {
+            self.end_composition("");
             if let Some(old_focusing) = &mut self.focusing {
+                old_focusing.set_focus_visible(false);
                 let blur_event = ElementEvent::new("blur", (), old_focusing.clone());
                 old_focusing.emit_event("blur", blur_event);
 
                 old_focusing.emit_focus_shift(());
             }
             self.focusing = focusing;
+            node.set_focus_visible(focus_visible);
             node.emit_focus(());
+            self.update_ime();
+            self.update_accessibility(false);
+        }
+    }
+
+    /// Focus element programmatically
+    pub fn request_focus(&mut self, node: ElementRef) {
+        if node.get_tab_index().is_some() {
+            self.focus(node);
+        }
+    }
+
+    /// Move focus back to body if the element is focusing
+    pub fn blur(&mut self, node: &ElementRef) {
+        if self.focusing.as_ref() == Some(node) {
+            if let Some(body) = self.body.clone() {
+                self.focus_with_visible(body, false);
+            }
         }
     }
 
+    pub fn get_focusing(&self) -> Option<ElementRef> {
+        self.focusing.clone()
+    }
+
+    /// Move focus to next tabbable element, elements with positive tabIndex come first.
+    /// Focus wraps around inside the frame, so it is trapped in modal frames.
+    pub fn focus_next(&mut self, backward: bool) {
+        let body = match &self.body {
+            None => return,
+            Some(b) => b.clone(),
+        };
+        let order = tab_order(&body);
+        if let Some(node) = next_tab_target(&order, self.focusing.as_ref(), backward) {
+            self.focus_with_visible(node, true);
+        }
+    }
+
+    pub fn handle_event_default_behavior(&mut self, event_type: &str, event: &mut ElementEvent) {
+        KeyDownEvent::try_match(event_type, event, |d| {
+            if d.named_key == Some(NamedKey::Tab) {
+                if d.modifiers == 0 {
+                    self.focus_next(false);
+                } else if d.modifiers == KEY_MOD_SHIFT {
+                    self.focus_next(true);
+                }
+            }
+        });
+        // dragover not handled, the target doesn't accept drop
+        DragOverEvent::try_match(event_type, event, |d| {
+            let mut dt = d.data_transfer.clone();
+            dt.set_drop_effect(DropEffect::None);
+        });
+    }
+
+    pub fn focus_window(&mut self) {
+        self.window.focus_window();
+    }
+
+    pub fn has_focus(&self) -> bool {
+        self.window.has_focus()
+    }
+
+    pub fn handle_accessibility_activated(&mut self) {
+        self.accessibility_tree.reset();
+        self.update_accessibility(false);
+    }
+
+    pub fn handle_accessibility_deactivated(&mut self) {
+        self.accessibility_tree.reset();
+    }
+
+    pub fn handle_accessibility_action(&mut self, request: ActionRequest) {
+        let body = match &self.body {
+            None => return,
+            Some(b) => b.clone(),
+        };
+        let mut target = match find_element_by_node_id(&body, request.target) {
+            None => return,
+            Some(t) => t,
+        };
+        if target.get_backend_mut().handle_accessibility_action(&request) {
+            return;
+        }
+        match request.action {
+            Action::Focus => {
+                self.focus(target);
+            }
+            Action::Default => {
+                let bounds = target.get_origin_bounds();
+                let frame_x = bounds.x + bounds.width / 2.0;
+                let frame_y = bounds.y + bounds.height / 2.0;
+                self.focus(target.clone());
+                emit_mouse_event(&mut target, "click", MouseClick, 1, frame_x, frame_y, 0.0, 0.0);
+            }
+            Action::ScrollIntoView => {
+                target.scroll_into_view(ScrollIntoViewOption {
+                    block: Some("nearest".to_string()),
+                    ..Default::default()
+                });
+            }
+            _ => {}
+        }
+    }
+
+    pub fn mark_accessibility_dirty(&mut self, element: ElementRef) {
+        self.accessibility_dirty.insert(element.get_id(), element);
+    }
+
+    fn update_accessibility(&mut self, layout_changed: bool) {
+        let body = self.body.clone();
+        let focusing = self.focusing.clone();
+        let scale_factor = self.window.scale_factor();
+        let dirty: Vec<ElementRef> = self.accessibility_dirty.drain().map(|(_, e)| e).collect();
+        let frame: &mut Frame = &mut self.inner;
+        frame.accessibility_adapter.update_if_active(|| {
+            frame.accessibility_tree.build_update(body.as_ref(), focusing.as_ref(), scale_factor, &dirty, layout_changed)
+        });
+    }
+
     fn release_press(&mut self) {
         let dragging = self.dragging;
-        if let Some(_) = &mut self.pressing {
+        if let Some((source, _)) = self.pressing.take() {
             if dragging {
                 self.dragging = false;
-                self.window.set_cursor(Cursor::Icon(CursorIcon::Default));
-                if let Some(last_drag_over) = &mut self.last_drag_over {
-                    let event = ElementEvent::new("drop", DropEventDetail{}, last_drag_over.clone());
-                    last_drag_over.emit_event("drop", event);
+                if let Some(data_transfer) = self.data_transfer.take() {
+                    self.finish_drag(data_transfer, Some(source));
+                }
+            }
+        }
+    }
+
+    fn get_pointer_position(&self) -> (f32, f32, f32, f32) {
+        (
+            self.cursor_position.x as f32,
+            self.cursor_position.y as f32,
+            self.cursor_root_position.x as f32,
+            self.cursor_root_position.y as f32,
+        )
+    }
+
+    fn handle_drag_move(&mut self) {
+        let data_transfer = match &self.data_transfer {
+            Some(dt) => dt.clone(),
+            None => return,
+        };
+        let (frame_x, frame_y, screen_x, screen_y) = self.get_pointer_position();
+        // drop effect negotiated by the previous dragover
+        let cursor = match data_transfer.get_drop_effect() {
+            DropEffect::None => CursorIcon::NoDrop,
+            DropEffect::Copy => CursorIcon::Copy,
+            DropEffect::Move => CursorIcon::Grabbing,
+            DropEffect::Link => CursorIcon::Alias,
+        };
+        self.window.set_cursor(Cursor::Icon(cursor));
+
+        let target = self.get_node_by_point();
+        if target != self.last_drag_over {
+            if let Some(last) = &mut self.last_drag_over {
+                emit_drag_event(last, "dragleave", &data_transfer, frame_x, frame_y, screen_x, screen_y);
+            }
+            if let Some(target) = &mut target.clone() {
+                emit_drag_event(target, "dragenter", &data_transfer, frame_x, frame_y, screen_x, screen_y);
+            }
+            self.last_drag_over = target.clone();
+        }
+        if let Some(target) = &mut target.clone() {
+            // Reset in the event queue so that the previous dragover handlers are not affected
+            let mut dt = data_transfer.clone();
+            unsafe {
+                schedule_macro_task_unsafe(move || dt.reset_drop_effect());
+            }
+            emit_drag_event(target, "dragover", &data_transfer, frame_x, frame_y, screen_x, screen_y);
+        } else {
+            let mut dt = data_transfer.clone();
+            unsafe {
+                schedule_macro_task_unsafe(move || dt.set_drop_effect(DropEffect::None));
+            }
+        }
+        if data_transfer.get_drag_image().is_some() {
+            self.mark_dirty(false);
+        }
+    }
+
+    /// Emit drop on the current target if accepted by the last dragover, then emit dragend on source
+    fn finish_drag(&mut self, data_transfer: DataTransfer, source: Option<ElementRef>) {
+        let (frame_x, frame_y, screen_x, screen_y) = self.get_pointer_position();
+        let target = self.last_drag_over.take();
+        self.window.set_cursor(Cursor::Icon(CursorIcon::Default));
+        if data_transfer.get_drag_image().is_some() {
+            self.mark_dirty(false);
+        }
+        let mut dt = data_transfer;
+        // Wait for pending dragover handlers
+        unsafe {
+            schedule_macro_task_unsafe(move || {
+                if let Some(mut target) = target {
+                    if dt.get_drop_effect() != DropEffect::None {
+                        dt.set_dropped();
+                        emit_drag_event(&mut target, "drop", &dt, frame_x, frame_y, screen_x, screen_y);
+                    } else {
+                        emit_drag_event(&mut target, "dragleave", &dt, frame_x, frame_y, screen_x, screen_y);
+                    }
+                } else {
+                    dt.set_drop_effect(DropEffect::None);
                 }
+                if let Some(mut source) = source {
+                    emit_drag_event(&mut source, "dragend", &dt, frame_x, frame_y, screen_x, screen_y);
+                }
+                dt.release();
+            });
+        }
+    }
+
+    fn handle_hovered_file(&mut self, path: String) {
+        if self.dragging {
+            return;
+        }
+        if let Some(dt) = &mut self.data_transfer {
+            if !dt.is_dropped() {
+                // the OS reports files one by one
+                dt.add_file(path);
+                return;
             }
-            self.pressing = None;
         }
+        let mut data_transfer = DataTransfer::new(true);
+        data_transfer.add_file(path);
+        self.data_transfer = Some(data_transfer);
+        self.last_drag_over = None;
+        self.handle_drag_move();
+    }
+
+    fn handle_hovered_file_cancelled(&mut self) {
+        let data_transfer = match self.data_transfer.take() {
+            Some(dt) => dt,
+            None => return,
+        };
+        let (frame_x, frame_y, screen_x, screen_y) = self.get_pointer_position();
+        if let Some(mut target) = self.last_drag_over.take() {
+            emit_drag_event(&mut target, "dragleave", &data_transfer, frame_x, frame_y, screen_x, screen_y);
+        }
+        self.window.set_cursor(Cursor::Icon(CursorIcon::Default));
+        data_transfer.release();
+    }
+
+    fn handle_dropped_file(&mut self, path: String) {
+        if self.dragging {
+            return;
+        }
+        if let Some(dt) = &mut self.data_transfer {
+            dt.add_file(path);
+            if !dt.is_dropped() {
+                dt.set_dropped();
+                let dt = dt.clone();
+                self.finish_drag(dt, None);
+            }
+            return;
+        }
+        // Some platforms report dropped files without hovering
+        let mut data_transfer = DataTransfer::new(true);
+        data_transfer.add_file(path);
+        data_transfer.reset_drop_effect();
+        data_transfer.set_dropped();
+        self.last_drag_over = self.get_node_by_point();
+        self.data_transfer = Some(data_transfer.clone());
+        self.finish_drag(data_transfer, None);
     }
 
     pub fn update(&mut self) {
@@ -580,27 +971,27 @@ impl FrameRef {
             }
         }
         self.paint();
+        let layout_changed = self.layout_dirty;
+        self.update_accessibility(layout_changed);
         self.layout_dirty = false;
         self.dirty = false;
     }
 
+    pub fn get_body(&self) -> Option<ElementRef> {
+        self.body.clone()
+    }
+
     pub fn set_body(&mut self, mut body: ElementRef) {
         body.set_window(Some(self.as_weak()));
         self.focusing = Some(body.clone());
+        self.update_ime();
 
         //TODO unbind when change body
         let myself = self.as_weak();
-        body.bind_caret_change(move |e, detail| {
+        body.bind_caret_change(move |e, _detail| {
             myself.upgrade_mut(|myself| {
                 if myself.focusing == Some(e.target.clone()) {
-                    let origin_ime_rect = &detail.origin_bounds;
-                    myself.window.set_ime_cursor_area(Position::Logical(LogicalPosition {
-                        x: origin_ime_rect.x as f64,
-                        y: origin_ime_rect.bottom() as f64,
-                    }), Size::Logical(LogicalSize {
-                        width: origin_ime_rect.width as f64,
-                        height: origin_ime_rect.height as f64
-                    }));
+                    myself.update_ime_cursor_area();
                 }
             });
         });
@@ -641,12 +1032,20 @@ impl FrameRef {
         };
         let start = SystemTime::now();
         let scale_factor = self.window.scale_factor() as f32;
+        let drag_image = self.data_transfer.as_ref()
+            .and_then(|dt| dt.get_drag_image())
+            .map(|di| (di.image.clone(), self.cursor_position.x as f32 - di.offset_x, self.cursor_position.y as f32 - di.offset_y));
         self.window.render(move |canvas| {
             canvas.save();
             if scale_factor != 1.0 {
                 canvas.scale((scale_factor, scale_factor));
             }
             draw_root(canvas, &mut body);
+            if let Some((image, x, y)) = &drag_image {
+                let mut paint = Paint::default();
+                paint.set_alpha_f(0.7);
+                canvas.draw_image(image, (*x, *y), Some(&paint));
+            }
             canvas.restore();
         });
         let _time = SystemTime::now().duration_since(start).unwrap();
@@ -659,45 +1058,37 @@ impl FrameRef {
     }
 
     fn get_node_by_point(&self) -> Option<ElementRef> {
-        let mut body = match self.body.clone() {
+        let body = match self.body.clone() {
             None => return None,
             Some(body) => body
         };
         let x = self.cursor_position.x as f32;
         let y = self.cursor_position.y as f32;
-        self.get_node_by_point_inner(&mut body, (x, y))
+        hit_test_root(&body, (x, y))
     }
 
     fn get_node_by_pos(&self, x: f32, y: f32) -> Option<ElementRef> {
-        let mut body = match self.body.clone() {
+        let body = match self.body.clone() {
             None => return None,
             Some(body) => body
         };
-        self.get_node_by_point_inner(&mut body, (x, y))
-    }
-
-    fn get_node_by_point_inner(&self, node: &mut ElementRef, point: (f32, f32)) -> Option<ElementRef> {
-        //TODO use clip path?
-        let bounds = node.get_bounds();
-        if bounds.contains_point(point.0, point.1){
-            let content_bounds = node.get_content_bounds().translate(bounds.x, bounds.y);
-            if content_bounds.contains_point(point.0, point.1) {
-                let p = (point.0 + node.get_scroll_left() - bounds.x, point.1 + node.get_scroll_top() - bounds.y);
-                for child in node.get_backend().get_children() {
-                    if let Some(n) = self.get_node_by_point_inner(&mut child.clone(), p) {
-                        return Some(n.clone());
-                    }
-                }
-            }
-            return Some(node.clone());
-        }
-        return None
+        hit_test_root(&body, (x, y))
     }
 
-    fn create_window(attributes: WindowAttributes) -> SkiaWindow {
+    fn create_window(frame_id: i32, attributes: WindowAttributes) -> (SkiaWindow, Adapter) {
+        let visible = attributes.visible;
         run_with_event_loop(|el| {
             //TODO support RenderBackedType parameter
-            SkiaWindow::new(el, attributes, RenderBackendType::SoftBuffer)
+            // accessibility adapter must be created before the window is shown
+            let window = SkiaWindow::new(el, attributes.with_visible(false), RenderBackendType::SoftBuffer);
+            let adapter = Adapter::with_direct_handlers(
+                window.winit_window(),
+                FrameAccessibilityHandler::new(frame_id),
+                FrameAccessibilityHandler::new(frame_id),
+                FrameAccessibilityHandler::new(frame_id),
+            );
+            window.set_visible(visible);
+            (window, adapter)
         })
     }
 
@@ -730,38 +1121,133 @@ impl DerefMut for FrameRef {
 fn draw_root(canvas: &Canvas, body: &mut ElementRef) {
     // draw background
     canvas.clear(Color::from_rgb(255, 255, 255));
-    draw_element(canvas, body);
+    // fixed elements are painted in the root stacking context, relative to the frame
+    let mut fixed_layers = Vec::new();
+    collect_fixed_elements(body, &mut |e| fixed_layers.push(PaintLayer::new(canvas, e)));
+    draw_stacking_context(canvas, body, fixed_layers);
     // print_tree(&body, "");
 }
 
-fn draw_element(canvas: &Canvas, element: &ElementRef) {
-    let bounds = element.get_bounds();
-    if let Some(lcb) = canvas.local_clip_bounds() {
-        if !lcb.intersects(&bounds.to_skia_rect()) {
+/// An element painted in the z-index order of its stacking context instead of the tree order
+struct PaintLayer {
+    element: ElementRef,
+    z_index: i32,
+    /// Canvas matrix and clip where the element is painted
+    matrix: M44,
+    clip: Option<IRect>,
+}
+
+impl PaintLayer {
+    fn new(canvas: &Canvas, element: ElementRef) -> Self {
+        Self {
+            z_index: element.get_z_index(),
+            element,
+            matrix: canvas.local_to_device(),
+            clip: canvas.device_clip_bounds(),
+        }
+    }
+
+    fn draw(&self, canvas: &Canvas) {
+        let Some(clip) = self.clip else {
             return;
+        };
+        canvas.session(|canvas| {
+            canvas.reset_matrix();
+            canvas.clip_irect(clip, SkClipOp::Intersect);
+            canvas.set_matrix(&self.matrix);
+            draw_stacking_context(canvas, &self.element, Vec::new());
+        });
+    }
+}
+
+/// Only walks subtrees with fixed elements, which are counted when styles or the tree change
+fn collect_fixed_elements<F: FnMut(ElementRef)>(element: &ElementRef, callback: &mut F) {
+    if !element.has_fixed_descendants() {
+        return;
+    }
+    for child in element.get_backend().get_children() {
+        if child.is_fixed() {
+            callback(child.clone());
+        }
+        collect_fixed_elements(&child, callback);
+    }
+}
+
+/// Draw an element which starts a stacking context, `layers` are extra layers of the context
+fn draw_stacking_context(canvas: &Canvas, element: &ElementRef, mut layers: Vec<PaintLayer>) {
+    canvas.session(|canvas| {
+        collect_paint_layers(canvas, element, &mut layers);
+    });
+    // stable sort keeps the tree order of layers with the same z-index
+    layers.sort_by_key(|l| l.z_index);
+    draw_element(canvas, element, &layers);
+}
+
+/// Collect descendants painted in the stacking context of `element`,
+/// the canvas is transformed and clipped the same way as `draw_element`
+fn collect_paint_layers(canvas: &Canvas, element: &ElementRef, layers: &mut Vec<PaintLayer>) {
+    if !element.has_stacking_descendants() {
+        return;
+    }
+    let bounds = element.get_bounds();
+    canvas.session(|canvas| {
+        enter_element(canvas, element, &bounds);
+        enter_children(canvas, element);
+        for child in element.get_backend().get_children() {
+            if child.is_fixed() {
+                // collected by the root
+            } else if child.is_stacking_context() {
+                layers.push(PaintLayer::new(canvas, child));
+            } else {
+                collect_paint_layers(canvas, &child, layers);
+            }
+        }
+    });
+}
+
+/// Translate the canvas to the left-top of `element`
+fn enter_element(canvas: &Canvas, element: &ElementRef, bounds: &Rect) {
+    canvas.translate((bounds.x, bounds.y));
+    if let Some(m) = element.layout.transform {
+        //TODO support transform origin
+        canvas.translate((bounds.width / 2.0, bounds.height / 2.0));
+        canvas.concat(&m);
+        canvas.translate((-bounds.width / 2.0, -bounds.height / 2.0));
+    }
+}
+
+/// Clip the canvas to the content box of `element` unless its overflow is visible, then scroll to its children
+fn enter_children(canvas: &Canvas, element: &ElementRef) {
+    if element.layout.overflow != Overflow::Visible {
+        canvas.clip_path(&element.get_border_box_path(), SkClipOp::Intersect, true);
+        canvas.clip_path(&element.get_content_box_path(), SkClipOp::Intersect, true);
+    }
+    canvas.translate((-element.get_scroll_left(), -element.get_scroll_top()));
+}
+
+/// Draw `element` and its descendants in the same stacking context,
+/// `layers` are sorted layers of the stacking context started by `element`
+fn draw_element(canvas: &Canvas, element: &ElementRef, layers: &[PaintLayer]) {
+    let bounds = element.get_bounds();
+    // descendants may overflow if overflow is visible
+    if element.layout.overflow != Overflow::Visible {
+        if let Some(lcb) = canvas.local_clip_bounds() {
+            if !lcb.intersects(&bounds.to_skia_rect()) {
+                return;
+            }
         }
     }
     canvas.session(move |canvas| {
 
         // translate to element left-top
-        canvas.translate((bounds.x, bounds.y));
-        if let Some(m) = element.layout.transform {
-            //TODO support transform origin
-            canvas.translate((bounds.width / 2.0, bounds.height / 2.0));
-            canvas.concat(&m);
-            canvas.translate((-bounds.width / 2.0, -bounds.height / 2.0));
-        }
+        enter_element(canvas, element, &bounds);
 
-        // set clip path
+        // draw background, border and content in border box
+        canvas.save();
         let clip_path = element.get_border_box_path();
         canvas.clip_path(&clip_path, SkClipOp::Intersect, true);
-
-        // draw background and border
         element.draw_background(&canvas);
         element.draw_border(&canvas);
-
-        // draw padding box and content box
-        canvas.save();
         if bounds.width > 0.0 && bounds.height > 0.0 {
             let (border_top_width, _, _, border_left_width) = element.get_border_width();
             let (padding_top, _, _, padding_left) = element.get_padding();
@@ -771,14 +1257,207 @@ fn draw_element(canvas: &Canvas, element: &ElementRef) {
         }
         canvas.restore();
 
-        // draw children
-        let content_path = element.get_content_box_path();
-        canvas.clip_path(&content_path, SkClipOp::Intersect, true);
-        canvas.translate((-element.get_scroll_left(), -element.get_scroll_top()));
+        // draw children, layers with negative z-index are under in-flow children
+        let (negative_layers, layers) = layers.split_at(layers.partition_point(|l| l.z_index < 0));
+        for layer in negative_layers {
+            layer.draw(canvas);
+        }
+        canvas.save();
+        enter_children(canvas, element);
         for child_rc in element.get_backend().get_children() {
-            draw_element(canvas, &child_rc);
+            if !child_rc.is_stacking_context() {
+                draw_element(canvas, &child_rc, &[]);
+            }
+        }
+        canvas.restore();
+        for layer in layers {
+            layer.draw(canvas);
+        }
+
+        // draw foreground
+        if bounds.width > 0.0 && bounds.height > 0.0 {
+            let (border_top_width, _, _, border_left_width) = element.get_border_width();
+            let (padding_top, _, _, padding_left) = element.get_padding();
+            canvas.clip_path(&clip_path, SkClipOp::Intersect, true);
+            canvas.translate((padding_left + border_left_width, padding_top + border_top_width));
+            element.get_backend().draw_foreground(canvas);
+        }
+    });
+}
+
+/// Like `PaintLayer`, `bounds` and `clip` are in frame coordinates and `None` means not clipped
+struct HitTestLayer {
+    element: ElementRef,
+    z_index: i32,
+    bounds: Rect,
+    clip: Option<Rect>,
+}
+
+impl HitTestLayer {
+    fn new(element: ElementRef, bounds: Rect, clip: Option<Rect>) -> Self {
+        Self {
+            z_index: element.get_z_index(),
+            element,
+            bounds,
+            clip,
+        }
+    }
+
+    fn hit_test(&self, point: (f32, f32)) -> Option<ElementRef> {
+        hit_test_stacking_context(&self.element, &self.bounds, &self.clip, Vec::new(), point)
+    }
+}
+
+/// Find the topmost element at `point` of the frame, which follows the painting order.
+/// Bounds of elements are passed down from the root instead of being computed from ancestors
+pub(crate) fn hit_test_root(body: &ElementRef, point: (f32, f32)) -> Option<ElementRef> {
+    let mut fixed_layers = Vec::new();
+    // bounds of fixed elements are relative to the frame
+    collect_fixed_elements(body, &mut |e| {
+        let bounds = e.get_bounds();
+        fixed_layers.push(HitTestLayer::new(e, bounds, None))
+    });
+    hit_test_stacking_context(body, &body.get_bounds(), &None, fixed_layers, point)
+}
+
+/// Hit test in the reverse order of `draw_stacking_context`
+fn hit_test_stacking_context(element: &ElementRef, bounds: &Rect, clip: &Option<Rect>, mut layers: Vec<HitTestLayer>, point: (f32, f32)) -> Option<ElementRef> {
+    if hit_test_foreground(element, bounds, clip, point) {
+        return Some(element.clone());
+    }
+    collect_hit_test_layers(element, bounds, clip, &mut layers, point);
+    layers.sort_by_key(|l| l.z_index);
+    let (negative_layers, layers) = layers.split_at(layers.partition_point(|l| l.z_index < 0));
+    layers.iter().rev().find_map(|l| l.hit_test(point))
+        .or_else(|| hit_test_children(element, bounds, clip, point))
+        .or_else(|| negative_layers.iter().rev().find_map(|l| l.hit_test(point)))
+        .or_else(|| hit_test_self(bounds, clip, point).then(|| element.clone()))
+}
+
+fn collect_hit_test_layers(element: &ElementRef, bounds: &Rect, clip: &Option<Rect>, layers: &mut Vec<HitTestLayer>, point: (f32, f32)) {
+    if !element.has_stacking_descendants() {
+        return;
+    }
+    let Some(clip) = children_clip(element, bounds, clip, point) else {
+        return;
+    };
+    let (x, y) = children_origin(element, bounds);
+    for child in element.get_backend().get_children() {
+        let child_bounds = child.get_bounds().translate(x, y);
+        if child.is_fixed() {
+            // collected by the root
+        } else if child.is_stacking_context() {
+            layers.push(HitTestLayer::new(child, child_bounds, clip.clone()));
+        } else {
+            collect_hit_test_layers(&child, &child_bounds, &clip, layers, point);
+        }
+    }
+}
+
+/// Hit test in-flow descendants of `element`, the last painted one first
+fn hit_test_children(element: &ElementRef, bounds: &Rect, clip: &Option<Rect>, point: (f32, f32)) -> Option<ElementRef> {
+    let clip = children_clip(element, bounds, clip, point)?;
+    let (x, y) = children_origin(element, bounds);
+    for child in element.get_backend().get_children().iter().rev() {
+        if child.is_stacking_context() {
+            continue;
+        }
+        let child_bounds = child.get_bounds().translate(x, y);
+        if hit_test_foreground(child, &child_bounds, &clip, point) {
+            return Some(child.clone());
+        }
+        if let Some(e) = hit_test_children(child, &child_bounds, &clip, point) {
+            return Some(e);
+        }
+        if hit_test_self(&child_bounds, &clip, point) {
+            return Some(child.clone());
         }
+    }
+    None
+}
+
+fn hit_test_self(bounds: &Rect, clip: &Option<Rect>, point: (f32, f32)) -> bool {
+    //TODO use clip path?
+    let in_clip = clip.as_ref().map(|c| c.contains_point(point.0, point.1)).unwrap_or(true);
+    in_clip && bounds.contains_point(point.0, point.1)
+}
+
+/// The foreground is painted over children and layers, so it is hit first
+fn hit_test_foreground(element: &ElementRef, bounds: &Rect, clip: &Option<Rect>, point: (f32, f32)) -> bool {
+    if !hit_test_self(bounds, clip, point) {
+        return false;
+    }
+    // the foreground is drawn in the content box
+    let (border_top, _, _, border_left) = element.get_border_width();
+    let (padding_top, _, _, padding_left) = element.get_padding();
+    let x = point.0 - bounds.x - border_left - padding_left;
+    let y = point.1 - bounds.y - border_top - padding_top;
+    element.get_backend().is_foreground_at(x, y)
+}
+
+/// Frame position which bounds of children are relative to, it moves with scrolling
+fn children_origin(element: &ElementRef, bounds: &Rect) -> (f32, f32) {
+    (bounds.x - element.get_scroll_left(), bounds.y - element.get_scroll_top())
+}
+
+/// Clip of children of `element` in frame coordinates, returns `None` if `point` is clipped out
+fn children_clip(element: &ElementRef, bounds: &Rect, clip: &Option<Rect>, point: (f32, f32)) -> Option<Option<Rect>> {
+    let clip = if element.layout.overflow == Overflow::Visible {
+        clip.clone()
+    } else {
+        let content_bounds = element.get_content_bounds().translate(bounds.x, bounds.y);
+        Some(clip.as_ref().map(|c| c.intersect(&content_bounds)).unwrap_or(content_bounds))
+    };
+    match &clip {
+        Some(c) if !c.contains_point(point.0, point.1) => None,
+        _ => Some(clip),
+    }
+}
+
+/// Tabbable elements in focus order, elements with positive tabIndex come first
+fn tab_order(body: &ElementRef) -> Vec<ElementRef> {
+    let mut elements = Vec::new();
+    collect_tabbable(body, &mut elements);
+    // stable sort keeps document order for elements with same tabIndex
+    elements.sort_by_key(|e| {
+        let tab_index = e.get_tab_index().unwrap_or(0);
+        if tab_index > 0 { tab_index } else { i32::MAX }
     });
+    elements
+}
+
+fn next_tab_target(order: &[ElementRef], current: Option<&ElementRef>, backward: bool) -> Option<ElementRef> {
+    if order.is_empty() {
+        return None;
+    }
+    let current = current.and_then(|f| order.iter().position(|e| e == f));
+    let len = order.len();
+    let next = match current {
+        None => if backward { len - 1 } else { 0 },
+        Some(idx) => if backward { (idx + len - 1) % len } else { (idx + 1) % len },
+    };
+    Some(order[next].clone())
+}
+
+fn collect_tabbable(node: &ElementRef, result: &mut Vec<ElementRef>) {
+    if node.is_tabbable() {
+        result.push(node.clone());
+    }
+    for c in node.get_children() {
+        collect_tabbable(&c, result);
+    }
+}
+
+fn mouse_wheel_detail(delta: MouseScrollDelta, scale_factor: f64) -> MouseWheelDetail {
+    match delta {
+        MouseScrollDelta::LineDelta(x, y) => {
+            MouseWheelDetail::from_lines(x, y)
+        }
+        MouseScrollDelta::PixelDelta(position) => {
+            let position = position.to_logical::<f32>(scale_factor);
+            MouseWheelDetail::from_pixels(position.x, position.y)
+        }
+    }
 }
 
 fn print_tree(node: &ElementRef, padding: &str) {
@@ -820,13 +1499,224 @@ fn emit_mouse_event(node: &mut ElementRef, event_type: &str, event_type_enum: Mo
         MouseEventType::MouseMove => node.emit_mouse_move(detail),
         MouseEventType::MouseEnter => node.emit_mouse_enter(detail),
         MouseEventType::MouseLeave => node.emit_mouse_leave(detail),
+        MouseEventType::ContextMenu => node.emit_context_menu(detail),
     }
 }
 
+fn emit_composition_event(node: &mut ElementRef, event_type: &str, data: String) {
+    let event = ElementEvent::new(event_type, CompositionEventDetail { data }, node.clone());
+    node.emit_event(event_type, event);
+}
+
+fn emit_drag_event(node: &mut ElementRef, event_type: &str, data_transfer: &DataTransfer, frame_x: f32, frame_y: f32, screen_x: f32, screen_y: f32) {
+    let node_bounds = node.get_origin_bounds();
+    let (border_top, _, _, border_left) = node.get_border_width();
+    let detail = DragEventDetail {
+        offset_x: frame_x - node_bounds.x - border_left,
+        offset_y: frame_y - node_bounds.y - border_top,
+        frame_x,
+        frame_y,
+        screen_x,
+        screen_y,
+        data_transfer: data_transfer.clone(),
+    };
+    let event = ElementEvent::new(event_type, detail, node.clone());
+    node.emit_event(event_type, event);
+}
+
 pub fn frame_input(frame_id: i32, content: String) {
-    FRAMES.with_borrow_mut(|m| {
-        if let Some(f) = m.get_mut(&frame_id) {
-            f.handle_input(&content);
+    with_frame(frame_id, |f| f.handle_input(&content));
+}
+
+pub fn with_frame<F: FnOnce(&mut FrameRef)>(frame_id: i32, callback: F) {
+    let frame = FRAMES.with_borrow(|m| m.get(&frame_id).map(|f| f.as_weak()));
+    if let Some(f) = frame {
+        f.upgrade_mut(callback);
+    }
+}
+
+#[test]
+fn test_tab_order() {
+    use crate::element::ElementBackend;
+    use crate::element::container::Container;
+    use crate::style::StyleProp;
+    let mut body = ElementRef::new(Container::create);
+    let mut children = Vec::new();
+    for tab_index in [0, -1, 2, 0, 1] {
+        let mut child = ElementRef::new(Container::create);
+        child.set_style_props(vec![StyleProp::parse("height", "10").unwrap()]);
+        child.set_tab_index(tab_index);
+        body.add_child_view(child.clone(), None);
+        children.push(child);
+    }
+    // hidden elements are skipped
+    let mut hidden = ElementRef::new(Container::create);
+    hidden.set_tab_index(0);
+    body.add_child_view(hidden.clone(), None);
+    body.calculate_layout(100.0, 100.0);
+
+    let order = tab_order(&body);
+    let expected = vec![children[4].clone(), children[2].clone(), children[0].clone(), children[3].clone()];
+    assert!(order == expected);
+
+    // Tab
+    assert!(next_tab_target(&order, None, false) == Some(children[4].clone()));
+    assert!(next_tab_target(&order, Some(&children[2]), false) == Some(children[0].clone()));
+    assert!(next_tab_target(&order, Some(&children[3]), false) == Some(children[4].clone()));
+    // Shift+Tab
+    assert!(next_tab_target(&order, None, true) == Some(children[3].clone()));
+    assert!(next_tab_target(&order, Some(&children[4]), true) == Some(children[3].clone()));
+    // elements with negative tabIndex are focusable but not tabbable, Tab continues from the start
+    assert!(next_tab_target(&order, Some(&children[1]), false) == Some(children[4].clone()));
+    assert!(next_tab_target(&[], None, false).is_none());
+}
+
+#[test]
+fn test_focus_visible_style() {
+    use crate::element::ElementBackend;
+    use crate::element::container::Container;
+    use crate::style::StyleProp;
+    let default_width = yoga::Node::new().get_style_width();
+    let mut element = ElementRef::new(Container::create);
+    element.set_focus_style_props(vec![StyleProp::parse("width", "20").unwrap()]);
+    assert_eq!(default_width, element.layout.get_style_width());
+    // focused by keyboard
+    element.set_focus_visible(true);
+    assert_eq!(yoga::StyleUnit::Point(ordered_float::OrderedFloat(20.0)), element.layout.get_style_width());
+    // focused by mouse or blurred
+    element.set_focus_visible(false);
+    assert_eq!(default_width, element.layout.get_style_width());
+}
+
+#[test]
+fn test_mouse_wheel_detail() {
+    use crate::element::scroll_animation::LINE_SCROLL_DISTANCE;
+    use winit::dpi::PhysicalPosition;
+    let detail = mouse_wheel_detail(MouseScrollDelta::LineDelta(0.0, -2.0), 2.0);
+    assert_eq!(-2.0, detail.rows);
+    assert_eq!(-2.0 * LINE_SCROLL_DISTANCE, detail.delta_y);
+    assert!(!detail.precise);
+
+    // pixel deltas are physical, they are converted to logical pixels and fractional rows
+    let position = PhysicalPosition::new(0.0, 60.0);
+    let detail = mouse_wheel_detail(MouseScrollDelta::PixelDelta(position), 2.0);
+    assert_eq!(30.0, detail.delta_y);
+    assert_eq!(30.0 / LINE_SCROLL_DISTANCE, detail.rows);
+    assert_eq!(0.0, detail.cols);
+    assert!(detail.precise);
+}
+
+#[cfg(test)]
+fn create_test_element(styles: &[(&str, &str)]) -> ElementRef {
+    use crate::element::ElementBackend;
+    use crate::element::container::Container;
+    use crate::style::StyleProp;
+    let mut element = ElementRef::new(Container::create);
+    element.set_style_props(styles.iter().map(|(k, v)| StyleProp::parse(k, v).unwrap()).collect());
+    element
+}
+
+#[test]
+fn test_z_index_order() {
+    use skia_safe::{surfaces, AlphaType};
+    let create = |left: &str, top: &str, size: &str, color: &str, z_index: Option<&str>| {
+        let mut styles = vec![
+            ("position", "absolute"), ("left", left), ("top", top),
+            ("width", size), ("height", size), ("backgroundColor", color),
+        ];
+        if let Some(z) = z_index {
+            styles.push(("zIndex", z));
         }
-    });
+        create_test_element(&styles)
+    };
+    let mut body = create_test_element(&[("width", "100"), ("height", "100")]);
+    // positive layers are over in-flow elements, which are over negative layers
+    let a = create("0", "0", "50", "#ff0000", Some("1"));
+    let mut b = create("25", "25", "50", "#00ff00", None);
+    let c = create("40", "40", "50", "#0000ff", Some("-1"));
+    // in the stacking context of body, because b doesn't start one
+    let mut d = create("0", "0", "10", "#ffff00", Some("5"));
+    b.add_child_view(d.clone(), None);
+    body.add_child_view(a.clone(), None);
+    body.add_child_view(b.clone(), None);
+    body.add_child_view(c.clone(), None);
+    body.calculate_layout(100.0, 100.0);
+    assert!(body.has_stacking_descendants());
+    assert!(!body.has_fixed_descendants());
+
+    let mut surface = surfaces::raster_n32_premul((100, 100)).unwrap();
+    draw_root(surface.canvas(), &mut body);
+    let mut pixel = |x: i32, y: i32| {
+        let info = ImageInfo::new((1, 1), ColorType::RGBA8888, AlphaType::Unpremul, None);
+        let mut bytes = [0u8; 4];
+        surface.read_pixels(&info, &mut bytes, 4, (x, y));
+        Color::from_rgb(bytes[0], bytes[1], bytes[2])
+    };
+    let expected = [
+        ((10, 10), &a, Color::from_rgb(255, 0, 0)),
+        ((30, 30), &d, Color::from_rgb(255, 255, 0)),
+        ((45, 45), &a, Color::from_rgb(255, 0, 0)),
+        ((60, 60), &b, Color::from_rgb(0, 255, 0)),
+        ((85, 85), &c, Color::from_rgb(0, 0, 255)),
+        ((95, 5), &body, Color::from_rgb(255, 255, 255)),
+    ];
+    for ((x, y), element, color) in expected {
+        assert_eq!(color, pixel(x, y));
+        assert!(hit_test_root(&body, (x as f32, y as f32)) == Some(element.clone()));
+    }
+
+    // fixed elements are counted when styles and the tree change
+    d.set_style_props(vec![crate::style::StyleProp::parse("position", "fixed").unwrap()]);
+    assert!(body.has_fixed_descendants());
+    body.remove_child_view(1);
+    assert!(!body.has_fixed_descendants());
+    body.remove_child_view(1);
+    assert!(body.has_stacking_descendants());
+    body.remove_child_view(0);
+    assert!(!body.has_stacking_descendants());
+}
+
+#[test]
+fn test_hit_test_overflow() {
+    use crate::style::StyleProp;
+    assert!(StyleProp::parse("overflow", "clip-path").is_none());
+
+    let mut body = create_test_element(&[("width", "100"), ("height", "100")]);
+    let mut container = create_test_element(&[("width", "50"), ("height", "50"), ("overflow", "visible")]);
+    let child = create_test_element(&[
+        ("position", "absolute"), ("left", "60"), ("top", "60"), ("width", "20"), ("height", "20"),
+    ]);
+    container.add_child_view(child.clone(), None);
+    body.add_child_view(container.clone(), None);
+    body.calculate_layout(100.0, 100.0);
+    // visible overflow is hit out of the container
+    assert!(hit_test_root(&body, (70.0, 70.0)) == Some(child.clone()));
+    assert!(hit_test_root(&body, (10.0, 10.0)) == Some(container.clone()));
+
+    // auto clips like scroll
+    container.set_style_props(vec![
+        StyleProp::parse("width", "50").unwrap(),
+        StyleProp::parse("height", "50").unwrap(),
+        StyleProp::parse("overflow", "auto").unwrap(),
+    ]);
+    body.calculate_layout(100.0, 100.0);
+    assert!(hit_test_root(&body, (70.0, 70.0)) == Some(body.clone()));
+
+    // children of a scrolled container move with scrolling
+    let mut body = create_test_element(&[("width", "100"), ("height", "100")]);
+    let mut list = create_test_element(&[("width", "100"), ("height", "50")]);
+    let mut items = Vec::new();
+    for _ in 0..3 {
+        let item = create_test_element(&[("height", "40"), ("flexShrink", "0")]);
+        list.add_child_view(item.clone(), None);
+        items.push(item);
+    }
+    body.add_child_view(list.clone(), None);
+    body.calculate_layout(100.0, 100.0);
+    list.set_scroll_top(30.0);
+    assert!(hit_test_root(&body, (10.0, 5.0)) == Some(items[0].clone()));
+    assert!(hit_test_root(&body, (10.0, 15.0)) == Some(items[1].clone()));
+    assert!(hit_test_root(&body, (10.0, 45.0)) == Some(items[2].clone()));
+    // the last item is clipped out of the list
+    assert!(hit_test_root(&body, (10.0, 60.0)) == Some(body.clone()));
 }
@@ -2,9 +2,11 @@ use std::collections::HashMap;
 use std::ffi::c_void;
 use std::rc::{Rc, Weak};
 use anyhow::Error;
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
 use image::{DynamicImage, EncodableLayout, ImageReader};
 use libc::memcpy;
-use skia_safe::{AlphaType, Bitmap, ColorSpace, ColorType, Image, ImageInfo};
+use skia_safe::{images, AlphaType, Bitmap, ColorSpace, ColorType, Data, Image, ImageInfo};
 use std::borrow::Borrow;
 use std::cell::RefCell;
 
@@ -31,7 +33,11 @@ impl ImgManager {
                 return Ok(ii);
             }
         }
-        let img = ImageReader::open(path)?.decode()?;
+        let img = if let Some(data) = parse_base64_data_url(path) {
+            image::load_from_memory(&BASE64_STANDARD.decode(data)?)?
+        } else {
+            ImageReader::open(path)?.decode()?
+        };
         let sk_img = dyn_image_to_skia_image(&img);
         let rc = Rc::new(sk_img.clone());
         self.cache.borrow_mut().insert(path.to_string(), Rc::downgrade(&rc));
@@ -52,6 +58,23 @@ impl ImgManager {
     }
 }
 
+/// Return the base64 part of url like `data:image/png;base64,...`
+fn parse_base64_data_url(url: &str) -> Option<&str> {
+    let data = url.strip_prefix("data:image/")?;
+    let (_, data) = data.split_once(";base64,")?;
+    Some(data)
+}
+
+/// Create skia image from RGBA8 pixels
+pub fn rgba_to_skia_image(width: i32, height: i32, bytes: &[u8]) -> Option<Image> {
+    let image_info = ImageInfo::new((width, height), ColorType::RGBA8888, AlphaType::Unpremul, ColorSpace::new_srgb());
+    let row_bytes = width as usize * 4;
+    if bytes.len() < row_bytes * height as usize {
+        return None;
+    }
+    images::raster_from_data(&image_info, Data::new_copy(bytes), row_bytes)
+}
+
 pub fn dyn_image_to_skia_image(src: &DynamicImage) -> Image {
     let width = src.width() as i32;
     let height = src.height() as i32;
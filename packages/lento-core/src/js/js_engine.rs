@@ -12,18 +12,24 @@ use crate::{export_js_api, export_js_async_api, export_js_object_api};
 use crate::app::exit_app;
 use crate::base::Size;
 use crate::console::Console;
-use crate::element::{element_create, ElementRef};
+use crate::data_transfer::{data_transfer_clear_data, data_transfer_set_data, data_transfer_set_drag_image, data_transfer_set_drop_effect, data_transfer_set_effect_allowed};
+use crate::element::batch::view_batch;
+use crate::element::{register_element, view_create, ElementBackend, ElementRef};
+use crate::element::text::search::SearchQuery;
 use crate::animation::{AnimationResource};
 use crate::ext::ext_appfs::{appfs_create_dir, appfs_create_dir_all, appfs_data_path, appfs_delete_file, appfs_exists, appfs_read, appfs_readdir, appfs_remove_dir, appfs_remove_dir_all, appfs_write, appfs_write_new};
 use crate::ext::ext_audio::{audio_add_event_listener, audio_create, audio_stop, audio_remove_event_listener, AudioResource, audio_play, audio_pause, AudioOptions};
 use crate::ext::ext_base64::base64_encode_str;
+use crate::ext::ext_clipboard::{clipboard_load_image, clipboard_read_html, clipboard_read_image, clipboard_read_text, clipboard_write_html, clipboard_write_image, clipboard_write_text};
 use crate::ext::ext_dialog::{dialog_show_file_dialog, FileDialogOptions};
 use crate::ext::ext_env::{env_exe_dir, env_exe_path};
 use crate::ext::ext_fetch::{fetch_create, fetch_response_body_string, fetch_response_headers, fetch_response_save, fetch_response_status, FetchOptions, FetchResponse};
-use crate::ext::ext_frame::{create_frame, frame_close, frame_set_modal, FrameAttrs, handle_window_event};
+use crate::ext::ext_frame::{create_frame, frame_close, frame_set_modal, frame_set_popup, FrameAttrs, handle_window_event};
 use crate::ext::ext_fs::{fs_create_dir, fs_create_dir_all, fs_delete_file, fs_exists, fs_read_dir, fs_remove_dir, fs_remove_dir_all, fs_rename, fs_stat};
 use crate::ext::ext_http::{http_request, http_upload, UploadOptions};
 use crate::ext::ext_localstorage::{localstorage_get, localstorage_set};
+use crate::ext::ext_canvas::{canvas_draw, canvas_get_image_data, canvas_measure_text, ImageDataRect};
+use crate::ext::ext_spellcheck::{spellcheck_load_dictionary, spellcheck_suggest, spellcheck_unload_dictionary};
 use crate::ext::ext_path::{path_filename, path_join};
 use crate::ext::ext_shell::shell_spawn;
 use crate::ext::ext_timer::{timer_clear_interval, timer_clear_timeout, timer_set_interval, timer_set_timeout};
@@ -93,6 +99,7 @@ impl JsEngine {
         // frame
         export_js_api!(js_context, "frame_create", create_frame, FrameAttrs);
         export_js_api!(js_context, "frame_set_modal", frame_set_modal, FrameWeak, FrameWeak);
+        export_js_api!(js_context, "frame_set_popup", frame_set_popup, FrameWeak, FrameWeak);
         export_js_api!(js_context, "frame_close", frame_close, FrameWeak);
         export_js_object_api!(js_context, "frame_set_body", FrameWeak, set_body, ElementRef);
         export_js_object_api!(js_context, "frame_set_title", FrameWeak, set_title, String);
@@ -102,13 +109,18 @@ impl JsEngine {
         export_js_object_api!(js_context, "frame_remove_event_listener", FrameWeak, remove_event_listener, String, u32);
 
         // view
-        export_js_api!(js_context, "view_create", element_create, i32);
+        export_js_api!(js_context, "view_create", view_create, JsValue);
+        export_js_api!(js_context, "view_batch", view_batch, JsValue);
         export_js_object_api!(js_context, "view_set_property",  ElementRef, set_property, String, JsValue);
         export_js_object_api!(js_context, "view_get_property", ElementRef, get_property, String);
         export_js_object_api!(js_context, "view_add_child", ElementRef, add_child, ElementRef, i32);
         export_js_object_api!(js_context, "view_remove_child", ElementRef, remove_child, u32);
         export_js_object_api!(js_context, "view_set_style", ElementRef, set_style, JsValue);
         export_js_object_api!(js_context, "view_set_hover_style", ElementRef, set_hover_style, JsValue);
+        export_js_object_api!(js_context, "view_set_focus_style", ElementRef, set_focus_style, JsValue);
+        export_js_object_api!(js_context, "view_focus", ElementRef, focus);
+        export_js_object_api!(js_context, "view_blur", ElementRef, blur);
+        export_js_object_api!(js_context, "view_search", ElementRef, search, SearchQuery);
         export_js_object_api!(js_context, "view_set_animation", ElementRef, set_animation, AnimationResource);
         export_js_object_api!(js_context, "view_bind_event",ElementRef, bind_event, String, JsValue);
         export_js_object_api!(js_context, "view_remove_event_listener",ElementRef, remove_event_listener, String, u32);
@@ -194,6 +206,41 @@ impl JsEngine {
         //animation
         // export_js_api!(js_context, "animation_create", animation_create, String, JsValue);
 
+        // clipboard
+        export_js_async_api!(js_context, "clipboard_read_text", clipboard_read_text);
+        export_js_async_api!(js_context, "clipboard_write_text", clipboard_write_text, String);
+        export_js_async_api!(js_context, "clipboard_read_html", clipboard_read_html);
+        export_js_async_api!(js_context, "clipboard_write_html", clipboard_write_html, String, Option<String>);
+        export_js_async_api!(js_context, "clipboard_read_image", clipboard_read_image);
+        let ctx = js_context.clone();
+        js_context.add_callback("clipboard_write_image", move |src: String| {
+            // The image is loaded before spawning because IMG_MANAGER is thread local
+            let image = clipboard_load_image(&src)?;
+            let mut ctx = ctx.clone();
+            let result = ctx.create_async_task(async move {
+                clipboard_write_image(image).await?;
+                Ok(JsValue::Undefined)
+            });
+            Ok::<_, anyhow::Error>(result)
+        }).unwrap();
+
+        // spell check
+        export_js_async_api!(js_context, "spellcheck_load_dictionary", spellcheck_load_dictionary, String);
+        export_js_async_api!(js_context, "spellcheck_unload_dictionary", spellcheck_unload_dictionary);
+        export_js_async_api!(js_context, "spellcheck_suggest", spellcheck_suggest, String);
+
+        // canvas
+        export_js_api!(js_context, "canvas_draw", canvas_draw, ElementRef, JsValue);
+        export_js_api!(js_context, "canvas_get_image_data", canvas_get_image_data, ElementRef, ImageDataRect);
+        export_js_api!(js_context, "canvas_measure_text", canvas_measure_text, ElementRef, String);
+
+        // drag and drop
+        export_js_api!(js_context, "data_transfer_set_data", data_transfer_set_data, u32, String, String);
+        export_js_api!(js_context, "data_transfer_clear_data", data_transfer_clear_data, u32, Option<String>);
+        export_js_api!(js_context, "data_transfer_set_effect_allowed", data_transfer_set_effect_allowed, u32, String);
+        export_js_api!(js_context, "data_transfer_set_drop_effect", data_transfer_set_drop_effect, u32, String);
+        export_js_api!(js_context, "data_transfer_set_drag_image", data_transfer_set_drag_image, u32, String, f32, f32);
+
         // base64
         export_js_api!(js_context, "base64_encode_str", base64_encode_str, String);
 
@@ -220,6 +267,11 @@ impl JsEngine {
         }).unwrap();
     }
 
+    /// Register a custom element backend, which is created by `new CustomElement(name)` in js
+    pub fn register_element<T: ElementBackend + 'static>(&mut self, name: &str) {
+        register_element::<T>(name);
+    }
+
     pub fn execute_main(&mut self) {
         self.js_context.execute_main();
     }
@@ -246,4 +298,28 @@ impl JsEngine {
         }
     }
 
-}
\ No newline at end of file
+}
+#[test]
+fn test_menu_layout_and_navigation() {
+    let context = Context::new().unwrap();
+    let libjs = String::from_utf8_lossy(include_bytes!("../../lib.js"));
+    context.eval_module(&libjs, "lib.js").unwrap();
+    context.eval("globalThis.items = [
+        {label: 'Cut'},
+        {separator: true},
+        {label: 'Copy', disabled: true},
+        {label: 'Paste'},
+    ]").unwrap();
+    let eval_i32 = |code: &str| context.eval_as::<i32>(code).unwrap();
+
+    // paddings, borders, three items and a separator
+    assert_eq!(4 * 2 + 2 + 28 * 3 + 9, eval_i32("Menu.measureHeight(items)"));
+    assert_eq!(5 + 28 + 9 + 28, eval_i32("Menu.itemTop(items, 3)"));
+
+    // separators and disabled items are skipped, moving wraps around
+    assert_eq!(0, eval_i32("Menu.nextSelectableIndex(items, -1, 1)"));
+    assert_eq!(3, eval_i32("Menu.nextSelectableIndex(items, 0, 1)"));
+    assert_eq!(0, eval_i32("Menu.nextSelectableIndex(items, 3, 1)"));
+    assert_eq!(3, eval_i32("Menu.nextSelectableIndex(items, 0, -1)"));
+    assert_eq!(-1, eval_i32("Menu.nextSelectableIndex([{separator: true}], -1, 1)"));
+}
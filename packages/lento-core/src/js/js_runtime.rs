@@ -229,6 +229,16 @@ impl FromJsValue for TextAlign {
     }
 }
 
+impl FromJsValue for i32 {
+    fn from_js_value(value: &JsValue) -> Option<Self> {
+        match value {
+            JsValue::Int(i) => Some(*i),
+            JsValue::Float(f) => Some(*f as i32),
+            _ => None
+        }
+    }
+}
+
 impl FromJsValue for usize {
     fn from_js_value(value: &JsValue) -> Option<Self> {
         if let JsValue::Int(i) = value {
@@ -274,6 +284,26 @@ impl FromJsValue for (usize, usize) {
     }
 }
 
+impl FromJsValue for (String, String) {
+    fn from_js_value(value: &JsValue) -> Option<Self> {
+        match value {
+            JsValue::Array(a) if a.len() == 2 => {
+                Some((String::from_js_value(&a[0])?, String::from_js_value(&a[1])?))
+            }
+            _ => None
+        }
+    }
+}
+
+impl FromJsValue for Vec<(String, String)> {
+    fn from_js_value(value: &JsValue) -> Option<Self> {
+        match value {
+            JsValue::Array(a) => a.iter().map(|e| <(String, String)>::from_js_value(e)).collect(),
+            _ => None
+        }
+    }
+}
+
 impl FromJsValue for CursorIcon {
     fn from_js_value(value: &JsValue) -> Option<Self> {
         match value {
@@ -2,27 +2,28 @@
 
 use std::collections::HashMap;
 use std::env;
+use std::rc::Rc;
 use std::str::FromStr;
-use std::time::{SystemTime};
+use std::time::SystemTime;
 use futures_util::StreamExt;
 use measure_time::{info, print_time};
 use memory_stats::memory_stats;
 use quick_js::loader::FsJsModuleLoader;
 use serde::{Deserialize, Serialize};
 use skia_safe::{Font, Paint};
-use skia_safe::textlayout::{paragraph, TextAlign};
+use skia_safe::textlayout::{paragraph, TextAlign, TextDirection};
 use skia_window::skia_window::{RenderBackendType, SkiaWindow};
 use tokio_tungstenite::connect_async;
 use winit::application::ApplicationHandler;
 use winit::event::WindowEvent;
 use yoga::Node;
 use crate::app::{App, AppEvent, LentoApp};
-use crate::element::ScrollByOption;
+use crate::element::{ElementBackend, ElementRef, ScrollByOption};
 use crate::event_loop::set_event_proxy;
 use crate::js::js_deserialze::JsDeserializer;
 use crate::element::label::{AttributeText, DEFAULT_TYPE_FACE, Label};
 use crate::element::text::Text;
-use crate::element::text::text_paragraph::TextParams;
+use crate::element::text::text_paragraph::{OverflowWrap, TextParams, WordBreak};
 use crate::loader::{DefaultModuleLoader, RemoteModuleLoader, StaticModuleLoader};
 use crate::performance::MemoryUsage;
 use crate::renderer::CpuRenderer;
@@ -32,8 +33,11 @@ use winit::platform::android::activity::AndroidApp;
 use winit::window::{WindowAttributes, WindowId};
 use crate::data_dir::get_data_path;
 
+pub use anyhow;
+pub use skia_safe;
 pub use quick_js::JsValue;
 pub use winit::event_loop::{ActiveEventLoop, EventLoop, EventLoopBuilder, EventLoopProxy};
+pub mod accessibility;
 pub mod border;
 pub mod base;
 pub mod style;
@@ -57,6 +61,7 @@ pub mod string;
 pub mod canvas_util;
 pub mod event;
 pub mod cursor;
+pub mod data_transfer;
 pub mod img_manager;
 pub mod data_dir;
 pub mod macro_mod;
@@ -66,6 +71,7 @@ pub mod performance;
 
 pub mod cache;
 pub mod animation;
+pub mod ui;
 #[cfg(target_os = "android")]
 mod android;
 
@@ -115,15 +121,24 @@ fn test_layout() {
         paint,
         line_height: Some(14.0),
         align: Default::default(),
+        mask: None,
+        direction: TextDirection::LTR,
+        ellipsis: None,
+        word_break: WordBreak::Normal,
+        overflow_wrap: OverflowWrap::BreakWord,
+        tab_size: None,
+        highlighter: None,
     };
+    let params = Rc::new(params);
     let mut paragraph = {
         print_time!("build time");
         Text::build_lines(&text, &params, true)
     };
     {
         print_time!("layout time");
-        for mut it in &mut paragraph {
-            it.paragraph.layout(700.0);
+        for it in &mut paragraph {
+            it.set_layout_width(700.0);
+            it.paragraph();
         }
         let mem_use = memory_stats().unwrap().physical_mem as f32 - start_mem_use;
         println!("mem use:{}", mem_use / 1024.0 / 1024.0);
@@ -132,8 +147,8 @@ fn test_layout() {
     {
         print_time!("draw time");
         let mut lines = 0;
-        for it in paragraph {
-            it.paragraph.paint(renderer.canvas(), (0.0, 0.0));
+        for mut it in paragraph {
+            it.paragraph().paint(renderer.canvas(), (0.0, 0.0));
             lines += 1;
             if lines >= 100 {
                 break;
@@ -143,6 +158,37 @@ fn test_layout() {
 }
 
 
+// large text should be editable without laying out all lines
+#[test]
+fn test_large_text_layout() {
+    let mut text = String::new();
+    for i in 0..100_000 {
+        text.push_str(&format!("line {} of a large document\n", i));
+    }
+    let mut el = ElementRef::new(Text::create);
+    let label = el.get_backend_mut_as::<Text>();
+    label.set_text(text.clone());
+    assert_eq!(100_001, label.with_lines_mut(|lines| lines.len()));
+    assert!(label.with_lines_mut(|lines| lines.iter().all(|it| !it.is_laid_out())));
+
+    // typing in the middle of the document only rebuilds the edited line
+    let mut caret = label.get_atom_count() / 2;
+    let (row, col) = label.get_location_by_atom_offset(caret);
+    for i in 0..1000 {
+        label.insert_text(caret, "x");
+        caret += 1;
+        if i % 2 == 1 {
+            label.delete_text(caret - 1, caret);
+            caret -= 1;
+        }
+    }
+    assert_eq!(100_001, label.with_lines_mut(|lines| lines.len()));
+    assert_eq!((row, col + 500), label.get_location_by_atom_offset(caret));
+    assert_eq!(text.len() + 1 + 500, label.get_atom_count());
+    assert_eq!(text.len() + 500, label.get_text().len());
+    assert!(label.with_lines_mut(|lines| lines.iter().filter(|it| it.is_laid_out()).count()) <= 1);
+}
+
 #[test]
 fn test_text_measure() {
     let text = include_str!("../Cargo.lock");
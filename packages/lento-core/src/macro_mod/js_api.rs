@@ -2,6 +2,26 @@
 use quick_js::JsValue;
 #[macro_export]
 macro_rules! export_js_async_api {
+    ($js_ctx: expr, $name: expr, $func: ident) => {
+        {
+            let js_ctx = $js_ctx.clone();
+            $js_ctx.add_callback($name, move || {
+                use crate::js::js_value_util::SerializeToJsValue;
+                use crate::js::js_value_util::ToJsValue;
+                let mut js_ctx = js_ctx.clone();
+                let result = js_ctx.create_async_task(async move {
+                    let result = $func().await;
+                    match result {
+                        Ok(r) => {
+                            Ok(r.to_js_value()?)
+                        },
+                        Err(e) => Err(anyhow!(e)),
+                    }
+                });
+                Ok::<_, anyhow::Error>(result)
+            }).unwrap();
+        }
+    };
     ($js_ctx: expr, $name: expr, $func: ident, $in_type: ty) => {
         {
             let js_ctx = $js_ctx.clone();
@@ -1,4 +1,5 @@
 use std::ops::{Bound, RangeBounds};
+use unicode_segmentation::UnicodeSegmentation;
 
 pub trait StringUtils {
     fn substring(&self, start: usize, len: usize) -> &str;
@@ -6,6 +7,15 @@ pub trait StringUtils {
     fn byte_index(&self, char_index: usize) -> usize;
     fn chars_count(&self) -> usize;
     fn trim_line_endings(&self) -> &str;
+    /// Count of extended grapheme clusters
+    fn graphemes_count(&self) -> usize;
+    /// Byte index of the grapheme at grapheme_index, len of string if out of range
+    fn grapheme_byte_index(&self, grapheme_index: usize) -> usize;
+    fn grapheme_substring(&self, start: usize, len: usize) -> &str;
+    /// Utf16 offset of the grapheme at grapheme_index
+    fn grapheme_utf16_index(&self, grapheme_index: usize) -> usize;
+    /// Index of the grapheme which contains the utf16 offset
+    fn utf16_grapheme_index(&self, utf16_offset: usize) -> usize;
 }
 
 impl StringUtils for str {
@@ -60,4 +70,48 @@ impl StringUtils for str {
         self.trim_end_matches(|e| e == '\r' || e == '\n')
     }
 
+    fn graphemes_count(&self) -> usize {
+        self.graphemes(true).count()
+    }
+
+    fn grapheme_byte_index(&self, grapheme_index: usize) -> usize {
+        self.grapheme_indices(true).nth(grapheme_index).map(|(i, _)| i).unwrap_or(self.len())
+    }
+
+    fn grapheme_substring(&self, start: usize, len: usize) -> &str {
+        let rest = &self[self.grapheme_byte_index(start)..];
+        &rest[..rest.grapheme_byte_index(len)]
+    }
+
+    fn grapheme_utf16_index(&self, grapheme_index: usize) -> usize {
+        self[..self.grapheme_byte_index(grapheme_index)].encode_utf16().count()
+    }
+
+    fn utf16_grapheme_index(&self, utf16_offset: usize) -> usize {
+        let mut utf16_end = 0;
+        let mut index = 0;
+        for g in self.graphemes(true) {
+            utf16_end += g.encode_utf16().count();
+            if utf16_end > utf16_offset {
+                break;
+            }
+            index += 1;
+        }
+        index
+    }
+
+}
+
+#[test]
+fn test_graphemes() {
+    // family emoji joined by ZWJ, flag, and e with combining acute accent
+    let text = "a\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}\u{1F1E8}\u{1F1F3}e\u{301}";
+    assert_eq!(4, text.graphemes_count());
+    assert_eq!("\u{1F1E8}\u{1F1F3}", text.grapheme_substring(2, 1));
+    assert_eq!("e\u{301}", text.grapheme_substring(3, 5));
+    assert_eq!(text.len(), text.grapheme_byte_index(4));
+    assert_eq!(9, text.grapheme_utf16_index(2));
+    assert_eq!(1, text.utf16_grapheme_index(5));
+    assert_eq!(3, text.utf16_grapheme_index(13));
+    assert_eq!(4, text.utf16_grapheme_index(100));
 }
@@ -207,6 +207,12 @@ impl PropValueParse for f32 {
     }
 }
 
+impl PropValueParse for i32 {
+    fn parse_prop_value(value: &str) -> Option<Self> {
+        i32::from_str(value).ok()
+    }
+}
+
 impl PropValueParse for FlexDirection {
     fn parse_prop_value(value: &str) -> Option<Self> {
         parse_flex_direction2(value)
@@ -225,15 +231,21 @@ impl PropValueParse for Align {
     }
 }
 
-impl PropValueParse for PositionType {
+impl PropValueParse for StylePosition {
     fn parse_prop_value(value: &str) -> Option<Self> {
-        Some(parse_position_type(value))
+        let position = match value.to_lowercase().as_str() {
+            "relative" => StylePosition::Relative,
+            "absolute" => StylePosition::Absolute,
+            "fixed" => StylePosition::Fixed,
+            _ => StylePosition::Static,
+        };
+        Some(position)
     }
 }
 
 impl PropValueParse for Overflow {
     fn parse_prop_value(value: &str) -> Option<Self> {
-        Some(parse_overflow(value))
+        parse_overflow(value)
     }
 }
 
@@ -263,6 +275,16 @@ impl PropValueParse for StyleTransform {
     }
 }
 
+impl PropValueParse for ScrollbarMode {
+    fn parse_prop_value(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "classic" => Some(ScrollbarMode::Classic),
+            "overlay" => Some(ScrollbarMode::Overlay),
+            _ => None,
+        }
+    }
+}
+
 impl PropValueParse for String {
     fn parse_prop_value(value: &str) -> Option<Self> {
         Some(value.to_string())
@@ -329,6 +351,12 @@ impl StyleTransform {
 #[derive(Clone, Debug)]
 pub struct StyleBorder(StyleUnit, StyleColor);
 
+impl StyleBorder {
+    pub fn new(width: f32, color: Color) -> Self {
+        Self(StyleUnit::Point(OrderedFloat(width)), StyleColor::Color(color))
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum StylePropVal<T> {
     Custom(T),
@@ -416,8 +444,9 @@ define_style_props!(
     FlexShrink => f32,
     AlignSelf => Align,
     Direction => Direction,
-    Position => PositionType,
+    Position => StylePosition,
     Overflow => Overflow,
+    ZIndex => i32,
 
     BorderTopLeftRadius => f32,
     BorderTopRightRadius => f32,
@@ -441,6 +470,18 @@ define_style_props!(
     AnimationName => String,
     AnimationDuration => f32,
     AnimationIterationCount => f32,
+
+    ScrollbarMode => ScrollbarMode,
+    ScrollbarWidth => f32,
+    ScrollbarRadius => f32,
+    ScrollbarTrackColor => StyleColor,
+    ScrollbarThumbColor => StyleColor,
+    ScrollbarThumbHoverColor => StyleColor,
+    ScrollbarThumbActiveColor => StyleColor,
+
+    TrackColor => StyleColor,
+    ThumbColor => StyleColor,
+    CheckedColor => StyleColor,
 );
 
 pub fn expand_mixed_style(mixed: HashMap<AllStylePropertyKey, StylePropertyValue>) -> HashMap<StylePropertyKey, StylePropertyValue> {
@@ -644,6 +685,91 @@ impl Style {
     }
 }
 
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum StylePosition {
+    Static,
+    Relative,
+    Absolute,
+    /// Positioned relative to the frame, and not moved by scrolling of ancestors
+    Fixed,
+}
+
+impl StylePosition {
+    pub fn to_position_type(&self) -> PositionType {
+        match self {
+            StylePosition::Static => PositionType::Static,
+            StylePosition::Relative => PositionType::Relative,
+            // Fixed nodes are out of flow like absolute ones, and laid out against the frame later
+            StylePosition::Absolute | StylePosition::Fixed => PositionType::Absolute,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ScrollbarMode {
+    /// Bars take space from the content
+    Classic,
+    /// Bars are drawn over the content and hidden when idle
+    Overlay,
+}
+
+/// Appearance of scroll bars, set by `scrollbar*` style properties
+#[derive(Clone, Debug)]
+pub struct ScrollbarStyle {
+    pub mode: ScrollbarMode,
+    pub width: f32,
+    pub radius: f32,
+    pub track_color: Color,
+    pub thumb_color: Color,
+    pub thumb_hover_color: Color,
+    pub thumb_active_color: Color,
+}
+
+impl ScrollbarStyle {
+    pub const DEFAULT_WIDTH: f32 = 14.0;
+    pub const DEFAULT_TRACK_COLOR: Color = Color::from_rgb(0x1E, 0x1F, 0x22);
+    pub const DEFAULT_THUMB_COLOR: Color = Color::from_rgb(0x44, 0x44, 0x46);
+    pub const DEFAULT_THUMB_HOVER_COLOR: Color = Color::from_rgb(0x5A, 0x5A, 0x5D);
+    pub const DEFAULT_THUMB_ACTIVE_COLOR: Color = Color::from_rgb(0x70, 0x70, 0x73);
+}
+
+impl Default for ScrollbarStyle {
+    fn default() -> Self {
+        Self {
+            mode: ScrollbarMode::Classic,
+            width: Self::DEFAULT_WIDTH,
+            radius: 0.0,
+            track_color: Self::DEFAULT_TRACK_COLOR,
+            thumb_color: Self::DEFAULT_THUMB_COLOR,
+            thumb_hover_color: Self::DEFAULT_THUMB_HOVER_COLOR,
+            thumb_active_color: Self::DEFAULT_THUMB_ACTIVE_COLOR,
+        }
+    }
+}
+
+/// Colors of form controls, e.g. checkbox, switch and slider, set by `trackColor`, `thumbColor` and `checkedColor`.
+/// Unset colors are derived from `color`
+#[derive(Clone, Debug, Default)]
+pub struct ControlStyle {
+    pub track_color: Option<Color>,
+    pub thumb_color: Option<Color>,
+    pub checked_color: Option<Color>,
+}
+
+fn resolve_optional_color(value: &StylePropVal<StyleColor>) -> Option<Color> {
+    match value.resolve(&ColorPropValue::Inherit) {
+        ColorPropValue::Color(c) => Some(c),
+        ColorPropValue::Inherit => None,
+    }
+}
+
+fn resolve_color(value: &StylePropVal<StyleColor>, default: Color) -> Color {
+    match value.resolve(&ColorPropValue::Color(default)) {
+        ColorPropValue::Color(c) => c,
+        ColorPropValue::Inherit => default,
+    }
+}
+
 pub trait ColorHelper {
     fn is_transparent(&self) -> bool;
 }
@@ -685,6 +811,14 @@ pub struct StyleNodeInner {
     pub background_color: ColorPropValue,
     pub background_image: Option<Image>,
     pub transform: Option<Matrix>,
+    pub scrollbar: ScrollbarStyle,
+    pub control: ControlStyle,
+    pub position: StylePosition,
+    /// Children are clipped to the content box unless it is `Overflow::Visible`
+    pub overflow: Overflow,
+    /// Nodes with z-index are painted in the order of z-index in the stacking context of their ancestor
+    pub z_index: Option<i32>,
+    fixed_position: (f32, f32),
     pub computed_style: ComputedStyle,
     animation_params: AnimationParams,
     animation_instance: Option<AnimationInstance>,
@@ -747,6 +881,12 @@ impl StyleNode {
             color: ColorPropValue::Inherit,
             background_image: None,
             transform: None,
+            scrollbar: ScrollbarStyle::default(),
+            control: ControlStyle::default(),
+            position: StylePosition::Static,
+            overflow: Overflow::Hidden,
+            z_index: None,
+            fixed_position: (0.0, 0.0),
             animation_instance: None,
             animation_params: AnimationParams::new(),
             computed_style: ComputedStyle::default(),
@@ -876,7 +1016,9 @@ impl StyleNode {
                 self.set_direction(value.resolve(&Direction::LTR))
             },
             StyleProp::Position (value) =>   {
-                self.set_position_type(value.resolve(&PositionType::Static))
+                let position = value.resolve(&StylePosition::Static);
+                self.position = position;
+                self.set_position_type(position.to_position_type())
             },
             StyleProp::Top (value) =>   {
                 self.yoga_node.set_position(Edge::Top, value.resolve(&StyleUnit::UndefinedValue));
@@ -891,7 +1033,16 @@ impl StyleNode {
                 self.yoga_node.set_position(Edge::Left, value.resolve(&StyleUnit::UndefinedValue));
             },
             StyleProp::Overflow (value) =>   {
-                self.set_overflow(value.resolve(&Overflow::Hidden))
+                let overflow = value.resolve(&Overflow::Hidden);
+                self.overflow = overflow;
+                self.set_overflow(overflow)
+            },
+            StyleProp::ZIndex (value) =>   {
+                self.z_index = match value {
+                    StylePropVal::Custom(v) => Some(*v),
+                    StylePropVal::Unset => None,
+                };
+                need_layout = false;
             },
             StyleProp::BorderTopLeftRadius (value) =>   {
                 self.border_radius[0] = value.resolve(&0.0)
@@ -934,6 +1085,45 @@ impl StyleNode {
                 }
             }
 
+            StyleProp::ScrollbarMode(value) => {
+                self.scrollbar.mode = value.resolve(&ScrollbarMode::Classic);
+            }
+            StyleProp::ScrollbarWidth(value) => {
+                self.scrollbar.width = value.resolve(&ScrollbarStyle::DEFAULT_WIDTH);
+            }
+            StyleProp::ScrollbarRadius(value) => {
+                self.scrollbar.radius = value.resolve(&0.0);
+                need_layout = false;
+            }
+            StyleProp::ScrollbarTrackColor(value) => {
+                self.scrollbar.track_color = resolve_color(value, ScrollbarStyle::DEFAULT_TRACK_COLOR);
+                need_layout = false;
+            }
+            StyleProp::ScrollbarThumbColor(value) => {
+                self.scrollbar.thumb_color = resolve_color(value, ScrollbarStyle::DEFAULT_THUMB_COLOR);
+                need_layout = false;
+            }
+            StyleProp::ScrollbarThumbHoverColor(value) => {
+                self.scrollbar.thumb_hover_color = resolve_color(value, ScrollbarStyle::DEFAULT_THUMB_HOVER_COLOR);
+                need_layout = false;
+            }
+            StyleProp::ScrollbarThumbActiveColor(value) => {
+                self.scrollbar.thumb_active_color = resolve_color(value, ScrollbarStyle::DEFAULT_THUMB_ACTIVE_COLOR);
+                need_layout = false;
+            }
+            StyleProp::TrackColor(value) => {
+                self.control.track_color = resolve_optional_color(value);
+                need_layout = false;
+            }
+            StyleProp::ThumbColor(value) => {
+                self.control.thumb_color = resolve_optional_color(value);
+                need_layout = false;
+            }
+            StyleProp::CheckedColor(value) => {
+                self.control.checked_color = resolve_optional_color(value);
+                need_layout = false;
+            }
+
             // container node style
             StyleProp::JustifyContent (value) =>   {
                 self.with_container_node_mut(|layout| {
@@ -1098,6 +1288,31 @@ impl StyleNode {
     }
 
 
+    /// Lay out a fixed node against the frame, the result position is returned by `get_fixed_position`
+    pub fn calculate_fixed_layout(&mut self, frame_width: f32, frame_height: f32) {
+        self.inner.yoga_node.calculate_layout(frame_width, frame_height, Direction::LTR);
+        let width = self.get_layout_width().de_nan(0.0);
+        let height = self.get_layout_height().de_nan(0.0);
+        let x = resolve_fixed_offset(
+            (self.get_style_position_left(), self.get_style_position_right()),
+            (self.get_style_margin_left(), self.get_style_margin_right()),
+            width,
+            frame_width,
+        );
+        let y = resolve_fixed_offset(
+            (self.get_style_position_top(), self.get_style_position_bottom()),
+            (self.get_style_margin_top(), self.get_style_margin_bottom()),
+            height,
+            frame_height,
+        );
+        self.inner.fixed_position = (x, y);
+    }
+
+    /// Position of a fixed node relative to the frame
+    pub fn get_fixed_position(&self) -> (f32, f32) {
+        self.inner.fixed_position
+    }
+
     pub fn calculate_shadow_layout(&mut self,
                                available_width: f32,
                                available_height: f32,
@@ -1365,14 +1580,31 @@ pub fn parse_position_type(value: &str) -> PositionType {
     }
 }
 
-pub fn parse_overflow(value: &str) -> Overflow {
+/// Offset of a fixed node on one axis, the leading inset wins if both insets are set
+fn resolve_fixed_offset(insets: (StyleUnit, StyleUnit), margins: (StyleUnit, StyleUnit), size: f32, frame_size: f32) -> f32 {
+    let resolve = |unit: StyleUnit| match unit {
+        StyleUnit::Point(v) => Some(v.0),
+        StyleUnit::Percent(v) => Some(frame_size * v.0 / 100.0),
+        _ => None,
+    };
+    let leading_margin = resolve(margins.0).unwrap_or(0.0);
+    match (resolve(insets.0), resolve(insets.1)) {
+        (Some(leading), _) => leading + leading_margin,
+        (None, Some(trailing)) => frame_size - size - trailing - resolve(margins.1).unwrap_or(0.0),
+        (None, None) => leading_margin,
+    }
+}
+
+/// Unknown values are rejected, so that they don't disable clipping
+pub fn parse_overflow(value: &str) -> Option<Overflow> {
     let key = value.to_lowercase();
-    match key.as_str() {
+    let r = match key.as_str() {
         "visible" => Overflow::Visible,
         "hidden" => Overflow::Hidden,
-        "scroll" => Overflow::Scroll,
-        _ => Overflow::Visible,
-    }
+        "scroll" | "auto" => Overflow::Scroll,
+        _ => return None,
+    };
+    Some(r)
 }
 
 fn parse_transform(value: &str) -> Option<Matrix> {
@@ -1456,4 +1688,14 @@ fn parse_border(value: &str) -> Option<StyleBorder> {
         }
     }
     Some(StyleBorder(width, StyleColor::Color(color)))
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_fixed_offset() {
+    let px = |v: f32| StyleUnit::Point(OrderedFloat(v));
+    let none = StyleUnit::UndefinedValue;
+    assert_eq!(10.0, resolve_fixed_offset((px(10.0), px(20.0)), (none, none), 50.0, 800.0));
+    assert_eq!(730.0, resolve_fixed_offset((none, px(20.0)), (none, none), 50.0, 800.0));
+    assert_eq!(400.0, resolve_fixed_offset((StyleUnit::Percent(OrderedFloat(50.0)), none), (none, none), 50.0, 800.0));
+    assert_eq!(5.0, resolve_fixed_offset((none, none), (px(5.0), none), 50.0, 800.0));
+}
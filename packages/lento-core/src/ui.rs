@@ -0,0 +1,407 @@
+use std::marker::PhantomData;
+use ordered_float::OrderedFloat;
+use skia_safe::Color;
+use yoga::{Align, Display, FlexDirection, Justify, Overflow, StyleUnit, Wrap};
+use crate::base::{CheckedChangeDetail, ElementEventContext, MouseDetail, SelectionChangeDetail, TextChangeDetail, ValueChangeDetail};
+use crate::element::button::Button;
+use crate::element::canvas::CanvasElement;
+use crate::element::checkbox::Checkbox;
+use crate::element::container::Container;
+use crate::element::entry::Entry;
+use crate::element::image::Image;
+use crate::element::progress_bar::ProgressBar;
+use crate::element::scroll::Scroll;
+use crate::element::select::{Select, SelectOption};
+use crate::element::slider::Slider;
+use crate::element::switch::Switch;
+use crate::element::text::Text;
+use crate::element::{ElementBackend, ElementRef};
+use crate::event::{BlurEventBind, CheckedChangeBind, ClickEventBind, FocusEventBind, KeyDownEventBind, KeyEventDetail, KeyUpEventBind, MouseDownEventBind, MouseEnterEventBind, MouseLeaveEventBind, MouseUpEventBind, SelectionChangeBind, TextChangeBind, ValueChangeBind};
+use crate::style::{ScrollbarMode, StyleBorder, StyleColor, StylePosition, StyleProp, StylePropVal};
+
+pub fn px(value: f32) -> StyleUnit {
+    StyleUnit::Point(OrderedFloat(value))
+}
+
+pub fn percent(value: f32) -> StyleUnit {
+    StyleUnit::Percent(OrderedFloat(value))
+}
+
+pub fn auto() -> StyleUnit {
+    StyleUnit::Auto
+}
+
+/// Typed alternative to style objects parsed from js
+#[derive(Clone, Debug, Default)]
+pub struct Style {
+    props: Vec<StyleProp>,
+}
+
+macro_rules! style_setters {
+    ($($func: ident => $name: ident : $type: ty,)*) => {
+        $(
+            pub fn $func(self, value: $type) -> Self {
+                self.prop(StyleProp::$name(StylePropVal::Custom(value)))
+            }
+        )*
+    };
+}
+
+impl Style {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn prop(mut self, prop: StyleProp) -> Self {
+        self.props.push(prop);
+        self
+    }
+
+    style_setters!(
+        display => Display: Display,
+        width => Width: StyleUnit,
+        height => Height: StyleUnit,
+        min_width => MinWidth: StyleUnit,
+        min_height => MinHeight: StyleUnit,
+        max_width => MaxWidth: StyleUnit,
+        max_height => MaxHeight: StyleUnit,
+        margin_top => MarginTop: StyleUnit,
+        margin_right => MarginRight: StyleUnit,
+        margin_bottom => MarginBottom: StyleUnit,
+        margin_left => MarginLeft: StyleUnit,
+        padding_top => PaddingTop: StyleUnit,
+        padding_right => PaddingRight: StyleUnit,
+        padding_bottom => PaddingBottom: StyleUnit,
+        padding_left => PaddingLeft: StyleUnit,
+        flex => Flex: f32,
+        flex_basis => FlexBasis: StyleUnit,
+        flex_grow => FlexGrow: f32,
+        flex_shrink => FlexShrink: f32,
+        flex_direction => FlexDirection: FlexDirection,
+        flex_wrap => FlexWrap: Wrap,
+        justify_content => JustifyContent: Justify,
+        align_items => AlignItems: Align,
+        align_content => AlignContent: Align,
+        align_self => AlignSelf: Align,
+        position => Position: StylePosition,
+        overflow => Overflow: Overflow,
+        z_index => ZIndex: i32,
+        top => Top: StyleUnit,
+        right => Right: StyleUnit,
+        bottom => Bottom: StyleUnit,
+        left => Left: StyleUnit,
+        row_gap => RowGap: f32,
+        column_gap => ColumnGap: f32,
+        scrollbar_mode => ScrollbarMode: ScrollbarMode,
+        scrollbar_width => ScrollbarWidth: f32,
+        scrollbar_radius => ScrollbarRadius: f32,
+    );
+
+    pub fn color(self, color: Color) -> Self {
+        self.prop(StyleProp::Color(StylePropVal::Custom(StyleColor::Color(color))))
+    }
+
+    pub fn background_color(self, color: Color) -> Self {
+        self.prop(StyleProp::BackgroundColor(StylePropVal::Custom(StyleColor::Color(color))))
+    }
+
+    pub fn scrollbar_colors(self, track: Color, thumb: Color, thumb_hover: Color, thumb_active: Color) -> Self {
+        self.prop(StyleProp::ScrollbarTrackColor(StylePropVal::Custom(StyleColor::Color(track))))
+            .prop(StyleProp::ScrollbarThumbColor(StylePropVal::Custom(StyleColor::Color(thumb))))
+            .prop(StyleProp::ScrollbarThumbHoverColor(StylePropVal::Custom(StyleColor::Color(thumb_hover))))
+            .prop(StyleProp::ScrollbarThumbActiveColor(StylePropVal::Custom(StyleColor::Color(thumb_active))))
+    }
+
+    pub fn control_colors(self, track: Color, thumb: Color, checked: Color) -> Self {
+        self.prop(StyleProp::TrackColor(StylePropVal::Custom(StyleColor::Color(track))))
+            .prop(StyleProp::ThumbColor(StylePropVal::Custom(StyleColor::Color(thumb))))
+            .prop(StyleProp::CheckedColor(StylePropVal::Custom(StyleColor::Color(checked))))
+    }
+
+    pub fn margin(self, value: StyleUnit) -> Self {
+        self.margin_top(value).margin_right(value).margin_bottom(value).margin_left(value)
+    }
+
+    pub fn padding(self, value: StyleUnit) -> Self {
+        self.padding_top(value).padding_right(value).padding_bottom(value).padding_left(value)
+    }
+
+    pub fn gap(self, value: f32) -> Self {
+        self.row_gap(value).column_gap(value)
+    }
+
+    pub fn border(self, width: f32, color: Color) -> Self {
+        let border = StyleBorder::new(width, color);
+        self.prop(StyleProp::BorderTop(StylePropVal::Custom(border.clone())))
+            .prop(StyleProp::BorderRight(StylePropVal::Custom(border.clone())))
+            .prop(StyleProp::BorderBottom(StylePropVal::Custom(border.clone())))
+            .prop(StyleProp::BorderLeft(StylePropVal::Custom(border)))
+    }
+
+    pub fn border_radius(self, radius: f32) -> Self {
+        self.prop(StyleProp::BorderTopLeftRadius(StylePropVal::Custom(radius)))
+            .prop(StyleProp::BorderTopRightRadius(StylePropVal::Custom(radius)))
+            .prop(StyleProp::BorderBottomRightRadius(StylePropVal::Custom(radius)))
+            .prop(StyleProp::BorderBottomLeftRadius(StylePropVal::Custom(radius)))
+    }
+
+    pub fn into_props(self) -> Vec<StyleProp> {
+        self.props
+    }
+}
+
+/// Builds an element of backend `T` from rust, without going through the js api
+pub struct ElementBuilder<T: ElementBackend> {
+    element: ElementRef,
+    _backend: PhantomData<T>,
+}
+
+macro_rules! event_setters {
+    ($($func: ident => $bind_func: ident : $detail: ty,)*) => {
+        $(
+            pub fn $func<F: FnMut(&mut ElementEventContext, &mut $detail) + 'static>(mut self, handler: F) -> Self {
+                self.element.$bind_func(handler);
+                self
+            }
+        )*
+    };
+}
+
+impl<T: ElementBackend + 'static> ElementBuilder<T> {
+    pub fn new() -> Self {
+        Self {
+            element: ElementRef::new(T::create),
+            _backend: PhantomData,
+        }
+    }
+
+    /// The element being built, e.g. to be captured by event handlers
+    pub fn element(&self) -> ElementRef {
+        self.element.clone()
+    }
+
+    pub fn with<F: FnOnce(&mut T)>(mut self, callback: F) -> Self {
+        callback(self.element.get_backend_mut_as::<T>());
+        self
+    }
+
+    pub fn style(mut self, style: Style) -> Self {
+        self.element.set_style_props(style.into_props());
+        self
+    }
+
+    pub fn hover_style(mut self, style: Style) -> Self {
+        self.element.set_hover_style_props(style.into_props());
+        self
+    }
+
+    pub fn focus_style(mut self, style: Style) -> Self {
+        self.element.set_focus_style_props(style.into_props());
+        self
+    }
+
+    pub fn child(mut self, child: impl Into<ElementRef>) -> Self {
+        self.element.add_child_view(child.into(), None);
+        self
+    }
+
+    pub fn children<C: Into<ElementRef>>(mut self, children: impl IntoIterator<Item = C>) -> Self {
+        for child in children {
+            self.element.add_child_view(child.into(), None);
+        }
+        self
+    }
+
+    event_setters!(
+        on_click => bind_click: MouseDetail,
+        on_mouse_down => bind_mouse_down: MouseDetail,
+        on_mouse_up => bind_mouse_up: MouseDetail,
+        on_mouse_enter => bind_mouse_enter: MouseDetail,
+        on_mouse_leave => bind_mouse_leave: MouseDetail,
+        on_key_down => bind_key_down: KeyEventDetail,
+        on_key_up => bind_key_up: KeyEventDetail,
+        on_focus => bind_focus: (),
+        on_blur => bind_blur: (),
+        on_text_change => bind_text_change: TextChangeDetail,
+        on_checked_change => bind_checked_change: CheckedChangeDetail,
+        on_value_change => bind_value_change: ValueChangeDetail,
+        on_selection_change => bind_selection_change: SelectionChangeDetail,
+    );
+
+    pub fn build(self) -> ElementRef {
+        self.element
+    }
+}
+
+impl<T: ElementBackend + 'static> Default for ElementBuilder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: ElementBackend> From<ElementBuilder<T>> for ElementRef {
+    fn from(builder: ElementBuilder<T>) -> Self {
+        builder.element
+    }
+}
+
+impl ElementBuilder<Text> {
+    pub fn text(self, text: impl Into<String>) -> Self {
+        self.with(|t| t.set_text(text.into()))
+    }
+}
+
+impl ElementBuilder<Button> {
+    pub fn title(self, title: impl Into<String>) -> Self {
+        self.with(|b| b.set_title(title.into()))
+    }
+}
+
+impl ElementBuilder<Entry> {
+    pub fn text(self, text: impl Into<String>) -> Self {
+        self.with(|e| e.set_text(text.into()))
+    }
+
+    pub fn placeholder(self, placeholder: impl Into<String>) -> Self {
+        self.with(|e| e.set_placeholder(placeholder.into()))
+    }
+
+    pub fn multiple_line(self, multiple_line: bool) -> Self {
+        self.with(|e| e.set_multiple_line(multiple_line))
+    }
+
+    pub fn disabled(self, disabled: bool) -> Self {
+        self.with(|e| e.set_disabled(disabled))
+    }
+}
+
+impl ElementBuilder<Image> {
+    pub fn src(self, src: impl Into<String>) -> Self {
+        self.with(|i| i.set_src(src.into()))
+    }
+}
+
+impl ElementBuilder<Checkbox> {
+    pub fn checked(self, checked: bool) -> Self {
+        self.with(|c| c.set_checked(checked))
+    }
+
+    pub fn disabled(self, disabled: bool) -> Self {
+        self.with(|c| c.set_disabled(disabled))
+    }
+}
+
+impl ElementBuilder<Switch> {
+    pub fn checked(self, checked: bool) -> Self {
+        self.with(|s| s.set_checked(checked))
+    }
+
+    pub fn disabled(self, disabled: bool) -> Self {
+        self.with(|s| s.set_disabled(disabled))
+    }
+}
+
+impl ElementBuilder<Slider> {
+    pub fn min(self, min: f32) -> Self {
+        self.with(|s| s.set_min(min))
+    }
+
+    pub fn max(self, max: f32) -> Self {
+        self.with(|s| s.set_max(max))
+    }
+
+    pub fn step(self, step: f32) -> Self {
+        self.with(|s| s.set_step(step))
+    }
+
+    pub fn value(self, value: f32) -> Self {
+        self.with(|s| s.set_value(value))
+    }
+}
+
+impl ElementBuilder<ProgressBar> {
+    pub fn value(self, value: f32) -> Self {
+        self.with(|p| p.set_value(value))
+    }
+
+    pub fn max(self, max: f32) -> Self {
+        self.with(|p| p.set_max(max))
+    }
+}
+
+impl ElementBuilder<Select> {
+    pub fn options(self, options: Vec<SelectOption>) -> Self {
+        self.with(|s| s.set_options(options))
+    }
+
+    pub fn value(self, value: impl Into<String>) -> Self {
+        self.with(|s| s.set_value(value.into()))
+    }
+
+    pub fn placeholder(self, placeholder: impl Into<String>) -> Self {
+        self.with(|s| s.set_placeholder(placeholder.into()))
+    }
+}
+
+pub fn container() -> ElementBuilder<Container> {
+    ElementBuilder::new()
+}
+
+pub fn scroll() -> ElementBuilder<Scroll> {
+    ElementBuilder::new()
+}
+
+pub fn label(text: impl Into<String>) -> ElementBuilder<Text> {
+    ElementBuilder::new().text(text)
+}
+
+pub fn button(title: impl Into<String>) -> ElementBuilder<Button> {
+    ElementBuilder::new().title(title)
+}
+
+pub fn entry() -> ElementBuilder<Entry> {
+    ElementBuilder::new()
+}
+
+pub fn image(src: impl Into<String>) -> ElementBuilder<Image> {
+    ElementBuilder::new().src(src)
+}
+
+pub fn checkbox() -> ElementBuilder<Checkbox> {
+    ElementBuilder::new()
+}
+
+pub fn switch() -> ElementBuilder<Switch> {
+    ElementBuilder::new()
+}
+
+pub fn slider() -> ElementBuilder<Slider> {
+    ElementBuilder::new()
+}
+
+pub fn progress_bar() -> ElementBuilder<ProgressBar> {
+    ElementBuilder::new()
+}
+
+pub fn select() -> ElementBuilder<Select> {
+    ElementBuilder::new()
+}
+
+pub fn canvas() -> ElementBuilder<CanvasElement> {
+    ElementBuilder::new()
+}
+
+#[test]
+fn test_style_builder() {
+    let props = Style::new()
+        .width(px(100.0))
+        .padding(px(4.0))
+        .border(1.0, Color::from_rgb(128, 128, 128))
+        .into_props();
+    let names: Vec<&str> = props.iter().map(|p| p.name()).collect();
+    assert_eq!(vec![
+        "Width",
+        "PaddingTop", "PaddingRight", "PaddingBottom", "PaddingLeft",
+        "BorderTop", "BorderRight", "BorderBottom", "BorderLeft",
+    ], names);
+}
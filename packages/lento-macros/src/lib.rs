@@ -1,6 +1,7 @@
 use proc_macro::TokenStream;
-use quote::{format_ident, quote};
-use syn::{parse_macro_input, FnArg, ItemFn};
+use quote::{format_ident, quote, ToTokens};
+use syn::parse::{Parse, ParseStream};
+use syn::{braced, parenthesized, parse_macro_input, token, Data, DeriveInput, Expr, Fields, FnArg, Ident, ItemFn, LitStr, Path, Token};
 
 #[proc_macro_attribute]
 pub fn js_func(_attr: TokenStream, func: TokenStream) -> TokenStream {
@@ -82,4 +83,276 @@ pub fn js_func(_attr: TokenStream, func: TokenStream) -> TokenStream {
 
     };
     expanded.into()
-}
\ No newline at end of file
+}
+
+/// Implement `ElementBackend` for a custom element.
+///
+/// The field marked with `#[element]` receives the `ElementRef`, other fields are initialized by `Default`.
+/// Fields marked with `#[property]` could be set and read by name from js, the js name could be changed
+/// by `#[property(name = "...")]`. The element name defaults to the struct name, and drawing could be
+/// delegated to a method by `#[element(name = "...", draw = "method")]`.
+///
+/// ```ignore
+/// #[derive(ElementBackend)]
+/// #[element(name = "Gauge", draw = "draw_gauge")]
+/// struct Gauge {
+///     #[element]
+///     element: ElementRef,
+///     #[property]
+///     value: f32,
+/// }
+/// ```
+#[proc_macro_derive(ElementBackend, attributes(element, property))]
+pub fn derive_element_backend(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand_element_backend(input) {
+        Ok(ts) => ts.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand_element_backend(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let struct_name = &input.ident;
+    let mut element_name = struct_name.to_string();
+    let mut draw_method: Option<Ident> = None;
+    for attr in &input.attrs {
+        if !attr.path().is_ident("element") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                element_name = meta.value()?.parse::<LitStr>()?.value();
+            } else if meta.path.is_ident("draw") {
+                let method = meta.value()?.parse::<LitStr>()?;
+                draw_method = Some(Ident::new(&method.value(), method.span()));
+            } else {
+                return Err(meta.error("unsupported element attribute"));
+            }
+            Ok(())
+        })?;
+    }
+
+    let fields = match &input.data {
+        Data::Struct(s) => match &s.fields {
+            Fields::Named(f) => &f.named,
+            _ => return Err(syn::Error::new_spanned(struct_name, "named fields are required")),
+        },
+        _ => return Err(syn::Error::new_spanned(struct_name, "only struct is supported")),
+    };
+
+    // setters mark the element dirty, so the element field is required before building them
+    let mut element_fields = fields.iter().filter(|f| f.attrs.iter().any(|a| a.path().is_ident("element")));
+    let element_field = match element_fields.next() {
+        Some(f) => f.ident.clone().unwrap(),
+        None => return Err(syn::Error::new_spanned(struct_name, "a field marked with #[element] is required")),
+    };
+    if let Some(f) = element_fields.next() {
+        return Err(syn::Error::new_spanned(f, "only one field could be marked with #[element]"));
+    }
+
+    let mut default_fields = Vec::new();
+    let mut setters = Vec::new();
+    let mut getters = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        for attr in &field.attrs {
+            if attr.path().is_ident("property") {
+                let mut js_name = ident.to_string();
+                if let syn::Meta::List(_) = &attr.meta {
+                    attr.parse_nested_meta(|meta| {
+                        if meta.path.is_ident("name") {
+                            js_name = meta.value()?.parse::<LitStr>()?.value();
+                            Ok(())
+                        } else {
+                            Err(meta.error("unsupported property attribute"))
+                        }
+                    })?;
+                }
+                setters.push(quote! {
+                    if property_name == #js_name {
+                        if let Ok(v) = <#ty as lento::js::js_value_util::DeserializeFromJsValue>::from_js_value(property_value) {
+                            self.#ident = v;
+                            self.#element_field.mark_dirty(false);
+                        }
+                        return;
+                    }
+                });
+                getters.push(quote! {
+                    if property_name == #js_name {
+                        let v = lento::js::js_value_util::SerializeToJsValue::to_js_value(self.#ident.clone())?;
+                        return Ok(Some(v));
+                    }
+                });
+            }
+        }
+        if *ident != element_field {
+            default_fields.push(ident.clone());
+        }
+    }
+
+    let draw = draw_method.map(|method| quote! {
+        fn draw(&self, canvas: &lento::skia_safe::Canvas) {
+            self.#method(canvas);
+        }
+    });
+
+    Ok(quote! {
+        impl lento::element::ElementBackend for #struct_name {
+            fn create(element: lento::element::ElementRef) -> Self {
+                Self {
+                    #element_field: element,
+                    #(#default_fields: Default::default(),)*
+                }
+            }
+
+            fn get_name(&self) -> &str {
+                #element_name
+            }
+
+            #draw
+
+            fn set_property(&mut self, property_name: &str, property_value: lento::JsValue) {
+                #(#setters)*
+                let _ = property_value;
+            }
+
+            fn get_property(&mut self, property_name: &str) -> Result<Option<lento::JsValue>, lento::anyhow::Error> {
+                #(#getters)*
+                Ok(None)
+            }
+        }
+    })
+}
+
+/// Build an element tree with `lento::ui::ElementBuilder`.
+///
+/// Each node is an element backend type followed by optional builder calls in parentheses and
+/// optional children in braces. A child could also be any expression convertible into `ElementRef`
+/// written as `@{ expr }`, the `@` keeps it from being taken as children of the previous node.
+/// The root node is built into an `ElementRef`.
+///
+/// ```ignore
+/// let root = view! {
+///     Container(style = Style::new().padding(px(8.0))) {
+///         Text(text = "Name")
+///         Entry(placeholder = "Input your name", on_text_change = |_, d| println!("{}", d.value))
+///         @{ button("Ok") }
+///     }
+/// };
+/// ```
+#[proc_macro]
+pub fn view(input: TokenStream) -> TokenStream {
+    let node = parse_macro_input!(input as ViewNode);
+    quote!((#node).build()).into()
+}
+
+struct ViewNode {
+    backend: Path,
+    props: Vec<(Ident, Expr)>,
+    children: Vec<ViewChild>,
+}
+
+enum ViewChild {
+    Node(ViewNode),
+    Expr(Expr),
+}
+
+impl Parse for ViewNode {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let backend = input.parse::<Path>()?;
+        let mut props = Vec::new();
+        if input.peek(token::Paren) {
+            let content;
+            parenthesized!(content in input);
+            while !content.is_empty() {
+                let name = content.parse::<Ident>()?;
+                content.parse::<Token![=]>()?;
+                let value = content.parse::<Expr>()?;
+                props.push((name, value));
+                if !content.is_empty() {
+                    content.parse::<Token![,]>()?;
+                }
+            }
+        }
+        let mut children = Vec::new();
+        if input.peek(token::Brace) {
+            let content;
+            braced!(content in input);
+            while !content.is_empty() {
+                if content.peek(Token![@]) {
+                    content.parse::<Token![@]>()?;
+                    let expr;
+                    braced!(expr in content);
+                    children.push(ViewChild::Expr(expr.parse()?));
+                } else {
+                    children.push(ViewChild::Node(content.parse()?));
+                }
+                if content.peek(Token![,]) {
+                    content.parse::<Token![,]>()?;
+                }
+            }
+        }
+        Ok(Self { backend, props, children })
+    }
+}
+
+impl ToTokens for ViewNode {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let backend = &self.backend;
+        let props = self.props.iter().map(|(name, value)| quote!(.#name(#value)));
+        let children = self.children.iter().map(|child| match child {
+            ViewChild::Node(node) => quote!(.child(#node)),
+            ViewChild::Expr(expr) => quote!(.child(#expr)),
+        });
+        tokens.extend(quote! {
+            lento::ui::ElementBuilder::<#backend>::new() #(#props)* #(#children)*
+        });
+    }
+}
+
+#[cfg(test)]
+fn parse_view(input: &str) -> syn::Result<ViewNode> {
+    syn::parse_str::<ViewNode>(input)
+}
+
+#[test]
+fn test_parse_view() {
+    let root = parse_view(r#"
+        Container(style = Style::new().padding(px(8.0)),) {
+            Text(text = "Name")
+            Entry(placeholder = "Input your name") {}
+            @{ button("Ok") },
+            Container { Text }
+        }
+    "#).unwrap();
+    assert!(root.backend.is_ident("Container"));
+    assert_eq!(1, root.props.len());
+    assert_eq!(4, root.children.len());
+    match &root.children[1] {
+        ViewChild::Node(entry) => {
+            assert!(entry.backend.is_ident("Entry"));
+            assert!(entry.children.is_empty());
+        }
+        ViewChild::Expr(_) => panic!("node expected"),
+    }
+    assert!(matches!(root.children[2], ViewChild::Expr(_)));
+    match &root.children[3] {
+        ViewChild::Node(container) => assert_eq!(1, container.children.len()),
+        ViewChild::Expr(_) => panic!("node expected"),
+    }
+
+    // expression without `@` is not a node
+    assert!(parse_view(r#"Container { { button("Ok") } }"#).is_err());
+    assert!(parse_view(r#"Container { Entry(placeholder) }"#).is_err());
+}
+
+#[test]
+fn test_expand_view() {
+    let root = parse_view(r#"Container { Text(text = "a") @{ button("Ok") } }"#).unwrap();
+    let expanded = root.to_token_stream().to_string().replace(' ', "");
+    assert_eq!(
+        r#"lento::ui::ElementBuilder::<Container>::new().child(lento::ui::ElementBuilder::<Text>::new().text("a")).child(button("Ok"))"#,
+        expanded,
+    );
+}
@@ -0,0 +1,11 @@
+#[test]
+fn test_derive_element_backend() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/element_backend_field_order.rs");
+}
+
+#[test]
+fn test_view() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/view.rs");
+}
@@ -0,0 +1,35 @@
+use lento::element::{ElementBackend, ElementRef};
+use lento::JsValue;
+
+#[derive(lento::ElementBackend)]
+struct ElementFirst {
+    #[element]
+    element: ElementRef,
+    #[property]
+    value: f32,
+}
+
+#[derive(lento::ElementBackend)]
+#[element(name = "Gauge")]
+struct PropertyFirst {
+    #[property(name = "label")]
+    text: String,
+    #[property]
+    value: f32,
+    #[element]
+    element: ElementRef,
+}
+
+fn main() {
+    let mut el = ElementRef::new(ElementFirst::create);
+    assert_eq!("ElementFirst", el.get_backend().get_name());
+    el.get_backend_mut().set_property("value", JsValue::Float(0.5));
+    assert_eq!(0.5, el.get_backend_as::<ElementFirst>().value);
+
+    let mut el = ElementRef::new(PropertyFirst::create);
+    assert_eq!("Gauge", el.get_backend().get_name());
+    el.get_backend_mut().set_property("label", JsValue::String("speed".to_string()));
+    let backend = el.get_backend_as::<PropertyFirst>();
+    assert_eq!("speed", backend.text);
+    assert_eq!(0.0, backend.value);
+}
@@ -0,0 +1,19 @@
+use lento::element::container::Container;
+use lento::element::entry::Entry;
+use lento::element::text::Text;
+use lento::ui::{button, px, Style};
+use lento::view;
+
+fn main() {
+    let root = view! {
+        Container(style = Style::new().padding(px(8.0))) {
+            Text(text = "Name")
+            Entry(placeholder = "Input your name", on_text_change = |_, d| println!("{}", d.value))
+            @{ button("Ok") }
+        }
+    };
+    let children = root.get_children();
+    assert_eq!(3, children.len());
+    assert!(children[1].get_children().is_empty());
+    assert_eq!("Button", children[2].get_backend().get_name());
+}